@@ -0,0 +1,171 @@
+//! # Zeep build
+//! Generates the code of WSDL documents and XML schemas from the build script of a crate:
+//!
+//! ```no_run
+//! fn main() -> Result<(), zeep_build::ZeepError> {
+//!     zeep_build::Config::new()
+//!         .wsdl("resources/hello.wsdl")
+//!         .configure(|writer| writer.with_builders(true))
+//!         .generate()
+//! }
+//! ```
+//!
+//! The code of every document is written to the `OUT_DIR` of the build, in a file named after the
+//! document, to be included in a module of the crate:
+//!
+//! ```ignore
+//! #[allow(dead_code, unused_imports)]
+//! mod hello {
+//!     include!(concat!(env!("OUT_DIR"), "/hello.rs"));
+//! }
+//! ```
+//!
+//! Cargo runs the build script again when one of the documents, or a document they import,
+//! changes.
+//!
+use std::path::{Path, PathBuf};
+pub use zeep_lib::error::{ZeepError, ZeepResult};
+use zeep_lib::writer::FileWriter;
+
+/// The documents to generate code for, and the options of the generator
+#[derive(Default)]
+pub struct Config {
+    inputs: Vec<PathBuf>,
+    out_dir: Option<PathBuf>,
+    ns_prefix: Option<String>,
+    default_namespace: Option<String>,
+    options: Vec<Box<dyn Fn(FileWriter) -> FileWriter>>,
+}
+
+impl Config {
+    pub fn new() -> Self {
+        Config::default()
+    }
+
+    /// Generate the code of a WSDL document, or an XML schema, by its path relative to the root
+    /// of the crate. The documents it imports are read from the same directory.
+    pub fn wsdl<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.inputs.push(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Generate the code of an XML schema, like `wsdl` does for a WSDL document
+    pub fn xsd<P: AsRef<Path>>(self, path: P) -> Self {
+        self.wsdl(path)
+    }
+
+    /// Write the code to this directory, rather than to the `OUT_DIR` of the build
+    pub fn out_dir<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        self.out_dir = Option::Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Use a different prefix than "tns" for the types in the default namespace
+    pub fn ns_prefix(mut self, prefix: &str) -> Self {
+        self.ns_prefix = Option::Some(prefix.to_string());
+        self
+    }
+
+    /// Generate no prefixes for the elements of this namespace
+    pub fn default_namespace(mut self, namespace: &str) -> Self {
+        self.default_namespace = Option::Some(namespace.to_string());
+        self
+    }
+
+    /// Set options of the generator, like `|writer| writer.with_blocking_clients(true)`
+    pub fn configure<F: Fn(FileWriter) -> FileWriter + 'static>(mut self, options: F) -> Self {
+        self.options.push(Box::new(options));
+        self
+    }
+
+    /// Generates the code of every document, and tells Cargo which files to watch. Files are
+    /// watched even when their code can not be generated, so fixing them starts a new build.
+    pub fn generate(&self) -> ZeepResult<()> {
+        let out_dir = match &self.out_dir {
+            Some(dir) => dir.clone(),
+            None => std::env::var_os("OUT_DIR")
+                .map(PathBuf::from)
+                .ok_or_else(|| ZeepError::Io {
+                    path: "OUT_DIR".to_string(),
+                    source: std::io::Error::new(
+                        std::io::ErrorKind::NotFound,
+                        "not set, outside of a build script",
+                    ),
+                })?,
+        };
+
+        for input in &self.inputs {
+            let base_path = match input.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => dir,
+                _ => Path::new("."),
+            };
+            let file_name = input
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            let writer = FileWriter::new(self.ns_prefix.clone(), self.default_namespace.clone())
+                .with_includable_output(true);
+            let mut writer = self
+                .options
+                .iter()
+                .fold(writer, |writer, options| options(writer));
+            let result = writer.generate_to_string(&base_path.to_string_lossy(), &file_name);
+
+            // the first source is the input itself, once it could be read
+            println!("cargo:rerun-if-changed={}", input.display());
+            for source in writer.sources().iter().skip(1) {
+                println!("cargo:rerun-if-changed={}", source);
+            }
+
+            let stem = input
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let path = out_dir.join(format!("{}.rs", stem));
+            std::fs::write(&path, result?).map_err(|source| ZeepError::Output {
+                path: path.display().to_string(),
+                source,
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate() {
+        let dir = std::env::temp_dir().join("zeep_build_test_generate");
+        std::fs::create_dir_all(&dir).expect("can not create directory");
+        Config::new()
+            .wsdl("../resources/fixtures/faults.wsdl")
+            .xsd("../resources/fixtures/attributes.xsd")
+            .configure(|writer| writer.with_builders(true))
+            .out_dir(&dir)
+            .generate()
+            .expect("can not generate");
+
+        let faults = std::fs::read_to_string(dir.join("faults.rs")).expect("can not read code");
+        assert!(faults.starts_with("// THIS IS A GENERATED FILE!\n"));
+        assert!(faults.contains("// #![allow(dead_code)]\n"));
+        assert!(!faults.contains("//!"));
+        assert!(faults.contains("pub trait Account {"));
+        let attributes =
+            std::fs::read_to_string(dir.join("attributes.rs")).expect("can not read code");
+        assert!(attributes.contains("pub fn builder() -> BookBuilder {"));
+        std::fs::remove_dir_all(dir).expect("can not clean up");
+
+        match Config::new()
+            .wsdl("../resources/fixtures/missing.wsdl")
+            .out_dir(std::env::temp_dir())
+            .generate()
+        {
+            Err(ZeepError::Io { path, .. }) => assert!(path.ends_with("missing.wsdl")),
+            other => panic!("expected an io error, got {:?}", other),
+        }
+    }
+}
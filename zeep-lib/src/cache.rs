@@ -0,0 +1,68 @@
+//! # Cache
+//! The documents fetched from URLs, kept in a directory so repeated runs do not fetch them again,
+//! and runs without a network can still use them. A document is stored under the hash of its
+//! content, and found by an entry under the hash of its URL, which names that content. Documents
+//! published at several URLs are stored once.
+//!
+use std::path::{Path, PathBuf};
+
+/// A directory of fetched documents
+#[derive(Clone, Debug)]
+pub struct DocumentCache {
+    dir: PathBuf,
+}
+
+impl DocumentCache {
+    pub fn new<P: AsRef<Path>>(dir: P) -> Self {
+        DocumentCache {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The document fetched from a URL before, if it is in the cache
+    pub fn get(&self, url: &str) -> Option<String> {
+        let entry = std::fs::read_to_string(self.entry_path(url)).ok()?;
+        let (content_hash, entry_url) = entry.split_once('\n')?;
+        // the URL is kept in the entry, so hash collisions do not return another document
+        if entry_url.trim_end() != url {
+            return None;
+        }
+        let content = std::fs::read_to_string(self.content_path(content_hash)).ok()?;
+        if hash(content.as_bytes()) != content_hash {
+            return None;
+        }
+        Some(content)
+    }
+
+    /// Stores the document fetched from a URL, replacing what was stored for it before
+    pub fn put(&self, url: &str, content: &str) -> std::io::Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        let content_hash = hash(content.as_bytes());
+        let content_path = self.content_path(&content_hash);
+        if !content_path.exists() {
+            std::fs::write(content_path, content)?;
+        }
+        std::fs::write(self.entry_path(url), format!("{}\n{}\n", content_hash, url))
+    }
+
+    fn entry_path(&self, url: &str) -> PathBuf {
+        self.dir.join(format!("{}.url", hash(url.as_bytes())))
+    }
+
+    fn content_path(&self, content_hash: &str) -> PathBuf {
+        self.dir.join(format!("{}.xml", content_hash))
+    }
+}
+
+/// The 64 bit FNV-1a hash of the bytes, in hexadecimal. Unlike the hasher of the standard
+/// library, it is the same for every build, so the cache outlives upgrades of the compiler.
+fn hash(bytes: &[u8]) -> String {
+    let hash = bytes.iter().fold(0xcbf29ce484222325_u64, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
+}
@@ -0,0 +1,344 @@
+//! # Config
+//! The options of the generator, read from a `zeep.toml` file. The options of the command line
+//! that shape the generated code have a key of the same name, except for these:
+//!
+//! | option               | key                 |
+//! |----------------------|---------------------|
+//! | `--ns`               | `ns-prefix`         |
+//! | `--dns`              | `default-namespace` |
+//! | `--derive`           | `derives`           |
+//! | `--type-derive`      | `type-derives`      |
+//! | `--namespace-module` | `modules`           |
+//!
+//! The inputs and outputs, `--report`, `--emit-ir`, the logging and the cache of fetched
+//! documents (`--cache-dir`, `--offline`, `--refresh`) are only given on the command line. The
+//! renames, type mappings and module layout, that do not fit on a command line, only have a table
+//! in the file:
+//!
+//! ```toml
+//! ns-prefix = "hello"
+//! builders = true
+//! derives = ["PartialEq"]
+//!
+//! [renames]
+//! sayHello = "Greeting"
+//!
+//! [types]
+//! decimal = "rust_decimal::Decimal"
+//!
+//! [layout.ports]
+//! module = "client"
+//!
+//! [layout.bindings]
+//! module = "client"
+//! visibility = "pub(crate)"
+//! ```
+//!
+use crate::error::{ZeepError, ZeepResult};
+use crate::writer::FileWriter;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// The name of the configuration file, looked for in the directory of the input
+pub const CONFIG_FILE: &str = "zeep.toml";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    /// Prefix for the types in the default namespace
+    pub ns_prefix: Option<String>,
+    /// Namespace whose elements get no prefix
+    pub default_namespace: Option<String>,
+    pub stub_unresolved: bool,
+    pub strict: bool,
+    pub typed_durations: bool,
+    pub chrono: bool,
+    pub regex: bool,
+    pub blocking: bool,
+    pub blocking_feature: bool,
+    pub server: bool,
+    pub mocks: bool,
+    pub ws_security: bool,
+    pub ws_signature: bool,
+    pub ws_addressing: bool,
+    pub mtom: bool,
+    pub format: bool,
+    pub serde: bool,
+    pub builders: bool,
+    pub round_trip_tests: bool,
+    pub extension_files: bool,
+    pub namespace_modules: bool,
+    pub keyword_suffix: Option<String>,
+    /// The operations to generate, all of them when there are none
+    pub operations: Vec<String>,
+    /// Derives added to every generated type
+    pub derives: Vec<String>,
+    /// Derives of a type instead, by the Rust name of the type
+    pub type_derives: BTreeMap<String, Vec<String>>,
+    /// The module names of namespaces, by namespace
+    pub modules: BTreeMap<String, String>,
+    /// Schema locations for imports of a namespace, by namespace
+    pub catalog: BTreeMap<String, String>,
+    /// Rust names of definitions, by their name in the documents or their generated name
+    pub renames: BTreeMap<String, String>,
+    /// Rust types of XSD types, by the local name of the XSD type
+    pub types: BTreeMap<String, String>,
+    /// The names and visibility of the generated modules, by the name they have by default
+    pub layout: BTreeMap<String, ModuleLayout>,
+}
+
+/// Where the code of a generated module goes
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ModuleLayout {
+    /// The name of the module, shared by the modules that are merged
+    pub module: Option<String>,
+    /// The visibility of the module, like `pub(crate)`
+    pub visibility: Option<String>,
+}
+
+impl Config {
+    /// Reads the configuration from a TOML file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> ZeepResult<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|source| ZeepError::Io {
+            path: path.display().to_string(),
+            source,
+        })?;
+        toml::from_str(&content).map_err(|e| ZeepError::Config {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })
+    }
+
+    /// Adds the options of the command line to those of the file: the switches that either one
+    /// turns on, the values it sets instead of those of the file, and the entries of both for the
+    /// lists and tables
+    pub fn merge(&mut self, other: Config) {
+        let Config {
+            ns_prefix,
+            default_namespace,
+            stub_unresolved,
+            strict,
+            typed_durations,
+            chrono,
+            regex,
+            blocking,
+            blocking_feature,
+            server,
+            mocks,
+            ws_security,
+            ws_signature,
+            ws_addressing,
+            mtom,
+            format,
+            serde,
+            builders,
+            round_trip_tests,
+            extension_files,
+            namespace_modules,
+            keyword_suffix,
+            operations,
+            derives,
+            type_derives,
+            modules,
+            catalog,
+            renames,
+            types,
+            layout,
+        } = other;
+
+        self.ns_prefix = ns_prefix.or_else(|| self.ns_prefix.take());
+        self.default_namespace = default_namespace.or_else(|| self.default_namespace.take());
+        self.keyword_suffix = keyword_suffix.or_else(|| self.keyword_suffix.take());
+
+        self.stub_unresolved |= stub_unresolved;
+        self.strict |= strict;
+        self.typed_durations |= typed_durations;
+        self.chrono |= chrono;
+        self.regex |= regex;
+        self.blocking |= blocking;
+        self.blocking_feature |= blocking_feature;
+        self.server |= server;
+        self.mocks |= mocks;
+        self.ws_security |= ws_security;
+        self.ws_signature |= ws_signature;
+        self.ws_addressing |= ws_addressing;
+        self.mtom |= mtom;
+        self.format |= format;
+        self.serde |= serde;
+        self.builders |= builders;
+        self.round_trip_tests |= round_trip_tests;
+        self.extension_files |= extension_files;
+        self.namespace_modules |= namespace_modules;
+
+        add_new(&mut self.operations, operations);
+        add_new(&mut self.derives, derives);
+        self.type_derives.extend(type_derives);
+        self.modules.extend(modules);
+        self.catalog.extend(catalog);
+        self.renames.extend(renames);
+        self.types.extend(types);
+        self.layout.extend(layout);
+    }
+
+    /// Sets the options of a writer. The namespace prefix and the default namespace are
+    /// arguments of the constructors of the writer instead.
+    pub fn apply(&self, writer: FileWriter) -> FileWriter {
+        let mut writer = writer
+            .with_unresolved_type_stubs(self.stub_unresolved)
+            .with_strict(self.strict)
+            .with_typed_durations(self.typed_durations)
+            .with_chrono_dates(self.chrono)
+            .with_pattern_validation(self.regex)
+            .with_blocking_clients(self.blocking)
+            .with_blocking_feature(self.blocking_feature)
+            .with_server_dispatchers(self.server)
+            .with_mocks(self.mocks)
+            .with_ws_security(self.ws_security)
+            .with_ws_signature(self.ws_signature)
+            .with_ws_addressing(self.ws_addressing)
+            .with_mtom(self.mtom)
+            .with_formatted_output(self.format)
+            .with_serde_backend(self.serde)
+            .with_builders(self.builders)
+            .with_round_trip_tests(self.round_trip_tests)
+            .with_extension_files(self.extension_files)
+            .with_namespace_modules(self.namespace_modules);
+        if let Some(suffix) = &self.keyword_suffix {
+            writer = writer.with_keyword_suffix(suffix);
+        }
+        if !self.operations.is_empty() {
+            writer = writer.with_operation_filter(&as_strs(&self.operations));
+        }
+        if !self.derives.is_empty() {
+            writer = writer.with_derives(&as_strs(&self.derives));
+        }
+        for (type_name, derives) in &self.type_derives {
+            writer = writer.with_type_derives(type_name, &as_strs(derives));
+        }
+        for (namespace, module) in &self.modules {
+            writer = writer.with_namespace_module(namespace, module);
+        }
+        for (namespace, location) in &self.catalog {
+            writer = writer.with_catalog_entry(namespace, location);
+        }
+        for (name, rust_name) in &self.renames {
+            writer = writer.with_type_rename(name, rust_name);
+        }
+        for (xsd_type, rust_type) in &self.types {
+            writer = writer.with_type_mapping(xsd_type, rust_type);
+        }
+        for (module, layout) in &self.layout {
+            if let Some(name) = &layout.module {
+                writer = writer.with_module_name(module, name);
+            }
+            if let Some(visibility) = &layout.visibility {
+                writer = writer.with_module_visibility(module, visibility);
+            }
+        }
+        writer
+    }
+}
+
+/// Adds the values that are not in the list yet
+fn add_new(values: &mut Vec<String>, other: Vec<String>) {
+    for value in other {
+        if !values.contains(&value) {
+            values.push(value);
+        }
+    }
+}
+
+fn as_strs(values: &[String]) -> Vec<&str> {
+    values.iter().map(|v| v.as_str()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESOURCES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../resources/fixtures");
+
+    #[test]
+    fn test_config() {
+        let config: Config = toml::from_str(
+            r#"
+            ns-prefix = "lib"
+            builders = true
+            derives = ["PartialEq"]
+
+            [type-derives]
+            Book = ["PartialEq", "PartialOrd"]
+
+            [renames]
+            item = "Article"
+
+            [types]
+            int = "u16"
+            "#,
+        )
+        .expect("can not parse");
+        assert_eq!(config.ns_prefix.as_deref(), Some("lib"));
+        assert!(config.builders && !config.strict);
+
+        let writer = FileWriter::new(config.ns_prefix.clone(), None);
+        let result = config
+            .apply(writer)
+            .generate_to_string(RESOURCES, "attributes.xsd")
+            .expect("can not generate");
+        assert!(result.contains("pub struct Article {"));
+        assert!(result.contains("pub fn builder() -> ArticleBuilder {"));
+        assert!(result.contains("pub id: u16,"));
+        assert!(result.contains(
+            "#[derive(Debug, Default, YaSerialize, YaDeserialize, Clone, PartialEq, PartialOrd)]\n#[yaserde(\n\trename = \"book\""
+        ));
+
+        match toml::from_str::<Config>("bilders = true") {
+            Err(e) => assert!(e.to_string().starts_with("unknown field `bilders`")),
+            Ok(_) => panic!("expected an unknown field"),
+        }
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut config: Config = toml::from_str(
+            r#"
+            ns-prefix = "lib"
+            builders = true
+            derives = ["PartialEq"]
+            operations = ["Withdraw"]
+
+            [type-derives]
+            Book = ["Eq"]
+            "#,
+        )
+        .expect("can not parse");
+
+        // the options of the command line
+        let mut cli = Config {
+            ns_prefix: Some("cli".to_string()),
+            chrono: true,
+            derives: vec!["PartialEq".to_string(), "Hash".to_string()],
+            operations: vec!["Deposit".to_string()],
+            ..Default::default()
+        };
+        cli.type_derives
+            .insert("Item".to_string(), vec!["Eq".to_string()]);
+        config.merge(cli);
+
+        assert_eq!(config.ns_prefix.as_deref(), Some("cli"));
+        assert!(config.builders && config.chrono && !config.strict);
+        assert_eq!(config.derives, vec!["PartialEq", "Hash"]);
+        assert_eq!(config.operations, vec!["Withdraw", "Deposit"]);
+        assert_eq!(
+            config.type_derives.keys().collect::<Vec<_>>(),
+            vec!["Book", "Item"]
+        );
+
+        // a value the command line leaves out is that of the file
+        config.merge(Config::default());
+        assert_eq!(config.ns_prefix.as_deref(), Some("cli"));
+    }
+}
@@ -0,0 +1,494 @@
+//! # Coverage
+//! The constructs of WSDL documents and XML schemas that code is generated for. Anything else in
+//! a document is skipped by the generator. The skipped constructs are listed, along with those
+//! that the generated code only approximates, for strict mode and the generation report. So are
+//! the problems of the documents that code is generated in spite of, like prefixes that are not
+//! declared.
+//!
+use crate::model::Position;
+use roxmltree::{Document, Node};
+use std::fmt;
+
+const XSD_NS: &str = "http://www.w3.org/2001/XMLSchema";
+const WSDL_NS: &str = "http://schemas.xmlsoap.org/wsdl/";
+const WSDL2_NS: &str = "http://www.w3.org/ns/wsdl";
+const WSDL2_SOAP_NS: &str = "http://www.w3.org/ns/wsdl/soap";
+
+/// The facets of a simple type restriction. Those that are not validated still restrict a type
+/// that is generated.
+const FACETS: &[&str] = &[
+    "enumeration",
+    "pattern",
+    "minInclusive",
+    "maxInclusive",
+    "minExclusive",
+    "maxExclusive",
+    "length",
+    "minLength",
+    "maxLength",
+    "totalDigits",
+    "fractionDigits",
+    "whiteSpace",
+];
+
+/// The attributes of WSDL and XML schema elements whose values are qualified names, or lists of
+/// them
+const QNAME_ATTRIBUTES: &[&str] = &[
+    "type",
+    "base",
+    "ref",
+    "itemType",
+    "memberTypes",
+    "substitutionGroup",
+    "message",
+    "binding",
+    "element",
+    "interface",
+    "extends",
+];
+
+/// The content of the XML schema elements that code is generated for, by element
+const XSD_CONTENT: &[(&str, &[&str])] = &[
+    (
+        "schema",
+        &[
+            "import",
+            "redefine",
+            "element",
+            "complexType",
+            "simpleType",
+            "group",
+            "attributeGroup",
+        ],
+    ),
+    (
+        "redefine",
+        &["simpleType", "complexType", "group", "attributeGroup"],
+    ),
+    ("element", &["complexType", "simpleType"]),
+    (
+        "complexType",
+        &[
+            "sequence",
+            "choice",
+            "all",
+            "group",
+            "attribute",
+            "attributeGroup",
+            "complexContent",
+            "simpleContent",
+        ],
+    ),
+    ("complexContent", &["extension", "restriction"]),
+    ("simpleContent", &["extension", "restriction"]),
+    (
+        "extension",
+        &[
+            "sequence",
+            "choice",
+            "all",
+            "group",
+            "attribute",
+            "attributeGroup",
+        ],
+    ),
+    (
+        "sequence",
+        &["element", "sequence", "choice", "group", "any"],
+    ),
+    ("choice", &["element", "sequence", "choice", "group", "any"]),
+    ("all", &["element"]),
+    ("group", &["sequence", "choice", "all"]),
+    ("attributeGroup", &["attribute", "attributeGroup"]),
+    ("attribute", &["simpleType"]),
+    ("simpleType", &["restriction", "list", "union"]),
+    ("list", &["simpleType"]),
+    ("union", &["simpleType"]),
+];
+
+/// The content of a restriction, of complex content as well as of a simple type, besides its
+/// facets
+const RESTRICTION_CONTENT: &[&str] = &[
+    "sequence",
+    "choice",
+    "all",
+    "group",
+    "attribute",
+    "attributeGroup",
+];
+
+/// The content of the WSDL 1.1 and 2.0 elements that code is generated for, by element. The
+/// extension elements of bindings, ports and endpoints are up to the binding.
+const WSDL_CONTENT: &[(&str, &[&str])] = &[
+    (
+        "definitions",
+        &[
+            "import", "types", "message", "portType", "binding", "service",
+        ],
+    ),
+    (
+        "description",
+        &[
+            "import",
+            "include",
+            "types",
+            "interface",
+            "binding",
+            "service",
+        ],
+    ),
+    ("types", &[]),
+    ("message", &["part"]),
+    ("portType", &["operation"]),
+    ("interface", &["operation", "fault"]),
+    (
+        "operation",
+        &["input", "output", "fault", "infault", "outfault"],
+    ),
+    ("binding", &["operation", "fault"]),
+    ("service", &["port", "endpoint"]),
+];
+
+/// How much of a construct the generated code covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Coverage {
+    /// No code is generated for the construct
+    Skipped,
+    /// Code is generated for the construct, but it does not express all of it
+    Approximated,
+}
+
+/// A construct of a document that the generated code does not fully cover
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Construct {
+    pub coverage: Coverage,
+    /// The qualified name of the element, as written in the document
+    pub tag: String,
+    pub file: String,
+    pub line: u32,
+    /// Why the construct is not fully covered
+    pub reason: String,
+}
+
+impl Construct {
+    pub(crate) fn new(coverage: Coverage, node: &Node, file: &str, reason: &str) -> Self {
+        Construct {
+            coverage,
+            tag: tag(node),
+            file: file.to_string(),
+            line: node.document().text_pos_at(node.range().start).row,
+            reason: reason.to_string(),
+        }
+    }
+
+    /// A construct at the position of a definition of the model
+    pub(crate) fn at(coverage: Coverage, position: &Position, reason: &str) -> Self {
+        Construct {
+            coverage,
+            tag: position.tag.clone(),
+            file: position.file.clone(),
+            line: position.line,
+            reason: reason.to_string(),
+        }
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "tag": self.tag,
+            "file": self.file,
+            "line": self.line,
+            "reason": self.reason,
+        })
+    }
+}
+
+impl fmt::Display for Construct {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}, {}",
+            self.file, self.line, self.tag, self.reason
+        )
+    }
+}
+
+/// A problem of the documents that code is generated in spite of
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    /// The file and line of the problem
+    pub position: String,
+    pub message: String,
+}
+
+impl Problem {
+    pub(crate) fn new(position: &str, message: &str) -> Self {
+        Problem {
+            position: position.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    fn at(node: &Node, file: &str, message: &str) -> Self {
+        let line = node.document().text_pos_at(node.range().start).row;
+        Problem::new(&format!("{}:{}", file, line), message)
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "position": self.position,
+            "message": self.message,
+        })
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.position, self.message)
+    }
+}
+
+/// The constructs of the documents of a run of the generator that the generated code does not
+/// fully cover, in the order they are come across, and the problems of the documents
+#[derive(Debug, Clone, Default)]
+pub struct GenerationReport {
+    pub constructs: Vec<Construct>,
+    /// References to types that none of the documents defines
+    pub unresolved: Vec<Problem>,
+    /// Prefixes that are not declared, and imports of a namespace that the imported document
+    /// does not define
+    pub namespaces: Vec<Problem>,
+}
+
+impl GenerationReport {
+    /// The constructs that no code is generated for
+    pub fn skipped(&self) -> impl Iterator<Item = &Construct> {
+        self.of(Coverage::Skipped)
+    }
+
+    /// The constructs that the generated code covers to some extent
+    pub fn approximated(&self) -> impl Iterator<Item = &Construct> {
+        self.of(Coverage::Approximated)
+    }
+
+    /// Whether no code is generated for some constructs, or the documents have problems.
+    /// Approximations are no problem.
+    pub fn has_problems(&self) -> bool {
+        self.skipped().next().is_some()
+            || !self.unresolved.is_empty()
+            || !self.namespaces.is_empty()
+    }
+
+    fn of(&self, coverage: Coverage) -> impl Iterator<Item = &Construct> {
+        self.constructs
+            .iter()
+            .filter(move |construct| construct.coverage == coverage)
+    }
+
+    /// The report as a JSON document, with the skipped and the approximated constructs apart
+    pub fn to_json(&self) -> String {
+        let report = serde_json::json!({
+            "skipped": self.skipped().map(Construct::to_json).collect::<Vec<_>>(),
+            "approximated": self.approximated().map(Construct::to_json).collect::<Vec<_>>(),
+            "unresolved": self.unresolved.iter().map(Problem::to_json).collect::<Vec<_>>(),
+            "namespaces": self.namespaces.iter().map(Problem::to_json).collect::<Vec<_>>(),
+        });
+        serde_json::to_string_pretty(&report).unwrap_or_default()
+    }
+}
+
+/// Lists the elements of a WSDL document or XML schema that the generator skips, and the facets
+/// that it does not validate, in document order. The content of a skipped element is not looked
+/// into.
+pub(crate) fn uncovered_constructs(
+    document: &Document,
+    file: &str,
+    pattern_validation: bool,
+) -> Vec<Construct> {
+    let mut constructs = vec![];
+    let root = document.root_element();
+    if is_generated(&root)
+        && ["definitions", "description", "schema"].contains(&root.tag_name().name())
+    {
+        collect(&root, file, pattern_validation, &mut constructs);
+    } else {
+        constructs.push(Construct::new(
+            Coverage::Skipped,
+            &root,
+            file,
+            "no WSDL document or XML schema",
+        ));
+    }
+    constructs
+}
+
+/// Lists the prefixes of qualified names in the attributes of a WSDL document or XML schema that
+/// are not declared where they are used
+pub(crate) fn undeclared_prefixes(document: &Document, file: &str) -> Vec<Problem> {
+    let mut problems = vec![];
+    for node in document
+        .descendants()
+        .filter(|n| n.is_element() && is_generated(n))
+    {
+        for attribute in node.attributes() {
+            if attribute.namespace().is_some() || !is_qname_attribute(&node, attribute.name()) {
+                continue;
+            }
+            for value in attribute.value().split_whitespace() {
+                if let Some((prefix, _)) = value.split_once(':') {
+                    if node.lookup_namespace_uri(Some(prefix)).is_none() {
+                        let message = format!(
+                            "undeclared prefix {} in {}=\"{}\"",
+                            prefix,
+                            attribute.name(),
+                            value
+                        );
+                        problems.push(Problem::at(&node, file, &message));
+                    }
+                }
+            }
+        }
+    }
+    problems
+}
+
+/// Whether the value of an attribute is a qualified name. The type of a WSDL 2.0 binding is a URI.
+fn is_qname_attribute(node: &Node, name: &str) -> bool {
+    QNAME_ATTRIBUTES.contains(&name)
+        && !(name == "type"
+            && node.tag_name().namespace() == Some(WSDL2_NS)
+            && node.tag_name().name() == "binding")
+}
+
+/// The problem of an import whose namespace is not the target namespace of the imported document
+pub(crate) fn foreign_namespace(
+    document: &Document,
+    file: &str,
+    namespace: &str,
+) -> Option<Problem> {
+    let root = document.root_element();
+    let target_namespace = root.attribute("targetNamespace").unwrap_or_default();
+    if target_namespace == namespace {
+        return None;
+    }
+    let message = format!(
+        "imported as namespace {}, but the target namespace is {}",
+        namespace,
+        if target_namespace.is_empty() {
+            "absent"
+        } else {
+            target_namespace
+        }
+    );
+    Some(Problem::at(&root, file, &message))
+}
+
+fn collect(node: &Node, file: &str, pattern_validation: bool, constructs: &mut Vec<Construct>) {
+    for child in node.children().filter(|c| c.is_element()) {
+        if is_documentation(&child) || !is_generated(&child) {
+            continue;
+        }
+
+        if !is_content(node, &child) {
+            let reason = format!("not supported in {}", tag(node));
+            constructs.push(Construct::new(Coverage::Skipped, &child, file, &reason));
+        } else if let Some(reason) = unsupported(&child) {
+            constructs.push(Construct::new(Coverage::Skipped, &child, file, reason));
+        } else if let Some(reason) = approximation(&child, pattern_validation) {
+            constructs.push(Construct::new(Coverage::Approximated, &child, file, reason));
+            collect(&child, file, pattern_validation, constructs);
+        } else {
+            collect(&child, file, pattern_validation, constructs);
+        }
+    }
+}
+
+/// Whether a node is in one of the namespaces that code is generated from
+fn is_generated(node: &Node) -> bool {
+    matches!(
+        node.tag_name().namespace(),
+        Some(XSD_NS) | Some(WSDL_NS) | Some(WSDL2_NS)
+    )
+}
+
+fn is_documentation(node: &Node) -> bool {
+    match node.tag_name().namespace() {
+        Some(XSD_NS) => node.has_tag_name("annotation"),
+        _ => node.has_tag_name("documentation"),
+    }
+}
+
+/// Whether a node can be part of the content of its parent. The schemas of a WSDL document are
+/// the only XML schema elements that can be part of WSDL elements.
+fn is_content(parent: &Node, node: &Node) -> bool {
+    let name = node.tag_name().name();
+    let parent_name = parent.tag_name().name();
+    match (parent.tag_name().namespace(), node.tag_name().namespace()) {
+        (Some(XSD_NS), Some(XSD_NS)) if parent_name == "restriction" => {
+            RESTRICTION_CONTENT.contains(&name) || FACETS.contains(&name)
+        }
+        (Some(XSD_NS), Some(XSD_NS)) => content_of(XSD_CONTENT, parent_name).contains(&name),
+        (Some(_), Some(XSD_NS)) => parent_name == "types" && name == "schema",
+        (Some(XSD_NS), Some(_)) => false,
+        _ => content_of(WSDL_CONTENT, parent_name).contains(&name),
+    }
+}
+
+fn content_of(table: &[(&str, &'static [&'static str])], name: &str) -> &'static [&'static str] {
+    table
+        .iter()
+        .find(|(element, _)| *element == name)
+        .map(|(_, content)| *content)
+        .unwrap_or_default()
+}
+
+/// Why no code is generated for a node that can be where it is, if so. Attributes are only
+/// generated where they are declared, not where they are referenced.
+fn unsupported(node: &Node) -> Option<&'static str> {
+    match node.tag_name().name() {
+        "attribute" if !node.has_attribute("name") => {
+            Some("attribute references are not supported")
+        }
+        "binding" if node.tag_name().namespace() == Some(WSDL2_NS) => {
+            match node.attribute("type") {
+                Some(WSDL2_SOAP_NS) => None,
+                _ => Some("WSDL 2.0 bindings other than SOAP are not supported"),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Why the generated code only approximates a node, if so: facets that it does not validate, and
+/// occurrences it can not read. Enumerations are types of their own, and numeric types validate
+/// their ranges.
+fn approximation(node: &Node, pattern_validation: bool) -> Option<&'static str> {
+    if node.tag_name().namespace() != Some(XSD_NS) {
+        return None;
+    }
+    let max_occurs = node.attribute("maxOccurs").unwrap_or("1");
+    if max_occurs != "unbounded" && max_occurs.trim().parse::<u32>().is_err() {
+        return Some("invalid maxOccurs, taken as 1");
+    }
+    match node.tag_name().name() {
+        "pattern" if !pattern_validation => {
+            Some("patterns are only validated with the regex crate")
+        }
+        "length" | "minLength" | "maxLength" | "totalDigits" | "fractionDigits" | "whiteSpace" => {
+            Some("the facet is not validated")
+        }
+        _ => None,
+    }
+}
+
+/// The name of an element with the prefix it is written with
+pub(crate) fn tag(node: &Node) -> String {
+    let name = node.tag_name().name();
+    match node
+        .tag_name()
+        .namespace()
+        .and_then(|ns| node.lookup_prefix(ns))
+    {
+        Some(prefix) if !prefix.is_empty() => format!("{}:{}", prefix, name),
+        _ => name.to_string(),
+    }
+}
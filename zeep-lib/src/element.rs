@@ -1,10 +1,12 @@
 //! # Element
 //! Responsible for rendering elements to Rust code
 //!
+use crate::emitter::{Emitter, YaserdeEmitter};
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
 
+#[derive(Clone)]
 pub enum ElementType {
     Root,
     Struct,
@@ -16,8 +18,15 @@ pub enum ElementType {
     Trait,
     TraitImpl,
     Function,
+    Enum,
+    Variant,
+    Union,
+    List,
+    Restriction,
+    Polymorphic,
 }
 
+#[derive(Clone)]
 pub struct Element {
     pub element_type: ElementType,
     pub prefix: Option<String>,
@@ -27,6 +36,9 @@ pub struct Element {
     pub children: Vec<Rc<RefCell<Element>>>,
     pub children_idx: HashMap<String, usize>,
     static_content: Option<String>,
+    /// The code of a static element for blocking clients, when it is not the same code without
+    /// async
+    pub blocking_content: Option<String>,
     pub optional: bool,
     pub field_type: Option<String>,
     pub vector: bool,
@@ -34,14 +46,69 @@ pub struct Element {
     pub comment: Option<String>,
     pub function_args: Option<FunctionArgs>,
     pub text_field: bool,
+    /// The default value of a field, as a Rust expression
+    pub default_value: Option<String>,
+    /// The documentation of the schema or service, rendered as doc comments
+    pub documentation: Option<String>,
+    /// The constraints on the value of a restricted type
+    pub facets: Vec<Facet>,
+    /// The elements of a repeating group, when the type holds one occurrence of the group
+    pub group_members: Vec<GroupMember>,
+    /// The value of an attribute that can not change, as written in the document
+    pub fixed_value: Option<String>,
+    /// Whether a trait, trait implementation, function or module is rendered for blocking
+    /// clients, without async
+    pub blocking: bool,
+    /// The derives of a type on top of the ones it needs
+    pub derives: Vec<String>,
+    /// Whether a struct comes with a builder
+    pub builder: bool,
+    /// The visibility of a module, `pub` when there is none
+    pub visibility: Option<String>,
+    /// The cargo feature a module is compiled with, if it is not always compiled
+    pub feature: Option<String>,
+    /// The file and line of the definition the element is generated from, for the warnings of
+    /// the passes over the generated elements
+    pub position: Option<String>,
+    /// Whether the deserialization of a struct is written by hand, so only its serialization is
+    /// derived
+    pub custom_deserialize: bool,
+    /// The other names a field is read by, on top of its XML name
+    pub aliases: Vec<String>,
 }
 
+/// A constraint on the value of a restricted simple type, with the bound as a Rust literal
+#[derive(Clone)]
+pub enum Facet {
+    MinInclusive(String),
+    MaxInclusive(String),
+    MinExclusive(String),
+    MaxExclusive(String),
+    /// A regular expression the entire value has to match
+    Pattern(String),
+}
+
+/// An element of a repeating group, used to find where an occurrence of the group starts
+#[derive(Clone)]
+pub struct GroupMember {
+    pub xml_name: String,
+    /// The position of the element in the group, shared by the branches of a choice
+    pub position: usize,
+    /// Whether the element repeats within an occurrence of the group
+    pub repeats: bool,
+}
+
+#[derive(Clone)]
 pub struct FunctionArgs {
     pub input_type: String,
     pub input_name: String,
     pub output_type: Option<String>,
     pub fault_type: Option<String>,
     pub comment: Option<String>,
+    /// The names of the parameters sent as MIME attachments
+    pub attachments: Vec<String>,
+    /// Whether the output comes with the MIME attachments of the response
+    pub output_attachments: bool,
 }
 
 pub fn root() -> Element {
@@ -53,6 +120,7 @@ pub fn root() -> Element {
         children: vec![],
         children_idx: HashMap::new(),
         static_content: None,
+        blocking_content: None,
         namespaces: vec![],
         optional: false,
         field_type: None,
@@ -61,16 +129,35 @@ pub fn root() -> Element {
         comment: None,
         function_args: None,
         text_field: false,
+        default_value: None,
+        documentation: None,
+        facets: vec![],
+        group_members: vec![],
+        fixed_value: None,
+        blocking: false,
+        derives: vec![],
+        builder: false,
+        visibility: None,
+        feature: None,
+        position: None,
+        custom_deserialize: false,
+        aliases: vec![],
     }
 }
 
 /// This trait renders the element to Rust code
 pub trait WritableElement {
-    fn render(&self) -> String;
+    /// Renders the element for yaserde
+    fn render(&self) -> String {
+        self.render_with(&YaserdeEmitter)
+    }
+
+    fn render_with(&self, emitter: &dyn Emitter) -> String;
 }
 
 /// An element that has a statically defined Rust code. There is no interpretation during rendering.
 pub trait StaticElement {
+    fn content(&self) -> Option<&str>;
     fn set_content(&mut self, content: &str);
     fn append_content(&mut self, content: &str);
 }
@@ -80,6 +167,10 @@ pub trait ParentElement {
     fn child(&self, name: &str) -> Option<Rc<RefCell<Element>>>;
     fn has_children(&self) -> bool;
     fn has_child(&self, name: &str) -> bool;
+    fn retain<F: FnMut(&Element) -> bool>(&mut self, keep: F);
+    /// Rebuilds the index of the children by their names, after they are inserted, renamed or
+    /// reordered in place
+    fn reindex(&mut self);
 }
 
 pub trait NamespacedElement {
@@ -88,18 +179,24 @@ pub trait NamespacedElement {
 
 /// Various render functions for the different Element types.
 impl WritableElement for Element {
-    fn render(&self) -> String {
+    fn render_with(&self, emitter: &dyn Emitter) -> String {
         match self.element_type {
-            ElementType::Root => self.render_root(),
-            ElementType::Struct => self.render_struct(),
-            ElementType::Field => self.render_field(),
+            ElementType::Root => self.render_root(emitter),
+            ElementType::Struct => self.render_struct(emitter),
+            ElementType::Field => emitter.field(self),
             ElementType::Static => self.render_static(),
-            ElementType::Alias => self.render_alias(),
-            ElementType::Module => self.render_module(),
-            ElementType::Attribute => self.render_atribute(),
-            ElementType::Trait => self.render_trait(),
-            ElementType::TraitImpl => self.render_trait_impl(),
+            ElementType::Alias => emitter.alias(self),
+            ElementType::Module => self.render_module(emitter),
+            ElementType::Attribute => emitter.attribute(self),
+            ElementType::Trait => self.render_trait(emitter),
+            ElementType::TraitImpl => self.render_trait_impl(emitter),
             ElementType::Function => self.render_function(),
+            ElementType::Enum => self.render_enum(emitter),
+            ElementType::Variant => emitter.variant(self),
+            ElementType::Union => self.render_union(emitter),
+            ElementType::List => self.render_list(emitter),
+            ElementType::Restriction => self.render_restriction(emitter),
+            ElementType::Polymorphic => self.render_polymorphic(emitter),
         }
     }
 }
@@ -111,6 +208,10 @@ impl NamespacedElement for Element {
 }
 
 impl StaticElement for Element {
+    fn content(&self) -> Option<&str> {
+        self.static_content.as_deref()
+    }
+
     fn set_content(&mut self, content: &str) {
         self.static_content = Option::Some(content.to_string());
     }
@@ -146,6 +247,20 @@ impl ParentElement for Element {
     fn has_child(&self, name: &str) -> bool {
         matches!(self.children_idx.get(name), Some(_pos))
     }
+
+    fn retain<F: FnMut(&Element) -> bool>(&mut self, mut keep: F) {
+        self.children.retain(|c| keep(&c.borrow()));
+        self.reindex();
+    }
+
+    fn reindex(&mut self) {
+        self.children_idx = self
+            .children
+            .iter()
+            .enumerate()
+            .map(|(pos, c)| (c.borrow().name.clone(), pos))
+            .collect();
+    }
 }
 
 impl Element {
@@ -156,6 +271,7 @@ impl Element {
             xml_name: None,
             name: name.to_string(),
             static_content: None,
+        blocking_content: None,
             children: vec![],
             children_idx: HashMap::new(),
             namespaces: vec![],
@@ -166,6 +282,19 @@ impl Element {
             comment: None,
             function_args: None,
             text_field: false,
+            default_value: None,
+            documentation: None,
+            facets: vec![],
+            group_members: vec![],
+            fixed_value: None,
+            blocking: false,
+            derives: vec![],
+            builder: false,
+            visibility: None,
+            feature: None,
+            position: None,
+            custom_deserialize: false,
+            aliases: vec![],
         }
     }
 
@@ -179,6 +308,7 @@ impl Element {
             children: vec![],
             children_idx: HashMap::new(),
             static_content: None,
+        blocking_content: None,
             optional,
             field_type: Option::Some(field_type.to_string()),
             vector: false,
@@ -186,6 +316,19 @@ impl Element {
             comment: None,
             function_args: None,
             text_field: false,
+            default_value: None,
+            documentation: None,
+            facets: vec![],
+            group_members: vec![],
+            fixed_value: None,
+            blocking: false,
+            derives: vec![],
+            builder: false,
+            visibility: None,
+            feature: None,
+            position: None,
+            custom_deserialize: false,
+            aliases: vec![],
         }
     }
 
@@ -201,162 +344,734 @@ impl Element {
             output_type: None,
             fault_type: None,
             comment: None,
+            attachments: Vec::new(),
+            output_attachments: false,
         });
         e
     }
 
-    fn render_root(&self) -> String {
-        self.children.iter().map(|c| c.borrow().render()).collect()
+    fn render_root(&self, emitter: &dyn Emitter) -> String {
+        let code: String = self
+            .children
+            .iter()
+            .map(|c| c.borrow().render_with(emitter))
+            .collect();
+        with_header(&code, &code, emitter)
+    }
+
+    /// A copy of the element, and of its children, for blocking clients: the same code without
+    /// async and without awaiting the calls, on the blocking client of reqwest
+    pub fn to_blocking(&self) -> Element {
+        let mut element = self.clone();
+        element.blocking = true;
+        element.static_content = match &self.blocking_content {
+            Some(content) => Some(content.clone()),
+            None => self.static_content.as_deref().map(blocking_code),
+        };
+        element.children = self
+            .children
+            .iter()
+            .map(|c| Rc::new(RefCell::new(c.borrow().to_blocking())))
+            .collect();
+        element
+    }
+
+    /// Describes a generated type as JSON: its kind, and its fields or variants. Elements that
+    /// are no types are not described.
+    pub fn to_json(&self) -> Option<serde_json::Value> {
+        let kind = match self.element_type {
+            ElementType::Struct => "struct",
+            ElementType::Enum => "enum",
+            ElementType::Alias => "alias",
+            ElementType::Union => "union",
+            ElementType::List => "list",
+            ElementType::Restriction => "restriction",
+            ElementType::Polymorphic => "polymorphic",
+            _ => return None,
+        };
+
+        let members: Vec<serde_json::Value> = self
+            .children
+            .iter()
+            .map(|c| c.borrow())
+            .filter(|c| {
+                matches!(
+                    c.element_type,
+                    ElementType::Field | ElementType::Attribute | ElementType::Variant
+                )
+            })
+            .map(|c| {
+                serde_json::json!({
+                    "name": c.name,
+                    "xml_name": c.xml_name,
+                    "type": c.field_type,
+                    "attribute": matches!(c.element_type, ElementType::Attribute),
+                    "optional": c.optional,
+                    "vector": c.vector,
+                })
+            })
+            .collect();
+
+        let mut description = serde_json::json!({
+            "name": self.name,
+            "kind": kind,
+            "xml_name": self.xml_name,
+            "type": self.field_type,
+            "documentation": self.documentation,
+        });
+        let key = match self.element_type {
+            ElementType::Struct => "fields",
+            _ => "variants",
+        };
+        description[key] = serde_json::Value::from(members);
+        Some(description)
+    }
+
+    /// Renders the root as a `mod.rs`, which declares the modules and holds everything they share,
+    /// along with a file for the content of every module.
+    pub fn render_files(&self, emitter: &dyn Emitter) -> Vec<(String, String)> {
+        let mut mod_file = String::new();
+        let mut files = Vec::new();
+        for child in &self.children {
+            let child = child.borrow();
+            match child.element_type {
+                ElementType::Module => {
+                    mod_file.push_str(&format!(
+                        "{}{} mod {};\n",
+                        child.module_cfg(),
+                        child.module_visibility(),
+                        child.name
+                    ));
+                    files.push((
+                        format!("{}.rs", child.name),
+                        child.render_module_content(emitter),
+                    ));
+                }
+                _ => mod_file.push_str(&child.render_with(emitter)),
+            }
+        }
+        // the modules take the imports of mod.rs
+        let code: String = files
+            .iter()
+            .map(|(_, content)| content.as_str())
+            .chain(std::iter::once(mod_file.as_str()))
+            .collect();
+        files.insert(
+            0,
+            ("mod.rs".to_string(), with_header(&mod_file, &code, emitter)),
+        );
+        files
     }
 
-    fn render_struct(&self) -> String {
+    fn render_struct(&self, emitter: &dyn Emitter) -> String {
         let mut result = if let Some(comment) = &self.comment {
             format!("//* {}\n */", comment)
         } else {
             "".to_string()
         };
+        result.push_str(&self.render_documentation(""));
 
-        result.push_str("#[derive(Debug, Default, YaSerialize, YaDeserialize, Clone)]\n");
-
-        let mut has_options = false;
+        let has_defaults = self
+            .children
+            .iter()
+            .any(|c| c.borrow().default_value.is_some());
 
-        let mut options = "#[yaserde(\n".to_string();
+        result.push_str(&emitter.struct_begin(self, !has_defaults));
 
-        if let Some(xml_name) = &self.xml_name {
-            options.push_str(&format!("\trename = \"{}\",\n", xml_name));
-            has_options = true;
+        let namespace_fields = emitter.namespace_fields(self);
+        for (_, code) in &namespace_fields {
+            result.push_str(code);
         }
 
-        for namespace in &self.namespaces {
-            options.push_str(&format!("\tnamespace = \"{}\",\n", namespace));
-            has_options = true;
+        if self.has_children() {
+            let r: String = self
+                .children
+                .iter()
+                .map(|c| c.borrow().render_with(emitter))
+                .collect();
+            result.push_str(&r);
         }
 
-        if let Some(prefix) = &self.prefix {
-            options.push_str(&format!("\tprefix = \"{}\",\n", prefix));
-            has_options = true;
+        let has_fixed = self.fixed_attributes().next().is_some();
+        if has_fixed {
+            result.push_str(&emitter.fixed_attributes_field(self));
         }
 
-        if self.flatten {
-            options.push_str("\tflatten, \n");
-            has_options = true;
+        result.push_str("}\n");
+
+        if has_defaults {
+            let names: Vec<&str> = namespace_fields.iter().map(|(n, _)| n.as_str()).collect();
+            result.push_str(&self.render_default(&names));
         }
 
-        options.push_str(")]\n");
+        result.push_str(&emitter.namespace_impls(self));
 
-        if has_options {
-            result.push_str(&options);
+        if has_fixed {
+            result.push_str(&self.render_fixed_attributes());
         }
 
-        result.push_str(&format!("pub struct {} {{\n", self.name));
+        if !self.group_members.is_empty() {
+            result.push('\n');
+            result.push_str(&self.render_group_members());
+        }
 
-        if self.has_children() {
-            let r: String = self.children.iter().map(|c| c.borrow().render()).collect();
-            result.push_str(&r);
+        if self.builder {
+            result.push_str(&self.render_builder());
         }
 
-        result.push_str("}\n");
         result
     }
 
-    fn render_trait(&self) -> String {
-        let mut result = format!("#[async_trait]\npub trait {0} {{\n", self.name);
-        let r: String = self.children.iter().map(|c| c.borrow().render()).collect();
+    /// Renders a builder that starts from the default value of the struct, with a setter per
+    /// field. Optional fields take the value itself, lists take all their items at once. Setters
+    /// of nested structs take their builders as well.
+    fn render_builder(&self) -> String {
+        let setters: String = self
+            .children
+            .iter()
+            .map(|c| c.borrow())
+            .filter(|c| c.fixed_value.is_none())
+            .filter_map(|c| {
+                let field_type = c.field_type.as_deref()?;
+                let (parameter, value) = if c.vector {
+                    (format!("Vec<{}>", field_type), "value.into()")
+                } else if c.optional {
+                    (field_type.to_string(), "Some(value.into())")
+                } else {
+                    (field_type.to_string(), "value.into()")
+                };
+                // the setter can not take the name of the function that finishes the builder,
+                // nor the one of Default, which starts it
+                let setter = match c.name.as_str() {
+                    "build" => "set_build",
+                    "default" => "set_default",
+                    name => name,
+                };
+
+                Some(format!(
+                    "\tpub fn {0}(mut self, value: impl Into<{1}>) -> Self {{\n\t\tself.value.{2} = {3};\n\t\tself\n\t}}\n\n",
+                    setter, parameter, c.name, value
+                ))
+            })
+            .collect();
+
+        format!(
+            r#"
+impl {0} {{
+	/// Starts to build a value from the default one
+	pub fn builder() -> {0}Builder {{
+		{0}Builder::default()
+	}}
+}}
+
+/// Builds a [`{0}`], field by field
+#[derive(Debug, Default, Clone)]
+pub struct {0}Builder {{
+	value: {0},
+}}
+
+impl {0}Builder {{
+{1}	/// Returns the value, with the fields that were not set at their defaults
+	pub fn build(self) -> {0} {{
+		self.value
+	}}
+}}
+
+impl From<{0}Builder> for {0} {{
+	fn from(builder: {0}Builder) -> Self {{
+		builder.value
+	}}
+}}
+"#,
+            self.name, setters
+        )
+    }
+
+    /// Renders how the elements of a repeating group are told apart
+    fn render_group_members(&self) -> String {
+        if self.group_members.is_empty() {
+            return String::new();
+        }
+
+        let positions: String = self
+            .group_members
+            .iter()
+            .map(|m| {
+                format!(
+                    "\t\t\t\"{}\" => Some(({}, {})),\n",
+                    m.xml_name, m.position, m.repeats
+                )
+            })
+            .collect();
+
+        format!(
+            "impl RepeatedGroup for {} {{\n\tfn position(name: &str) -> Option<(usize, bool)> {{\n\t\tmatch name {{\n{}\t\t\t_ => None,\n\t\t}}\n\t}}\n}}\n\n",
+            self.name, positions
+        )
+    }
+
+    /// The attributes with a fixed value, which are not rendered as fields
+    fn fixed_attributes(&self) -> impl Iterator<Item = std::cell::Ref<'_, Element>> {
+        self.children
+            .iter()
+            .map(|c| c.borrow())
+            .filter(|c| matches!(c.element_type, ElementType::Attribute) && c.fixed_value.is_some())
+    }
+
+    /// Renders the fixed attribute values as constants, and lists them for serialization
+    fn render_fixed_attributes(&self) -> String {
+        let mut constants = String::new();
+        let mut attributes = String::new();
+
+        for attribute in self.fixed_attributes() {
+            let xml_name = attribute.xml_name.as_deref().unwrap_or(&attribute.name);
+            let qualified_name = match &attribute.prefix {
+                Some(prefix) => format!("{}:{}", prefix, xml_name),
+                None => xml_name.to_string(),
+            };
+            let constant = attribute.name.trim_start_matches("r#").to_uppercase();
+
+            constants.push_str(&format!(
+                "\t/// The fixed value of the {} attribute\n\tpub const {}: &'static str = {:?};\n",
+                xml_name,
+                constant,
+                attribute.fixed_value.as_deref().unwrap_or_default()
+            ));
+            attributes.push_str(&format!(
+                "\t\t({:?}, {}::{}),\n",
+                qualified_name, self.name, constant
+            ));
+        }
+
+        format!(
+            "\nimpl {0} {{\n{1}}}\n\nimpl FixedAttributes for {0} {{\n\tconst FIXED_ATTRIBUTES: &'static [(&'static str, &'static str)] = &[\n{2}\t];\n}}\n",
+            self.name, constants, attributes
+        )
+    }
+
+    /// Renders the derive attribute of a type, the derives it needs followed by the extra ones.
+    /// Every type implements Default, by hand if need be, so that one is never added.
+    pub(crate) fn render_derive(&self, derives: &[&str]) -> String {
+        let extra = self
+            .derives
+            .iter()
+            .map(|d| d.as_str())
+            .filter(|d| *d != "Default" && !derives.contains(d));
+        let derives: Vec<&str> = derives.iter().copied().chain(extra).collect();
+        format!("#[derive({})]\n", derives.join(", "))
+    }
+
+    /// Renders a Default implementation that starts every field at its default value
+    fn render_default(&self, namespace_fields: &[&str]) -> String {
+        let mut fields: String = namespace_fields
+            .iter()
+            .map(|name| format!("\t\t\t{}: Default::default(),\n", name))
+            .collect();
+        fields += &self
+            .children
+            .iter()
+            .map(|c| c.borrow())
+            .filter(|c| c.fixed_value.is_none())
+            .map(|c| {
+                let value = c.default_value.as_deref().unwrap_or("Default::default()");
+                format!("\t\t\t{}: {},\n", c.name, value)
+            })
+            .collect::<String>();
+        if self.fixed_attributes().next().is_some() {
+            fields.push_str("\t\t\tfixed_attributes: Default::default(),\n");
+        }
+
+        format!(
+            "\nimpl Default for {0} {{\n\tfn default() -> Self {{\n\t\t{0} {{\n{1}\t\t}}\n\t}}\n}}\n",
+            self.name, fields
+        )
+    }
+
+    fn render_enum(&self, emitter: &dyn Emitter) -> String {
+        let mut result = if let Some(comment) = &self.comment {
+            format!("/** {}\n */\n", comment)
+        } else {
+            "".to_string()
+        };
+        result.push_str(&self.render_documentation(""));
+
+        // enumerations of integers have a base type and the values as discriminants
+        if let Some(base_type) = &self.field_type {
+            result.push_str(&self.render_numeric_enum(base_type, emitter));
+            return result;
+        }
+
+        // variants carrying generated types can not be compared
+        let comparable = self
+            .children
+            .iter()
+            .all(|c| c.borrow().field_type.is_none());
+        result.push_str(&emitter.enum_begin(self, comparable));
+
+        let r: String = self
+            .children
+            .iter()
+            .map(|c| c.borrow().render_with(emitter))
+            .collect();
         result.push_str(&r);
-        result.push_str("}\n");
+        result.push_str("}\n\n");
+
+        // the first variant is the default value
+        if let Some(first) = self.children.first() {
+            let first = first.borrow();
+            let value = match first.field_type {
+                None => first.name.clone(),
+                Some(_) => format!("{}(Default::default())", first.name),
+            };
+
+            result.push_str(&format!(
+                "impl Default for {0} {{\n\tfn default() -> Self {{\n\t\t{0}::{1}\n\t}}\n}}\n\n",
+                self.name, value
+            ));
+        }
+
+        result.push_str(&self.render_group_members());
         result
     }
 
-    fn render_trait_impl(&self) -> String {
-        let field_type = match &self.field_type {
-            None => return String::new(),
-            Some(ft) => ft,
+    /// A numeric enumeration is (de)serialized as the number of its variant.
+    fn render_numeric_enum(&self, base_type: &str, emitter: &dyn Emitter) -> String {
+        let mut result = self.render_derive(&["Debug", "PartialEq", "Eq", "Clone", "Copy"]);
+        result.push_str(&format!("#[repr({})]\n", base_type));
+        result.push_str(&format!("pub enum {} {{\n", self.name));
+
+        let mut variants = String::new();
+        let mut matches = String::new();
+        for variant in &self.children {
+            let variant = variant.borrow();
+            let value = variant.default_value.as_deref().unwrap_or_default();
+            variants.push_str(&variant.render_documentation("\t"));
+            variants.push_str(&format!("\t{} = {},\n", variant.name, value));
+            matches.push_str(&format!(
+                "\t\t\t{} => Ok({}::{}),\n",
+                value, self.name, variant.name
+            ));
+        }
+        result.push_str(&variants);
+        result.push_str("}\n\n");
+
+        if let Some(first) = self.children.first() {
+            result.push_str(&format!(
+                "impl Default for {0} {{\n\tfn default() -> Self {{\n\t\t{0}::{1}\n\t}}\n}}\n\n",
+                self.name,
+                first.borrow().name
+            ));
+        }
+
+        result.push_str(&format!(
+            r#"impl std::convert::TryFrom<{1}> for {0} {{
+	type Error = String;
+
+	fn try_from(value: {1}) -> Result<Self, Self::Error> {{
+		match value {{
+{2}			_ => Err(format!("{{}} is not a valid {0}", value)),
+		}}
+	}}
+}}
+
+impl From<{0}> for {1} {{
+	fn from(value: {0}) -> Self {{
+		value as {1}
+	}}
+}}
+
+impl std::fmt::Display for {0} {{
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {{
+		write!(f, "{{}}", *self as {1})
+	}}
+}}
+
+impl std::str::FromStr for {0} {{
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {{
+		let value: {1} = s.trim().parse().map_err(|_| format!("{{}} is not a valid {0}", s))?;
+		std::convert::TryFrom::try_from(value)
+	}}
+}}
+
+"#,
+            self.name, base_type, matches
+        ));
+        result.push_str(&emitter.text_value(self));
+
+        result
+    }
+
+    /// A type hierarchy is deserialized as the type named by xsi:type, or else as the base type,
+    /// which is the first variant. Derived types are serialized with their xsi:type.
+    fn render_polymorphic(&self, emitter: &dyn Emitter) -> String {
+        let mut result = self.render_documentation("");
+        result.push_str(&self.render_derive(&["Debug", "Clone"]));
+        result.push_str(&format!("pub enum {} {{\n", self.name));
+
+        let variants: String = self
+            .children
+            .iter()
+            .map(|c| {
+                let c = c.borrow();
+                format!("\t{}({}),\n", c.name, c.render_field_type())
+            })
+            .collect();
+        result.push_str(&variants);
+        result.push_str("}\n\n");
+
+        let base = match self.children.first() {
+            None => return result,
+            Some(b) => b.borrow().name.clone(),
         };
 
-        let mut result = format!(
-            "#[async_trait]\n\timpl {0} for {1} {{\n",
-            field_type, self.name
-        );
+        result.push_str(&format!(
+            "impl Default for {0} {{\n\tfn default() -> Self {{\n\t\t{0}::{1}(Default::default())\n\t}}\n}}\n\n",
+            self.name, base
+        ));
 
-        let r: String = self.children.iter().map(|c| c.borrow().render()).collect();
-        result.push_str(&r);
-        result.push_str("}\n");
+        result.push_str(&emitter.polymorphic_impls(self, &base));
         result
     }
 
-    fn render_field(&self) -> String {
-        let mut bool_options = if self.flatten {
-            "flatten, ".to_string()
+    /// A union is (de)serialized as text, trying its member types in order.
+    fn render_union(&self, emitter: &dyn Emitter) -> String {
+        let mut result = if let Some(comment) = &self.comment {
+            format!("/** {}\n */\n", comment)
         } else {
             "".to_string()
         };
+        result.push_str(&self.render_documentation(""));
+
+        result.push_str(&self.render_derive(&["Debug", "PartialEq", "Clone"]));
+        result.push_str(&format!("pub enum {} {{\n", self.name));
+
+        let r: String = self
+            .children
+            .iter()
+            .map(|c| {
+                let c = c.borrow();
+                format!("\t{}({}),\n", c.name, c.render_field_type())
+            })
+            .collect();
+        result.push_str(&r);
+        result.push_str("}\n\n");
 
-        if self.text_field {
-            bool_options.push_str("text, ")
+        if let Some(first) = self.children.first() {
+            result.push_str(&format!(
+                "impl Default for {0} {{\n\tfn default() -> Self {{\n\t\t{0}::{1}(Default::default())\n\t}}\n}}\n\n",
+                self.name,
+                first.borrow().name
+            ));
         }
 
-        let comment = match &self.comment {
-            None => "".to_string(),
-            Some(c) => format!("// {}", c),
-        };
+        let display: String = self
+            .children
+            .iter()
+            .map(|c| {
+                format!(
+                    "\t\t\t{}::{}(value) => write!(f, \"{{}}\", value),\n",
+                    self.name,
+                    c.borrow().name
+                )
+            })
+            .collect();
+        result.push_str(&format!(
+            "impl std::fmt::Display for {} {{\n\tfn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {{\n\t\tmatch self {{\n{}\t\t}}\n\t}}\n}}\n\n",
+            self.name, display
+        ));
+
+        let parse: String = self
+            .children
+            .iter()
+            .map(|c| {
+                format!(
+                    "\t\tif let Ok(value) = s.parse() {{\n\t\t\treturn Ok({}::{}(value));\n\t\t}}\n",
+                    self.name,
+                    c.borrow().name
+                )
+            })
+            .collect();
+        result.push_str(&format!(
+            "impl std::str::FromStr for {0} {{\n\ttype Err = String;\n\n\tfn from_str(s: &str) -> Result<Self, Self::Err> {{\n{1}\t\tErr(format!(\"{{}} is not a valid {0}\", s))\n\t}}\n}}\n\n",
+            self.name, parse
+        ));
+
+        result.push_str(&emitter.text_value(self));
+        result
+    }
 
-        let prefix = match &self.prefix {
-            Some(p) => format!("prefix = \"{}\", ", p),
-            None => "".to_string(),
+    /// A list is (de)serialized as its whitespace separated items.
+    fn render_list(&self, emitter: &dyn Emitter) -> String {
+        let item_type = match &self.field_type {
+            None => return String::new(),
+            Some(t) => t,
         };
 
-        if let Some(xml_name) = &self.xml_name {
-            format!(
-                "\t#[yaserde(rename = \"{0}\", {3}{5}default)]\n\tpub {1}: {2}, {4}\n",
-                xml_name,
-                self.name,
-                self.render_field_type(),
-                bool_options,
-                comment,
-                prefix,
-            )
+        let mut result = if let Some(comment) = &self.comment {
+            format!("/** {}\n */\n", comment)
         } else {
-            format!(
-                "\t#[yaserde({2}{4}default)]\n\tpub {0}: {1}, {3}\n",
-                self.name,
-                self.render_field_type(),
-                bool_options,
-                comment,
-                prefix
-            )
-        }
+            "".to_string()
+        };
+        result.push_str(&self.render_documentation(""));
+
+        result.push_str(&self.render_derive(&["Debug", "Default", "PartialEq", "Clone"]));
+        result.push_str(&format!(
+            "pub struct {}(pub Vec<{}>);\n\n",
+            self.name, item_type
+        ));
+
+        result.push_str(&format!(
+            r#"impl std::fmt::Display for {0} {{
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {{
+		let items: Vec<String> = self.0.iter().map(|item| item.to_string()).collect();
+		write!(f, "{{}}", items.join(" "))
+	}}
+}}
+
+impl std::str::FromStr for {0} {{
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {{
+		s.split_whitespace()
+			.map(|item| item.parse().map_err(|_| format!("{{}} is not a valid item of {0}", item)))
+			.collect::<Result<Vec<{1}>, String>>()
+			.map({0})
+	}}
+}}
+
+"#,
+            self.name, item_type
+        ));
+        result.push_str(&emitter.text_value(self));
+
+        result
     }
 
-    fn render_atribute(&self) -> String {
-        let field_type = match &self.field_type {
+    /// A restriction is a newtype that checks its facets whenever it is constructed through
+    /// `TryFrom`, or parsed.
+    fn render_restriction(&self, emitter: &dyn Emitter) -> String {
+        let base_type = match &self.field_type {
             None => return String::new(),
-            Some(ft) => ft,
+            Some(t) => t,
+        };
+
+        let mut result = if let Some(comment) = &self.comment {
+            format!("/** {}\n */\n", comment)
+        } else {
+            "".to_string()
         };
+        result.push_str(&self.render_documentation(""));
+
+        result.push_str(&self.render_derive(&[
+            "Debug",
+            "Default",
+            "PartialEq",
+            "PartialOrd",
+            "Clone",
+        ]));
+        result.push_str(&format!("pub struct {}(pub {});\n\n", self.name, base_type));
+
+        let checks: String = self.facets.iter().map(|f| f.render_check()).collect();
+
+        result.push_str(&format!(
+            r#"impl {0} {{
+	/// Checks the value against the facets of the type
+	pub fn validate(&self) -> Result<(), String> {{
+{2}		Ok(())
+	}}
+}}
+
+impl std::convert::TryFrom<{1}> for {0} {{
+	type Error = String;
+
+	fn try_from(value: {1}) -> Result<Self, Self::Error> {{
+		let value = {0}(value);
+		value.validate()?;
+		Ok(value)
+	}}
+}}
+
+impl std::fmt::Display for {0} {{
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {{
+		write!(f, "{{}}", self.0)
+	}}
+}}
+
+impl std::str::FromStr for {0} {{
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {{
+		let value: {1} = s.trim().parse().map_err(|_| format!("{{}} is not a valid {0}", s))?;
+		std::convert::TryFrom::try_from(value)
+	}}
+}}
+
+"#,
+            self.name, base_type, checks
+        ));
+        result.push_str(&emitter.text_value(self));
+
+        result
+    }
 
-        let xml_name = match &self.xml_name {
+    /// Renders the documentation as doc comments, one per line of text.
+    pub(crate) fn render_documentation(&self, indent: &str) -> String {
+        let documentation = match &self.documentation {
             None => return String::new(),
-            Some(xn) => xn,
+            Some(d) => d,
         };
 
-        let prefix = match &self.prefix {
-            Some(p) => format!("prefix = \"{}\", ", p),
-            None => "".to_string(),
+        documentation
+            .lines()
+            .map(|line| match line.trim() {
+                "" => format!("{}///\n", indent),
+                line => format!("{}/// {}\n", indent, line),
+            })
+            .collect()
+    }
+
+    fn render_trait(&self, emitter: &dyn Emitter) -> String {
+        let mut result = self.render_documentation("");
+        if !self.blocking {
+            result.push_str("#[async_trait]\n");
+        }
+        result.push_str(&format!("pub trait {0} {{\n", self.name));
+        let r: String = self
+            .children
+            .iter()
+            .map(|c| c.borrow().render_with(emitter))
+            .collect();
+        result.push_str(&r);
+        result.push_str("}\n");
+        result
+    }
+
+    fn render_trait_impl(&self, emitter: &dyn Emitter) -> String {
+        let field_type = match &self.field_type {
+            None => return String::new(),
+            Some(ft) => ft,
         };
 
-        if self.optional {
-            format!(
-                "#[yaserde({}rename=\"{}\", attribute)]\npub {}: Option<{}>,\n",
-                prefix, xml_name, self.name, field_type
-            )
+        let mut result = if self.blocking {
+            String::new()
         } else {
-            format!(
-                "#[yaserde({}rename=\"{}\", attribute)]\npub {}: {},\n",
-                prefix, xml_name, self.name, field_type
-            )
-        }
+            "#[async_trait]\n".to_string()
+        };
+        result.push_str(&format!("\timpl {0} for {1} {{\n", field_type, self.name));
+
+        let r: String = self
+            .children
+            .iter()
+            .map(|c| c.borrow().render_with(emitter))
+            .collect();
+        result.push_str(&r);
+        result.push_str("}\n");
+        result
     }
 
-    fn render_field_type(&self) -> String {
+    pub(crate) fn render_field_type(&self) -> String {
         if let Some(field_type) = &self.field_type {
             if self.vector {
                 format!("Vec<{}>", field_type)
@@ -374,38 +1089,46 @@ impl Element {
         match &self.static_content {
             None => String::new(),
             Some(c) => {
+                let c = self.render_documentation("") + c;
                 if let Some(comment) = &self.comment {
                     format!("/** {}\n */\n{}", comment, c)
                 } else {
-                    c.clone()
+                    c
                 }
             }
         }
     }
 
-    fn render_alias(&self) -> String {
-        if let Some(field_type) = &self.field_type {
-            format!("pub type {} = {};\n\n", self.name, field_type)
-        } else {
-            String::new()
+    fn module_visibility(&self) -> &str {
+        self.visibility.as_deref().unwrap_or("pub")
+    }
+
+    /// The attribute that compiles a module with its feature only
+    fn module_cfg(&self) -> String {
+        match &self.feature {
+            None => String::new(),
+            Some(feature) => format!("#[cfg(feature = {:?})]\n", feature),
         }
     }
 
-    fn render_module(&self) -> String {
-        let mut result = format!("pub mod {} {{\n", self.name);
-        result.push_str(
-            r#"use yaserde::{YaSerialize, YaDeserialize};
-            use yaserde::de::from_str;
-            use async_trait::async_trait;
-            use yaserde::ser::to_string;
-            use super::*;
-            "#,
-        );
+    fn render_module(&self, emitter: &dyn Emitter) -> String {
+        format!(
+            "{}{} mod {} {{\n{}}}\n\n",
+            self.module_cfg(),
+            self.module_visibility(),
+            self.name,
+            self.render_module_content(emitter)
+        )
+    }
 
-        let child_content: String = self.children.iter().map(|c| c.borrow().render()).collect();
+    fn render_module_content(&self, emitter: &dyn Emitter) -> String {
+        let child_content: String = self
+            .children
+            .iter()
+            .map(|c| c.borrow().render_with(emitter))
+            .collect();
+        let mut result = emitter.module_imports(&child_content);
         result.push_str(child_content.as_str());
-
-        result.push_str("}\n\n");
         result
     }
 
@@ -415,29 +1138,124 @@ impl Element {
             Some(a) => a,
         };
 
-        let function_result = match &args.fault_type {
-            None => match &args.output_type {
+        let function = format!(
+            "\t{}fn {} (&self, {}) {};\n",
+            if self.blocking { "" } else { "async " },
+            self.name,
+            args.render_parameters(),
+            args.render_result()
+        );
+
+        self.render_documentation("\t") + &function
+    }
+}
+
+impl FunctionArgs {
+    /// Renders the parameters of the function, following `&self`
+    pub fn render_parameters(&self) -> String {
+        format!("{}: {}", self.input_name, self.input_type)
+            + &self
+                .attachments
+                .iter()
+                .map(|a| format!(", {}: Attachment", a))
+                .collect::<String>()
+    }
+
+    /// Renders what the function returns, if it returns anything
+    pub fn render_result(&self) -> String {
+        let output = self.output_type.as_ref().map(|o| {
+            if self.output_attachments {
+                format!("({}, transport::ResponseParts)", o)
+            } else {
+                o.clone()
+            }
+        });
+        match &self.fault_type {
+            None => match output {
                 None => String::new(),
                 Some(o) => format!("-> SoapResult<{}, ()>", o),
             },
-            Some(fault) => match &args.output_type {
+            Some(fault) => match output {
                 None => format!("-> {}", fault),
                 Some(o) => format!("-> SoapResult<{}, {}>", o, fault),
             },
-        };
+        }
+    }
+}
 
-        let function_input = format!("{}: {}", args.input_name, args.input_type);
+impl Facet {
+    /// Renders the check of a value against the facet, as part of a `validate` function
+    fn render_check(&self) -> String {
+        if let Facet::Pattern(regex) = self {
+            return format!(
+                "\t\tlet pattern = regex::Regex::new({0:?}).map_err(|e| e.to_string())?;\n\t\tif !pattern.is_match(&self.0) {{\n\t\t\treturn Err(format!(\"{{}} does not match {{}}\", self.0, {0:?}));\n\t\t}}\n",
+                regex
+            );
+        }
+
+        let (violation, bound, message) = match self {
+            Facet::MinInclusive(bound) => ("<", bound, "is less than the minimum of"),
+            Facet::MaxInclusive(bound) => (">", bound, "is greater than the maximum of"),
+            Facet::MinExclusive(bound) => ("<=", bound, "is not greater than"),
+            Facet::MaxExclusive(bound) => (">=", bound, "is not less than"),
+            Facet::Pattern(_) => unreachable!(),
+        };
 
         format!(
-            "\tasync fn {} (&self, {}) {};\n",
-            self.name, function_input, function_result
+            "\t\tif self.0 {0} {1} {{\n\t\t\treturn Err(format!(\"{{}} {2} {1}\", self.0));\n\t\t}}\n",
+            violation, bound, message
         )
     }
+
+    /// Whether the default value of the type, zero or the empty string, satisfies the facet.
+    /// Patterns are not evaluated, so they are assumed to reject it.
+    pub(crate) fn admits_default(&self) -> bool {
+        let bound = match self {
+            Facet::Pattern(_) => return false,
+            Facet::MinInclusive(bound)
+            | Facet::MaxInclusive(bound)
+            | Facet::MinExclusive(bound)
+            | Facet::MaxExclusive(bound) => match bound.parse::<f64>() {
+                Ok(bound) => bound,
+                Err(_) => return false,
+            },
+        };
+
+        match self {
+            Facet::MinInclusive(_) => bound <= 0.0,
+            Facet::MaxInclusive(_) => bound >= 0.0,
+            Facet::MinExclusive(_) => bound < 0.0,
+            Facet::MaxExclusive(_) => bound > 0.0,
+            Facet::Pattern(_) => false,
+        }
+    }
+}
+
+/// The code of an async client as the code of a blocking one
+fn blocking_code(code: &str) -> String {
+    code.replace("#[async_trait]\n", "")
+        .replace("async fn ", "fn ")
+        .replace(".await", "")
+        .replace("reqwest::Client", "reqwest::blocking::Client")
+        .replace("reqwest::Response", "reqwest::blocking::Response")
+}
+
+/// Puts the header of the emitter, with the imports that all of the code takes, after the inner
+/// doc comments at the top of the rendered code
+fn with_header(rendered: &str, code: &str, emitter: &dyn Emitter) -> String {
+    let doc_len: usize = rendered
+        .split_inclusive('\n')
+        .take_while(|line| line.starts_with("//!"))
+        .map(str::len)
+        .sum();
+    let (doc, rest) = rendered.split_at(doc_len);
+    format!("{}{}{}", doc, emitter.header(code), rest)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::emitter::SupportType;
 
     #[test]
     fn test_base_struct() {
@@ -512,6 +1330,385 @@ pub struct SoapFault {
         assert_eq!(global_header.render(), expected);
     }
 
+    #[test]
+    fn test_blocking_copy() {
+        let mut module = Element::new_module("bindings");
+        let mut binding = Element::new("Echo", ElementType::TraitImpl);
+        binding.field_type = Option::Some("ports::Echo".to_string());
+        let mut call = Element::new("ping", ElementType::Static);
+        call.set_content("\tasync fn ping (&self) {\n\t\tself.transport.call().await\n\t}\n");
+        binding.add(call);
+        module.add(binding);
+
+        let blocking = module.to_blocking();
+        assert_eq!(
+            blocking.render(),
+            "pub mod bindings {\nuse super::*;\n\timpl ports::Echo for Echo {\n\tfn ping (&self) {\n\t\tself.transport.call()\n\t}\n}\n}\n\n"
+        );
+        // the async elements are left as they are
+        assert!(module
+            .render()
+            .contains("#[async_trait]\n\timpl ports::Echo for Echo {\n\tasync fn ping"));
+    }
+
+    #[test]
+    fn test_reindex() {
+        let mut module = Element::new_module("ports");
+        module.add(Element::new("Echo", ElementType::Static));
+        module.children.insert(
+            0,
+            Rc::new(RefCell::new(Element::new("shared", ElementType::Static))),
+        );
+        module.reindex();
+        assert_eq!(
+            module.child("Echo").map(|e| e.borrow().name.clone()),
+            Some("Echo".to_string())
+        );
+        assert!(module.has_child("shared"));
+    }
+
+    #[test]
+    fn test_enum() {
+        let expected = r#"#[derive(Debug, PartialEq, YaSerialize, YaDeserialize, Clone)]
+#[yaserde(
+	rename = "status",
+)]
+pub enum Status {
+	#[yaserde(rename = "active")]
+	Active,
+	#[yaserde(rename = "in-active")]
+	InActive,
+}
+
+impl Default for Status {
+	fn default() -> Self {
+		Status::Active
+	}
+}
+
+"#;
+
+        let mut status = Element::new("Status", ElementType::Enum);
+        status.xml_name = Option::Some("status".to_string());
+
+        let mut active = Element::new("Active", ElementType::Variant);
+        active.xml_name = Option::Some("active".to_string());
+        status.add(active);
+
+        let mut in_active = Element::new("InActive", ElementType::Variant);
+        in_active.xml_name = Option::Some("in-active".to_string());
+        status.add(in_active);
+
+        assert_eq!(status.render(), expected.to_string());
+    }
+
+    #[test]
+    fn test_choice_enum() {
+        let expected = r#"#[derive(Debug, YaSerialize, YaDeserialize, Clone)]
+pub enum ContactChoice {
+	#[yaserde(prefix = "tns", rename = "email")]
+	Email(String),
+	#[yaserde(prefix = "tns", rename = "address")]
+	Address(Address),
+}
+
+impl Default for ContactChoice {
+	fn default() -> Self {
+		ContactChoice::Email(Default::default())
+	}
+}
+
+"#;
+
+        let mut choice = Element::new("ContactChoice", ElementType::Enum);
+
+        for (name, xml_name, field_type) in &[
+            ("Email", "email", "String"),
+            ("Address", "address", "Address"),
+        ] {
+            let mut variant = Element::new(name, ElementType::Variant);
+            variant.prefix = Option::Some("tns".to_string());
+            variant.xml_name = Option::Some(xml_name.to_string());
+            variant.field_type = Option::Some(field_type.to_string());
+            choice.add(variant);
+        }
+
+        assert_eq!(choice.render(), expected.to_string());
+    }
+
+    #[test]
+    fn test_union() {
+        let expected = r#"#[derive(Debug, PartialEq, Clone)]
+pub enum Size {
+	Int(i32),
+	String(String),
+}
+
+impl Default for Size {
+	fn default() -> Self {
+		Size::Int(Default::default())
+	}
+}
+
+impl std::fmt::Display for Size {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Size::Int(value) => write!(f, "{}", value),
+			Size::String(value) => write!(f, "{}", value),
+		}
+	}
+}
+
+impl std::str::FromStr for Size {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if let Ok(value) = s.parse() {
+			return Ok(Size::Int(value));
+		}
+		if let Ok(value) = s.parse() {
+			return Ok(Size::String(value));
+		}
+		Err(format!("{} is not a valid Size", s))
+	}
+}
+
+impl_text_value!(Size);
+
+"#;
+
+        let mut union = Element::new("Size", ElementType::Union);
+
+        for (name, field_type) in &[("Int", "i32"), ("String", "String")] {
+            let mut variant = Element::new(name, ElementType::Variant);
+            variant.field_type = Option::Some(field_type.to_string());
+            union.add(variant);
+        }
+
+        assert_eq!(union.render(), expected.to_string());
+    }
+
+    #[test]
+    fn test_list() {
+        let expected = r#"#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Sizes(pub Vec<i32>);
+
+impl std::fmt::Display for Sizes {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		let items: Vec<String> = self.0.iter().map(|item| item.to_string()).collect();
+		write!(f, "{}", items.join(" "))
+	}
+}
+
+impl std::str::FromStr for Sizes {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		s.split_whitespace()
+			.map(|item| item.parse().map_err(|_| format!("{} is not a valid item of Sizes", item)))
+			.collect::<Result<Vec<i32>, String>>()
+			.map(Sizes)
+	}
+}
+
+impl_text_value!(Sizes);
+
+"#;
+
+        let mut list = Element::new("Sizes", ElementType::List);
+        list.field_type = Option::Some("i32".to_string());
+
+        assert_eq!(list.render(), expected.to_string());
+    }
+
+    #[test]
+    fn test_restriction() {
+        let expected = r#"#[derive(Debug, Default, PartialEq, PartialOrd, Clone)]
+pub struct Percentage(pub f64);
+
+impl Percentage {
+	/// Checks the value against the facets of the type
+	pub fn validate(&self) -> Result<(), String> {
+		if self.0 < 0.0 {
+			return Err(format!("{} is less than the minimum of 0.0", self.0));
+		}
+		if self.0 >= 100.0 {
+			return Err(format!("{} is not less than 100.0", self.0));
+		}
+		Ok(())
+	}
+}
+
+impl std::convert::TryFrom<f64> for Percentage {
+	type Error = String;
+
+	fn try_from(value: f64) -> Result<Self, Self::Error> {
+		let value = Percentage(value);
+		value.validate()?;
+		Ok(value)
+	}
+}
+
+impl std::fmt::Display for Percentage {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(f, "{}", self.0)
+	}
+}
+
+impl std::str::FromStr for Percentage {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let value: f64 = s.trim().parse().map_err(|_| format!("{} is not a valid Percentage", s))?;
+		std::convert::TryFrom::try_from(value)
+	}
+}
+
+impl_text_value!(Percentage);
+
+"#;
+
+        let mut percentage = Element::new("Percentage", ElementType::Restriction);
+        percentage.field_type = Option::Some("f64".to_string());
+        percentage.facets = vec![
+            Facet::MinInclusive("0.0".to_string()),
+            Facet::MaxExclusive("100.0".to_string()),
+        ];
+
+        assert_eq!(percentage.render(), expected.to_string());
+    }
+
+    #[test]
+    fn test_numeric_enum() {
+        let expected = r#"#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[repr(i32)]
+pub enum Status {
+	/// Accepted
+	Value1 = 1,
+	Value2 = -2,
+}
+
+impl Default for Status {
+	fn default() -> Self {
+		Status::Value1
+	}
+}
+
+impl std::convert::TryFrom<i32> for Status {
+	type Error = String;
+
+	fn try_from(value: i32) -> Result<Self, Self::Error> {
+		match value {
+			1 => Ok(Status::Value1),
+			-2 => Ok(Status::Value2),
+			_ => Err(format!("{} is not a valid Status", value)),
+		}
+	}
+}
+
+impl From<Status> for i32 {
+	fn from(value: Status) -> Self {
+		value as i32
+	}
+}
+
+impl std::fmt::Display for Status {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(f, "{}", *self as i32)
+	}
+}
+
+impl std::str::FromStr for Status {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let value: i32 = s.trim().parse().map_err(|_| format!("{} is not a valid Status", s))?;
+		std::convert::TryFrom::try_from(value)
+	}
+}
+
+impl_text_value!(Status);
+
+"#;
+
+        let mut status = Element::new("Status", ElementType::Enum);
+        status.field_type = Option::Some("i32".to_string());
+
+        let mut accepted = Element::new("Value1", ElementType::Variant);
+        accepted.default_value = Option::Some("1".to_string());
+        accepted.documentation = Option::Some("Accepted".to_string());
+        status.add(accepted);
+
+        let mut rejected = Element::new("Value2", ElementType::Variant);
+        rejected.default_value = Option::Some("-2".to_string());
+        status.add(rejected);
+
+        assert_eq!(status.render(), expected.to_string());
+    }
+
+    #[test]
+    fn test_default_values() {
+        let expected = r#"#[derive(Debug, YaSerialize, YaDeserialize, Clone)]
+pub struct Settings {
+	#[yaserde(rename = "retries", default)]
+	pub retries: i32, 
+	#[yaserde(rename = "label", default)]
+	pub label: Option<String>, 
+}
+
+impl Default for Settings {
+	fn default() -> Self {
+		Settings {
+			retries: 3,
+			label: Default::default(),
+		}
+	}
+}
+"#;
+        let mut settings = Element::new("Settings", ElementType::Struct);
+        let mut retries = Element::new_field("retries", "retries", "i32", false);
+        retries.default_value = Option::Some("3".to_string());
+        settings.add(retries);
+        settings.add(Element::new_field("label", "label", "String", true));
+
+        assert_eq!(settings.render(), expected.to_string());
+    }
+
+    #[test]
+    fn test_documentation() {
+        let expected = r#"/// A person
+///
+/// Used by the directory
+#[derive(Debug, Default, YaSerialize, YaDeserialize, Clone)]
+pub struct Person {
+	/// The full name
+	#[yaserde(rename = "name", default)]
+	pub name: String, 
+}
+"#;
+        let mut person = Element::new("Person", ElementType::Struct);
+        person.documentation = Option::Some("A person\n\n    Used by the directory".to_string());
+        let mut name = Element::new_field("name", "name", "String", false);
+        name.documentation = Option::Some("The full name".to_string());
+        person.add(name);
+
+        assert_eq!(person.render(), expected.to_string());
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut module = Element::new_module("types");
+        module.add(Element::new("First", ElementType::Struct));
+        module.add(Element::new("Second", ElementType::Struct));
+        module.add(Element::new("Third", ElementType::Struct));
+
+        module.retain(|e| e.name != "Second");
+
+        assert!(!module.has_child("Second"));
+        assert_eq!(module.child("Third").unwrap().borrow().name, "Third");
+        assert_eq!(module.children.len(), 2);
+    }
+
     #[test]
     fn test_alias() {
         let expected = r#"pub type SomeElement = other_mod::SomeElement;
@@ -522,4 +1719,74 @@ pub struct SoapFault {
         alias_element.field_type = Option::Some("other_mod::SomeElement".to_string());
         assert_eq!(alias_element.render(), expected)
     }
+
+    /// Renders plain structs with public fields, leaving out (de)serialization
+    struct PlainEmitter;
+
+    impl Emitter for PlainEmitter {
+        fn header(&self, _code: &str) -> String {
+            String::new()
+        }
+
+        fn module_imports(&self, _code: &str) -> String {
+            String::new()
+        }
+
+        fn support_types(&self) -> Vec<SupportType> {
+            Vec::new()
+        }
+
+        fn qname_impls(&self) -> &'static str {
+            ""
+        }
+
+        fn struct_begin(&self, element: &Element, _derive_default: bool) -> String {
+            format!("pub struct {} {{\n", element.name)
+        }
+
+        fn field(&self, field: &Element) -> String {
+            format!("\tpub {}: {},\n", field.name, field.render_field_type())
+        }
+
+        fn attribute(&self, attribute: &Element) -> String {
+            self.field(attribute)
+        }
+
+        fn fixed_attributes_field(&self, _element: &Element) -> String {
+            String::new()
+        }
+
+        fn enum_begin(&self, element: &Element, _comparable: bool) -> String {
+            format!("pub enum {} {{\n", element.name)
+        }
+
+        fn variant(&self, variant: &Element) -> String {
+            format!("\t{},\n", variant.name)
+        }
+
+        fn text_value(&self, _element: &Element) -> String {
+            String::new()
+        }
+
+        fn polymorphic_impls(&self, _element: &Element, _base: &str) -> String {
+            String::new()
+        }
+    }
+
+    #[test]
+    fn test_render_with_emitter() {
+        let mut module = Element::new_module("types");
+        let mut person = Element::new("Person", ElementType::Struct);
+        person.xml_name = Option::Some("person".to_string());
+        person.add(Element::new_field("name", "name", "String", false));
+        module.add(person);
+
+        assert_eq!(
+            module.render_with(&PlainEmitter),
+            "pub mod types {\npub struct Person {\n\tpub name: String,\n}\n}\n\n"
+        );
+        assert!(module
+            .render()
+            .contains("#[yaserde(\n\trename = \"person\",\n)]\n"));
+    }
 }
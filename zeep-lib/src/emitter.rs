@@ -0,0 +1,772 @@
+//! # Emitter
+//! Writes the parts of the elements that depend on the XML (de)serialization crate of the
+//! generated code. The elements decide what is generated, the emitter how it is (de)serialized.
+//! The types that the generated code builds on are kept per crate, in the files next to this one.
+//!
+use crate::element::Element;
+
+/// A type or macro that the generated types build on, written once at the top of the code
+pub struct SupportType {
+    pub name: &'static str,
+    pub comment: Option<&'static str>,
+    pub code: &'static str,
+}
+
+/// The code of the XML (de)serialization crate the generated types build on
+pub trait Emitter {
+    /// The attributes and imports at the top of the generated code, given all of the code. Only
+    /// the imports that the code refers to are included.
+    fn header(&self, code: &str) -> String;
+
+    /// The imports at the top of a generated module that the code of the module refers to
+    fn module_imports(&self, code: &str) -> String;
+
+    /// The derives and attributes of a struct, up to and including its opening brace
+    fn struct_begin(&self, element: &Element, derive_default: bool) -> String;
+
+    /// The types and macros that the (de)serialization of the generated types builds on, like
+    /// the values of wildcards and of xs:anyType
+    fn support_types(&self) -> Vec<SupportType>;
+
+    /// The (de)serialization of the Qname type, after its definition
+    fn qname_impls(&self) -> &'static str;
+
+    /// The fields of a struct that declare the namespaces of its element, as their names and code
+    fn namespace_fields(&self, _element: &Element) -> Vec<(String, String)> {
+        Vec::new()
+    }
+
+    /// What the fields that declare the namespaces of a struct write, after the struct
+    fn namespace_impls(&self, _element: &Element) -> String {
+        String::new()
+    }
+
+    /// A field of a struct, for a child element or the text content
+    fn field(&self, field: &Element) -> String;
+
+    /// A field of a struct, for an XML attribute
+    fn attribute(&self, attribute: &Element) -> String;
+
+    /// The field of a struct that writes the attributes with a fixed value
+    fn fixed_attributes_field(&self, element: &Element) -> String;
+
+    /// The derives and attributes of an enum of elements or values, up to and including its
+    /// opening brace
+    fn enum_begin(&self, element: &Element, comparable: bool) -> String;
+
+    /// A variant of an enum of elements or values
+    fn variant(&self, variant: &Element) -> String;
+
+    /// The (de)serialization of a type that is written as text, through its `Display` and
+    /// `FromStr` implementations
+    fn text_value(&self, element: &Element) -> String;
+
+    /// The (de)serialization of a type hierarchy, which tells the types apart by their xsi:type
+    /// and falls back to the base type
+    fn polymorphic_impls(&self, element: &Element, base: &str) -> String;
+
+    /// An alias of a type
+    fn alias(&self, element: &Element) -> String {
+        match &element.field_type {
+            Some(field_type) => {
+                let alias = format!("pub type {} = {};\n\n", element.name, field_type);
+                element.render_documentation("") + &alias
+            }
+            None => String::new(),
+        }
+    }
+}
+
+/// Generates types that are (de)serialized with yaserde
+pub struct YaserdeEmitter;
+
+impl YaserdeEmitter {
+    /// Renders the yaserde options of a struct or enum, if there are any.
+    fn render_options(&self, element: &Element) -> String {
+        let mut has_options = false;
+
+        let mut options = "#[yaserde(\n".to_string();
+
+        if let Some(xml_name) = &element.xml_name {
+            options.push_str(&format!("\trename = \"{}\",\n", xml_name));
+            has_options = true;
+        }
+
+        for namespace in &element.namespaces {
+            options.push_str(&format!("\tnamespace = \"{}\",\n", namespace));
+            has_options = true;
+        }
+
+        if let Some(prefix) = &element.prefix {
+            options.push_str(&format!("\tprefix = \"{}\",\n", prefix));
+            has_options = true;
+        }
+
+        if element.flatten {
+            options.push_str("\tflatten, \n");
+            has_options = true;
+        }
+
+        options.push_str(")]\n");
+
+        if has_options {
+            options
+        } else {
+            String::new()
+        }
+    }
+}
+
+impl Emitter for YaserdeEmitter {
+    fn header(&self, code: &str) -> String {
+        let mut result = "\n#![allow(dead_code)]\n#![allow(unused_imports)]\n".to_string();
+        if refers_to(code, "YaSerialize") || refers_to(code, "YaDeserialize") {
+            result.push_str("use yaserde::{YaSerialize, YaDeserialize};\nuse yaserde_derive::*;\n");
+        }
+
+        let macros: Vec<&str> = ["debug", "info", "warn", "error"]
+            .iter()
+            .filter(|m| refers_to(code, &format!("{}!", m)))
+            .copied()
+            .collect();
+        result.push_str(&use_declaration("log", &macros));
+
+        let io_traits: Vec<&str> = ["Read", "Write"]
+            .iter()
+            .filter(|t| refers_to(code, t))
+            .copied()
+            .collect();
+        result.push_str(&use_declaration("std::io", &io_traits));
+
+        result.push_str(
+            "\npub const SOAP_ENCODING: &str = \"http://www.w3.org/2003/05/soap-encoding\";\n",
+        );
+        result
+    }
+
+    fn module_imports(&self, code: &str) -> String {
+        let mut result = String::new();
+        if refers_to(code, "YaSerialize") || refers_to(code, "YaDeserialize") {
+            result.push_str("use yaserde::{YaSerialize, YaDeserialize};\n");
+        }
+        if refers_to(code, "from_str") {
+            result.push_str("use yaserde::de::from_str;\n");
+        }
+        if refers_to(code, "async_trait") {
+            result.push_str("use async_trait::async_trait;\n");
+        }
+        if refers_to(code, "to_string") {
+            result.push_str("use yaserde::ser::to_string;\n");
+        }
+        result.push_str("use super::*;\n");
+        result
+    }
+
+    fn support_types(&self) -> Vec<SupportType> {
+        vec![
+            SupportType {
+                name: "impl_text_value",
+                comment: Some(TEXT_VALUE_COMMENT),
+                code: include_str!("emitter/yaserde/text_value.rs"),
+            },
+            SupportType {
+                name: "RawXml",
+                comment: Some(
+                    "Child elements matched by a wildcard (xs:any), kept verbatim so they survive a round-trip",
+                ),
+                code: include_str!("emitter/yaserde/raw_xml.rs"),
+            },
+            SupportType {
+                name: "XmlValue",
+                comment: Some(
+                    "An element of type xs:anyType, with its attributes and content kept verbatim",
+                ),
+                code: include_str!("emitter/yaserde/xml_value.rs"),
+            },
+            SupportType {
+                name: "Repeated",
+                comment: None,
+                code: include_str!("emitter/yaserde/repeated.rs"),
+            },
+            SupportType {
+                name: "Fixed",
+                comment: None,
+                code: include_str!("emitter/yaserde/fixed.rs"),
+            },
+        ]
+    }
+
+    fn qname_impls(&self) -> &'static str {
+        include_str!("emitter/yaserde/qname.rs")
+    }
+
+    fn struct_begin(&self, element: &Element, derive_default: bool) -> String {
+        let mut result = element.render_derive(&struct_derives(
+            element,
+            derive_default,
+            &["YaSerialize", "YaDeserialize"],
+        ));
+        result.push_str(&self.render_options(element));
+        result.push_str(&format!("pub struct {} {{\n", element.name));
+        result
+    }
+
+    fn field(&self, field: &Element) -> String {
+        let mut bool_options = if field.flatten {
+            "flatten, ".to_string()
+        } else {
+            "".to_string()
+        };
+
+        if field.text_field {
+            bool_options.push_str("text, ")
+        }
+
+        let comment = match &field.comment {
+            None => "".to_string(),
+            Some(c) => format!("// {}", c),
+        };
+
+        let prefix = match &field.prefix {
+            Some(p) => format!("prefix = \"{}\", ", p),
+            None => "".to_string(),
+        };
+
+        let documentation = field.render_documentation("\t");
+
+        let rendered = if let Some(xml_name) = &field.xml_name {
+            format!(
+                "\t#[yaserde(rename = \"{0}\", {3}{5}default)]\n\tpub {1}: {2}, {4}\n",
+                xml_name,
+                field.name,
+                field.render_field_type(),
+                bool_options,
+                comment,
+                prefix,
+            )
+        } else {
+            format!(
+                "\t#[yaserde({2}{4}default)]\n\tpub {0}: {1}, {3}\n",
+                field.name,
+                field.render_field_type(),
+                bool_options,
+                comment,
+                prefix
+            )
+        };
+
+        documentation + &rendered
+    }
+
+    fn attribute(&self, attribute: &Element) -> String {
+        // fixed values are constants of the owning struct
+        if attribute.fixed_value.is_some() {
+            return String::new();
+        }
+
+        let field_type = match &attribute.field_type {
+            None => return String::new(),
+            Some(ft) => ft,
+        };
+
+        let xml_name = match &attribute.xml_name {
+            None => return String::new(),
+            Some(xn) => xn,
+        };
+
+        let prefix = match &attribute.prefix {
+            Some(p) => format!("prefix = \"{}\", ", p),
+            None => "".to_string(),
+        };
+
+        let rendered = if attribute.optional {
+            format!(
+                "#[yaserde({}rename=\"{}\", attribute)]\npub {}: Option<{}>,\n",
+                prefix, xml_name, attribute.name, field_type
+            )
+        } else {
+            format!(
+                "#[yaserde({}rename=\"{}\", attribute)]\npub {}: {},\n",
+                prefix, xml_name, attribute.name, field_type
+            )
+        };
+
+        attribute.render_documentation("") + &rendered
+    }
+
+    fn fixed_attributes_field(&self, element: &Element) -> String {
+        format!(
+            "\t#[yaserde(rename = \"$fixed\", flatten, default)]\n\tpub fixed_attributes: Fixed<{}>,\n",
+            element.name
+        )
+    }
+
+    fn enum_begin(&self, element: &Element, comparable: bool) -> String {
+        let mut result = if comparable {
+            element.render_derive(&[
+                "Debug",
+                "PartialEq",
+                "YaSerialize",
+                "YaDeserialize",
+                "Clone",
+            ])
+        } else {
+            element.render_derive(&["Debug", "YaSerialize", "YaDeserialize", "Clone"])
+        };
+        result.push_str(&self.render_options(element));
+        result.push_str(&format!("pub enum {} {{\n", element.name));
+        result
+    }
+
+    fn variant(&self, variant: &Element) -> String {
+        let mut options = vec![];
+
+        if let Some(prefix) = &variant.prefix {
+            options.push(format!("prefix = \"{}\"", prefix));
+        }
+
+        if let Some(xml_name) = &variant.xml_name {
+            options.push(format!("rename = \"{}\"", xml_name));
+        }
+
+        let mut result = variant.render_documentation("\t");
+        if !options.is_empty() {
+            result.push_str(&format!("\t#[yaserde({})]\n", options.join(", ")));
+        }
+
+        if variant.field_type.is_some() {
+            result.push_str(&format!(
+                "\t{}({}),\n",
+                variant.name,
+                variant.render_field_type()
+            ));
+        } else {
+            result.push_str(&format!("\t{},\n", variant.name));
+        }
+
+        result
+    }
+
+    fn text_value(&self, element: &Element) -> String {
+        format!("impl_text_value!({});\n\n", element.name)
+    }
+
+    fn polymorphic_impls(&self, element: &Element, base: &str) -> String {
+        let mut matches = String::new();
+        let mut writes = String::new();
+        for variant in &element.children {
+            let variant = variant.borrow();
+            let path = variant.children.first().map(|c| c.borrow().name.clone());
+            match (&variant.xml_name, path) {
+                (Some(xsi_type), Some(path)) => {
+                    let local_name = xsi_type.rsplit(':').next().unwrap_or_default();
+                    matches.push_str(&format!(
+                        "\t\t\tSome(\"{}\") => {}::deserialize(reader).map({}::{}),\n",
+                        local_name,
+                        variant.render_field_type(),
+                        element.name,
+                        variant.name
+                    ));
+                    writes.push_str(&format!(
+                        "\t\t\t{}::{}(value) => {{\n\t\t\t\tlet mut value = value.clone();\n\t\t\t\tvalue.{} = Some(\"{}\".to_string());\n\t\t\t\tvalue.serialize(writer)\n\t\t\t}}\n",
+                        element.name, variant.name, path, xsi_type
+                    ));
+                }
+                _ => writes.push_str(&format!(
+                    "\t\t\t{}::{}(value) => value.serialize(writer),\n",
+                    element.name, variant.name
+                )),
+            }
+        }
+
+        format!(
+            r#"impl YaDeserialize for {0} {{
+	fn deserialize<R: std::io::Read>(
+		reader: &mut yaserde::de::Deserializer<R>,
+	) -> Result<Self, String> {{
+		let xsi_type = match reader.peek()? {{
+			yaserde::xml::reader::XmlEvent::StartElement {{ attributes, .. }} => attributes
+				.iter()
+				.find(|a| {{
+					a.name.local_name == "type"
+						&& a.name.namespace.as_deref() == Some("http://www.w3.org/2001/XMLSchema-instance")
+				}})
+				.and_then(|a| a.value.rsplit(':').next().map(|t| t.to_string())),
+			_ => None,
+		}};
+
+		match xsi_type.as_deref() {{
+{1}			_ => {4}::deserialize(reader).map({0}::{2}),
+		}}
+	}}
+}}
+
+impl YaSerialize for {0} {{
+	fn serialize<W: std::io::Write>(
+		&self,
+		writer: &mut yaserde::ser::Serializer<W>,
+	) -> Result<(), String> {{
+		match self {{
+{3}		}}
+	}}
+
+	fn serialize_attributes(
+		&self,
+		attributes: Vec<yaserde::xml::attribute::OwnedAttribute>,
+		namespace: yaserde::xml::namespace::Namespace,
+	) -> Result<
+		(
+			Vec<yaserde::xml::attribute::OwnedAttribute>,
+			yaserde::xml::namespace::Namespace,
+		),
+		String,
+	> {{
+		Ok((attributes, namespace))
+	}}
+}}
+
+"#,
+            element.name,
+            matches,
+            base,
+            writes,
+            base_type(element)
+        )
+    }
+}
+
+/// Generates types that are (de)serialized with serde and quick-xml. Attributes and text content
+/// follow the naming conventions of quick-xml. Names are written with their prefix, and read
+/// without it, as quick-xml matches the local names; the structs declare their namespaces in
+/// fields of their own.
+pub struct SerdeEmitter;
+
+impl Emitter for SerdeEmitter {
+    fn header(&self, _code: &str) -> String {
+        r#"#![allow(dead_code)]
+#![allow(unused_imports)]
+use serde::{Deserialize, Serialize};
+
+/// Serializes a value as XML, with quick-xml
+pub fn to_string<T: Serialize>(value: &T) -> Result<String, String> {
+    quick_xml::se::to_string(value).map_err(|e| e.to_string())
+}
+
+/// Deserializes a value from XML, with quick-xml
+pub fn from_str<T: serde::de::DeserializeOwned>(xml: &str) -> Result<T, String> {
+    quick_xml::de::from_str(xml).map_err(|e| e.to_string())
+}
+
+"#
+        .to_string()
+    }
+
+    fn module_imports(&self, _code: &str) -> String {
+        "use serde::{Deserialize, Serialize};\nuse super::*;\n".to_string()
+    }
+
+    fn support_types(&self) -> Vec<SupportType> {
+        vec![
+            SupportType {
+                name: "impl_text_value",
+                comment: Some(TEXT_VALUE_COMMENT),
+                code: include_str!("emitter/serde/text_value.rs"),
+            },
+            SupportType {
+                name: "XmlValue",
+                comment: Some("An element of type xs:anyType, of which the text is kept"),
+                code: include_str!("emitter/serde/xml_value.rs"),
+            },
+            SupportType {
+                name: "Fixed",
+                comment: None,
+                code: include_str!("emitter/serde/fixed.rs"),
+            },
+            SupportType {
+                name: "Xmlns",
+                comment: None,
+                code: include_str!("emitter/serde/xmlns.rs"),
+            },
+        ]
+    }
+
+    fn qname_impls(&self) -> &'static str {
+        "impl_text_value!(Qname);\n"
+    }
+
+    fn struct_begin(&self, element: &Element, derive_default: bool) -> String {
+        let mut result = element.render_derive(&struct_derives(
+            element,
+            derive_default,
+            &["Serialize", "Deserialize"],
+        ));
+        if let Some(xml_name) = &element.xml_name {
+            result.push_str(&format!(
+                "#[serde({})]\n",
+                rename(xml_name, &element.prefix)
+            ));
+        }
+        result.push_str(&format!("pub struct {} {{\n", element.name));
+        result
+    }
+
+    fn namespace_fields(&self, element: &Element) -> Vec<(String, String)> {
+        declared_namespaces(element)
+            .enumerate()
+            .map(|(index, (prefix, _))| {
+                let (name, attribute) = if prefix.is_empty() {
+                    ("xmlns".to_string(), "xmlns".to_string())
+                } else {
+                    let name: String = prefix
+                        .chars()
+                        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                        .collect();
+                    (
+                        format!("xmlns_{}", name.to_lowercase()),
+                        format!("xmlns:{}", prefix),
+                    )
+                };
+                let code = format!(
+                    "\t#[serde(rename = \"@{}\", default)]\n\tpub {}: Xmlns<{}, {}>,\n",
+                    attribute, name, element.name, index
+                );
+                (name, code)
+            })
+            .collect()
+    }
+
+    fn namespace_impls(&self, element: &Element) -> String {
+        let namespaces: String = declared_namespaces(element)
+            .map(|(prefix, uri)| format!("\t\t({:?}, {:?}),\n", prefix, uri))
+            .collect();
+        if namespaces.is_empty() {
+            return String::new();
+        }
+
+        format!(
+            "\nimpl XmlNamespaces for {0} {{\n\tconst NAMESPACES: &'static [(&'static str, &'static str)] = &[\n{1}\t];\n}}\n",
+            element.name, namespaces
+        )
+    }
+
+    fn field(&self, field: &Element) -> String {
+        let comment = match &field.comment {
+            None => "".to_string(),
+            Some(c) => format!("// {}", c),
+        };
+
+        // flattened fields take the elements of their type, whatever the name of the field
+        let mut options = if field.flatten {
+            "flatten".to_string()
+        } else if field.text_field {
+            "rename = \"$text\", default".to_string()
+        } else {
+            match &field.xml_name {
+                Some(xml_name) => format!("{}, default", rename(xml_name, &field.prefix)),
+                None => "default".to_string(),
+            }
+        };
+        for alias in &field.aliases {
+            options.push_str(&format!(", alias = \"{}\"", alias));
+        }
+        if field.optional && !field.vector {
+            options.push_str(", skip_serializing_if = \"Option::is_none\"");
+        }
+
+        format!(
+            "{}\t#[serde({})]\n\tpub {}: {}, {}\n",
+            field.render_documentation("\t"),
+            options,
+            field.name,
+            field.render_field_type(),
+            comment
+        )
+    }
+
+    fn attribute(&self, attribute: &Element) -> String {
+        // fixed values are constants of the owning struct
+        if attribute.fixed_value.is_some() {
+            return String::new();
+        }
+
+        let (field_type, xml_name) = match (&attribute.field_type, &attribute.xml_name) {
+            (Some(ft), Some(xn)) => (ft, xn),
+            _ => return String::new(),
+        };
+
+        let (field_type, skip) = if attribute.optional {
+            (
+                format!("Option<{}>", field_type),
+                ", skip_serializing_if = \"Option::is_none\"",
+            )
+        } else {
+            (field_type.to_string(), "")
+        };
+
+        format!(
+            "{}#[serde({}, default{})]\npub {}: {},\n",
+            attribute.render_documentation(""),
+            rename(&format!("@{}", xml_name), &attribute.prefix),
+            skip,
+            attribute.name,
+            field_type
+        )
+    }
+
+    fn fixed_attributes_field(&self, element: &Element) -> String {
+        format!(
+            "\t#[serde(skip)]\n\tpub fixed_attributes: Fixed<{}>,\n",
+            element.name
+        )
+    }
+
+    fn enum_begin(&self, element: &Element, comparable: bool) -> String {
+        let derive = if comparable {
+            element.render_derive(&["Debug", "PartialEq", "Serialize", "Deserialize", "Clone"])
+        } else {
+            element.render_derive(&["Debug", "Serialize", "Deserialize", "Clone"])
+        };
+        format!("{}pub enum {} {{\n", derive, element.name)
+    }
+
+    fn variant(&self, variant: &Element) -> String {
+        let mut result = variant.render_documentation("\t");
+        if let Some(xml_name) = &variant.xml_name {
+            result.push_str(&format!(
+                "\t#[serde({})]\n",
+                rename(xml_name, &variant.prefix)
+            ));
+        }
+
+        if variant.field_type.is_some() {
+            result.push_str(&format!(
+                "\t{}({}),\n",
+                variant.name,
+                variant.render_field_type()
+            ));
+        } else {
+            result.push_str(&format!("\t{},\n", variant.name));
+        }
+
+        result
+    }
+
+    fn text_value(&self, element: &Element) -> String {
+        format!("impl_text_value!({});\n\n", element.name)
+    }
+
+    fn polymorphic_impls(&self, element: &Element, base: &str) -> String {
+        let writes: String = element
+            .children
+            .iter()
+            .map(|variant| {
+                format!(
+                    "\t\t\t{}::{}(value) => value.serialize(serializer),\n",
+                    element.name,
+                    variant.borrow().name
+                )
+            })
+            .collect();
+
+        // without xsi:type, every value is deserialized as the base type
+        format!(
+            r#"impl Serialize for {0} {{
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {{
+		match self {{
+{2}		}}
+	}}
+}}
+
+impl<'de> Deserialize<'de> for {0} {{
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {{
+		{3}::deserialize(deserializer).map({0}::{1})
+	}}
+}}
+
+"#,
+            element.name,
+            base,
+            writes,
+            base_type(element)
+        )
+    }
+}
+
+/// The comment of the macro that (de)serializes text values
+const TEXT_VALUE_COMMENT: &str =
+    "(De)serializes a type as the text of an element, by means of its `Display` and `FromStr` implementations";
+
+/// The derives of a struct, with the derives of (de)serialization of the crate, leaving out the
+/// deserialization when it is written by hand
+fn struct_derives<'a>(
+    element: &Element,
+    derive_default: bool,
+    crate_derives: &[&'a str; 2],
+) -> Vec<&'a str> {
+    let mut derives = vec!["Debug"];
+    if derive_default {
+        derives.push("Default");
+    }
+    derives.push(crate_derives[0]);
+    if !element.custom_deserialize {
+        derives.push(crate_derives[1]);
+    }
+    derives.push("Clone");
+    derives
+}
+
+/// The serde rename of an XML name, which is written with its prefix and read without it. The
+/// prefix of an attribute goes after its @.
+fn rename(xml_name: &str, prefix: &Option<String>) -> String {
+    match prefix {
+        Some(prefix) => {
+            let (marker, local_name) = match xml_name.strip_prefix('@') {
+                Some(local_name) => ("@", local_name),
+                None => ("", xml_name),
+            };
+            format!(
+                "rename(serialize = \"{}{}:{}\", deserialize = \"{}\")",
+                marker, prefix, local_name, xml_name
+            )
+        }
+        None => format!("rename = \"{}\"", xml_name),
+    }
+}
+
+/// The namespaces that an element declares, as their prefixes and URIs
+fn declared_namespaces(element: &Element) -> impl Iterator<Item = (&str, &str)> {
+    element
+        .namespaces
+        .iter()
+        .filter_map(|namespace| namespace.split_once(": "))
+}
+
+/// Imports some of the names of a path, if any
+fn use_declaration(path: &str, names: &[&str]) -> String {
+    match names {
+        [] => String::new(),
+        [name] => format!("use {}::{};\n", path, name),
+        _ => format!("use {}::{{{}}};\n", path, names.join(", ")),
+    }
+}
+
+/// Whether code refers to a name without a path, which takes an import: `from_str` in
+/// `from_str(&xml)`, but not in `yaserde::de::from_str(&xml)` or in `xml.from_str()`
+fn refers_to(code: &str, name: &str) -> bool {
+    let is_identifier = |c: char| c.is_alphanumeric() || c == '_';
+    code.match_indices(name).any(|(start, _)| {
+        let before = code[..start].chars().next_back();
+        let after = code[start + name.len()..].chars().next();
+        !matches!(before, Some(c) if is_identifier(c) || c == ':' || c == '.')
+            && !matches!(after, Some(c) if is_identifier(c))
+    })
+}
+
+/// The type of the first variant of a polymorphic type, the base of the hierarchy
+fn base_type(element: &Element) -> String {
+    element
+        .children
+        .first()
+        .map(|base| base.borrow().render_field_type())
+        .unwrap_or_default()
+}
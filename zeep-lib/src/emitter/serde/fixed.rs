@@ -0,0 +1,15 @@
+/// A type with attributes that always have the same value
+pub trait FixedAttributes {
+    /// The qualified names of the attributes, with their values
+    const FIXED_ATTRIBUTES: &'static [(&'static str, &'static str)];
+}
+
+/// Stands in for the fixed attributes of its parent, which are not (de)serialized
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fixed<T>(std::marker::PhantomData<T>);
+
+impl<T> Default for Fixed<T> {
+    fn default() -> Self {
+        Fixed(std::marker::PhantomData)
+    }
+}
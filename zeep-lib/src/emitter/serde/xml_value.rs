@@ -0,0 +1,5 @@
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct XmlValue {
+    #[serde(rename = "$text", default)]
+    pub content: String,
+}
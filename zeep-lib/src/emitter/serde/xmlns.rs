@@ -0,0 +1,29 @@
+/// A type of which the element declares namespaces
+pub trait XmlNamespaces {
+    /// The prefixes of the namespaces, with their URIs
+    const NAMESPACES: &'static [(&'static str, &'static str)];
+}
+
+/// Declares one of the namespaces of its parent, as an xmlns attribute. The value read is left
+/// out, the namespace of the type is written whatever it was
+#[derive(Debug, Clone, PartialEq)]
+pub struct Xmlns<T, const N: usize>(std::marker::PhantomData<T>);
+
+impl<T, const N: usize> Default for Xmlns<T, N> {
+    fn default() -> Self {
+        Xmlns(std::marker::PhantomData)
+    }
+}
+
+impl<T: XmlNamespaces, const N: usize> serde::Serialize for Xmlns<T, N> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(T::NAMESPACES[N].1)
+    }
+}
+
+impl<'de, T, const N: usize> serde::Deserialize<'de> for Xmlns<T, N> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        serde::de::IgnoredAny::deserialize(deserializer)?;
+        Ok(Xmlns::default())
+    }
+}
@@ -0,0 +1,51 @@
+/// A type with attributes that always have the same value
+pub trait FixedAttributes {
+    /// The qualified names of the attributes, with their values
+    const FIXED_ATTRIBUTES: &'static [(&'static str, &'static str)];
+}
+
+/// Writes the fixed attributes of its parent, flattened into the parent element
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fixed<T>(std::marker::PhantomData<T>);
+
+impl<T> Default for Fixed<T> {
+    fn default() -> Self {
+        Fixed(std::marker::PhantomData)
+    }
+}
+
+impl<T> YaDeserialize for Fixed<T> {
+    fn deserialize<R: std::io::Read>(
+        _reader: &mut yaserde::de::Deserializer<R>,
+    ) -> Result<Self, String> {
+        Ok(Fixed::default())
+    }
+}
+
+impl<T: FixedAttributes> YaSerialize for Fixed<T> {
+    fn serialize<W: std::io::Write>(
+        &self,
+        _writer: &mut yaserde::ser::Serializer<W>,
+    ) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn serialize_attributes(
+        &self,
+        mut attributes: Vec<yaserde::xml::attribute::OwnedAttribute>,
+        namespace: yaserde::xml::namespace::Namespace,
+    ) -> Result<
+        (
+            Vec<yaserde::xml::attribute::OwnedAttribute>,
+            yaserde::xml::namespace::Namespace,
+        ),
+        String,
+    > {
+        for (name, value) in T::FIXED_ATTRIBUTES {
+            let name = yaserde::xml::name::Name::from(*name).to_owned();
+            attributes.push(yaserde::xml::attribute::OwnedAttribute::new(name, *value));
+        }
+
+        Ok((attributes, namespace))
+    }
+}
@@ -0,0 +1,81 @@
+impl YaDeserialize for Qname {
+    fn deserialize<R: std::io::Read>(
+        reader: &mut yaserde::de::Deserializer<R>,
+    ) -> Result<Self, String> {
+        let namespace = match reader.peek()? {
+            yaserde::xml::reader::XmlEvent::StartElement { namespace, .. } => Some(namespace.clone()),
+            _ => None,
+        };
+        if namespace.is_some() {
+            reader.next_event()?;
+        }
+
+        let text = match reader.peek()? {
+            yaserde::xml::reader::XmlEvent::Characters(text) => text.clone(),
+            _ => String::new(),
+        };
+        if !text.is_empty() {
+            reader.next_event()?;
+        }
+
+        // like built-in types, consume the end element, leaving the parent to read on
+        if let yaserde::xml::reader::XmlEvent::EndElement { .. } = reader.peek()? {
+            reader.next_event()?;
+        }
+
+        let mut qname: Qname = text.parse()?;
+        if let Some(namespace) = namespace {
+            qname.namespace = namespace
+                .get(qname.prefix.as_deref().unwrap_or_default())
+                .filter(|uri| !uri.is_empty())
+                .map(|uri| uri.to_string());
+        }
+
+        Ok(qname)
+    }
+}
+
+impl YaSerialize for Qname {
+    fn serialize<W: std::io::Write>(
+        &self,
+        writer: &mut yaserde::ser::Serializer<W>,
+    ) -> Result<(), String> {
+        let name = writer
+            .get_start_event_name()
+            .unwrap_or_else(|| "Qname".to_string());
+        let skip_start_end = writer.skip_start_end();
+
+        if !skip_start_end {
+            let mut start = yaserde::xml::writer::XmlEvent::start_element(name.as_str());
+            if let (Some(prefix), Some(uri)) = (&self.prefix, &self.namespace) {
+                start = start.ns(prefix.as_str(), uri.as_str());
+            }
+            writer.write(start).map_err(|e| e.to_string())?;
+        }
+
+        writer
+            .write(yaserde::xml::writer::XmlEvent::characters(&self.to_string()))
+            .map_err(|e| e.to_string())?;
+
+        if !skip_start_end {
+            writer
+                .write(yaserde::xml::writer::XmlEvent::end_element())
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    fn serialize_attributes(
+        &self,
+        attributes: Vec<yaserde::xml::attribute::OwnedAttribute>,
+        namespace: yaserde::xml::namespace::Namespace,
+    ) -> Result<
+        (
+            Vec<yaserde::xml::attribute::OwnedAttribute>,
+            yaserde::xml::namespace::Namespace,
+        ),
+        String,
+    > {
+        Ok((attributes, namespace))
+    }
+}
@@ -0,0 +1,99 @@
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RawXml(pub String);
+
+impl YaDeserialize for RawXml {
+    fn deserialize<R: std::io::Read>(
+        reader: &mut yaserde::de::Deserializer<R>,
+    ) -> Result<Self, String> {
+        // the unmatched elements are wrapped in the start element of their parent
+        if let yaserde::xml::reader::XmlEvent::StartElement { .. } = reader.peek()? {
+            reader.next_event()?;
+        }
+
+        read_inner_xml(reader).map(RawXml)
+    }
+}
+
+impl YaSerialize for RawXml {
+    fn serialize<W: std::io::Write>(
+        &self,
+        writer: &mut yaserde::ser::Serializer<W>,
+    ) -> Result<(), String> {
+        write_inner_xml(writer, &self.0)
+    }
+
+    fn serialize_attributes(
+        &self,
+        attributes: Vec<yaserde::xml::attribute::OwnedAttribute>,
+        namespace: yaserde::xml::namespace::Namespace,
+    ) -> Result<
+        (
+            Vec<yaserde::xml::attribute::OwnedAttribute>,
+            yaserde::xml::namespace::Namespace,
+        ),
+        String,
+    > {
+        Ok((attributes, namespace))
+    }
+}
+
+/// Reads the remaining content of the current element as XML, up to its end element
+fn read_inner_xml<R: std::io::Read>(
+    reader: &mut yaserde::de::Deserializer<R>,
+) -> Result<String, String> {
+    let mut buffer = Vec::new();
+    {
+        let mut writer = yaserde::xml::EmitterConfig::new()
+            .write_document_declaration(false)
+            .create_writer(&mut buffer);
+        let depth = reader.depth();
+
+        loop {
+            let at_parent = reader.depth() == depth;
+            match reader.peek()? {
+                yaserde::xml::reader::XmlEvent::EndDocument => break,
+                yaserde::xml::reader::XmlEvent::EndElement { .. } if at_parent => break,
+                _ => {}
+            }
+
+            let event = reader.next_event()?;
+            if let Some(event) = event.as_writer_event() {
+                writer.write(event).map_err(|e| e.to_string())?;
+            }
+        }
+    }
+
+    String::from_utf8(buffer).map_err(|e| e.to_string())
+}
+
+/// Writes XML read by `read_inner_xml`
+fn write_inner_xml<W: std::io::Write>(
+    writer: &mut yaserde::ser::Serializer<W>,
+    content: &str,
+) -> Result<(), String> {
+    let content = format!("<raw>{}</raw>", content);
+    let reader = yaserde::xml::EventReader::new(content.as_bytes());
+    let mut depth = 0;
+
+    for event in reader {
+        let event = event.map_err(|e| e.to_string())?;
+        match event {
+            yaserde::xml::reader::XmlEvent::StartElement { .. } => depth += 1,
+            yaserde::xml::reader::XmlEvent::EndElement { .. } => depth -= 1,
+            _ => {}
+        }
+
+        // skip the wrapping element
+        let is_wrapper = depth == 0
+            || matches!(event, yaserde::xml::reader::XmlEvent::StartElement { .. } if depth == 1);
+        if is_wrapper {
+            continue;
+        }
+
+        if let Some(event) = event.as_writer_event() {
+            writer.write(event).map_err(|e| e.to_string())?;
+        }
+    }
+
+    Ok(())
+}
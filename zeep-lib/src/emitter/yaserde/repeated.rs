@@ -0,0 +1,133 @@
+/// A group of elements that repeats as a whole, like a sequence or choice with maxOccurs > 1
+pub trait RepeatedGroup {
+    /// The position of an element in the group, and whether it repeats within an occurrence
+    fn position(name: &str) -> Option<(usize, bool)>;
+}
+
+/// The occurrences of a repeating group, flattened into the parent element
+#[derive(Debug, Clone, PartialEq)]
+pub struct Repeated<T>(pub Vec<T>);
+
+impl<T> Default for Repeated<T> {
+    fn default() -> Self {
+        Repeated(Vec::new())
+    }
+}
+
+impl<T: YaDeserialize + RepeatedGroup> YaDeserialize for Repeated<T> {
+    fn deserialize<R: std::io::Read>(
+        reader: &mut yaserde::de::Deserializer<R>,
+    ) -> Result<Self, String> {
+        // the unmatched elements are wrapped in the start element of their parent
+        let parent = match reader.peek()? {
+            yaserde::xml::reader::XmlEvent::StartElement { name, namespace, .. } => {
+                yaserde::xml::reader::XmlEvent::StartElement {
+                    name: name.clone(),
+                    attributes: Vec::new(),
+                    namespace: namespace.clone(),
+                }
+            }
+            _ => yaserde::xml::reader::XmlEvent::StartElement {
+                name: yaserde::xml::name::OwnedName::local("occurrence"),
+                attributes: Vec::new(),
+                namespace: yaserde::xml::namespace::Namespace::empty(),
+            },
+        };
+        if let yaserde::xml::reader::XmlEvent::StartElement { .. } = reader.peek()? {
+            reader.next_event()?;
+        }
+
+        let content = format!("<raw>{}</raw>", read_inner_xml(reader)?);
+        let mut occurrences: Vec<Vec<yaserde::xml::reader::XmlEvent>> = Vec::new();
+        let mut last_position = None;
+        let mut in_group = false;
+        let mut depth = 0;
+
+        // a new occurrence starts whenever an element does not follow the previous one
+        for event in yaserde::xml::EventReader::new(content.as_bytes()) {
+            let event = event.map_err(|e| e.to_string())?;
+            match &event {
+                yaserde::xml::reader::XmlEvent::StartElement { name, .. } => {
+                    depth += 1;
+                    if depth == 2 {
+                        let position = T::position(&name.local_name);
+                        in_group = position.is_some();
+                        if let Some((position, repeats)) = position {
+                            let follows = match last_position {
+                                None => false,
+                                Some(last) => position > last || (position == last && repeats),
+                            };
+                            if !follows {
+                                occurrences.push(Vec::new());
+                            }
+                            last_position = Some(position);
+                        }
+                    }
+                }
+                yaserde::xml::reader::XmlEvent::EndElement { .. } => depth -= 1,
+                _ => {}
+            }
+
+            let inside = depth >= 2
+                || (depth == 1 && matches!(event, yaserde::xml::reader::XmlEvent::EndElement { .. }));
+            if in_group && inside {
+                if let Some(occurrence) = occurrences.last_mut() {
+                    occurrence.push(event);
+                }
+            }
+        }
+
+        occurrences
+            .iter()
+            .map(|events| {
+                let mut buffer = Vec::new();
+                {
+                    let mut writer = yaserde::xml::EmitterConfig::new()
+                        .write_document_declaration(false)
+                        .create_writer(&mut buffer);
+                    for event in std::iter::once(&parent).chain(events) {
+                        if let Some(event) = event.as_writer_event() {
+                            writer.write(event).map_err(|e| e.to_string())?;
+                        }
+                    }
+                    writer
+                        .write(yaserde::xml::writer::XmlEvent::end_element())
+                        .map_err(|e| e.to_string())?;
+                }
+
+                let content = String::from_utf8(buffer).map_err(|e| e.to_string())?;
+                yaserde::de::from_str(&content)
+            })
+            .collect::<Result<Vec<T>, String>>()
+            .map(Repeated)
+    }
+}
+
+impl<T: YaSerialize> YaSerialize for Repeated<T> {
+    fn serialize<W: std::io::Write>(
+        &self,
+        writer: &mut yaserde::ser::Serializer<W>,
+    ) -> Result<(), String> {
+        for occurrence in &self.0 {
+            writer.set_skip_start_end(true);
+            occurrence.serialize(writer)?;
+        }
+        writer.set_skip_start_end(false);
+
+        Ok(())
+    }
+
+    fn serialize_attributes(
+        &self,
+        attributes: Vec<yaserde::xml::attribute::OwnedAttribute>,
+        namespace: yaserde::xml::namespace::Namespace,
+    ) -> Result<
+        (
+            Vec<yaserde::xml::attribute::OwnedAttribute>,
+            yaserde::xml::namespace::Namespace,
+        ),
+        String,
+    > {
+        Ok((attributes, namespace))
+    }
+}
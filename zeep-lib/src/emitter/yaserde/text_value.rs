@@ -0,0 +1,70 @@
+#[allow(unused_macros)]
+macro_rules! impl_text_value {
+    ($name:ident) => {
+        impl yaserde::YaSerialize for $name {
+            fn serialize<W: std::io::Write>(
+                &self,
+                writer: &mut yaserde::ser::Serializer<W>,
+            ) -> Result<(), String> {
+                let name = writer
+                    .get_start_event_name()
+                    .unwrap_or_else(|| stringify!($name).to_string());
+                let skip_start_end = writer.skip_start_end();
+
+                if !skip_start_end {
+                    writer
+                        .write(yaserde::xml::writer::XmlEvent::start_element(name.as_str()))
+                        .map_err(|e| e.to_string())?;
+                }
+                writer
+                    .write(yaserde::xml::writer::XmlEvent::characters(&self.to_string()))
+                    .map_err(|e| e.to_string())?;
+                if !skip_start_end {
+                    writer
+                        .write(yaserde::xml::writer::XmlEvent::end_element())
+                        .map_err(|e| e.to_string())?;
+                }
+                Ok(())
+            }
+
+            fn serialize_attributes(
+                &self,
+                attributes: Vec<yaserde::xml::attribute::OwnedAttribute>,
+                namespace: yaserde::xml::namespace::Namespace,
+            ) -> Result<
+                (
+                    Vec<yaserde::xml::attribute::OwnedAttribute>,
+                    yaserde::xml::namespace::Namespace,
+                ),
+                String,
+            > {
+                Ok((attributes, namespace))
+            }
+        }
+
+        impl yaserde::YaDeserialize for $name {
+            fn deserialize<R: std::io::Read>(
+                reader: &mut yaserde::de::Deserializer<R>,
+            ) -> Result<Self, String> {
+                if let yaserde::xml::reader::XmlEvent::StartElement { .. } = reader.peek()? {
+                    reader.next_event()?;
+                }
+
+                let text = match reader.peek()? {
+                    yaserde::xml::reader::XmlEvent::Characters(text) => text.clone(),
+                    _ => String::new(),
+                };
+                if !text.is_empty() {
+                    reader.next_event()?;
+                }
+
+                // like built-in types, consume the end element, leaving the parent to read on
+                if let yaserde::xml::reader::XmlEvent::EndElement { .. } = reader.peek()? {
+                    reader.next_event()?;
+                }
+
+                text.parse()
+            }
+        }
+    };
+}
@@ -0,0 +1,87 @@
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct XmlValue {
+    pub attributes: Vec<yaserde::xml::attribute::OwnedAttribute>,
+    /// The namespaces in scope, as (prefix, uri), so qualified names in the value resolve
+    pub namespaces: Vec<(String, String)>,
+    pub content: String,
+}
+
+impl YaDeserialize for XmlValue {
+    fn deserialize<R: std::io::Read>(
+        reader: &mut yaserde::de::Deserializer<R>,
+    ) -> Result<Self, String> {
+        let (attributes, namespace) = match reader.next_event()? {
+            yaserde::xml::reader::XmlEvent::StartElement {
+                attributes,
+                namespace,
+                ..
+            } => (attributes, namespace),
+            event => return Err(format!("unexpected event {:?}", event)),
+        };
+
+        let namespaces = namespace
+            .0
+            .into_iter()
+            .filter(|(prefix, uri)| prefix != "xml" && prefix != "xmlns" && !uri.is_empty())
+            .collect();
+
+        let content = read_inner_xml(reader)?;
+
+        // like built-in types, consume the end element, leaving the parent to read on
+        if let yaserde::xml::reader::XmlEvent::EndElement { .. } = reader.peek()? {
+            reader.next_event()?;
+        }
+
+        Ok(XmlValue {
+            attributes,
+            namespaces,
+            content,
+        })
+    }
+}
+
+impl YaSerialize for XmlValue {
+    fn serialize<W: std::io::Write>(
+        &self,
+        writer: &mut yaserde::ser::Serializer<W>,
+    ) -> Result<(), String> {
+        let name = writer
+            .get_start_event_name()
+            .unwrap_or_else(|| "XmlValue".to_string());
+        let skip_start_end = writer.skip_start_end();
+
+        if !skip_start_end {
+            let mut start = yaserde::xml::writer::XmlEvent::start_element(name.as_str());
+            for (prefix, uri) in &self.namespaces {
+                start = start.ns(prefix.as_str(), uri.as_str());
+            }
+            for attribute in &self.attributes {
+                start = start.attr(attribute.name.borrow(), &attribute.value);
+            }
+            writer.write(start).map_err(|e| e.to_string())?;
+        }
+
+        write_inner_xml(writer, &self.content)?;
+
+        if !skip_start_end {
+            writer
+                .write(yaserde::xml::writer::XmlEvent::end_element())
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    fn serialize_attributes(
+        &self,
+        attributes: Vec<yaserde::xml::attribute::OwnedAttribute>,
+        namespace: yaserde::xml::namespace::Namespace,
+    ) -> Result<
+        (
+            Vec<yaserde::xml::attribute::OwnedAttribute>,
+            yaserde::xml::namespace::Namespace,
+        ),
+        String,
+    > {
+        Ok((attributes, namespace))
+    }
+}
@@ -1,36 +1,85 @@
-use std::{error, fmt};
+use crate::coverage::Construct;
+use std::{error, fmt, io};
 
-pub type WriterResult<T> = std::result::Result<T, WriterError>;
+pub type ZeepResult<T> = std::result::Result<T, ZeepError>;
 
-#[derive(Debug, Clone)]
-pub struct WriterError {
-    pub message: String,
+/// The reasons a WSDL document or XML schema can not be turned into code
+#[derive(Debug)]
+pub enum ZeepError {
+    /// A document could not be read, or retrieved from its url
+    Io { path: String, source: io::Error },
+    /// The generated code could not be written to the file or directory it goes to
+    Output { path: String, source: io::Error },
+    /// A document is no well-formed XML, the position is where the parser gave up
+    Xml {
+        path: String,
+        line: u32,
+        column: u32,
+        message: String,
+    },
+    /// A construct that is valid, or common, but that zeep has no translation for, with the
+    /// file and line it is at
+    Unsupported { position: String, message: String },
+    /// A reference to a definition that none of the documents contain, with the file and line
+    /// of the reference
+    Unresolved { position: String, message: String },
+    /// The constructs that no code was generated for, which strict mode does not allow
+    Skipped(Vec<Construct>),
+    /// A configuration file is no valid TOML, or has options that do not exist
+    Config { path: String, message: String },
 }
 
-impl fmt::Display for WriterError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "writer error: {}", self.message)
-    }
-}
-
-impl error::Error for WriterError {
-    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
-        None
+impl ZeepError {
+    pub(crate) fn xml(path: &str, err: roxmltree::Error) -> Self {
+        let position = err.pos();
+        ZeepError::Xml {
+            path: path.to_string(),
+            line: position.row,
+            column: position.col,
+            message: err.to_string(),
+        }
     }
 }
 
-impl std::convert::From<std::io::Error> for WriterError {
-    fn from(err: std::io::Error) -> Self {
-        WriterError {
-            message: err.to_string(),
+impl fmt::Display for ZeepError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ZeepError::Io { path, source } => write!(f, "{}: {}", path, source),
+            ZeepError::Output { path, source } => {
+                write!(f, "unable to write {}: {}", path, source)
+            }
+            ZeepError::Xml {
+                path,
+                line,
+                column,
+                message,
+            } => write!(
+                f,
+                "unable to parse {} at {}:{}: {}",
+                path, line, column, message
+            ),
+            ZeepError::Unsupported { position, message } => {
+                write!(f, "{}: unsupported {}", position, message)
+            }
+            ZeepError::Unresolved { position, message } => {
+                write!(f, "{}: unresolved {}", position, message)
+            }
+            ZeepError::Skipped(nodes) => {
+                write!(f, "{} unsupported constructs:", nodes.len())?;
+                nodes.iter().try_for_each(|node| write!(f, "\n\t{}", node))
+            }
+            ZeepError::Config { path, message } => {
+                write!(f, "invalid configuration {}: {}", path, message)
+            }
         }
     }
 }
 
-impl std::convert::From<roxmltree::Error> for WriterError {
-    fn from(err: roxmltree::Error) -> Self {
-        WriterError {
-            message: err.to_string(),
+impl error::Error for ZeepError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            ZeepError::Io { source, .. } | ZeepError::Output { source, .. } => Some(source),
+            _ => None,
         }
     }
 }
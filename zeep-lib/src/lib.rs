@@ -1,6 +1,12 @@
+pub mod cache;
+pub mod config;
+pub mod coverage;
 mod debug;
 mod element;
-mod error;
+mod emitter;
+pub mod error;
+pub mod model;
+pub mod scaffold;
 pub mod writer;
 
 #[macro_use]
@@ -0,0 +1,949 @@
+//! # Model
+//! The services described by WSDL documents: their messages, port types, bindings and services.
+//! The model is read from a document before any code is generated for it, and the messages, port
+//! types, bindings and services are generated from the model, rather than from how the document
+//! is written.
+//!
+//! Only the WSDL side of the documents is modelled. There is no model of the schemas: the writer
+//! still generates their types while it walks the nodes of the schemas, so it is no thin layer
+//! over the model.
+//!
+use crate::coverage::tag;
+use inflector::cases::pascalcase::to_pascal_case;
+use roxmltree::Node;
+use serde_json::{json, Value};
+use std::collections::HashSet;
+
+const WSDL2_NS: &str = "http://www.w3.org/ns/wsdl";
+const WSDL2_SOAP_NS: &str = "http://www.w3.org/ns/wsdl/soap";
+const SOAP12_NS: &str = "http://schemas.xmlsoap.org/wsdl/soap12/";
+const HTTP_NS: &str = "http://schemas.xmlsoap.org/wsdl/http/";
+
+/// A name qualified by the namespace it is defined in
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct QName {
+    pub namespace: Option<String>,
+    pub name: String,
+}
+
+/// Where a definition is written, for the errors and warnings that point at it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Position {
+    pub file: String,
+    pub line: u32,
+    /// The name of the element, with the prefix it is written with
+    pub tag: String,
+}
+
+/// Everything the WSDL documents processed so far describe, imported documents included
+#[derive(Debug, Clone, Default)]
+pub struct Definitions {
+    pub messages: Vec<Message>,
+    pub port_types: Vec<PortType>,
+    pub bindings: Vec<Binding>,
+    pub services: Vec<Service>,
+}
+
+/// A WSDL message. The messages of a WSDL 2.0 document are made up, one for every element an
+/// operation or fault of an interface refers to.
+#[derive(Debug, Clone)]
+pub struct Message {
+    pub name: QName,
+    pub parts: Vec<Part>,
+    pub position: Position,
+}
+
+/// A part of a message, declared with either an element or a type. The part of a made up message
+/// is named after its element.
+#[derive(Debug, Clone)]
+pub struct Part {
+    pub name: String,
+    pub element: Option<QName>,
+    pub type_name: Option<QName>,
+    pub position: Position,
+}
+
+/// A WSDL 1.1 port type, or a WSDL 2.0 interface
+#[derive(Debug, Clone)]
+pub struct PortType {
+    pub name: QName,
+    pub operations: Vec<Operation>,
+    pub documentation: Option<String>,
+    pub position: Position,
+}
+
+/// An abstract operation, with the messages it receives and sends
+#[derive(Debug, Clone)]
+pub struct Operation {
+    pub name: String,
+    pub input: Option<OperationMessage>,
+    pub output: Option<OperationMessage>,
+    pub faults: Vec<Fault>,
+    pub documentation: Option<String>,
+}
+
+/// The input or output of an operation: the message, and the name the operation gives it, if any
+#[derive(Debug, Clone)]
+pub struct OperationMessage {
+    pub name: Option<String>,
+    pub message: QName,
+}
+
+/// A fault an operation declares, by name and the message it sends
+#[derive(Debug, Clone)]
+pub struct Fault {
+    pub name: String,
+    pub message: QName,
+}
+
+/// How a binding sends the messages of its port type
+#[derive(Debug, Clone, PartialEq)]
+pub enum Protocol {
+    Soap(SoapVersion),
+    /// Plain HTTP, with the verb of the http:binding
+    Http(String),
+    /// A WSDL 2.0 binding of another type, by the URI of the type
+    Other(String),
+}
+
+/// How a binding operation lays the parts of its messages out in the SOAP body
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Style {
+    /// The parts are the body
+    #[default]
+    Document,
+    /// The parts are wrapped in an element named after the operation
+    Rpc,
+}
+
+/// The SOAP version of a binding, which decides the envelope and how the action is sent
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SoapVersion {
+    Soap11,
+    Soap12,
+}
+
+/// A binding of a port type to a protocol
+#[derive(Debug, Clone)]
+pub struct Binding {
+    pub name: QName,
+    pub port_type: QName,
+    pub protocol: Protocol,
+    pub operations: Vec<BindingOperation>,
+    pub documentation: Option<String>,
+    pub position: Position,
+}
+
+/// An operation of a binding, with the SOAPAction or HTTP location it is called at
+#[derive(Debug, Clone)]
+pub struct BindingOperation {
+    pub name: String,
+    pub action: Option<String>,
+    pub location: Option<String>,
+    /// The style of the operation, or else of its binding
+    pub style: Style,
+    pub input: BindingMessage,
+    pub output: BindingMessage,
+    pub documentation: Option<String>,
+    pub position: Position,
+}
+
+/// How a binding operation sends its input, or receives its output
+#[derive(Debug, Clone, Default)]
+pub struct BindingMessage {
+    /// The parts the soap:body takes, none when it takes every part
+    pub body_parts: Option<Vec<String>>,
+    /// The namespace of the soap:body, which qualifies the wrapper of rpc style parts
+    pub namespace: Option<String>,
+    /// The encoding style of encoded parts, none for literal ones
+    pub encoding_style: Option<String>,
+    pub headers: Vec<Header>,
+    /// Whether a MIME binding sends the message in a multipart/related body
+    pub multipart: bool,
+    /// The parts the MIME binding sends as attachments
+    pub attachments: Vec<String>,
+    /// Whether the parts of an HTTP input take the place of the placeholders of the location
+    pub url_replacement: bool,
+}
+
+/// A part of a message that a binding operation sends in the SOAP header
+#[derive(Debug, Clone)]
+pub struct Header {
+    pub message: QName,
+    pub part: String,
+    pub position: Position,
+}
+
+/// A WSDL service, with the ports, or WSDL 2.0 endpoints, it is offered at
+#[derive(Debug, Clone)]
+pub struct Service {
+    pub name: QName,
+    pub ports: Vec<Port>,
+    pub documentation: Option<String>,
+    pub position: Position,
+}
+
+/// The address a binding is offered at
+#[derive(Debug, Clone)]
+pub struct Port {
+    pub name: String,
+    pub binding: QName,
+    pub address: String,
+    pub documentation: Option<String>,
+}
+
+impl Definitions {
+    /// Reads the messages, port types, bindings and services of a WSDL 1.1 definitions, or a
+    /// WSDL 2.0 description, in the file given. Imports are left to the caller.
+    pub fn read(node: &Node, file: &str) -> Definitions {
+        let source = Source {
+            tns: node.attribute("targetNamespace"),
+            file,
+        };
+        let children = |tag: &'static str| node.children().filter(move |c| c.has_tag_name(tag));
+
+        let mut definitions = Definitions::default();
+        if node.tag_name().namespace() == Some(WSDL2_NS) {
+            for interface in children("interface") {
+                definitions
+                    .messages
+                    .extend(Message::read_interface(&interface, source));
+                definitions
+                    .port_types
+                    .extend(PortType::read(&interface, source));
+            }
+        } else {
+            definitions.messages = children("message")
+                .filter_map(|n| Message::read(&n, source))
+                .collect();
+            definitions.port_types = children("portType")
+                .filter_map(|n| PortType::read(&n, source))
+                .collect();
+        }
+        definitions.bindings = children("binding")
+            .filter_map(|n| Binding::read(&n, source))
+            .collect();
+        definitions.services = children("service")
+            .filter_map(|n| Service::read(&n, source))
+            .collect();
+        definitions
+    }
+
+    /// Adds the definitions of another document
+    pub fn extend(&mut self, other: Definitions) {
+        self.messages.extend(other.messages);
+        self.port_types.extend(other.port_types);
+        self.bindings.extend(other.bindings);
+        self.services.extend(other.services);
+    }
+
+    /// The message of a name
+    pub fn message(&self, name: &QName) -> Option<&Message> {
+        self.messages.iter().find(|m| &m.name == name)
+    }
+
+    /// The first operation of a binding with the given name
+    pub fn binding_operation(&self, name: &str) -> Option<&BindingOperation> {
+        self.bindings
+            .iter()
+            .flat_map(|b| b.operations.iter())
+            .find(|o| o.name == name)
+    }
+
+    /// How the binding operations send or receive a message, for every operation of a port type
+    /// that sends or receives it
+    fn message_bindings(&self, message: &QName) -> Vec<&BindingMessage> {
+        let mut bindings = Vec::new();
+
+        let operations = self.port_types.iter().flat_map(|p| p.operations.iter());
+        for operation in operations {
+            let binding_operations = || {
+                self.bindings
+                    .iter()
+                    .flat_map(|b| b.operations.iter())
+                    .filter(|o| o.name == operation.name)
+            };
+            if operation.input.as_ref().map(|i| &i.message) == Some(message) {
+                bindings.extend(binding_operations().map(|o| &o.input));
+            }
+            if operation.output.as_ref().map(|o| &o.message) == Some(message) {
+                bindings.extend(binding_operations().map(|o| &o.output));
+            }
+        }
+
+        bindings
+    }
+
+    /// The parts of a message that the soap:body of the bindings takes, none when the body takes
+    /// every part
+    pub fn body_parts(&self, message: &QName) -> Option<HashSet<&str>> {
+        let mut parts = None;
+
+        for binding in self.message_bindings(message) {
+            match &binding.body_parts {
+                None => return None,
+                Some(p) => parts
+                    .get_or_insert_with(HashSet::new)
+                    .extend(p.iter().map(String::as_str)),
+            }
+        }
+
+        parts
+    }
+
+    /// The parts of a message that a binding sends apart from the body: in the SOAP header, or as
+    /// attachments of a MIME binding
+    pub fn outside_parts(&self, message: &QName) -> HashSet<&str> {
+        let headers = self
+            .bindings
+            .iter()
+            .flat_map(|b| b.operations.iter())
+            .flat_map(|o| o.input.headers.iter().chain(o.output.headers.iter()))
+            .filter(|h| &h.message == message)
+            .map(|h| h.part.as_str());
+        let attachments = self
+            .message_bindings(message)
+            .into_iter()
+            .flat_map(|b| b.attachments.iter().map(String::as_str));
+
+        headers.chain(attachments).collect()
+    }
+
+    /// The definitions as JSON, for tooling that builds on the model rather than on the WSDL
+    /// documents
+    pub fn to_json(&self) -> Value {
+        json!({
+            "messages": self.messages.iter().map(Message::to_json).collect::<Vec<_>>(),
+            "port_types": self.port_types.iter().map(PortType::to_json).collect::<Vec<_>>(),
+            "bindings": self.bindings.iter().map(Binding::to_json).collect::<Vec<_>>(),
+            "services": self.services.iter().map(Service::to_json).collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// The document that definitions are read from
+#[derive(Clone, Copy)]
+struct Source<'a> {
+    tns: Option<&'a str>,
+    file: &'a str,
+}
+
+impl Source<'_> {
+    /// A name defined by the document, in its target namespace
+    fn defined(&self, name: &str) -> QName {
+        QName {
+            namespace: self.tns.map(str::to_string),
+            name: name.to_string(),
+        }
+    }
+
+    fn position(&self, node: &Node) -> Position {
+        Position {
+            file: self.file.to_string(),
+            line: node.document().text_pos_at(node.range().start).row,
+            tag: tag(node),
+        }
+    }
+}
+
+impl QName {
+    /// Resolves a prefixed name in the scope of a node. Names without a prefix are in the
+    /// default namespace of the node.
+    pub fn resolve(node: &Node, name: &str) -> QName {
+        let (prefix, local_name) = match name.split_once(':') {
+            Some((prefix, local_name)) => (Some(prefix), local_name),
+            None => (None, name),
+        };
+        QName {
+            namespace: node.lookup_namespace_uri(prefix).map(str::to_string),
+            name: local_name.to_string(),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({ "namespace": self.namespace, "name": self.name })
+    }
+}
+
+impl std::fmt::Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)
+    }
+}
+
+impl Message {
+    fn to_json(&self) -> Value {
+        json!({
+            "name": self.name.to_json(),
+            "parts": self.parts.iter().map(|part| json!({
+                "name": part.name,
+                "element": part.element.as_ref().map(QName::to_json),
+                "type": part.type_name.as_ref().map(QName::to_json),
+            })).collect::<Vec<_>>(),
+        })
+    }
+}
+
+impl PortType {
+    fn to_json(&self) -> Value {
+        let message = |m: &Option<OperationMessage>| m.as_ref().map(|m| m.message.to_json());
+        json!({
+            "name": self.name.to_json(),
+            "operations": self.operations.iter().map(|operation| json!({
+                "name": operation.name,
+                "input": message(&operation.input),
+                "output": message(&operation.output),
+                "faults": operation.faults.iter().map(|fault| json!({
+                    "name": fault.name,
+                    "message": fault.message.to_json(),
+                })).collect::<Vec<_>>(),
+                "documentation": operation.documentation,
+            })).collect::<Vec<_>>(),
+            "documentation": self.documentation,
+        })
+    }
+}
+
+impl Binding {
+    fn to_json(&self) -> Value {
+        let protocol = match &self.protocol {
+            Protocol::Soap(SoapVersion::Soap11) => json!({ "soap": "1.1" }),
+            Protocol::Soap(SoapVersion::Soap12) => json!({ "soap": "1.2" }),
+            Protocol::Http(verb) => json!({ "http": verb }),
+            Protocol::Other(uri) => json!({ "other": uri }),
+        };
+        json!({
+            "name": self.name.to_json(),
+            "port_type": self.port_type.to_json(),
+            "protocol": protocol,
+            "operations": self.operations.iter().map(|operation| json!({
+                "name": operation.name,
+                "action": operation.action,
+                "location": operation.location,
+                "style": match operation.style {
+                    Style::Document => "document",
+                    Style::Rpc => "rpc",
+                },
+                "input": operation.input.to_json(),
+                "output": operation.output.to_json(),
+                "documentation": operation.documentation,
+            })).collect::<Vec<_>>(),
+            "documentation": self.documentation,
+        })
+    }
+}
+
+impl BindingMessage {
+    fn to_json(&self) -> Value {
+        json!({
+            "body_parts": self.body_parts,
+            "headers": self.headers.iter().map(|header| json!({
+                "message": header.message.to_json(),
+                "part": header.part,
+            })).collect::<Vec<_>>(),
+            "attachments": self.attachments,
+        })
+    }
+}
+
+impl Service {
+    fn to_json(&self) -> Value {
+        json!({
+            "name": self.name.to_json(),
+            "ports": self.ports.iter().map(|port| json!({
+                "name": port.name,
+                "binding": port.binding.to_json(),
+                "address": port.address,
+                "documentation": port.documentation,
+            })).collect::<Vec<_>>(),
+            "documentation": self.documentation,
+        })
+    }
+}
+
+impl Message {
+    fn read(node: &Node, source: Source) -> Option<Message> {
+        Some(Message {
+            name: source.defined(node.attribute("name")?),
+            parts: node
+                .children()
+                .filter(|c| c.has_tag_name("part"))
+                .filter_map(|part| {
+                    Some(Part {
+                        name: part.attribute("name")?.to_string(),
+                        element: part.attribute("element").map(|e| QName::resolve(&part, e)),
+                        type_name: part.attribute("type").map(|t| QName::resolve(&part, t)),
+                        position: source.position(&part),
+                    })
+                })
+                .collect(),
+            position: source.position(node),
+        })
+    }
+
+    /// The messages made up for the elements the faults of a WSDL 2.0 interface, and the inputs
+    /// and outputs of its operations, refer to
+    fn read_interface(node: &Node, source: Source) -> Vec<Message> {
+        let references = node.children().filter(|c| c.has_tag_name("fault")).chain(
+            node.children()
+                .filter(|c| c.has_tag_name("operation"))
+                .flat_map(|c| c.children())
+                .filter(|c| c.has_tag_name("input") || c.has_tag_name("output")),
+        );
+
+        references
+            .filter_map(|c| {
+                let name = interface_message_name(&c)?;
+                let element = QName::resolve(&c, c.attribute("element")?);
+                Some(Message {
+                    name: source.defined(&name),
+                    parts: vec![Part {
+                        name: element.name.clone(),
+                        element: Some(element),
+                        type_name: None,
+                        position: source.position(&c),
+                    }],
+                    position: source.position(&c),
+                })
+            })
+            .collect()
+    }
+}
+
+impl PortType {
+    fn read(node: &Node, source: Source) -> Option<PortType> {
+        Some(PortType {
+            name: source.defined(node.attribute("name")?),
+            operations: node
+                .children()
+                .filter(|c| c.has_tag_name("operation"))
+                .filter_map(|n| Operation::read(&n, source))
+                .collect(),
+            documentation: documentation(node),
+            position: source.position(node),
+        })
+    }
+}
+
+impl Operation {
+    fn read(node: &Node, source: Source) -> Option<Operation> {
+        // WSDL 2.0 refers to the messages made up for the interface
+        let message = |tag: &str| {
+            let child = node.children().find(|c| c.has_tag_name(tag))?;
+            let message = match child.attribute("message") {
+                Some(m) => QName::resolve(&child, m),
+                None => source.defined(&interface_message_name(&child)?),
+            };
+            Some(OperationMessage {
+                name: child.attribute("name").map(str::to_string),
+                message,
+            })
+        };
+
+        Some(Operation {
+            name: node.attribute("name")?.to_string(),
+            input: message("input"),
+            output: message("output"),
+            faults: node
+                .children()
+                .filter(|c| c.has_tag_name("fault") || c.has_tag_name("outfault"))
+                .filter_map(|fault| {
+                    let message = match fault.attribute("message") {
+                        Some(m) => QName::resolve(&fault, m),
+                        None => source.defined(&interface_message_name(&fault)?),
+                    };
+                    let name = fault
+                        .attribute("name")
+                        .map(str::to_string)
+                        .unwrap_or_else(|| message.name.clone());
+                    Some(Fault { name, message })
+                })
+                .collect(),
+            documentation: documentation(node),
+        })
+    }
+}
+
+impl Binding {
+    fn read(node: &Node, source: Source) -> Option<Binding> {
+        let wsdl2 = node.tag_name().namespace() == Some(WSDL2_NS);
+        let port_type = node.attribute(if wsdl2 { "interface" } else { "type" })?;
+        let protocol = match (wsdl2, http_verb(node)) {
+            (true, _) if node.attribute("type") != Some(WSDL2_SOAP_NS) => {
+                Protocol::Other(node.attribute("type").unwrap_or_default().to_string())
+            }
+            (_, Some(verb)) => Protocol::Http(verb.to_string()),
+            (_, None) => Protocol::Soap(SoapVersion::of_binding(node)),
+        };
+
+        Some(Binding {
+            name: source.defined(node.attribute("name")?),
+            port_type: QName::resolve(node, port_type),
+            protocol,
+            operations: node
+                .children()
+                .filter(|c| c.has_tag_name("operation"))
+                .filter_map(|n| BindingOperation::read(&n, source))
+                .collect(),
+            documentation: documentation(node),
+            position: source.position(node),
+        })
+    }
+}
+
+impl BindingOperation {
+    fn read(node: &Node, source: Source) -> Option<BindingOperation> {
+        // the operations of a WSDL 2.0 binding refer to those of the interface
+        let name = match node.attribute("name") {
+            Some(name) => name,
+            None => node.attribute("ref")?.rsplit(':').next()?,
+        };
+        let details = node.children().find(|c| c.has_tag_name("operation"));
+        let style = details.and_then(|d| d.attribute("style")).or_else(|| {
+            node.parent()?
+                .children()
+                .find(|c| c.has_tag_name("binding"))?
+                .attribute("style")
+        });
+
+        Some(BindingOperation {
+            name: name.to_string(),
+            action: details
+                .and_then(|d| d.attribute("soapAction"))
+                .or_else(|| node.attribute((WSDL2_SOAP_NS, "action")))
+                .map(str::to_string),
+            location: details
+                .and_then(|d| d.attribute("location"))
+                .map(str::to_string),
+            style: match style {
+                Some("rpc") => Style::Rpc,
+                _ => Style::Document,
+            },
+            input: BindingMessage::read(node, "input", source),
+            output: BindingMessage::read(node, "output", source),
+            documentation: documentation(node),
+            position: source.position(node),
+        })
+    }
+}
+
+impl BindingMessage {
+    /// Reads the input or output of a binding operation, which is the default when it has none
+    fn read(operation: &Node, direction: &str, source: Source) -> BindingMessage {
+        let node = match operation.children().find(|c| c.has_tag_name(direction)) {
+            None => return BindingMessage::default(),
+            Some(n) => n,
+        };
+        let body = node.descendants().find(|c| c.has_tag_name("body"));
+        let multipart = node
+            .children()
+            .filter(|c| c.has_tag_name("multipartRelated"));
+
+        BindingMessage {
+            body_parts: body
+                .and_then(|b| b.attribute("parts"))
+                .map(|p| p.split_whitespace().map(str::to_string).collect()),
+            namespace: body
+                .and_then(|b| b.attribute("namespace"))
+                .map(str::to_string),
+            encoding_style: body
+                .filter(|b| b.attribute("use") == Some("encoded"))
+                .map(|b| {
+                    b.attribute("encodingStyle")
+                        .unwrap_or("http://schemas.xmlsoap.org/soap/encoding/")
+                        .to_string()
+                }),
+            headers: node
+                .descendants()
+                .filter(|c| c.has_tag_name("header"))
+                .filter_map(|header| {
+                    Some(Header {
+                        message: QName::resolve(&header, header.attribute("message")?),
+                        part: header.attribute("part")?.to_string(),
+                        position: source.position(&header),
+                    })
+                })
+                .collect(),
+            multipart: multipart.clone().next().is_some(),
+            attachments: multipart
+                .flat_map(|c| c.descendants())
+                .filter(|c| c.has_tag_name("content"))
+                .filter_map(|c| c.attribute("part"))
+                .map(str::to_string)
+                .collect(),
+            url_replacement: node.children().any(|c| c.has_tag_name("urlReplacement")),
+        }
+    }
+}
+
+impl Service {
+    fn read(node: &Node, source: Source) -> Option<Service> {
+        Some(Service {
+            name: source.defined(node.attribute("name")?),
+            ports: node
+                .children()
+                .filter(|c| c.has_tag_name("port") || c.has_tag_name("endpoint"))
+                .filter_map(|port| {
+                    let address = match port.attribute("address") {
+                        Some(address) => address,
+                        None => port
+                            .children()
+                            .find(|c| c.has_tag_name("address"))?
+                            .attribute("location")
+                            .unwrap_or_default(),
+                    };
+                    Some(Port {
+                        name: port.attribute("name")?.to_string(),
+                        binding: QName::resolve(&port, port.attribute("binding")?),
+                        address: address.to_string(),
+                        documentation: documentation(&port),
+                    })
+                })
+                .collect(),
+            documentation: documentation(node),
+            position: source.position(node),
+        })
+    }
+}
+
+impl Protocol {
+    /// The SOAP version of the envelopes of the binding, SOAP 1.1 for bindings without envelopes
+    pub fn soap_version(&self) -> SoapVersion {
+        match self {
+            Protocol::Soap(version) => *version,
+            _ => SoapVersion::Soap11,
+        }
+    }
+}
+
+impl SoapVersion {
+    /// The version of a WSDL binding, told by the namespace of its soap:binding element, or the
+    /// wsoap:version of a WSDL 2.0 binding, which defaults to SOAP 1.2
+    pub(crate) fn of_binding(node: &Node) -> Self {
+        let soap12 = node
+            .children()
+            .any(|c| c.has_tag_name("binding") && c.tag_name().namespace() == Some(SOAP12_NS))
+            || (node.tag_name().namespace() == Some(WSDL2_NS)
+                && node.attribute((WSDL2_SOAP_NS, "version")) != Some("1.1"));
+
+        if soap12 {
+            SoapVersion::Soap12
+        } else {
+            SoapVersion::Soap11
+        }
+    }
+
+    pub(crate) fn envelope_namespace(self) -> &'static str {
+        match self {
+            SoapVersion::Soap11 => "http://schemas.xmlsoap.org/soap/envelope/",
+            SoapVersion::Soap12 => "http://www.w3.org/2003/05/soap-envelope",
+        }
+    }
+
+    /// Appended to the name of a message to name its envelope, so both versions can coexist
+    pub(crate) fn envelope_suffix(self) -> &'static str {
+        match self {
+            SoapVersion::Soap11 => "SoapEnvelope",
+            SoapVersion::Soap12 => "Soap12Envelope",
+        }
+    }
+}
+
+/// The verb of a plain HTTP binding, none for SOAP bindings
+pub(crate) fn http_verb<'a>(node: &Node<'a, '_>) -> Option<&'a str> {
+    node.children()
+        .find(|c| c.has_tag_name("binding") && c.tag_name().namespace() == Some(HTTP_NS))
+        .map(|c| c.attribute("verb").unwrap_or("GET"))
+}
+
+/// The name of the message made up for an input, output or fault of a WSDL 2.0 interface, after
+/// the operation, or the fault a fault reference refers to
+pub(crate) fn interface_message_name(node: &Node) -> Option<String> {
+    let local_name = |name: &str| name.rsplit(':').next().unwrap_or_default().to_string();
+
+    match node.tag_name().name() {
+        "input" | "output" => {
+            let operation = node.parent()?.attribute("name")?;
+            let label = if node.has_tag_name("input") {
+                "Input"
+            } else {
+                "Output"
+            };
+            // #any and #none stand for no element in particular
+            node.attribute("element").filter(|e| !e.starts_with('#'))?;
+            Some(format!("{}{}", to_pascal_case(operation), label))
+        }
+        "fault" => node.attribute("name").map(to_pascal_case),
+        "infault" | "outfault" => node
+            .attribute("ref")
+            .map(|r| to_pascal_case(&local_name(r))),
+        _ => None,
+    }
+}
+
+/// Returns the text of the documentation of a schema component or WSDL definition
+pub(crate) fn documentation(node: &Node) -> Option<String> {
+    let text: Vec<String> = node
+        .children()
+        .filter(|c| c.has_tag_name("annotation"))
+        .flat_map(|annotation| annotation.children())
+        .chain(node.children())
+        .filter(|c| c.has_tag_name("documentation"))
+        .map(|d| {
+            let text: String = d
+                .descendants()
+                .filter(|t| t.is_text())
+                .filter_map(|t| t.text())
+                .collect();
+            text.trim().to_string()
+        })
+        .filter(|text| !text.is_empty())
+        .collect();
+
+    if text.is_empty() {
+        None
+    } else {
+        Option::Some(text.join("\n\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../resources/fixtures");
+
+    fn read_fixture(file_name: &str) -> Definitions {
+        let xml = std::fs::read_to_string(format!("{}/{}", FIXTURES, file_name))
+            .expect("can not read fixture");
+        let doc = roxmltree::Document::parse(&xml).expect("can not parse fixture");
+        Definitions::read(&doc.root_element(), file_name)
+    }
+
+    fn qname(name: &str) -> QName {
+        QName {
+            namespace: Some("http://example.com/fixtures".to_string()),
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_read_definitions() {
+        let definitions = read_fixture("http_binding.wsdl");
+
+        let message = &definitions.messages[2];
+        assert_eq!(message.name, qname("GetQuoteOnDayIn"));
+        assert_eq!(message.parts[1].name, "day");
+        assert_eq!(
+            message.parts[1].type_name,
+            Some(QName {
+                namespace: Some("http://www.w3.org/2001/XMLSchema".to_string()),
+                name: "int".to_string(),
+            })
+        );
+
+        let operation = &definitions.port_types[0].operations[1];
+        assert_eq!(operation.name, "GetQuoteOnDay");
+        assert_eq!(
+            operation.input.as_ref().map(|i| &i.message),
+            Some(&qname("GetQuoteOnDayIn"))
+        );
+        assert_eq!(
+            operation.output.as_ref().map(|o| &o.message),
+            Some(&qname("GetQuoteOut"))
+        );
+
+        let binding = &definitions.bindings[0];
+        assert_eq!(binding.port_type, qname("Quotes"));
+        assert_eq!(binding.protocol, Protocol::Http("GET".to_string()));
+        assert_eq!(
+            binding.operations[1].location.as_deref(),
+            Some("/quotes/(symbol)/(day)")
+        );
+
+        let port = &definitions.services[0].ports[1];
+        assert_eq!(port.binding, qname("QuotesHttpPost"));
+        assert_eq!(port.address, "http://localhost/quotes");
+    }
+
+    #[test]
+    fn test_read_description() {
+        let definitions = read_fixture("wsdl2.wsdl");
+
+        let names: Vec<&str> = definitions
+            .messages
+            .iter()
+            .map(|m| m.name.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["RoomUnavailableFault", "ReserveInput", "ReserveOutput"]
+        );
+
+        let operation = &definitions.port_types[0].operations[0];
+        assert_eq!(
+            operation.input.as_ref().map(|i| &i.message),
+            Some(&qname("ReserveInput"))
+        );
+        assert_eq!(operation.faults[0].message, qname("RoomUnavailableFault"));
+        assert_eq!(
+            operation.documentation.as_deref(),
+            Some("Books a room for a number of nights")
+        );
+
+        assert_eq!(
+            definitions.bindings[0].protocol,
+            Protocol::Soap(SoapVersion::Soap11)
+        );
+        assert_eq!(
+            definitions.bindings[1].protocol,
+            Protocol::Soap(SoapVersion::Soap12)
+        );
+        assert_eq!(
+            definitions.bindings[1].operations[0].action.as_deref(),
+            Some("http://example.com/fixtures/Reserve")
+        );
+        assert_eq!(
+            definitions.services[0].ports[1].address,
+            "http://localhost/reservation12"
+        );
+    }
+
+    #[test]
+    fn test_read_binding_operations() {
+        let definitions = read_fixture("mime.wsdl");
+        let upload = definitions.binding_operation("Upload").expect("no Upload");
+        assert!(upload.input.multipart);
+        assert_eq!(upload.input.attachments, vec!["content"]);
+        assert!(!upload.output.multipart);
+        let message = &definitions.port_types[0].operations[0]
+            .input
+            .as_ref()
+            .expect("no input")
+            .message;
+        assert_eq!(
+            definitions.body_parts(message),
+            Some(HashSet::from(["parameters"]))
+        );
+        assert!(definitions.outside_parts(message).contains("content"));
+
+        let definitions = read_fixture("soap_header.wsdl");
+        let header = &definitions.bindings[0].operations[0].input.headers[0];
+        assert_eq!(header.message, qname("Session"));
+        assert_eq!(header.part, "session");
+        assert_eq!(header.position.to_string(), "soap_header.wsdl:57");
+        assert_eq!(header.position.tag, "soap:header");
+
+        let definitions = read_fixture("rpc.wsdl");
+        let greet = definitions.binding_operation("Greet").expect("no Greet");
+        assert_eq!(greet.style, Style::Rpc);
+        assert_eq!(
+            greet.input.namespace.as_deref(),
+            Some("urn:example:greeter")
+        );
+        assert_eq!(
+            greet.output.encoding_style.as_deref(),
+            Some("http://schemas.xmlsoap.org/soap/encoding/")
+        );
+    }
+}
@@ -0,0 +1,140 @@
+//! # Scaffold
+//! A crate around the generated code, ready to build: a `Cargo.toml` with the dependencies that
+//! the code has with the options of the generator, the modules in `src` with `src/lib.rs` as
+//! their root, and an example that calls one of the generated operations.
+//!
+use std::path::Path;
+
+/// The example of a scaffold, in its `examples` directory
+pub const EXAMPLE_FILE: &str = "call_service.rs";
+
+/// The options of the generator that decide the dependencies of the generated code
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Dependencies {
+    pub blocking: bool,
+    /// Blocking clients behind the blocking feature, next to the async ones
+    pub blocking_feature: bool,
+    pub chrono: bool,
+    pub regex: bool,
+    pub serde: bool,
+    pub mocks: bool,
+    pub ws_security: bool,
+    pub ws_signature: bool,
+    pub ws_addressing: bool,
+}
+
+/// An operation for the example to call: the service whose client calls it, and the port type
+/// it is an operation of, by their paths in the crate
+#[derive(Debug, Clone)]
+pub(crate) struct Call {
+    pub service: String,
+    pub port_type: String,
+    pub operation: String,
+    /// The number of arguments of the operation, the input and its attachments
+    pub arguments: usize,
+}
+
+/// The name of the crate of a scaffold, after its directory
+pub fn crate_name(dir: &Path) -> String {
+    let name: String = dir
+        .canonicalize()
+        .unwrap_or_else(|_| dir.to_path_buf())
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let name = name.trim_matches('-');
+    match name.chars().next() {
+        None => "service".to_string(),
+        Some(c) if c.is_ascii_digit() => format!("service-{}", name),
+        Some(_) => name.to_string(),
+    }
+}
+
+pub(crate) fn cargo_manifest(name: &str, dependencies: &Dependencies) -> String {
+    let mut manifest = format!(
+        "[package]\nname = \"{}\"\nversion = \"0.1.0\"\nedition = \"2018\"\n\n[dependencies]\n",
+        name
+    );
+    // the xs:base64Binary values of both backends
+    manifest.push_str("base64 = \"0.13\"\n");
+    if dependencies.serde {
+        manifest.push_str("serde = { version = \"1.0\", features = [\"derive\"] }\n");
+        manifest.push_str("quick-xml = { version = \"0.31\", features = [\"serialize\"] }\n");
+    } else {
+        manifest.push_str("yaserde = \"0.7.1\"\nyaserde_derive = \"0.7.1\"\nxml-rs = \"0.8.4\"\n");
+        manifest.push_str("log = \"0.4.14\"\nasync-trait = \"0.1.51\"\n");
+        if dependencies.blocking {
+            manifest.push_str("reqwest = { version = \"0.11.4\", features = [\"blocking\"] }\n");
+        } else {
+            manifest.push_str("reqwest = \"0.11.4\"\n");
+        }
+    }
+    if dependencies.chrono {
+        manifest.push_str("chrono = \"0.4\"\n");
+    }
+    if dependencies.regex {
+        manifest.push_str("regex = \"1\"\n");
+    }
+    if dependencies.ws_security {
+        manifest.push_str("sha1 = \"0.10\"\nrand = \"0.8\"\n");
+    }
+    if dependencies.ws_signature {
+        manifest.push_str("rsa = \"0.9\"\nsha2 = { version = \"0.10\", features = [\"oid\"] }\n");
+    }
+    if dependencies.ws_addressing {
+        manifest.push_str("uuid = { version = \"1\", features = [\"v4\"] }\n");
+    }
+    // the example runs the async clients on tokio
+    if !dependencies.serde && !dependencies.blocking {
+        manifest.push_str(
+            "\n[dev-dependencies]\ntokio = { version = \"1.9.0\", features = [\"macros\", \"rt-multi-thread\"] }\n",
+        );
+    }
+    let mut features = vec![];
+    if dependencies.blocking_feature {
+        features.push("blocking = [\"reqwest/blocking\"]\n");
+    }
+    if dependencies.mocks {
+        features.push("mocks = []\n");
+    }
+    if !features.is_empty() {
+        manifest.push_str("\n[features]\n");
+        manifest.extend(features);
+    }
+    manifest
+}
+
+pub(crate) fn example(crate_name: &str, call: &Call, blocking: bool) -> String {
+    let crate_ident = crate_name.replace('-', "_");
+    let arguments = vec!["Default::default()"; call.arguments].join(", ");
+    let (main, wait) = if blocking {
+        ("fn main()", "")
+    } else {
+        ("#[tokio::main]\nasync fn main()", ".await")
+    };
+    format!(
+        r#"//! Calls the {name} operation with a default input. Fill in the input, and the
+//! credentials of the client, to call the service for real.
+use {krate}::{port_type};
+use {krate}::{service};
+
+{main} {{
+    let client = {service_name}::new_client(None);
+    let result = client.{operation}({arguments}){wait};
+    println!("{{:?}}", result);
+}}
+"#,
+        operation = call.operation,
+        name = call.operation.trim_start_matches("r#"),
+        krate = crate_ident,
+        port_type = call.port_type,
+        service = call.service,
+        service_name = call.service.rsplit("::").next().unwrap_or_default(),
+        main = main,
+        arguments = arguments,
+        wait = wait,
+    )
+}
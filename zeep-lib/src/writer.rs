@@ -1,16 +1,29 @@
+use crate::cache::DocumentCache;
+use crate::coverage::{
+    foreign_namespace, uncovered_constructs, undeclared_prefixes, Construct, Coverage,
+    GenerationReport, Problem,
+};
 use crate::debug::DebugBuffer;
 use crate::element::{
-    root, Element, ElementType, NamespacedElement, ParentElement, StaticElement, WritableElement,
+    root, Element, ElementType, Facet, GroupMember, NamespacedElement, ParentElement,
+    StaticElement, WritableElement,
+};
+use crate::emitter::{Emitter, SerdeEmitter, YaserdeEmitter};
+use crate::error::{ZeepError, ZeepResult};
+use crate::model::{
+    self, documentation, Binding, BindingOperation, Definitions, Header, Message, Position,
+    Protocol, QName, Service, SoapVersion, Style,
 };
-use crate::error::{WriterError, WriterResult};
+use crate::scaffold::{cargo_manifest, crate_name, example, Call, Dependencies, EXAMPLE_FILE};
 use inflector::cases::pascalcase::to_pascal_case;
 use inflector::cases::snakecase::to_snake_case;
 use roxmltree::Node;
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs::File;
 use std::io::{stdout, Write};
 use std::ops::Deref;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
 const MESSAGES_MOD: &str = "messages";
@@ -18,6 +31,24 @@ const TYPES_MOD: &str = "types";
 const PORTS_MOD: &str = "ports";
 const BINDINGS_MOD: &str = "bindings";
 const SERVICES_MOD: &str = "services";
+/// The module of the blocking clients, next to the async ones, and the feature it is compiled with
+const BLOCKING_MOD: &str = "blocking";
+/// The module of the WS-Security headers of the bindings
+const SECURITY_MOD: &str = "security";
+/// The module of the WS-Addressing headers of the bindings
+const ADDRESSING_MOD: &str = "addressing";
+/// The namespace of the WS-Addressing headers
+const WSA_NS: &str = "http://www.w3.org/2005/08/addressing";
+/// The module of the transport that posts the envelopes of the bindings
+const TRANSPORT_MOD: &str = "transport";
+/// The modules of the clients, which are async unless they are generated blocking
+const CLIENT_MODULES: [&str; 4] = [TRANSPORT_MOD, PORTS_MOD, BINDINGS_MOD, SERVICES_MOD];
+/// The functions shared by the modules of the WS-* headers
+const ENVELOPE_HELPERS: &str = "envelope_helpers";
+/// The namespace of the swaRef type of the WS-I Attachments Profile
+const WSI_SWA_NS: &str = "http://ws-i.org/profiles/basic/1.1/xsd";
+/// The namespace of the xmime:expectedContentTypes of the binary values that MTOM optimizes
+const XMIME_NS: &str = "http://www.w3.org/2005/05/xmlmime";
 
 const SIGNATURE: &str = r#"//! THIS IS A GENERATED FILE!
 //! Take care when hand editing. Changes will be lost during subsequent runs of the code generator.
@@ -26,11 +57,32 @@ const SIGNATURE: &str = r#"//! THIS IS A GENERATED FILE!
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 const DEFAULT_NS_PREFIX: &str = "tns";
 const IMPORT_PREFIX: &str = "nsi";
+const RPC_PREFIX: &str = "rpc";
+const ANY_FIELD: &str = "any";
+
+/// The strict, reserved and weak keywords of Rust, which can not name anything as they are
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+/// The keywords that can not be raw identifiers either
+const PATH_KEYWORDS: &[&str] = &["crate", "self", "Self", "super"];
 
 pub struct FileWriter {
     base_path: String,
     writer: Option<Box<dyn std::io::Write>>,
-    target_name_space: Vec<String>,
+    /// When set, every module is written to a file of its own in this directory
+    output_dir: Option<PathBuf>,
+    /// When set, a crate is written around the modules in this directory
+    scaffold_dir: Option<PathBuf>,
+    /// The target namespaces of the documents being processed, innermost last. Schemas
+    /// without a target namespace are in no namespace.
+    target_name_space: Vec<Option<String>>,
+    /// The prefix used in the generated code for every target namespace
+    namespace_prefixes: HashMap<String, String>,
 
     /// A map of WSDL ports by operation name to their type.
     ///
@@ -59,14 +111,253 @@ pub struct FileWriter {
     ns_prefix: String,
     default_namespace: Option<String>,
     root: Element,
+
+    /// All references to generated types, verified once all input has been processed
+    type_references: Vec<TypeReference>,
+    stub_unresolved_types: bool,
+    /// Generate a `Duration` type for xs:duration, rather than using a string
+    typed_durations: bool,
+    /// Generate chrono based types for the date and time types, rather than using strings
+    chrono_dates: bool,
+    /// Validate the pattern facets of string restrictions, using the regex crate
+    pattern_validation: bool,
+    /// Generate synchronous port traits and bindings on the blocking reqwest client, rather
+    /// than async ones
+    blocking_clients: bool,
+    /// Generate blocking port traits and bindings as well, behind the blocking feature
+    blocking_feature: bool,
+    /// Generate a dispatcher per SOAP binding, which serves requests with an implementation of
+    /// the port type
+    server_dispatchers: bool,
+    /// Generate a mock of every port type, for tests
+    mocks: bool,
+    /// Generate WS-Security headers for the SOAP bindings to send
+    ws_security: bool,
+    /// Sign the body and the timestamp of the requests with an X.509 certificate as well
+    ws_signature: bool,
+    /// Generate WS-Addressing headers for the SOAP bindings to send
+    ws_addressing: bool,
+    /// Send and receive the binary values with an expected content type in MTOM messages
+    mtom: bool,
+    /// Format the generated code with prettyplease
+    formatted_output: bool,
+    /// Generate serde types, (de)serialized with quick-xml, instead of yaserde types
+    serde_backend: bool,
+
+    /// When set, only the operations in this list are generated
+    operation_filter: Option<HashSet<String>>,
+
+    /// The attributes of every global attribute group, by group name
+    attribute_groups: HashMap<String, Vec<Element>>,
+
+    /// The fields of every global element group, by group name
+    element_groups: HashMap<String, Vec<Element>>,
+
+    /// The global element declarations, by element name
+    global_elements: HashMap<String, GlobalElement>,
+
+    /// The members of every substitution group, by the name of its head element
+    substitution_groups: HashMap<String, Vec<String>>,
+
+    /// The types extending a complex type, by the name of the base type
+    derived_types: BTreeMap<String, Vec<String>>,
+
+    /// The base of the original definition of every redefined simple type, by type name
+    redefined_bases: HashMap<String, String>,
+
+    /// The Rust names of global definitions whose local name is taken by another namespace, by
+    /// namespace and local name
+    type_names: HashMap<(String, String), String>,
+
+    /// The locations of the WSDL documents processed so far, so imports do not go round in circles
+    wsdl_documents: HashSet<String>,
+    /// Whether the bindings and services of the WSDL documents are being generated, in a second
+    /// pass over the documents once the first one has generated all their messages and port types
+    binding_pass: bool,
+    /// The content of the documents read in this run, by location, so the second pass does not
+    /// read them again
+    document_contents: HashMap<String, String>,
+    /// The global definitions of the schemas processed so far, by namespace, kind and name, so a
+    /// schema imported by several others only defines its types once
+    defined_types: HashSet<(String, String, String)>,
+    /// The target namespace of every generated type, by the Rust name of the type
+    type_namespaces: HashMap<String, String>,
+    /// Whether the types are generated in a module per namespace
+    namespace_modules: bool,
+    /// The names of the modules of namespaces, by namespace URI
+    namespace_module_names: HashMap<String, String>,
+    /// The location of the schema for a namespace, used for imports without a schemaLocation
+    namespace_catalog: HashMap<String, String>,
+    /// Derives added to every generated type
+    extra_derives: Vec<String>,
+    /// Derives added to a type instead of the extra derives, by the Rust name of the type
+    type_derives: HashMap<String, Vec<String>>,
+    /// The Rust names of definitions, by their name in the documents or the name they would get
+    type_renames: HashMap<String, String>,
+    /// Rust types used for XSD types instead of the generated or built-in ones, by local name
+    type_mappings: HashMap<String, String>,
+    /// Whether every struct comes with a builder
+    builders: bool,
+    /// Generate a test per struct that (de)serializes its default value
+    round_trip_tests: bool,
+    /// Include a hand-written extension file at the end of every module file
+    extension_files: bool,
+    /// Leave out the inner attributes and doc comments, so the code can be included
+    includable_output: bool,
+    /// The local files read in the last run
+    sources: Vec<String>,
+    /// Documents generated in the same run as the input, in the base path
+    additional_inputs: Vec<String>,
+    /// The names of the generated modules, by the name they have by default
+    module_names: HashMap<String, String>,
+    /// The visibility of the generated modules, by the name they have by default
+    module_visibility: HashMap<String, String>,
+    /// Where the documents fetched from URLs are kept
+    cache: Option<DocumentCache>,
+    /// Only read documents from the cache, never from the network
+    offline: bool,
+    /// Fetch documents again, even when they are in the cache
+    refresh: bool,
+    /// Appended to names that are Rust keywords, instead of making them raw identifiers
+    keyword_suffix: Option<String>,
+    /// Whether generating fails when any construct of the documents is skipped
+    strict: bool,
+    /// The constructs of the documents processed so far that the generated code does not cover
+    report: GenerationReport,
+    /// The documents being processed, innermost last
+    documents: Vec<String>,
+    /// The form defaults of the schemas being processed, innermost last
+    form_defaults: Vec<FormDefaults>,
+
+    /// What the WSDL documents describe, read from every document before its code is generated
+    model: Definitions,
+}
+
+/// Whether the local elements and attributes of a schema are namespace qualified
+struct FormDefaults {
+    elements_qualified: bool,
+    attributes_qualified: bool,
+}
+
+/// What is needed to refer to a global element declaration
+struct GlobalElement {
+    /// The generated type of the element
+    type_name: String,
+    is_abstract: bool,
+}
+
+/// A member of a generated type that refers to another generated type
+struct TypeReference {
+    type_name: String,
+    owner: String,
+    member: String,
+    /// The file and line of the reference
+    position: String,
 }
 
 #[derive(Clone)]
 struct PortType {
     name: String,
+    /// The alias of the input in the ports, along with the message it stands for
     input_type: Option<(String, Option<String>)>,
     output_type: Option<(String, Option<String>)>,
-    fault_type: Option<(String, Option<String>)>,
+    fault_types: Vec<(String, Option<String>)>,
+    /// The error of an operation with several faults, along with the SOAP fault that carries them
+    fault_error: Option<(String, String)>,
+}
+
+/// What the client code of an operation sends and gets back
+struct SoapCall<'a> {
+    input_variable: &'a str,
+    input_type: &'a str,
+    /// The output type, along with the expression of the fault of the response
+    output: Option<(&'a str, &'a str)>,
+    action_name: &'a str,
+    namespace: Option<&'a str>,
+    header_variable: Option<&'a str>,
+    /// The parameters sent as MIME attachments
+    attachments: &'a [String],
+    /// Whether the attachments of the response are handed back with the output
+    output_attachments: bool,
+    version: SoapVersion,
+}
+
+/// The WS-* headers that a SOAP binding adds to the envelopes it sends, which are fields of the
+/// binding that are set with its `with_` methods
+#[derive(Clone, Copy, Default)]
+struct EnvelopeHeaders {
+    security: bool,
+    signature: bool,
+    addressing: bool,
+}
+
+impl EnvelopeHeaders {
+    fn fields(&self) -> String {
+        let mut fields = String::new();
+        if self.security {
+            fields.push_str(",\n                security: security::Security");
+        }
+        if self.addressing {
+            fields.push_str(",\n                addressing: Option<addressing::Addressing>");
+        }
+        fields
+    }
+
+    fn initial_values(&self) -> String {
+        let mut values = String::new();
+        if self.security {
+            values.push_str("\n                        security: security::Security::default(),");
+        }
+        if self.addressing {
+            values.push_str("\n                        addressing: Option::None,");
+        }
+        values
+    }
+
+    fn setters(&self) -> String {
+        let mut setters = String::new();
+        if self.security {
+            setters.push_str(
+                r#"
+                /// Sends a WS-Security UsernameToken in the header of every request
+                pub fn with_username_token(mut self, token: security::UsernameToken) -> Self {
+                    self.security.username_token = Option::Some(token);
+                    self
+                }
+
+                /// Sends a WS-Security Timestamp in the header of every request, and rejects
+                /// responses with a timestamp that has expired
+                pub fn with_timestamp(mut self, timestamp: security::Timestamp) -> Self {
+                    self.security.timestamp = Option::Some(timestamp);
+                    self
+                }
+"#,
+            );
+        }
+        if self.signature {
+            setters.push_str(
+                r#"
+                /// Signs the body and the timestamp of every request
+                pub fn with_signature(mut self, signature: security::Signature) -> Self {
+                    self.security.signature = Option::Some(signature);
+                    self
+                }
+"#,
+            );
+        }
+        if self.addressing {
+            setters.push_str(
+                r#"
+                /// Sends the WS-Addressing headers in every request
+                pub fn with_addressing(mut self, addressing: addressing::Addressing) -> Self {
+                    self.addressing = Option::Some(addressing);
+                    self
+                }
+"#,
+            );
+        }
+        setters
+    }
 }
 
 impl Default for FileWriter {
@@ -74,7 +365,10 @@ impl Default for FileWriter {
         FileWriter {
             base_path: String::default(),
             writer: Option::Some(Box::new(stdout())),
+            output_dir: None,
+            scaffold_dir: None,
             target_name_space: Vec::new(),
+            namespace_prefixes: HashMap::new(),
             port_types: HashMap::new(),
             message_types: HashMap::new(),
             namespaces: HashMap::new(),
@@ -82,6 +376,58 @@ impl Default for FileWriter {
             ns_prefix: DEFAULT_NS_PREFIX.to_string(),
             default_namespace: Option::None,
             root: root(),
+            type_references: Vec::new(),
+            stub_unresolved_types: false,
+            typed_durations: false,
+            chrono_dates: false,
+            pattern_validation: false,
+            blocking_clients: false,
+            blocking_feature: false,
+            server_dispatchers: false,
+            mocks: false,
+            ws_security: false,
+            ws_signature: false,
+            ws_addressing: false,
+            mtom: false,
+            formatted_output: false,
+            serde_backend: false,
+            operation_filter: None,
+            attribute_groups: HashMap::new(),
+            element_groups: HashMap::new(),
+            global_elements: HashMap::new(),
+            substitution_groups: HashMap::new(),
+            derived_types: BTreeMap::new(),
+            redefined_bases: HashMap::new(),
+            type_names: HashMap::new(),
+            wsdl_documents: HashSet::new(),
+            binding_pass: false,
+            document_contents: HashMap::new(),
+            defined_types: HashSet::new(),
+            type_namespaces: HashMap::new(),
+            namespace_modules: false,
+            namespace_module_names: HashMap::new(),
+            namespace_catalog: HashMap::new(),
+            extra_derives: vec![],
+            type_derives: HashMap::new(),
+            type_renames: HashMap::new(),
+            type_mappings: HashMap::new(),
+            builders: false,
+            round_trip_tests: false,
+            extension_files: false,
+            includable_output: false,
+            sources: vec![],
+            additional_inputs: vec![],
+            module_names: HashMap::new(),
+            module_visibility: HashMap::new(),
+            cache: None,
+            offline: false,
+            refresh: false,
+            keyword_suffix: None,
+            strict: false,
+            report: GenerationReport::default(),
+            documents: vec![],
+            form_defaults: Vec::new(),
+            model: Definitions::default(),
         }
     }
 }
@@ -100,18 +446,9 @@ impl FileWriter {
         ns_prefix: Option<String>,
         default_namespace: Option<String>,
     ) -> Self {
-        FileWriter {
-            base_path: String::default(),
-            writer: Option::Some(Box::new(dest_file_name)),
-            target_name_space: Vec::new(),
-            port_types: HashMap::new(),
-            message_types: HashMap::new(),
-            namespaces: HashMap::new(),
-            import_count: 0,
-            ns_prefix: ns_prefix.unwrap_or_else(|| DEFAULT_NS_PREFIX.to_string()),
-            default_namespace,
-            root: root(),
-        }
+        let mut fw = FileWriter::new(ns_prefix, default_namespace);
+        fw.writer = Option::Some(Box::new(dest_file_name));
+        fw
     }
 
     #[allow(dead_code)]
@@ -125,1559 +462,10730 @@ impl FileWriter {
         fw
     }
 
-    fn init_modules(&mut self) {
-        self.root.add(Element::new_module(MESSAGES_MOD));
-        self.root.add(Element::new_module(TYPES_MOD));
-        self.root.add(Element::new_module(PORTS_MOD));
-        self.root.add(Element::new_module(BINDINGS_MOD));
-        self.root.add(Element::new_module(SERVICES_MOD));
+    /// Writes the `types.rs`, `messages.rs`, `ports.rs`, `bindings.rs` and `services.rs` modules
+    /// to the directory, together with a `mod.rs` that declares them. The directory is created
+    /// when it does not exist.
+    pub fn new_dir<P: AsRef<Path>>(
+        path: P,
+        ns_prefix: Option<String>,
+        default_namespace: Option<String>,
+    ) -> Self {
+        let mut fw = FileWriter::new(ns_prefix, default_namespace);
+        fw.writer = None;
+        fw.output_dir = Option::Some(path.as_ref().to_path_buf());
+        fw
     }
 
-    pub fn process_file(&mut self, base_path: &str, file_name: &str) -> WriterResult<()> {
-        self.base_path = base_path.to_string();
-        self.print_global_header();
-        self.print_common_structs();
-        self.init_modules();
-        self.process_file_in_path(file_name, true)?;
-        Ok(())
+    /// Writes a crate around the generated code to the directory: the modules in `src`, with a
+    /// `lib.rs` that declares them, along with a `Cargo.toml` and an example that calls one of the
+    /// operations. The manifest and the example are created once, so they can be edited.
+    pub fn new_scaffold<P: AsRef<Path>>(
+        path: P,
+        ns_prefix: Option<String>,
+        default_namespace: Option<String>,
+    ) -> Self {
+        let mut fw = FileWriter::new_dir(path.as_ref().join("src"), ns_prefix, default_namespace);
+        fw.scaffold_dir = Option::Some(path.as_ref().to_path_buf());
+        fw
     }
 
-    fn process_file_in_path(&mut self, file_name: &str, print_when_done: bool) -> WriterResult<()> {
-        let xml = self.read_to_string(file_name)?;
-        let doc = roxmltree::Document::parse(&xml).map_err(|e| WriterError {
-            message: format!("Unable to parse file {}: {}", file_name, e.to_string()),
-        })?;
-        doc.root().children().try_for_each(|n| self.print(&n))?;
-
-        if !print_when_done {
-            return Ok(());
-        }
-
-        // once all elements are processed, write them to output
-        if let Some(mut writer) = self.writer.take() {
-            writer.write_all(self.root.render().as_bytes())?;
-            self.writer.replace(writer);
-        }
+    /// Generate a `String` alias for every referenced type that is not defined by the input.
+    pub fn with_unresolved_type_stubs(mut self, enabled: bool) -> Self {
+        self.stub_unresolved_types = enabled;
+        self
+    }
 
-        Ok(())
+    /// Generate a `Duration` type with ISO 8601 (de)serialization for xs:duration, instead of
+    /// a `String`.
+    pub fn with_typed_durations(mut self, enabled: bool) -> Self {
+        self.typed_durations = enabled;
+        self
     }
 
-    fn read_to_string(&self, file_name: &str) -> WriterResult<String> {
-        let f_in = format!("{}/{}", self.base_path, file_name);
-        if file_name.starts_with("http://") || file_name.starts_with("https://") {
-            let body = reqwest::blocking::get(file_name)
-                .map_err(|e| WriterError {
-                    message: format!("Unable to retrieve {}: {}", file_name, e.to_string()),
-                })?
-                .text()
-                .map_err(|e| WriterError {
-                    message: format!("Unable to get body from {}: {}", file_name, e.to_string()),
-                })?;
-            return Ok(body);
-        }
-        std::fs::read_to_string(&f_in).map_err(|e| WriterError {
-            message: format!("Unable to read file {}: {}", f_in, e.to_string()),
-        })
+    /// Generate wrappers around chrono types for xs:dateTime, xs:date and xs:time, instead of
+    /// using a `String`. The generated code then depends on chrono.
+    pub fn with_chrono_dates(mut self, enabled: bool) -> Self {
+        self.chrono_dates = enabled;
+        self
     }
 
-    pub fn have_seen_type(&self, type_def: &str, module: &Element) -> bool {
-        module.has_child(type_def)
+    /// Generate newtypes for string restrictions with pattern facets, validating their values
+    /// against the patterns. The generated code then depends on regex.
+    pub fn with_pattern_validation(mut self, enabled: bool) -> Self {
+        self.pattern_validation = enabled;
+        self
     }
 
-    fn print_global_header(&mut self) {
-        let mut global_header = Element::new("global_header", ElementType::Static);
-        global_header.set_content(SIGNATURE);
-        global_header.append_content(format!("//! version: {}\n//!\n", VERSION).as_str());
-        global_header.append_content(
-            r#"
-            #![allow(dead_code)]           
-            #![allow(unused_imports)]
-            use yaserde::{{YaSerialize, YaDeserialize}};
-            use yaserde_derive::*;
-            use log::{debug, info, warn, error};
-            use std::io::{Read, Write};
-            
-            pub const SOAP_ENCODING: &str = "http://www.w3.org/2003/05/soap-encoding";
-            "#,
-        );
+    /// Generate plain port traits, with bindings on the blocking reqwest client, instead of the
+    /// async ones. The generated code then needs the blocking feature of reqwest, but no
+    /// async_trait or executor.
+    pub fn with_blocking_clients(mut self, enabled: bool) -> Self {
+        self.blocking_clients = enabled;
+        self
+    }
 
-        self.root.add(global_header);
+    /// Generate the blocking port traits, bindings and services as well as the async ones, in a
+    /// `blocking` module that is compiled with the `blocking` feature of the generated code. That
+    /// feature has to enable the blocking feature of reqwest.
+    pub fn with_blocking_feature(mut self, enabled: bool) -> Self {
+        self.blocking_feature = enabled;
+        self
     }
 
-    fn print_common_structs(&mut self) {
-        let header = Element::new("Header", ElementType::Struct);
-        let mut soap_fault = Element::new("SoapFault", ElementType::Struct);
-        soap_fault.xml_name = Option::Some("Fault".to_string());
-        soap_fault.add_ns("soapenv", "http://schemas.xmlsoap.org/soap/envelope/");
-        soap_fault.prefix = Option::Some("soapenv".to_string());
-        soap_fault.add(Element::new_field(
-            "fault_code",
-            "faultcode",
-            "String",
-            true,
-        ));
-        soap_fault.add(Element::new_field(
-            "fault_string",
-            "faultstring",
-            "String",
-            true,
-        ));
+    /// Generate the server side of every SOAP binding as well: a dispatcher that reads request
+    /// envelopes, calls the operation on an implementation of the port type and writes the
+    /// response envelopes.
+    pub fn with_server_dispatchers(mut self, enabled: bool) -> Self {
+        self.server_dispatchers = enabled;
+        self
+    }
 
-        let mut soap_response = Element::new("SoapResponse", ElementType::Alias);
-        soap_response.field_type =
-            Option::Some("Result<(reqwest::StatusCode, String), reqwest::Error>".to_string());
+    /// Generate a `Mock` implementation of every port type, which answers with closures set by the
+    /// tests. The mocks are compiled for the tests of the generated code, or with its mocks
+    /// feature.
+    pub fn with_mocks(mut self, enabled: bool) -> Self {
+        self.mocks = enabled;
+        self
+    }
 
-        let mut soap_error = Element::new("SoapError", ElementType::Static);
-        soap_error.set_content(
-            r#"#[derive(Debug)]
-pub enum SoapError<E> {
-    Fault(E),
-    RequestError(reqwest::Error),
-    XmlError(std::string::String),
-}
-"#,
-        );
+    /// Generate a `security` module with WS-Security headers, like a UsernameToken, that the SOAP
+    /// bindings add to every envelope they send. The headers need the sha1, rand and chrono
+    /// crates.
+    pub fn with_ws_security(mut self, enabled: bool) -> Self {
+        self.ws_security = enabled;
+        self
+    }
 
-        let mut soap_result = Element::new("SoapResult", ElementType::Static);
-        soap_result.set_content("pub type SoapResult<T, E> = Result<T, SoapError<E>>;\n");
+    /// Generate the X.509 signature of the body and the timestamp of the requests as well, by the
+    /// private key of a certificate that is sent along, with exclusive canonicalization, SHA-256
+    /// and RSA. Implies the WS-Security headers; the signature needs the rsa and sha2 crates.
+    pub fn with_ws_signature(mut self, enabled: bool) -> Self {
+        self.ws_signature = enabled;
+        self.ws_security |= enabled;
+        self
+    }
 
-        self.root.add(header);
-        self.root.add(soap_fault);
-        self.root.add(soap_response);
-        self.root.add(soap_error);
-        self.root.add(soap_result);
+    /// Generate an `addressing` module with the WS-Addressing headers, that the SOAP bindings add
+    /// to the envelopes they send once they are given them. The ids of the messages are UUIDs,
+    /// generated with the uuid crate.
+    pub fn with_ws_addressing(mut self, enabled: bool) -> Self {
+        self.ws_addressing = enabled;
+        self
     }
 
-    /// print parses the root of the XML file
-    fn print(&mut self, node: &Node) -> WriterResult<()> {
-        if !node.is_element() {
-            return Ok(());
-        }
+    /// Generate the xs:base64Binary fields with an xmime:expectedContentTypes as XopBinary values,
+    /// which the SOAP bindings send in parts of their own of an MTOM message, by xop:Include
+    /// references, instead of as inline base64. The parts of MTOM responses are resolved the
+    /// same way. Only applies to yaserde types.
+    pub fn with_mtom(mut self, enabled: bool) -> Self {
+        self.mtom = enabled;
+        self
+    }
 
-        match node.tag_name().name() {
-            "definitions" => self.print_definitions(node)?,
-            "schema" => self.print_xsd(node)?,
-            _ => {}
-        }
+    /// Format the generated code with prettyplease, so every run gives the same canonical layout.
+    /// Comments that are no doc comments do not survive the formatting.
+    pub fn with_formatted_output(mut self, enabled: bool) -> Self {
+        self.formatted_output = enabled;
+        self
+    }
 
-        Ok(())
+    /// Generate a builder for every struct, with a setter per field, that starts from the default
+    /// value of the struct.
+    pub fn with_builders(mut self, enabled: bool) -> Self {
+        self.builders = enabled;
+        self
     }
 
-    fn print_definitions(&mut self, node: &Node) -> WriterResult<()> {
-        let tns = self.get_some_attribute(node, "targetNamespace");
-        if let Some(s) = tns {
-            self.target_name_space.push(s.to_string());
-        }
+    /// Generate a test module with the types, with a test per struct that serializes its default
+    /// value, deserializes the result and checks that it serializes to the same XML again.
+    pub fn with_round_trip_tests(mut self, enabled: bool) -> Self {
+        self.round_trip_tests = enabled;
+        self
+    }
 
-        node.children()
-            .filter(|child| child.tag_name().name() == "types")
-            .try_for_each(|node| self.print_types(&node))?;
+    /// End every module file of the output directory with an `include!` of its extension file,
+    /// like `types_ext.rs` for `types.rs`, for hand-written code in the scope of the module. An
+    /// extension file is created when it does not exist, and never overwritten.
+    pub fn with_extension_files(mut self, enabled: bool) -> Self {
+        self.extension_files = enabled;
+        self
+    }
 
-        node.children()
-            .filter(|child| child.tag_name().name() == "message")
-            .for_each(|node| self.print_message(&node));
+    /// Generate code that can be included with `include!`, in a module of the including crate.
+    /// The inner attributes and doc comments at the top of the code become plain comments, so
+    /// the module that includes the code allows dead code and unused imports itself.
+    pub fn with_includable_output(mut self, enabled: bool) -> Self {
+        self.includable_output = enabled;
+        self
+    }
 
-        node.children()
-            .filter(|child| child.tag_name().name() == "portType")
-            .for_each(|node| self.print_port_type(&node));
+    /// Fail on documents with constructs that no code is generated for, listing all of them,
+    /// rather than skipping those constructs.
+    pub fn with_strict(mut self, enabled: bool) -> Self {
+        self.strict = enabled;
+        self
+    }
 
-        node.children()
-            .filter(|child| child.tag_name().name() == "binding")
-            .for_each(|node| self.print_binding(&node));
+    /// Append a suffix to the names that are Rust keywords, like `type_`, rather than generating
+    /// raw identifiers like `r#type`.
+    pub fn with_keyword_suffix(mut self, suffix: &str) -> Self {
+        self.keyword_suffix = Option::Some(suffix.to_string());
+        self
+    }
 
-        node.children()
-            .filter(|child| child.tag_name().name() == "service")
-            .for_each(|node| self.print_service(&node));
+    /// Generate types with serde derives, (de)serialized with quick-xml, instead of yaserde.
+    /// The serde backend generates the types of XML schemas only: no messages, bindings or clients
+    /// of WSDL documents, and no repeating sequences and groups.
+    pub fn with_serde_backend(mut self, enabled: bool) -> Self {
+        self.serde_backend = enabled;
+        self
+    }
 
-        if tns.is_some() {
-            self.target_name_space.pop();
-        }
-        Ok(())
+    /// Only generate the given operations, together with the messages and types they refer to.
+    pub fn with_operation_filter(mut self, operations: &[&str]) -> Self {
+        self.operation_filter = Option::Some(operations.iter().map(|o| o.to_string()).collect());
+        self
     }
 
-    fn print_types(&mut self, node: &Node) -> WriterResult<()> {
-        node.children()
-            .filter(|c| c.has_tag_name("schema"))
-            .try_for_each(|c| self.print_xsd(&c))?;
+    /// Generate the types of every namespace in a module of their own, within the types module,
+    /// instead of next to each other. Types keep their local names in their module.
+    pub fn with_namespace_modules(mut self, enabled: bool) -> Self {
+        self.namespace_modules = enabled;
+        self
+    }
 
-        Ok(())
+    /// Name the module of the types of a namespace, rather than after the last segment of the
+    /// namespace URI.
+    pub fn with_namespace_module(mut self, namespace: &str, module: &str) -> Self {
+        self.namespace_module_names
+            .insert(namespace.to_string(), module.to_string());
+        self
     }
 
-    fn print_xsd(&mut self, node: &Node) -> WriterResult<()> {
-        let tns = self.get_some_attribute(node, "targetNamespace");
-        if let Some(s) = tns {
-            self.target_name_space.push(s.to_string());
-        }
+    /// Import the schema for a namespace from a file in the base path, or a URL, whenever an
+    /// import of that namespace does not specify a schemaLocation.
+    pub fn with_catalog_entry(mut self, namespace: &str, location: &str) -> Self {
+        self.namespace_catalog
+            .insert(namespace.to_string(), location.to_string());
+        self
+    }
 
-        self.find_namespaces(node);
+    /// Generate the code of more documents in the same run as the input, by their location in
+    /// the base path. The services of all documents end up in one module tree, and the schemas
+    /// they share are generated once.
+    pub fn with_additional_inputs(mut self, file_names: &[&str]) -> Self {
+        self.additional_inputs = file_names.iter().map(|f| f.to_string()).collect();
+        self
+    }
 
-        node.children()
-            .try_for_each(|child| match child.tag_name().name() {
-                "import" => self.import_file(&child),
-                "element" => {
-                    let module = self.pick_section(TYPES_MOD);
-                    let mut _module = &mut *module.deref().borrow_mut();
-                    self.print_element(&child, true, &mut None, _module)
-                }
-                "complexType" => {
-                    if let Some(n) = self.get_some_attribute(&child, "name") {
-                        let module = self.pick_section(TYPES_MOD);
-                        let mut _module = &mut *module.deref().borrow_mut();
-                        self.print_complex_element(&child, n, false, _module)
-                    } else {
-                        Ok(())
-                    }
-                }
-                "simpleType" => {
-                    if let Some(n) = self.get_some_attribute(&child, "name") {
-                        let module = self.pick_section(TYPES_MOD);
-                        let mut _module = &mut *module.deref().borrow_mut();
-                        self.print_simplex_element(&child, n, _module)
-                    } else {
-                        Ok(())
-                    }
-                }
-                _ => Ok(()),
-            })?;
+    /// Name a generated module differently, like `with_module_name("types", "model")`. The modules
+    /// are `types`, `messages`, `ports`, `bindings` and `services`, and those given the same name
+    /// are merged into one.
+    pub fn with_module_name(mut self, module: &str, name: &str) -> Self {
+        self.module_names
+            .insert(module.to_string(), name.to_string());
+        self
+    }
 
-        if tns.is_some() {
-            self.target_name_space.pop();
-        }
+    /// Declare a generated module with another visibility than `pub`, like `pub(crate)`. The
+    /// module is the one of that name by default, as for `with_module_name`.
+    pub fn with_module_visibility(mut self, module: &str, visibility: &str) -> Self {
+        self.module_visibility
+            .insert(module.to_string(), visibility.to_string());
+        self
+    }
 
-        Ok(())
+    /// Keep the documents fetched from URLs in this directory, and read them from there on later
+    /// runs rather than fetching them again.
+    pub fn with_cache_dir<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        self.cache = Option::Some(DocumentCache::new(dir));
+        self
     }
 
-    fn find_namespaces(&mut self, node: &Node) {
-        node.namespaces().iter().for_each(|ns| {
-            if let Some(name) = ns.name() {
-                self.namespaces
-                    .insert(name.to_string(), ns.uri().to_string());
-            }
-        });
+    /// Read documents with a URL from the cache only, failing on those that are not in it
+    pub fn with_offline(mut self, enabled: bool) -> Self {
+        self.offline = enabled;
+        self
     }
 
-    fn import_file(&mut self, node: &Node) -> WriterResult<()> {
-        let name = match self.get_some_attribute(node, "schemaLocation") {
-            None => return Ok(()),
-            Some(n) => n,
-        };
+    /// Fetch documents with a URL again, and update the cache with them
+    pub fn with_refresh(mut self, enabled: bool) -> Self {
+        self.refresh = enabled;
+        self
+    }
 
-        let namespace = match self.get_some_attribute(node, "namespace") {
-            None => self
-                .target_name_space
-                .last()
-                .map(|s| s.clone())
-                .unwrap_or_default(),
-            Some(n) => n.to_string(),
-        };
+    /// Add derives to every generated struct and enum, on top of the ones the types need. The
+    /// types implement Default either way.
+    pub fn with_derives(mut self, derives: &[&str]) -> Self {
+        self.extra_derives = derives.iter().map(|d| d.to_string()).collect();
+        self
+    }
 
-        self.target_name_space.push(namespace);
+    /// Add derives to the type with the given Rust name, instead of the derives set by
+    /// `with_derives`.
+    pub fn with_type_derives(mut self, type_name: &str, derives: &[&str]) -> Self {
+        self.type_derives.insert(
+            type_name.to_string(),
+            derives.iter().map(|d| d.to_string()).collect(),
+        );
+        self
+    }
 
-        self.import_count += 1;
-        let prefix = format!("{}{}", IMPORT_PREFIX, self.import_count);
+    /// Give a definition another Rust name. The definition is found by its name in the documents,
+    /// or by the Rust name it would get otherwise.
+    pub fn with_type_rename(mut self, name: &str, rust_name: &str) -> Self {
+        self.type_renames
+            .insert(name.to_string(), rust_name.to_string());
+        self
+    }
 
-        let my_prefix = self.ns_prefix.clone();
-        self.ns_prefix = prefix;
+    /// Use a Rust type for every reference to an XSD type, by the local name of the XSD type, like
+    /// `decimal` or `Money`. The Rust type (de)serializes from the text of the element or attribute.
+    pub fn with_type_mapping(mut self, xsd_type: &str, rust_type: &str) -> Self {
+        self.type_mappings
+            .insert(xsd_type.to_string(), rust_type.to_string());
+        self
+    }
 
-        self.process_file_in_path(name, false)?;
+    /// The messages, port types, bindings and services of the WSDL documents of the last run
+    pub fn model(&self) -> &Definitions {
+        &self.model
+    }
 
-        self.ns_prefix = my_prefix;
+    /// The model of the processed documents together with the generated types, as a JSON
+    /// document. The types of the namespace modules are named by their path in the types module.
+    pub fn model_json(&self) -> String {
+        let mut model = self.model.to_json();
+        let mut types = vec![];
+        if let Some(module) = self.root.child(self.module_name(TYPES_MOD)) {
+            describe_types(&module.borrow(), "", &mut types);
+        }
+        model["types"] = serde_json::Value::from(types);
+        serde_json::to_string_pretty(&model).unwrap_or_default()
+    }
 
-        self.target_name_space.pop();
-        Ok(())
+    fn is_operation_selected(&self, operation_name: &str) -> bool {
+        match &self.operation_filter {
+            None => true,
+            Some(filter) => filter.contains(operation_name),
+        }
     }
 
-    fn on_default_namespace(&self) -> bool {
-        if let (Some(default_namespace), Some(namespace)) =
-            (&self.default_namespace, self.target_name_space.last())
+    /// Adds the modules in a fixed order, each builds on the modules before it
+    /// Renames the generated modules, and sets their visibility. Modules given the same name are
+    /// merged into the first of them.
+    fn apply_module_layout(&mut self) {
+        let sections = [
+            TYPES_MOD,
+            MESSAGES_MOD,
+            PORTS_MOD,
+            BINDINGS_MOD,
+            SERVICES_MOD,
+        ];
+        for module in self
+            .module_names
+            .keys()
+            .chain(self.module_visibility.keys())
         {
-            return default_namespace == namespace;
+            if !sections.contains(&module.as_str()) {
+                warn!(
+                    "ignoring [layout.{}] of the configuration, no such module is generated",
+                    module
+                );
+            }
         }
 
-        false
+        let mut root = std::mem::replace(&mut self.root, root());
+        self.layout_modules(&mut root);
+        if let Some(blocking) = root.child(BLOCKING_MOD) {
+            self.layout_modules(&mut blocking.borrow_mut());
+        }
+        self.root = root;
     }
 
-    fn print_element(
-        &mut self,
-        node: &Node,
-        is_top_level: bool,
-        parent: &mut Option<&mut Element>,
-        module: &mut Element,
-    ) -> WriterResult<()> {
-        let as_enum = node.has_tag_name("choice");
-        if as_enum {
-            let seq: Vec<Node> = node
-                .children()
-                .filter(|child| child.has_tag_name("sequence"))
-                .collect();
-
-            for node in seq {
-                self.print_sequence(&node, parent, module)?
+    /// Merges, renames and sets the visibility of the sections of a module
+    fn layout_modules(&self, parent: &mut Element) {
+        let sections = [
+            TYPES_MOD,
+            MESSAGES_MOD,
+            PORTS_MOD,
+            BINDINGS_MOD,
+            SERVICES_MOD,
+        ];
+        let mut merged = HashSet::new();
+        for (pos, section) in sections.iter().enumerate() {
+            let module = match parent.child(section) {
+                Some(module) if !merged.contains(section) => module,
+                _ => continue,
+            };
+            let name = self.module_name(section);
+            for other in &sections[pos + 1..] {
+                if self.module_name(other) != name {
+                    continue;
+                }
+                let other_module = match parent.child(other) {
+                    Some(other_module) => other_module,
+                    None => continue,
+                };
+                let children = std::mem::take(&mut other_module.borrow_mut().children);
+                let mut module = module.borrow_mut();
+                for child in children {
+                    let child = child.borrow().clone();
+                    let clashes = module
+                        .child(&child.name)
+                        .is_some_and(|c| is_definition(&c.borrow()) && is_definition(&child));
+                    if clashes {
+                        warn!(
+                            "{}: module {} gets {} from both {} and {}, keeping the one of {}",
+                            child.position.as_deref().unwrap_or_default(),
+                            name,
+                            child.name,
+                            section,
+                            other,
+                            section
+                        );
+                        continue;
+                    }
+                    module.add(child);
+                }
+                merged.insert(*other);
             }
+        }
+        parent.retain(|child| {
+            !matches!(child.element_type, ElementType::Module)
+                || !merged.contains(child.name.as_str())
+        });
 
-            let elements: Vec<Node> = node
-                .children()
-                .filter(|child| child.has_tag_name("element"))
-                .collect();
-
-            for node in elements {
-                self.print_element(&node, false, parent, module)?
-            }
+        for section in sections {
+            let module = match parent.child(section) {
+                Some(module) => module,
+                None => continue,
+            };
+            // a merged module takes the first visibility of the modules it is made of
+            let name = self.module_name(section);
+            let visibility = sections
+                .iter()
+                .filter(|other| self.module_name(other) == name)
+                .find_map(|other| self.module_visibility.get(*other));
+            let mut module = module.borrow_mut();
+            module.name = name.to_string();
+            module.visibility = visibility.cloned();
         }
+        parent.reindex();
+    }
 
-        let element_name = match self.get_some_attribute(node, "name") {
-            None => return Ok(()),
-            Some(n) => n,
-        };
-
-        let as_vec = { !matches!(self.get_some_attribute(node, "maxOccurs"), Some("1") | None) };
-
-        let as_option = {
-            matches!(
-                (
-                    self.get_some_attribute(node, "nillable"),
-                    self.get_some_attribute(node, "minOccurs"),
-                ),
-                (Some(_), _) | (_, Some("0"))
-            )
-        };
-
-        let maybe_complex = node
-            .children()
-            .find(|child| child.has_tag_name("complexType"));
-
-        let maybe_simplex = node
-            .children()
-            .find(|child| child.has_tag_name("simpleType"));
-
-        let mut type_name = match self.get_some_attribute(node, "type") {
-            None => to_pascal_case(element_name),
-            Some(t) => t.to_string(),
-        };
+    /// The name of a generated module in the code, by the name it has by default
+    fn module_name<'a>(&'a self, module: &'a str) -> &'a str {
+        self.module_names
+            .get(module)
+            .map(|name| name.as_str())
+            .unwrap_or(module)
+    }
 
-        if is_top_level {
-            // top-level == type alias
-            let top_level_name = to_pascal_case(element_name);
-            let alias = self.fetch_type(&type_name);
+    fn init_modules(&mut self) {
+        for name in [
+            TYPES_MOD,
+            MESSAGES_MOD,
+            PORTS_MOD,
+            BINDINGS_MOD,
+            SERVICES_MOD,
+        ] {
+            self.root.add(Element::new_module(name));
+        }
+    }
 
-            if top_level_name != alias {
-                let mut alias_element = Element::new(top_level_name.as_str(), ElementType::Alias);
-                alias_element.field_type = Option::Some(alias);
-                module.add(alias_element);
-                return Ok(());
-            }
+    /// Writes the (de)serialization of the generated types
+    fn emitter(&self) -> &'static dyn Emitter {
+        if self.serde_backend {
+            &SerdeEmitter
         } else {
-            let snake_name = to_snake_case(element_name);
-            let field_name = self.shield_reserved_names(&snake_name);
+            &YaserdeEmitter
+        }
+    }
 
-            // fields
-            let mut element = if let Some(_tns) = self.target_name_space.last() {
-                if is_top_level {
-                    let mut e = Element::new(field_name, ElementType::Field);
-                    e.xml_name = Option::Some(element_name.to_string());
-                    e.add_ns("xsi", "http://www.w3.org/2001/XMLSchema-instance");
-                    e
-                } else if self.on_default_namespace() {
-                    let mut e = Element::new(field_name, ElementType::Field);
-                    e.xml_name = Option::Some(element_name.to_string());
-                    e
-                } else {
-                    let mut e = Element::new(field_name, ElementType::Field);
-                    e.xml_name = Option::Some(element_name.to_string());
-                    e.prefix = Option::Some(self.ns_prefix.to_string());
-                    e
+    pub fn process_file(&mut self, base_path: &str, file_name: &str) -> ZeepResult<()> {
+        if let Some(dir) = self.output_dir.clone() {
+            std::fs::create_dir_all(&dir).map_err(|source| ZeepError::Output {
+                path: dir.display().to_string(),
+                source,
+            })?;
+            for (name, content) in self.generate_to_files(base_path, file_name)? {
+                if self.extension_files && name != "mod.rs" {
+                    let path = dir.join(extension_file_name(&name));
+                    if !path.exists() {
+                        let module = name.trim_end_matches(".rs");
+                        std::fs::write(&path, extension_file(module)).map_err(|source| {
+                            ZeepError::Output {
+                                path: path.display().to_string(),
+                                source,
+                            }
+                        })?;
+                    }
                 }
-            } else {
-                Element::new(field_name, ElementType::Field)
-            };
 
-            if let Some(simple) = maybe_simplex {
-                type_name = match self.deconstruct_simplex_element(&simple) {
-                    Ok(tn) => tn,
-                    Err(_) => type_name,
+                // the modules of a scaffold are those of its crate
+                let path = match name.as_str() {
+                    "mod.rs" if self.scaffold_dir.is_some() => dir.join("lib.rs"),
+                    _ => dir.join(name),
                 };
+                std::fs::write(&path, content).map_err(|source| ZeepError::Output {
+                    path: path.display().to_string(),
+                    source,
+                })?;
             }
-
-            // add the element to the owning structure
-            element.field_type = Option::Some(self.fetch_type(&type_name));
-            element.vector = as_vec;
-            element.optional = as_option;
-
-            if let Some(p) = parent {
-                p.add(element);
+            if let Some(scaffold_dir) = self.scaffold_dir.clone() {
+                self.write_scaffold(&scaffold_dir)?;
             }
+            return Ok(());
         }
 
-        if let Some(complex) = maybe_complex {
-            self.print_complex_element(&complex, element_name, is_top_level, module)?
+        let output = self.generate_to_string(base_path, file_name)?;
+
+        // once all elements are processed, write them to output
+        if let Some(mut writer) = self.writer.take() {
+            writer
+                .write_all(output.as_bytes())
+                .map_err(|source| ZeepError::Output {
+                    path: "output".to_string(),
+                    source,
+                })?;
+            self.writer.replace(writer);
         }
 
         Ok(())
     }
 
-    fn get_some_attribute<'a>(&self, node: &'a Node, attr_name: &str) -> Option<&'a str> {
-        match node.attributes().iter().find(|a| a.name() == attr_name) {
-            None => None,
-            Some(a) => Some(a.value()),
+    /// Writes the manifest and the example of a scaffold, unless they exist
+    fn write_scaffold(&self, dir: &Path) -> ZeepResult<()> {
+        let name = crate_name(dir);
+        let mut files = vec![(
+            dir.join("Cargo.toml"),
+            cargo_manifest(&name, &self.dependencies()),
+        )];
+        match self.example_call() {
+            Some(call) => files.push((
+                dir.join("examples").join(EXAMPLE_FILE),
+                example(&name, &call, self.blocking_clients),
+            )),
+            None => info!("no operation to call in an example of {}", name),
         }
-    }
 
-    fn get_some_attribute_as_string(&self, node: &Node, attr_name: &str) -> Option<String> {
-        match node.attributes().iter().find(|a| a.name() == attr_name) {
-            None => None,
-            Some(a) => Some(a.value().to_string()),
+        for (path, content) in files {
+            if path.exists() {
+                continue;
+            }
+            let write = |path: &Path| {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(path, &content)
+            };
+            write(&path).map_err(|source| ZeepError::Output {
+                path: path.display().to_string(),
+                source,
+            })?;
         }
+        Ok(())
     }
 
-    fn fetch_type(&self, node_type: &str) -> String {
-        match self.split_type(node_type) {
-            "byte" => "i8".to_string(),
-            "string" | "normalizedString" | "base64Binary" | "hexBinary" | "anyURI" => {
-                "String".to_string()
-            }
-            "decimal" | "double" => "f64".to_string(),
-            "float" => "f32".to_string(),
-            "integer" | "int" | "negativeInteger" | "nonNegativeInteger" | "nonPositiveInteger"
-            | "positiveInteger" => "i32".to_string(),
-            "long" => "i64".to_string(),
-            "unsignedLong" => "u64".to_string(),
-            "unsignedInt" => "u32".to_string(),
-            "unsignedShort" => "u16".to_string(),
-            "unsignedByte" => "u8".to_string(),
-            "short" => "i16".to_string(),
-            "boolean" => "bool".to_string(),
-            // use String for date types
-            "date" | "dateTime" | "time" => "String".to_string(),
-            v => to_pascal_case(v),
+    /// The dependencies of the generated code with the options of the writer
+    fn dependencies(&self) -> Dependencies {
+        Dependencies {
+            blocking: self.blocking_clients,
+            blocking_feature: self.blocking_feature && !self.blocking_clients,
+            // the timestamps of WS-Security are read and written with chrono
+            chrono: self.chrono_dates || self.ws_security,
+            regex: self.pattern_validation,
+            serde: self.serde_backend,
+            mocks: self.mocks,
+            ws_security: self.ws_security,
+            ws_signature: self.ws_signature,
+            ws_addressing: self.ws_addressing,
         }
     }
 
-    fn split_type<'a>(&self, node_type: &'a str) -> &'a str {
-        node_type.split(':').last().unwrap_or("String")
-    }
+    /// The first operation of the first port of a service that is generated, to call in the
+    /// example of a scaffold. The client of a service is that of its first port.
+    fn example_call(&self) -> Option<Call> {
+        self.model.services.iter().find_map(|service| {
+            let port = service.ports.first()?;
+            let binding = self
+                .model
+                .bindings
+                .iter()
+                .find(|binding| binding.name == port.binding)?;
+            let port_type = self.type_ident(&binding.port_type.name);
+            let function = find_trait(&self.root, &port_type)?
+                .borrow()
+                .children
+                .iter()
+                .find_map(|function| {
+                    let function = function.borrow();
+                    let args = function.function_args.as_ref()?;
+                    Some((function.name.clone(), 1 + args.attachments.len()))
+                })?;
 
-    fn init_element(&self, name: &str, is_top_level: bool) -> Element {
-        let some_tns = self.target_name_space.last();
+            Some(Call {
+                service: format!(
+                    "{}::{}",
+                    self.module_name(SERVICES_MOD),
+                    self.type_ident(&service.name.name)
+                ),
+                port_type: format!("{}::{}", self.module_name(PORTS_MOD), port_type),
+                operation: function.0,
+                arguments: function.1,
+            })
+        })
+    }
 
-        if let Some(tns) = some_tns {
-            let element_name = to_pascal_case(name);
-            let mut e = Element::new(&element_name, ElementType::Struct);
+    /// Runs the full generator pipeline and returns the generated source, without touching the
+    /// configured output writer. Any output of a previous run is discarded.
+    pub fn generate_to_string(&mut self, base_path: &str, file_name: &str) -> ZeepResult<String> {
+        self.generate(base_path, file_name)?;
+        let mut output = self.root.render_with(self.emitter());
+        if self.formatted_output {
+            output = format_source(output);
+        }
+        if self.includable_output {
+            output = includable(&output);
+        }
+        Ok(output)
+    }
 
-            if is_top_level {
-                e.prefix = Option::Some(self.ns_prefix.to_string());
-                e.xml_name = Option::Some(name.to_string());
+    /// Runs the analysis of the generator on the documents without rendering any code, for the
+    /// report of what it finds. Unlike generating, strict mode does not stop the analysis.
+    pub fn check(&mut self, base_path: &str, file_name: &str) -> ZeepResult<&GenerationReport> {
+        let strict = std::mem::replace(&mut self.strict, false);
+        let result = self.generate(base_path, file_name);
+        self.strict = strict;
+        result.map(move |_| &self.report)
+    }
 
-                // declare all namespaces
-                e.add_ns("tns", tns);
-                e.add_ns("xsi", "http://www.w3.org/2001/XMLSchema-instance");
-                e
-            } else if self.on_default_namespace() {
-                e.xml_name = Option::Some(name.to_string());
-                e
-            } else {
-                e.prefix = Option::Some(self.ns_prefix.to_string());
-                e.xml_name = Option::Some(name.to_string());
-                e.add_ns(&self.ns_prefix, tns);
-                e
+    /// Runs the full generator pipeline like `generate_to_string`, but returns the source of
+    /// every module apart, as file name and content, along with the `mod.rs` that declares them.
+    pub fn generate_to_files(
+        &mut self,
+        base_path: &str,
+        file_name: &str,
+    ) -> ZeepResult<Vec<(String, String)>> {
+        self.generate(base_path, file_name)?;
+        let mut files = self.root.render_files(self.emitter());
+        if self.extension_files {
+            for (name, content) in files.iter_mut().filter(|(name, _)| name != "mod.rs") {
+                content.push_str(&format!("\ninclude!(\"{}\");\n", extension_file_name(name)));
             }
-        } else {
-            let element_name = to_pascal_case(name);
-            let mut e = Element::new(&element_name, ElementType::Struct);
-            e.xml_name = Option::Some(name.to_string());
-            e
         }
+        if self.formatted_output {
+            return Ok(files
+                .into_iter()
+                .map(|(name, content)| (name, format_source(content)))
+                .collect());
+        }
+        Ok(files)
     }
 
-    fn print_simplex_element(
-        &mut self,
-        node: &Node,
-        name: &str,
-        module: &mut Element,
-    ) -> WriterResult<()> {
-        if self.have_seen_type(name, module) {
-            return Ok(());
+    fn generate(&mut self, base_path: &str, file_name: &str) -> ZeepResult<()> {
+        self.generate_modules(base_path, file_name)?;
+        if self.blocking_clients {
+            self.make_clients_blocking();
+        } else if self.blocking_feature && !self.serde_backend && !self.model.bindings.is_empty() {
+            self.add_blocking_module();
         }
+        self.apply_module_layout();
+        Ok(())
+    }
 
-        let mut parent_element = self.init_element(name, false);
-        let type_name = match self.deconstruct_simplex_element(&node) {
-            Ok(tn) => tn,
-            Err(_) => to_pascal_case(name),
-        };
-
-        let mut field = Element::new("body", ElementType::Field);
-        let field_type = self.fetch_type(&type_name);
-        field.text_field = field_type == "String";
-        field.field_type = Option::Some(field_type);
-        field.xml_name = None;
-
-        // text fields are automatically "flattened"
-        field.flatten = !field.text_field;
+    /// Turns the async clients that are generated into blocking ones
+    fn make_clients_blocking(&mut self) {
+        for section in CLIENT_MODULES {
+            if let Some(module) = self.root.child(section) {
+                let blocking = module.borrow().to_blocking();
+                *module.borrow_mut() = blocking;
+            }
+        }
+    }
 
-        parent_element.add(field);
+    /// Adds blocking copies of the async clients, in a module of their own behind the blocking
+    /// feature. The port types keep the items that are the same for both, like the faults, in
+    /// the module of the async clients.
+    fn add_blocking_module(&mut self) {
+        let emitter = self.emitter();
+        let mut blocking = Element::new_module(BLOCKING_MOD);
+        blocking.blocking = true;
+        blocking.feature = Option::Some(BLOCKING_MOD.to_string());
+        for section in CLIENT_MODULES {
+            let async_module = match self.root.child(section) {
+                Some(module) => module,
+                None => continue,
+            };
+            let async_module = async_module.borrow();
+            let mut module = async_module.to_blocking();
+            if section == PORTS_MOD {
+                module.retain(|child| {
+                    async_module.child(&child.name).is_none_or(|async_child| {
+                        async_child.borrow().render_with(emitter) != child.render_with(emitter)
+                    })
+                });
+                let mut shared = Element::new("shared_port_items", ElementType::Static);
+                shared.set_content(&format!(
+                    "pub use super::super::{}::*;\n",
+                    self.module_name(PORTS_MOD)
+                ));
+                module.children.insert(0, Rc::new(RefCell::new(shared)));
+                module.reindex();
+            }
+            blocking.add(module);
+        }
+        self.root.add(blocking);
+    }
 
-        if !self.have_seen_type(&parent_element.name, module) {
-            module.add(parent_element);
+    fn generate_modules(&mut self, base_path: &str, file_name: &str) -> ZeepResult<()> {
+        self.base_path = base_path.to_string();
+        self.target_name_space.clear();
+        self.namespace_prefixes.clear();
+        self.form_defaults.clear();
+        self.model = Definitions::default();
+        self.port_types.clear();
+        self.message_types.clear();
+        self.namespaces.clear();
+        self.attribute_groups.clear();
+        self.element_groups.clear();
+        self.global_elements.clear();
+        self.substitution_groups.clear();
+        self.derived_types.clear();
+        self.redefined_bases.clear();
+        self.type_names.clear();
+        self.wsdl_documents.clear();
+        self.binding_pass = false;
+        self.document_contents.clear();
+        self.defined_types.clear();
+        self.type_namespaces.clear();
+        self.import_count = 0;
+        self.type_references.clear();
+        self.report = GenerationReport::default();
+        self.documents.clear();
+        self.sources.clear();
+        self.root = root();
+        self.print_global_header();
+        if self.serde_backend {
+            self.print_serde_structs();
+        } else {
+            self.print_common_structs();
+        }
+        self.init_modules();
+        // a binding may refer to a port type of any document, whichever imports the other, so the
+        // bindings are generated in a second pass, once the port types of all documents are
+        self.process_inputs(file_name)?;
+        self.binding_pass = true;
+        let result = self.process_inputs(file_name);
+        self.binding_pass = false;
+        result?;
+        let skipped: Vec<Construct> = self.report.skipped().cloned().collect();
+        if self.strict && !skipped.is_empty() {
+            return Err(ZeepError::Skipped(skipped));
+        }
+        self.resolve_polymorphic_types();
+        if self.operation_filter.is_some() {
+            self.prune_unreferenced();
+        }
+        self.resolve_type_references();
+        self.resolve_default_values();
+        self.apply_type_options();
+        if self.serde_backend {
+            self.lower_flattened_fields();
+        }
+        if self.namespace_modules {
+            self.split_namespace_modules();
         }
+        if self.round_trip_tests {
+            self.print_round_trip_tests();
+        }
+        Ok(())
+    }
 
+    /// Processes the input and the additional inputs, along with the documents they import
+    fn process_inputs(&mut self, file_name: &str) -> ZeepResult<()> {
+        self.wsdl_documents.clear();
+        self.wsdl_documents.insert(file_name.to_string());
+        self.process_file_in_path(file_name, None)?;
+        for input in self.additional_inputs.clone() {
+            // an input that another one imports is processed once
+            if self.wsdl_documents.insert(input.clone()) {
+                self.process_file_in_path(&input, None)?;
+            }
+        }
         Ok(())
     }
 
-    fn print_complex_element(
-        &mut self,
-        node: &Node,
-        name: &str,
-        is_top_level: bool,
-        module: &mut Element,
-    ) -> WriterResult<()> {
-        if self.have_seen_type(name, module) {
-            return Ok(());
+    /// Processes a document, that is imported as the namespace given, if any
+    fn process_file_in_path(&mut self, file_name: &str, namespace: Option<&str>) -> ZeepResult<()> {
+        let xml = match self.document_contents.get(file_name) {
+            Some(xml) => xml.clone(),
+            None => {
+                let xml = self.read_to_string(file_name)?;
+                self.document_contents
+                    .insert(file_name.to_string(), xml.clone());
+                xml
+            }
+        };
+        let doc = roxmltree::Document::parse(&xml).map_err(|e| ZeepError::xml(file_name, e))?;
+        if !self.binding_pass {
+            self.record_uncovered(&doc, file_name);
+            let foreign = namespace.and_then(|ns| foreign_namespace(&doc, file_name, ns));
+            for problem in undeclared_prefixes(&doc, file_name)
+                .into_iter()
+                .chain(foreign)
+            {
+                self.record_namespace_problem(problem);
+            }
         }
+        self.documents.push(file_name.to_string());
+        let result = doc.root().children().try_for_each(|n| self.print(&n));
+        self.documents.pop();
+        result
+    }
 
-        let mut element = self.init_element(name, is_top_level);
+    /// Keeps track of the constructs of a document that the generated code does not cover
+    fn record_uncovered(&mut self, doc: &roxmltree::Document, file_name: &str) {
+        for construct in uncovered_constructs(doc, file_name, self.pattern_validation) {
+            self.record(construct);
+        }
+    }
 
-        let maybe_sequence = node.children().find(|child| child.has_tag_name("sequence"));
+    /// Where a node of the document being processed is, as file and line
+    fn position(&self, node: &Node) -> String {
+        format!(
+            "{}:{}",
+            self.documents
+                .last()
+                .map(String::as_str)
+                .unwrap_or_default(),
+            node.document().text_pos_at(node.range().start).row
+        )
+    }
 
-        let maybe_complex = node
-            .children()
-            .find(|child| child.has_tag_name("complexContent"));
+    /// Keeps track of a construct of the document being processed that the generated code skips
+    /// or approximates
+    fn report_construct(&mut self, coverage: Coverage, node: &Node, reason: &str) {
+        let file = self.documents.last().cloned().unwrap_or_default();
+        self.record(Construct::new(coverage, node, &file, reason));
+    }
 
-        node.children()
-            .filter(|c| c.has_tag_name("attribute"))
-            .for_each(|c| {
-                self.print_attribute(&c, &mut element);
-            });
+    /// Same as `report_construct`, for a definition of the model
+    fn report_at(&mut self, coverage: Coverage, position: &Position, reason: &str) {
+        self.record(Construct::at(coverage, position, reason));
+    }
 
-        if let Some(sequence) = maybe_sequence {
-            self.print_sequence(&sequence, &mut Some(&mut element), module)?;
+    /// Records a construct once, for a document that is processed more than once
+    fn record(&mut self, construct: Construct) {
+        if self.report.constructs.contains(&construct) {
+            return;
         }
-
-        if let Some(complex) = maybe_complex {
-            self.print_complex_content(&complex, &mut Some(&mut element), module)?;
+        match construct.coverage {
+            Coverage::Skipped => warn!(
+                "{}:{}: skipping {}, {}",
+                construct.file, construct.line, construct.tag, construct.reason
+            ),
+            Coverage::Approximated => warn!(
+                "{}:{}: approximating {}, {}",
+                construct.file, construct.line, construct.tag, construct.reason
+            ),
         }
+        self.report.constructs.push(construct);
+    }
 
-        if !self.have_seen_type(&element.name, module) {
-            module.add(element);
+    /// Records a problem of the namespaces of the documents once
+    fn record_namespace_problem(&mut self, problem: Problem) {
+        if !self.report.namespaces.contains(&problem) {
+            warn!("{}", problem);
+            self.report.namespaces.push(problem);
         }
-
-        Ok(())
     }
 
-    fn print_attribute(&mut self, node: &Node, parent: &mut Element) {
-        let element_name = match self.get_some_attribute(node, "name") {
-            None => return,
-            Some(n) => n,
-        };
-
-        let element_type = match self.get_some_attribute(node, "type") {
-            None => return,
-            Some(n) => self.fetch_type(n),
-        };
+    /// The constructs of the documents of the last run that the generated code skips or only
+    /// approximates, and the problems of the documents
+    pub fn report(&self) -> &GenerationReport {
+        &self.report
+    }
 
-        let optional = match self.get_some_attribute(node, "use") {
-            None => false,
-            Some(a) => a != "required",
-        };
+    fn read_to_string(&mut self, file_name: &str) -> ZeepResult<String> {
+        if is_url(file_name) {
+            return self.fetch_document(file_name);
+        }
+        let f_in = format!("{}/{}", self.base_path, file_name);
+        let xml = std::fs::read_to_string(&f_in).map_err(|source| ZeepError::Io {
+            path: f_in.clone(),
+            source,
+        })?;
+        if !self.sources.contains(&f_in) {
+            self.sources.push(f_in);
+        }
+        Ok(xml)
+    }
 
-        let mut element =
-            Element::new(to_snake_case(element_name).as_str(), ElementType::Attribute);
+    fn fetch_document(&self, url: &str) -> ZeepResult<String> {
+        if !self.refresh {
+            if let Some(content) = self.cache.as_ref().and_then(|cache| cache.get(url)) {
+                debug!("read {} from the cache", url);
+                return Ok(content);
+            }
+        }
+        if self.offline {
+            return Err(ZeepError::Io {
+                path: url.to_string(),
+                source: std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "not in the cache, and fetching is disabled",
+                ),
+            });
+        }
 
-        element.xml_name = Option::Some(element_name.to_string());
-        element.field_type = Option::Some(element_type);
-        element.optional = optional;
-        parent.add(element)
+        let content = fetch_url(url)?;
+        if let Some(cache) = &self.cache {
+            cache.put(url, &content).map_err(|source| ZeepError::Io {
+                path: cache.dir().display().to_string(),
+                source,
+            })?;
+        }
+        Ok(content)
     }
 
-    fn deconstruct_simplex_element(&mut self, node: &Node) -> WriterResult<String> {
-        let restriction = match node.children().find(|c| c.has_tag_name("restriction")) {
-            None => {
-                return Err(WriterError {
-                    message: "restriction element is missing".to_string(),
-                })
+    /// The location of a document referred to by the current one. Relative locations in a
+    /// document fetched from a URL are resolved against that URL, like a browser would.
+    fn resolve_location(&self, location: &str) -> String {
+        #[cfg(feature = "remote")]
+        if let Some(document) = self.documents.last().filter(|d| is_url(d)) {
+            if let Ok(url) = reqwest::Url::parse(document).and_then(|url| url.join(location)) {
+                return url.to_string();
             }
-            Some(b) => b,
-        };
+        }
+        location.to_string()
+    }
 
-        let base = match self.get_some_attribute(&restriction, "base") {
-            None => {
-                return Err(WriterError {
-                    message: "base type is missing".to_string(),
-                })
-            }
-            Some(b) => b,
-        };
+    /// The paths of the local documents read in the last run, in the order they were read, to
+    /// regenerate the code when one of them changes
+    pub fn sources(&self) -> &[String] {
+        &self.sources
+    }
 
-        Ok(base.to_string())
+    pub fn have_seen_type(&self, type_def: &str, module: &Element) -> bool {
+        module.has_child(type_def)
     }
 
-    fn print_sequence(
-        &mut self,
-        node: &Node,
-        parent: &mut Option<&mut Element>,
-        module: &mut Element,
-    ) -> WriterResult<()> {
-        node.children().try_for_each(|child| {
-            if let Some(p) = parent {
-                self.print_element(&child, false, &mut Some(p), module)
-            } else {
-                Ok(())
-            }
-        })?;
-        Ok(())
-    }
-
-    fn print_complex_content(
-        &mut self,
-        node: &Node,
-        parent: &mut Option<&mut Element>,
-        module: &mut Element,
-    ) -> WriterResult<()> {
-        if let Some(extension) = node
-            .children()
-            .find(|child| child.has_tag_name("extension"))
-        {
-            self.print_extension(&extension, parent);
-
-            let maybe_sequence = extension
-                .children()
-                .find(|ext_child| ext_child.has_tag_name("sequence"));
-
-            if let Some(sequence) = maybe_sequence {
-                self.print_sequence(&sequence, parent, module)?;
-            }
-        }
+    fn print_global_header(&mut self) {
+        let mut global_header = Element::new("global_header", ElementType::Static);
+        global_header.set_content(SIGNATURE);
+        global_header.append_content(format!("//! version: {}\n//!\n", VERSION).as_str());
 
-        self.print_sequence(node, parent, module)
+        self.root.add(global_header);
     }
 
-    fn print_extension(&mut self, node: &Node, parent: &mut Option<&mut Element>) {
-        if let Some(p) = parent {
-            let base = match self.get_some_attribute(node, "base") {
-                None => return,
-                Some(n) => n,
-            };
+    fn print_common_structs(&mut self) {
+        let header = Element::new("Header", ElementType::Struct);
+        let mut soap_fault = Element::new("SoapFault", ElementType::Struct);
+        soap_fault.xml_name = Option::Some("Fault".to_string());
+        soap_fault.add_ns("soapenv", "http://schemas.xmlsoap.org/soap/envelope/");
+        soap_fault.prefix = Option::Some("soapenv".to_string());
+        soap_fault.add(Element::new_field(
+            "fault_code",
+            "faultcode",
+            "String",
+            true,
+        ));
+        soap_fault.add(Element::new_field(
+            "fault_string",
+            "faultstring",
+            "String",
+            true,
+        ));
+        soap_fault.add(Element::new_field(
+            "fault_actor",
+            "faultactor",
+            "String",
+            true,
+        ));
+        // the detail of a fault that no operation declares is kept as it is
+        soap_fault.add(Element::new_field("detail", "detail", "XmlValue", true));
 
-            let mut element = Element::new(
-                to_snake_case(&self.fetch_type(base)).as_str(),
-                ElementType::Field,
-            );
-            element.flatten = true;
-            element.field_type = Option::Some(self.fetch_type(base));
-            p.add(element);
+        let mut soap_response = Element::new("SoapResponse", ElementType::Alias);
+        soap_response.field_type =
+            Option::Some("Result<(reqwest::StatusCode, String), reqwest::Error>".to_string());
 
-            let type_name = self.fetch_type(base);
-            let mut xsi = Element::new("xsi_type", ElementType::Attribute);
-            xsi.field_type = Option::Some("String".to_string());
-            xsi.prefix = Option::Some("xsi".to_string());
-            xsi.xml_name = Option::Some("type".to_string());
-            xsi.comment = Option::Some(type_name);
-            p.add(xsi);
+        let mut soap_error = Element::new("SoapError", ElementType::Static);
+        let mut soap_error_content = r#"#[derive(Debug)]
+pub enum SoapError<E> {
+    Fault(E),
+    RequestError(reqwest::Error),
+    XmlError(std::string::String),
+}
+
+impl<E: std::fmt::Debug> std::fmt::Display for SoapError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SoapError::Fault(fault) => write!(f, "SOAP fault: {:?}", fault),
+            SoapError::RequestError(err) => write!(f, "SOAP request failed: {}", err),
+            SoapError::XmlError(err) => write!(f, "invalid SOAP message: {}", err),
         }
     }
+}
 
-    fn shield_reserved_names<'a>(&self, type_name: &'a str) -> &'a str {
-        match type_name {
-            "type" => "rs_type",
-            other => other,
+impl<E: std::fmt::Debug> std::error::Error for SoapError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SoapError::RequestError(err) => Some(err),
+            _ => None,
         }
     }
+}
 
-    fn pick_section(&mut self, target: &str) -> Rc<RefCell<Element>> {
-        self.root
-            .child(target)
-            .expect("modules have not been initialized properly")
+impl<E> From<reqwest::Error> for SoapError<E> {
+    fn from(err: reqwest::Error) -> Self {
+        SoapError::RequestError(err)
     }
+}
+"#
+        .to_string();
+        // responses with security headers that do not hold up are rejected
+        if self.ws_security {
+            soap_error_content = soap_error_content
+                .replace(
+                    "    XmlError(std::string::String),\n",
+                    "    XmlError(std::string::String),\n    SecurityError(std::string::String),\n",
+                )
+                .replace(
+                    "            SoapError::XmlError(err) => write!(f, \"invalid SOAP message: {}\", err),\n",
+                    "            SoapError::XmlError(err) => write!(f, \"invalid SOAP message: {}\", err),\n            SoapError::SecurityError(err) => write!(f, \"rejected SOAP message: {}\", err),\n",
+                );
+        }
+        soap_error.set_content(&soap_error_content);
 
-    // WSDL Messages
-
-    fn print_message(&mut self, node: &Node) {
-        let parent = self.pick_section(MESSAGES_MOD);
-        let mut _parent = &mut *parent.deref().borrow_mut();
+        let mut soap_result = Element::new("SoapResult", ElementType::Static);
+        soap_result.set_content("pub type SoapResult<T, E> = Result<T, SoapError<E>>;\n");
 
-        if let Some(name) = self.get_some_attribute(node, "name") {
-            let mut element = Element::new(to_pascal_case(name).as_str(), ElementType::Struct);
-            element.xml_name = Option::Some(name.to_string());
+        self.root.add(header);
+        self.root.add(soap_fault);
+        self.root.add(Self::soap_fault_error("SoapFault"));
+        self.root.add(soap_response);
+        self.root.add(soap_error);
+        self.root.add(soap_result);
+        self.print_support_types();
+        self.print_binary_types();
+        self.print_qname();
 
-            let maybe_part = node.children().find(|child| child.has_tag_name("part"));
+        if self.mtom {
+            self.print_xop_binary();
+        }
 
-            if let Some(part) = maybe_part {
-                if let Some(type_name) = self.get_some_attribute(&part, "type") {
-                    // simple type
-                    self.print_simple_part(name, &part, type_name, &mut element);
-                } else {
-                    self.print_element_part(name, &part, &mut element);
-                }
-            }
+        if self.typed_durations {
+            self.print_duration();
+        }
 
-            if !self.have_seen_type(&element.name, _parent) {
-                _parent.add(element);
-            }
+        if self.chrono_dates {
+            self.print_chrono_types();
         }
     }
 
-    fn print_element_part(&mut self, message_name: &str, node: &Node, parent: &mut Element) {
-        let element_name = match self.get_some_attribute(node, "name") {
-            None => return,
-            Some(n) => n,
-        };
-
-        if let Some(type_name) = self.get_some_attribute(node, "element") {
-            let type_name = self.fetch_type(type_name);
+    /// The helpers of the types generated for serde, which leave out the SOAP messages
+    fn print_serde_structs(&mut self) {
+        self.print_support_types();
+        self.print_binary_types();
+        self.print_qname();
 
-            let mut element = Element::new(
-                self.shield_reserved_names(&to_snake_case(element_name)),
-                ElementType::Field,
-            );
-            element.flatten = true;
-            element.field_type = Option::Some(format!("{}::{}", TYPES_MOD, type_name));
-            parent.add(element);
+        if self.typed_durations {
+            self.print_duration();
+        }
 
-            self.message_types
-                .insert(message_name.to_string(), type_name.clone());
+        if self.chrono_dates {
+            self.print_chrono_types();
         }
     }
 
-    fn print_simple_part(
-        &mut self,
-        message_name: &str,
-        node: &Node,
-        type_name: &str,
-        parent: &mut Element,
-    ) {
-        let element_name = match self.get_some_attribute(node, "name") {
-            None => return,
-            Some(n) => n,
-        };
+    /// The types and macros that the (de)serialization of the generated types builds on
+    fn print_support_types(&mut self) {
+        for support_type in self.emitter().support_types() {
+            let mut element = Element::new(support_type.name, ElementType::Static);
+            element.comment = support_type.comment.map(|c| c.to_string());
+            element.set_content(support_type.code);
+            self.root.add(element);
+        }
+    }
 
-        let element = Element::new_field(
-            self.shield_reserved_names(&to_snake_case(element_name)),
-            element_name,
-            self.fetch_type(type_name).as_str(),
-            false,
+    fn print_chrono_types(&mut self) {
+        let mut date_time = Element::new("DateTime", ElementType::Static);
+        date_time.comment = Option::Some(
+            "An xs:dateTime. Values without a timezone are taken to be in UTC".to_string(),
         );
+        date_time.set_content(
+            r#"#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateTime(pub chrono::DateTime<chrono::Utc>);
 
-        parent.add(element);
-
-        self.message_types
-            .insert(message_name.to_string(), type_name.to_string());
+impl Default for DateTime {
+    fn default() -> Self {
+        // the Unix epoch
+        DateTime(chrono::DateTime::<chrono::Utc>::from(std::time::UNIX_EPOCH))
     }
+}
 
-    // WSDL Port Types
-    fn print_port_type(&mut self, node: &Node) {
-        let parent = self.pick_section(PORTS_MOD);
-        let mut _parent = &mut *parent.deref().borrow_mut();
+impl std::fmt::Display for DateTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true))
+    }
+}
 
-        let element_name = match self.get_some_attribute(node, "name") {
-            None => return,
-            Some(n) => n,
-        };
+impl std::str::FromStr for DateTime {
+    type Err = String;
 
-        let struct_name = to_pascal_case(element_name);
-        let mut element = Element::new(struct_name.as_str(), ElementType::Trait);
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use chrono::TimeZone;
 
-        node.children().for_each(|child| {
-            self.print_operation(
-                to_pascal_case(element_name).as_str(),
-                &child,
-                &mut element,
-                &mut _parent,
-            )
-        });
+        let s = s.trim();
+        if let Ok(date_time) = chrono::DateTime::parse_from_rfc3339(s) {
+            return Ok(DateTime(date_time.with_timezone(&chrono::Utc)));
+        }
 
-        _parent.add(element);
+        chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f")
+            .map(|date_time| DateTime(chrono::Utc.from_utc_datetime(&date_time)))
+            .map_err(|e| format!("invalid dateTime {}: {}", s, e))
     }
+}
 
-    // WSDL bindings
+impl_text_value!(DateTime);
+"#,
+        );
 
-    fn print_binding(&mut self, node: &Node) {
-        let parent = self.pick_section(BINDINGS_MOD);
-        let mut _parent = &mut *parent.deref().borrow_mut();
+        let mut date = Element::new("Date", ElementType::Static);
+        date.comment = Option::Some("An xs:date. The timezone, if any, is ignored".to_string());
+        date.set_content(
+            r#"#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date(pub chrono::NaiveDate);
 
-        let element_name = match self.get_some_attribute(node, "name") {
-            None => return,
-            Some(n) => n,
-        };
+impl Default for Date {
+    fn default() -> Self {
+        Date(DateTime::default().0.naive_utc().date())
+    }
+}
 
-        let type_name = match self.get_some_attribute(node, "type") {
-            None => return,
-            Some(n) => n,
-        };
+impl std::fmt::Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0.format("%Y-%m-%d"))
+    }
+}
 
-        let struct_name = to_pascal_case(element_name);
-        let trait_name = self.fetch_type(type_name);
+impl std::str::FromStr for Date {
+    type Err = String;
 
-        if !self.have_seen_type(&struct_name, _parent) {
-            self.print_binding_helpers(&struct_name, _parent);
-        }
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        chrono::NaiveDate::parse_from_str(strip_timezone(s.trim()), "%Y-%m-%d")
+            .map(Date)
+            .map_err(|e| format!("invalid date {}: {}", s, e))
+    }
+}
 
-        let mut client = Element::new(&struct_name, ElementType::Static);
-        client.set_content(
-            format!(
-                r#"#[derive(Debug, Clone)]
-                pub struct {0} {{
-                client: reqwest::Client,
-                url: String,
-                credentials: Option<(String,String)>
-                }}
-                "#,
-                struct_name
-            )
-            .as_str(),
+impl_text_value!(Date);
+"#,
         );
 
-        let mut t_impl = Element::new(&struct_name, ElementType::TraitImpl);
-        t_impl.field_type = Option::Some(format!("{1}::{0}", trait_name, PORTS_MOD));
-
-        node.children().for_each(|child| {
-            self.print_binding_operation(&trait_name, &child, &mut t_impl, &mut _parent)
-        });
+        let mut time = Element::new("Time", ElementType::Static);
+        time.comment = Option::Some("An xs:time. The timezone, if any, is ignored".to_string());
+        time.set_content(
+            r#"#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Time(pub chrono::NaiveTime);
 
-        self.print_default_constructor(struct_name.as_str(), _parent);
-        self.print_constructor(struct_name.as_str(), _parent);
+impl Default for Time {
+    fn default() -> Self {
+        Time(DateTime::default().0.naive_utc().time())
+    }
+}
 
-        _parent.add(client);
-        _parent.add(t_impl);
+impl std::fmt::Display for Time {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0.format("%H:%M:%S%.f"))
     }
+}
 
-    fn print_binding_helpers(&mut self, struct_name: &str, parent: &mut Element) {
-        let mut e = Element::new(struct_name, ElementType::Static);
-        e.set_content(format!(r#"
-            impl {0} {{
-                async fn send_soap_request<T: YaSerialize>(&self, request: &T, action: &str) -> SoapResponse {{
-                    let body = to_string(request).expect("failed to generate xml");
-                    debug!("SOAP Request: {{}}", body);
-                    let mut req = self
-                        .client
-                        .post(&self.url)
-                        .body(body)
-                        .header("Content-Type", "text/xml")
-                        .header("Soapaction", action);
-                    if let Some(credentials) = &self.credentials {{
-                        req = req.basic_auth(
-                            credentials.0.to_string(),
-                            Option::Some(credentials.1.to_string()),
-                        );
-                    }}
-                    let res = req.send().await?;
-                    let status = res.status();
-                    debug!("SOAP Status: {{}}", status);
-                    let txt = res.text().await.unwrap_or_default();
-                    debug!("SOAP Response: {{}}", txt);
-                    Ok((status, txt))
-                }}
-            }}
-            "#, struct_name).as_str());
+impl std::str::FromStr for Time {
+    type Err = String;
 
-        parent.add(e);
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        chrono::NaiveTime::parse_from_str(strip_timezone(s.trim()), "%H:%M:%S%.f")
+            .map(Time)
+            .map_err(|e| format!("invalid time {}: {}", s, e))
     }
+}
 
-    fn print_default_constructor(&mut self, struct_name: &str, parent: &mut Element) {
-        let url = match self.target_name_space.last() {
-            None => "String::new()".to_string(),
-            Some(tns) => tns.to_string(),
-        };
-
-        let mut e = Element::new(struct_name, ElementType::Static);
-        e.set_content(
-            format!(
-                r#"impl Default for {0} {{
-                fn default() -> Self {{
-                    {0} {{
-                        client: reqwest::Client::new(),
-                        url: "{1}".to_string(),
-                        credentials: Option::None,
-                     }}
-                }}
-            }}
-            "#,
-                struct_name, url
-            )
-            .as_str(),
-        );
+impl_text_value!(Time);
 
-        parent.add(e);
+/// Removes the timezone from a lexical xs:date or xs:time
+fn strip_timezone(s: &str) -> &str {
+    if let Some(stripped) = s.strip_suffix('Z') {
+        return stripped;
     }
 
-    fn print_constructor(&mut self, struct_name: &str, parent: &mut Element) {
-        let mut e = Element::new(struct_name, ElementType::Static);
-        e.set_content(
-            format!(
-                r#"impl {0} {{
-                pub fn new(url: &str, credentials: Option<(String,String)>) -> Self {{
-                    {0} {{
-                        client: reqwest::Client::new(),
-                        url: url.to_string(),
-                        credentials,
-                    }}
-                }}
-        }}
-        "#,
-                struct_name
-            )
-            .as_str(),
+    // a [+-]hh:mm suffix, taking care not to mistake the separators of a date for an offset
+    match s.len().checked_sub(6).and_then(|i| s.get(i..).map(|tz| (i, tz))) {
+        Some((i, tz)) if (tz.starts_with('+') || tz.starts_with('-')) && tz.get(3..4) == Some(":") => &s[..i],
+        _ => s,
+    }
+}
+"#,
         );
 
-        parent.add(e);
+        self.root.add(date_time);
+        self.root.add(date);
+        self.root.add(time);
     }
 
-    fn map_name_message(&self, node: &Node) -> (String, Option<String>) {
-        let msg = self
-            .get_some_attribute_as_string(node, "message")
-            .map(|m| self.fetch_type(&m));
+    fn print_binary_types(&mut self) {
+        let mut base64 = Element::new("Base64Binary", ElementType::Static);
+        base64.comment = Option::Some("An xs:base64Binary, holding the decoded bytes".to_string());
+        base64.set_content(
+            r#"#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Base64Binary(pub Vec<u8>);
 
-        let name = self.get_some_attribute_as_string(node, "name");
+impl std::fmt::Display for Base64Binary {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(&base64::encode(&self.0))
+    }
+}
 
-        let name = match name {
-            None => match &msg {
-                None => String::new(),
-                Some(msg) => to_pascal_case(msg.as_str()),
-            },
-            Some(name) => name,
-        };
+impl std::str::FromStr for Base64Binary {
+    type Err = String;
 
-        (name, msg)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // the lexical space of xs:base64Binary allows whitespace between the characters
+        let text: String = s.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+        base64::decode(&text)
+            .map(Base64Binary)
+            .map_err(|e| format!("invalid base64Binary {}: {}", s, e))
     }
+}
 
-    fn print_operation(
-        &mut self,
-        port_type_name: &str,
-        node: &Node,
-        parent: &mut Element,
-        module: &mut Element,
-    ) {
-        let element_name = match self.get_some_attribute(node, "name") {
-            None => return,
-            Some(n) => n,
-        };
+impl_text_value!(Base64Binary);
+"#,
+        );
 
-        let func_name = to_snake_case(element_name);
+        let mut hex = Element::new("HexBinary", ElementType::Static);
+        hex.comment = Option::Some("An xs:hexBinary, holding the decoded bytes".to_string());
+        hex.set_content(
+            r#"#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct HexBinary(pub Vec<u8>);
 
-        let some_documentation = node
-            .children()
-            .find(|c| c.has_tag_name("documentation"))
-            .map(|c| c.text().unwrap_or_default());
+impl std::fmt::Display for HexBinary {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{:02X}", byte))
+    }
+}
 
-        let some_input = node
-            .children()
-            .find(|c| c.has_tag_name("input"))
-            .map(|c| self.map_name_message(&c));
+impl std::str::FromStr for HexBinary {
+    type Err = String;
 
-        let some_output = node
-            .children()
-            .find(|c| c.has_tag_name("output"))
-            .map(|c| self.map_name_message(&c));
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if !s.is_ascii() || s.len() % 2 != 0 {
+            return Err(format!("invalid hexBinary {}", s));
+        }
 
-        let some_fault = node
-            .children()
-            .find(|c| c.has_tag_name("fault"))
-            .map(|c| self.map_name_message(&c));
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .map(HexBinary)
+            .map_err(|e| format!("invalid hexBinary {}: {}", s, e))
+    }
+}
 
-        let port_type = PortType {
-            name: format!("{}::{}", port_type_name, element_name.to_string()),
-            input_type: some_input,
-            output_type: some_output,
-            fault_type: some_fault,
+impl_text_value!(HexBinary);
+"#,
+        );
+
+        self.root.add(base64);
+        self.root.add(hex);
+    }
+
+    fn print_qname(&mut self) {
+        let mut qname = Element::new("Qname", ElementType::Static);
+        qname.comment = Option::Some(
+            "An xs:QName. Deserialized elements resolve the prefix to the namespace in scope"
+                .to_string(),
+        );
+        qname.set_content(
+            r#"#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Qname {
+    pub prefix: Option<String>,
+    pub local_name: String,
+    /// The namespace URI bound to the prefix, declared on the element when serialized
+    pub namespace: Option<String>,
+}
+
+impl std::fmt::Display for Qname {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.prefix {
+            Some(prefix) => write!(f, "{}:{}", prefix, self.local_name),
+            None => write!(f, "{}", self.local_name),
+        }
+    }
+}
+
+impl std::str::FromStr for Qname {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        // the empty text of a default Qname reads back as the default
+        if s.is_empty() {
+            return Ok(Qname::default());
+        }
+
+        let (prefix, local_name) = match s.split_once(':') {
+            Some((prefix, local_name)) => (Some(prefix.to_string()), local_name),
+            None => (None, s),
         };
 
-        let input_type_element = match &port_type.input_type {
-            Some((type_name, Some(message_type_name))) => {
-                let mut e = Element::new(to_pascal_case(type_name).as_str(), ElementType::Alias);
-                e.field_type = Option::Some(format!("{0}::{1}", MESSAGES_MOD, message_type_name,));
+        if local_name.is_empty() || prefix.as_deref() == Some("") {
+            return Err(format!("invalid QName {}", s));
+        }
 
-                Option::Some(e)
+        Ok(Qname {
+            prefix,
+            local_name: local_name.to_string(),
+            namespace: None,
+        })
+    }
+}
+
+"#,
+        );
+        qname.append_content(self.emitter().qname_impls());
+        self.root.add(qname);
+    }
+
+    /// Generates the attachments of MIME bindings, with the building of the multipart/related
+    /// bodies that carry them
+    fn print_attachment(&mut self) {
+        if self.have_seen_type("Attachment", &self.root) {
+            return;
+        }
+
+        let mut attachment = Element::new("Attachment", ElementType::Static);
+        attachment.comment =
+            Option::Some("A MIME part sent along with a SOAP envelope".to_string());
+        attachment.set_content(
+            r#"#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Attachment {
+    /// The Content-ID of the part, without angle brackets. Left empty, the name of the message
+    /// part is used
+    pub content_id: String,
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
+
+impl Attachment {
+    pub fn new(content_type: &str, data: Vec<u8>) -> Self {
+        Attachment {
+            content_id: String::new(),
+            content_type: content_type.to_string(),
+            data,
+        }
+    }
+
+    pub fn with_content_id(mut self, content_id: &str) -> Self {
+        self.content_id = content_id.to_string();
+        self
+    }
+
+    /// The cid: URL of the part, that references it from the envelope
+    pub fn href(&self) -> String {
+        let mut href = String::from("cid:");
+        for byte in self.content_id.bytes() {
+            if byte.is_ascii_alphanumeric() || b"-._~@!$&'()*+,;=:/".contains(&byte) {
+                href.push(byte as char);
+            } else {
+                href.push_str(&format!("%{:02X}", byte));
             }
-            _ => None,
-        };
+        }
+        href
+    }
 
-        let mut function_element = match &port_type.input_type {
-            Some((name, Some(_msg))) => Element::new_function(
-                &func_name,
-                to_snake_case(name.as_str()).as_str(),
-                to_pascal_case(name.as_str()).as_str(),
-            ),
-            _ => return,
+    /// The part that a cid: URL, like the href of an element, references among the parts of a
+    /// message
+    pub fn find<'a>(attachments: &'a [Attachment], href: &str) -> Option<&'a Attachment> {
+        let content_id = content_id(href).ok()?;
+        attachments.iter().find(|a| a.content_id == content_id)
+    }
+
+    /// The part of a message that carries a part of the WSDL message, by its Content-ID: the
+    /// name of the part, or the name followed by `=` and a unique id as the WS-I Attachments
+    /// Profile has it
+    pub fn find_part<'a>(attachments: &'a [Attachment], part: &str) -> Option<&'a Attachment> {
+        attachments.iter().find(|a| {
+            a.content_id == part
+                || a.content_id
+                    .strip_prefix(part)
+                    .map_or(false, |rest| rest.starts_with('='))
+        })
+    }
+
+}
+
+/// The Content-ID of a cid: URL, whose reserved characters are %-escaped
+fn content_id(href: &str) -> Result<String, String> {
+    let url = href
+        .strip_prefix("cid:")
+        .ok_or_else(|| format!("{} is no cid: URL", href))?;
+    let mut bytes = Vec::with_capacity(url.len());
+    let mut rest = url.as_bytes();
+    while let Some((byte, tail)) = rest.split_first() {
+        match (byte, tail.get(..2)) {
+            (b'%', Some(hex)) => {
+                let hex = std::str::from_utf8(hex).map_err(|e| e.to_string())?;
+                bytes.push(u8::from_str_radix(hex, 16).map_err(|e| e.to_string())?);
+                rest = &tail[2..];
+            }
+            _ => {
+                bytes.push(*byte);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8(bytes).map_err(|e| e.to_string())
+}
+
+const MULTIPART_BOUNDARY: &str = "----=_Part_zeep_multipart_related";
+const MULTIPART_START: &str = "root.message@zeep";
+
+/// Builds a multipart/related body with the envelope as its root part, followed by the
+/// attachments. Returns the content type of the body along with the body.
+fn multipart_related(
+    envelope: &str,
+    root_type: &str,
+    attachments: &[(&str, Attachment)],
+) -> (String, Vec<u8>) {
+    let mut body = format!(
+        "--{}\r\nContent-Type: {}\r\nContent-Transfer-Encoding: 8bit\r\nContent-ID: <{}>\r\n\r\n{}",
+        MULTIPART_BOUNDARY, root_type, MULTIPART_START, envelope
+    )
+    .into_bytes();
+
+    for (part, attachment) in attachments {
+        let content_id = if attachment.content_id.is_empty() {
+            part
+        } else {
+            attachment.content_id.as_str()
         };
+        body.extend_from_slice(
+            format!(
+                "\r\n--{}\r\nContent-Type: {}\r\nContent-Transfer-Encoding: binary\r\nContent-ID: <{}>\r\n\r\n",
+                MULTIPART_BOUNDARY, attachment.content_type, content_id
+            )
+            .as_bytes(),
+        );
+        body.extend_from_slice(&attachment.data);
+    }
+    body.extend_from_slice(format!("\r\n--{}--\r\n", MULTIPART_BOUNDARY).as_bytes());
+
+    let media_type = root_type.split(';').next().unwrap_or_default();
+    let content_type = format!(
+        "multipart/related; type=\"{}\"; start=\"<{}>\"; boundary=\"{}\"",
+        media_type, MULTIPART_START, MULTIPART_BOUNDARY
+    );
+    (content_type, body)
+}
+"#,
+        );
 
-        let (output_type_element, fault_type_element) = match &port_type.output_type {
-            Some((type_name, Some(msg))) => {
-                let mut e = Element::new(to_pascal_case(type_name).as_str(), ElementType::Alias);
-                e.field_type = Option::Some(format!("{}::{}", MESSAGES_MOD, msg));
+        self.root.add(attachment);
+    }
 
-                if let Some((fault_name, Some(fault_type))) = &port_type.fault_type {
-                    let mut f = Element::new(fault_name.as_str(), ElementType::Alias);
-                    f.field_type = Option::Some(format!("{}::{}", MESSAGES_MOD, fault_type,));
+    /// Generates the binary values that MTOM messages carry in parts of their own, with the
+    /// (de)serialization of messages that collects and resolves those parts
+    fn print_xop_binary(&mut self) {
+        self.print_attachment();
 
-                    if !self.have_seen_type(fault_name, module) {
-                        self.fault_soap_wrapper(fault_name, fault_type, module);
-                    }
+        let mut xop = Element::new("XopBinary", ElementType::Static);
+        xop.comment = Option::Some(
+            "An xs:base64Binary that MTOM messages carry in a part of their own".to_string(),
+        );
+        xop.set_content(
+            r#"#[derive(Debug, Default, Clone, PartialEq)]
+pub struct XopBinary {
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
 
-                    if let Some(mut args) = function_element.function_args.take() {
-                        args.output_type = Option::Some(to_pascal_case(type_name));
-                        args.fault_type =
-                            Option::Some(format!("Option<Soap{}>", to_pascal_case(fault_name)));
-                        function_element.function_args.replace(args);
-                    }
+impl XopBinary {
+    pub fn new(content_type: &str, data: Vec<u8>) -> Self {
+        XopBinary {
+            content_type: content_type.to_string(),
+            data,
+        }
+    }
+}
 
-                    (Option::Some(e), Option::Some(f))
-                } else {
-                    if let Some(mut args) = function_element.function_args.take() {
-                        args.output_type = Option::Some(to_pascal_case(type_name));
-                        args.fault_type = Option::Some("Option<SoapFault>".to_string());
-                        function_element.function_args.replace(args);
-                    }
+const XOP_NAMESPACE: &str = "http://www.w3.org/2004/08/xop/include";
 
-                    (Option::Some(e), Option::None)
+thread_local! {
+    /// The parts of the MTOM message that is (de)serialized on this thread. Outside of one the
+    /// binary values are inline base64.
+    static XOP_PARTS: std::cell::RefCell<Option<Vec<Attachment>>> = std::cell::RefCell::new(None);
+}
+
+/// Serializes a message, with its binary values in parts of their own, which come along
+fn to_string_with_parts<T: YaSerialize>(message: &T) -> Result<(String, Vec<Attachment>), String> {
+    XOP_PARTS.with(|parts| *parts.borrow_mut() = Some(Vec::new()));
+    let xml = yaserde::ser::to_string(message);
+    let parts = XOP_PARTS.with(|parts| parts.borrow_mut().take()).unwrap_or_default();
+    xml.map(|xml| (xml, parts))
+}
+
+/// Deserializes a message, whose xop:Include references are resolved to the parts it came with
+fn from_str_with_parts<T: YaDeserialize>(xml: &str, parts: Vec<Attachment>) -> Result<T, String> {
+    XOP_PARTS.with(|current| *current.borrow_mut() = Some(parts));
+    let message = yaserde::de::from_str(xml);
+    XOP_PARTS.with(|current| current.borrow_mut().take());
+    message
+}
+
+impl YaDeserialize for XopBinary {
+    fn deserialize<R: std::io::Read>(
+        reader: &mut yaserde::de::Deserializer<R>,
+    ) -> Result<Self, String> {
+        if let yaserde::xml::reader::XmlEvent::StartElement { .. } = reader.peek()? {
+            reader.next_event()?;
+        }
+
+        let mut value = XopBinary::default();
+        let mut text = String::new();
+        let mut depth = 0;
+        loop {
+            match reader.next_event()? {
+                yaserde::xml::reader::XmlEvent::StartElement {
+                    name, attributes, ..
+                } => {
+                    depth += 1;
+                    if name.local_name != "Include" {
+                        continue;
+                    }
+                    let href = attributes
+                        .iter()
+                        .find(|a| a.name.local_name == "href")
+                        .ok_or_else(|| "xop:Include without href".to_string())?;
+                    let content_id = content_id(&href.value)?;
+                    let part = XOP_PARTS.with(|parts| {
+                        let mut parts = parts.borrow_mut();
+                        let parts = parts.as_mut()?;
+                        let index = parts.iter().position(|p| p.content_id == content_id)?;
+                        Some(parts.swap_remove(index))
+                    });
+                    let part = part.ok_or_else(|| format!("no part {} in the message", content_id))?;
+                    value = XopBinary::new(&part.content_type, part.data);
                 }
+                yaserde::xml::reader::XmlEvent::EndElement { .. } if depth == 0 => break,
+                yaserde::xml::reader::XmlEvent::EndElement { .. } => depth -= 1,
+                yaserde::xml::reader::XmlEvent::Characters(characters) if depth == 0 => {
+                    text.push_str(&characters)
+                }
+                _ => {}
             }
-            _ => (Option::None, Option::None),
-        };
+        }
 
-        if let Some(input_type_element) = input_type_element {
-            if !self.have_seen_type(&input_type_element.name, module) {
-                module.add(input_type_element);
-            }
+        if !text.trim().is_empty() {
+            value.data = text.parse::<Base64Binary>()?.0;
         }
+        Ok(value)
+    }
+}
 
-        if let Some(output_type_element) = output_type_element {
-            if !self.have_seen_type(&output_type_element.name, module) {
-                module.add(output_type_element);
-            }
+impl YaSerialize for XopBinary {
+    fn serialize<W: std::io::Write>(
+        &self,
+        writer: &mut yaserde::ser::Serializer<W>,
+    ) -> Result<(), String> {
+        let name = writer
+            .get_start_event_name()
+            .unwrap_or_else(|| "XopBinary".to_string());
+        let skip_start_end = writer.skip_start_end();
+
+        if !skip_start_end {
+            writer
+                .write(yaserde::xml::writer::XmlEvent::start_element(name.as_str()))
+                .map_err(|e| e.to_string())?;
         }
 
-        if let Some(fault_type_element) = fault_type_element {
-            if !self.have_seen_type(&fault_type_element.name, module) {
-                module.add(fault_type_element);
+        // in an MTOM message the data goes in a part of its own, else inline
+        let content_id = XOP_PARTS.with(|parts| {
+            parts.borrow_mut().as_mut().map(|parts| {
+                let content_id = format!("part{}@zeep", parts.len() + 1);
+                let content_type = if self.content_type.is_empty() {
+                    "application/octet-stream"
+                } else {
+                    self.content_type.as_str()
+                };
+                let mut part = Attachment::new(content_type, self.data.clone());
+                part.content_id = content_id.clone();
+                parts.push(part);
+                content_id
+            })
+        });
+        match content_id {
+            Some(content_id) => {
+                let href = format!("cid:{}", content_id);
+                writer
+                    .write(
+                        yaserde::xml::writer::XmlEvent::start_element("xop:Include")
+                            .ns("xop", XOP_NAMESPACE)
+                            .attr("href", &href),
+                    )
+                    .map_err(|e| e.to_string())?;
+                writer
+                    .write(yaserde::xml::writer::XmlEvent::end_element())
+                    .map_err(|e| e.to_string())?;
+            }
+            None => {
+                let text = Base64Binary(self.data.clone()).to_string();
+                writer
+                    .write(yaserde::xml::writer::XmlEvent::characters(&text))
+                    .map_err(|e| e.to_string())?;
             }
         }
 
-        if let Some(doc) = some_documentation {
-            function_element.comment = Option::Some(doc.to_string());
+        if !skip_start_end {
+            writer
+                .write(yaserde::xml::writer::XmlEvent::end_element())
+                .map_err(|e| e.to_string())?;
         }
+        Ok(())
+    }
 
-        parent.add(function_element);
-        self.port_types.insert(port_type.name.clone(), port_type);
+    fn serialize_attributes(
+        &self,
+        attributes: Vec<yaserde::xml::attribute::OwnedAttribute>,
+        namespace: yaserde::xml::namespace::Namespace,
+    ) -> Result<
+        (
+            Vec<yaserde::xml::attribute::OwnedAttribute>,
+            yaserde::xml::namespace::Namespace,
+        ),
+        String,
+    > {
+        Ok((attributes, namespace))
     }
+}
+"#,
+        );
 
-    fn fault_soap_wrapper(&self, fault_name: &str, fault_type: &str, parent: &mut Element) {
-        let soap_fault_name = format!("Soap{}", fault_name);
+        self.root.add(xop);
+    }
 
-        let mut e = Element::new(&soap_fault_name, ElementType::Struct);
-        e.xml_name = Option::Some("Fault".to_string());
-        e.add_ns("soapenv", "http://schemas.xmlsoap.org/soap/envelope/");
-        e.prefix = Option::Some("soapenv".to_string());
+    /// Generates the WS-I swaRef references of the envelope to the attachments of a message
+    fn print_swa_ref(&mut self) {
+        if self.have_seen_type("SwaRef", &self.root) {
+            return;
+        }
+        self.print_attachment();
+
+        let mut swa_ref = Element::new("SwaRef", ElementType::Static);
+        swa_ref.comment =
+            Option::Some("A WS-I swaRef, the cid: URL of an attachment of the message".to_string());
+        swa_ref.set_content(
+            r#"#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct SwaRef(pub String);
+
+impl SwaRef {
+    /// The reference to an attachment, by its Content-ID
+    pub fn to(attachment: &Attachment) -> Self {
+        SwaRef(attachment.href())
+    }
 
-        let fault_code = Element::new_field("fault_code", "faultcode", "String", true);
-        let fault_string = Element::new_field("fault_string", "faultstring", "String", true);
-        let detail = Element::new_field("detail", fault_type, fault_name, true);
+    /// The attachment that is referenced, among the attachments of the message
+    pub fn resolve<'a>(&self, attachments: &'a [Attachment]) -> Option<&'a Attachment> {
+        Attachment::find(attachments, &self.0)
+    }
+}
 
-        e.add(fault_code);
-        e.add(fault_string);
-        e.add(detail);
+impl std::fmt::Display for SwaRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
 
-        if !self.have_seen_type(&e.name, parent) {
-            parent.add(e);
-        }
+impl std::str::FromStr for SwaRef {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(SwaRef(s.trim().to_string()))
     }
+}
 
-    fn construct_soap_wrapper(&self, soap_name: &str, body_type: &str) -> String {
-        let tns = match self.target_name_space.last() {
-            None => "Option::None".to_string(),
-            Some(t) => format!("Option::Some(\"{}\".to_string())", t),
-        };
+impl_text_value!(SwaRef);
+"#,
+        );
 
-        format!(
-            r#"#[derive(Debug, Default, YaSerialize, YaDeserialize)]
-        #[yaserde(
-            rename = "Envelope",
-            namespace = "soapenv: http://schemas.xmlsoap.org/soap/envelope/",
-            prefix = "soapenv"
-        )]
-        pub struct {0}SoapEnvelope {{
-            #[yaserde(rename = "encodingStyle", prefix = "soapenv", attribute)]
-            pub encoding_style: String,
-            #[yaserde(rename = "{3}", prefix = "xmlns", attribute)]
-            pub tnsattr: Option<String>,
-            #[yaserde(rename = "urn", prefix = "xmlns", attribute)]
-            pub urnattr: Option<String>,
-            #[yaserde(rename = "xsi", prefix = "xmlns", attribute)]
-            pub xsiattr: Option<String>,
-            #[yaserde(rename = "Header", prefix = "soapenv")]
-            pub header: Option<Header>,
-            #[yaserde(rename = "Body", prefix = "soapenv")]
-            pub body: {1},
-        }}
-        
-        impl {0}SoapEnvelope {{
-            pub fn new(body: {1}) -> Self {{
-                {0}SoapEnvelope {{
-                    encoding_style: SOAP_ENCODING.to_string(),
-                    tnsattr: {2},
-                    body,
-                    urnattr: None,
-                    xsiattr: None,
-                    header: None,
-                }}
-            }}
-        }}        
-        "#,
-            soap_name, body_type, tns, self.ns_prefix
-        )
+        self.root.add(swa_ref);
     }
 
-    fn print_binding_operation(
-        &mut self,
-        bind_type_name: &str,
-        node: &Node,
-        parent: &mut Element,
-        module: &mut Element,
-    ) {
-        let operation_name = match self.get_some_attribute(node, "name") {
-            None => return,
-            Some(n) => n,
-        };
+    fn print_duration(&mut self) {
+        let mut duration = Element::new("Duration", ElementType::Static);
+        duration.comment =
+            Option::Some("An xs:duration, as the components of its ISO 8601 notation".to_string());
+        duration.set_content(
+            r#"#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Duration {
+    pub negative: bool,
+    pub years: u32,
+    pub months: u32,
+    pub days: u32,
+    pub hours: u32,
+    pub minutes: u32,
+    pub seconds: f64,
+}
 
-        let message_type_name = match self.message_types.get(operation_name) {
-            None => operation_name.to_string(),
-            Some(mt) => self.split_type(mt).to_string(),
-        };
+impl Duration {
+    /// The length of the duration, counting a year as 365 days and a month as 30 days.
+    /// Negative durations can not be expressed.
+    pub fn to_std(&self) -> Option<std::time::Duration> {
+        if self.negative {
+            return None;
+        }
 
-        let port_type_name = format!("{}::{}", bind_type_name, operation_name);
+        let days = self.years as u64 * 365 + self.months as u64 * 30 + self.days as u64;
+        let seconds = ((days * 24 + self.hours as u64) * 60 + self.minutes as u64) * 60;
+        Some(std::time::Duration::from_secs(seconds) + std::time::Duration::from_secs_f64(self.seconds))
+    }
+}
 
-        let port_type = match self.port_types.get(&port_type_name) {
-            None => {
-                warn!(
-                    "failed to find matching port type for binding: {} with type: {}",
-                    operation_name, port_type_name
-                );
-                return;
+impl std::fmt::Display for Duration {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.negative {
+            write!(f, "-")?;
+        }
+        write!(f, "P")?;
+
+        for (value, designator) in [(self.years, 'Y'), (self.months, 'M'), (self.days, 'D')] {
+            if value > 0 {
+                write!(f, "{}{}", value, designator)?;
             }
-            Some(pt) => pt.clone(),
-        };
+        }
 
-        let func_name = to_snake_case(&operation_name);
+        if self.hours > 0 || self.minutes > 0 || self.seconds > 0.0 {
+            write!(f, "T")?;
+            for (value, designator) in [(self.hours, 'H'), (self.minutes, 'M')] {
+                if value > 0 {
+                    write!(f, "{}{}", value, designator)?;
+                }
+            }
+            if self.seconds > 0.0 {
+                write!(f, "{}S", self.seconds)?;
+            }
+        } else if self.years == 0 && self.months == 0 && self.days == 0 {
+            write!(f, "T0S")?;
+        }
 
-        let (input_name, input_type, input_soap_name, has_input) = match &port_type.input_type {
-            Some((input_name, Some(input_type))) => {
-                let soap_name = format!("Soap{}", input_type);
+        Ok(())
+    }
+}
 
-                (
-                    to_snake_case(input_name),
-                    input_type.clone(),
-                    soap_name,
-                    true,
-                )
-            }
-            _ => (String::new(), String::new(), String::new(), false),
+impl std::str::FromStr for Duration {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid duration: {}", s);
+
+        let text = s.trim();
+        let (negative, text) = match text.strip_prefix('-') {
+            Some(t) => (true, t),
+            None => (false, text),
+        };
+        let text = text.strip_prefix('P').ok_or_else(invalid)?;
+        let (date, time) = match text.split_once('T') {
+            Some((_, "")) => return Err(invalid()),
+            Some((date, time)) => (date, time),
+            None => (text, ""),
         };
 
-        let input_template = if has_input {
-            format!("{}: {}::{}", input_name, PORTS_MOD, input_type)
-        } else {
-            String::new()
+        let mut duration = Duration {
+            negative,
+            ..Default::default()
         };
+        let mut has_components = false;
+        let mut number = String::new();
+
+        for c in date.chars() {
+            match c {
+                '0'..='9' => number.push(c),
+                'Y' | 'M' | 'D' => {
+                    let value = number.parse().map_err(|_| invalid())?;
+                    match c {
+                        'Y' => duration.years = value,
+                        'M' => duration.months = value,
+                        _ => duration.days = value,
+                    }
+                    number.clear();
+                    has_components = true;
+                }
+                _ => return Err(invalid()),
+            }
+        }
 
-        let soap_wrapper_in = if has_input {
-            if !self.have_seen_type(&input_soap_name, parent) {
-                Option::Some(format!(
-                    r#"#[derive(Debug, Default, YaSerialize, YaDeserialize)]
-                    pub struct {0} {{
-                        #[yaserde(rename = "{3}", default)]
-                        pub body: {2}::{1},
-                        #[yaserde(attribute)]
-                        pub xmlns: Option<String>,
-                    }}
-                    {4}
-                    "#,
-                    input_soap_name,
-                    input_type,
-                    PORTS_MOD,
-                    message_type_name,
-                    self.construct_soap_wrapper(input_type.as_str(), input_soap_name.as_str())
-                ))
-            } else {
-                Option::None
+        for c in time.chars() {
+            match c {
+                '0'..='9' | '.' => number.push(c),
+                'H' | 'M' => {
+                    let value = number.parse().map_err(|_| invalid())?;
+                    match c {
+                        'H' => duration.hours = value,
+                        _ => duration.minutes = value,
+                    }
+                    number.clear();
+                    has_components = true;
+                }
+                'S' => {
+                    duration.seconds = number.parse().map_err(|_| invalid())?;
+                    number.clear();
+                    has_components = true;
+                }
+                _ => return Err(invalid()),
+            }
+        }
+
+        if !has_components || !number.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(duration)
+    }
+}
+
+impl_text_value!(Duration);
+"#,
+        );
+
+        self.root.add(duration);
+    }
+
+    /// print parses the root of the XML file
+    fn print(&mut self, node: &Node) -> ZeepResult<()> {
+        if !node.is_element() {
+            return Ok(());
+        }
+
+        match node.tag_name().name() {
+            "definitions" | "description" if self.serde_backend => {
+                return Err(ZeepError::Unsupported {
+                    position: self.position(node),
+                    message: "WSDL documents with the serde backend, which generates the types of XML schemas only"
+                        .to_string(),
+                })
             }
+            "definitions" => self.print_definitions(node)?,
+            "description" => self.print_description(node)?,
+            "schema" if !self.binding_pass => self.print_xsd(node)?,
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn print_definitions(&mut self, node: &Node) -> ZeepResult<()> {
+        let tns = self.get_some_attribute(node, "targetNamespace");
+        let outer_prefix = self.enter_namespace(tns);
+
+        // imported definitions come first, the definitions of this document build on them. In
+        // the second pass, the imported bindings come first as well.
+        node.children()
+            .filter(|child| child.tag_name().name() == "import")
+            .try_for_each(|node| self.import_definitions(&node))?;
+
+        self.find_namespaces(node);
+        self.print_model(node)?;
+
+        self.leave_namespace(outer_prefix);
+        Ok(())
+    }
+
+    /// Processes a WSDL 2.0 document. Its interfaces make up the ports and its endpoints the
+    /// services, with messages made up of the elements the operations refer to.
+    fn print_description(&mut self, node: &Node) -> ZeepResult<()> {
+        let tns = self.get_some_attribute(node, "targetNamespace");
+        let outer_prefix = self.enter_namespace(tns);
+
+        node.children()
+            .filter(|child| {
+                child.tag_name().name() == "import" || child.tag_name().name() == "include"
+            })
+            .try_for_each(|node| self.import_definitions(&node))?;
+
+        self.find_namespaces(node);
+        self.print_model(node)?;
+
+        self.leave_namespace(outer_prefix);
+        Ok(())
+    }
+
+    /// Generates the code of a WSDL document from its model, after the types of its schemas: the
+    /// messages and port types in the first pass, the bindings and services in the second
+    fn print_model(&mut self, node: &Node) -> ZeepResult<()> {
+        let file = self.documents.last().cloned().unwrap_or_default();
+        let definitions = Definitions::read(node, &file);
+
+        if self.binding_pass {
+            definitions
+                .bindings
+                .iter()
+                .try_for_each(|binding| self.print_binding(binding, &definitions))?;
+
+            definitions
+                .services
+                .iter()
+                .for_each(|service| self.print_service(service));
+
+            self.model.extend(definitions);
         } else {
-            Option::None
+            node.children()
+                .filter(|child| child.tag_name().name() == "types")
+                .try_for_each(|node| self.print_types(&node))?;
+
+            definitions
+                .messages
+                .iter()
+                .for_each(|message| self.print_message(message, &definitions));
+
+            definitions
+                .port_types
+                .iter()
+                .for_each(|port_type| self.print_port_type(port_type, &definitions));
+        }
+        Ok(())
+    }
+
+    /// Processes the WSDL document, or schema, at the location of a wsdl:import. A document is
+    /// processed once, however often it is imported.
+    fn import_definitions(&mut self, node: &Node) -> ZeepResult<()> {
+        let location = match self.get_some_attribute(node, "location") {
+            None => {
+                self.report_construct(Coverage::Skipped, node, "WSDL import without a location");
+                return Ok(());
+            }
+            Some(l) => self.resolve_location(l),
         };
 
-        let (output_type, output_soap_name, output_xml_type, has_output) =
-            match &port_type.output_type {
-                Some((output_name, Some(output_type))) => {
-                    let soap_name = format!("Soap{}", output_type);
-                    let output_xml_name = self.message_types.get(output_name).unwrap();
+        if !self.wsdl_documents.insert(location.clone()) {
+            return Ok(());
+        }
 
-                    (
-                        output_type.clone(),
-                        soap_name,
-                        output_xml_name.clone(),
-                        true,
-                    )
+        let namespace = self.get_some_attribute(node, "namespace");
+        self.process_file_in_path(&location, namespace)
+    }
+
+    fn print_types(&mut self, node: &Node) -> ZeepResult<()> {
+        let schemas: Vec<Node> = node
+            .children()
+            .filter(|c| c.has_tag_name("schema"))
+            .collect();
+
+        self.disambiguate_types(&schemas);
+        schemas.iter().try_for_each(|c| self.print_xsd(c))?;
+
+        Ok(())
+    }
+
+    /// Names the global definitions of schemas with different target namespaces, so definitions
+    /// with the same local name do not clash. The first namespace keeps the plain name, any other
+    /// gets the prefix of its namespace appended.
+    fn disambiguate_types(&mut self, schemas: &[Node]) {
+        let mut owners: HashMap<String, String> = HashMap::new();
+
+        for schema in schemas {
+            let tns = match schema.attribute("targetNamespace") {
+                None => continue,
+                Some(tns) => tns,
+            };
+
+            let names = schema
+                .children()
+                .filter(|c| {
+                    c.has_tag_name("complexType")
+                        || c.has_tag_name("simpleType")
+                        || c.has_tag_name("element")
+                })
+                .filter_map(|c| c.attribute("name"));
+
+            for name in names {
+                let type_name = to_pascal_case(name);
+                match owners.get(&type_name) {
+                    None => {
+                        owners.insert(type_name, tns.to_string());
+                    }
+                    Some(owner) if owner == tns => {}
+                    Some(_) => {
+                        let prefix = self.namespace_prefix(tns);
+                        self.type_names.insert(
+                            (tns.to_string(), name.to_string()),
+                            format!("{}{}", type_name, to_pascal_case(&prefix)),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// The Rust name of a global definition in the current namespace
+    fn type_name(&self, name: &str) -> String {
+        self.target_namespace()
+            .and_then(|tns| self.type_names.get(&(tns.clone(), name.to_string())))
+            .cloned()
+            .unwrap_or_else(|| self.type_ident(name))
+    }
+
+    fn print_xsd(&mut self, node: &Node) -> ZeepResult<()> {
+        let tns = self.get_some_attribute(node, "targetNamespace");
+        let outer_prefix = self.enter_namespace(tns);
+        let outer_namespaces = self.namespaces.clone();
+
+        // local declarations are unqualified, unless the schema states otherwise
+        self.form_defaults.push(FormDefaults {
+            elements_qualified: self.get_some_attribute(node, "elementFormDefault")
+                == Some("qualified"),
+            attributes_qualified: self.get_some_attribute(node, "attributeFormDefault")
+                == Some("qualified"),
+        });
+
+        self.find_namespaces(node);
+        self.collect_attribute_groups(node);
+        self.collect_element_groups(node)?;
+        self.collect_global_elements(node);
+
+        node.children()
+            .try_for_each(|child| match child.tag_name().name() {
+                "import" => self.import_file(&child),
+                "redefine" => self.redefine_file(&child),
+                "element" | "complexType" | "simpleType" if !self.first_definition(&child) => {
+                    Ok(())
+                }
+                "element" => {
+                    let module = self.pick_section(TYPES_MOD);
+                    let mut _module = &mut *module.deref().borrow_mut();
+                    let first = _module.children.len();
+                    self.print_element(&child, true, &mut None, _module)?;
+                    self.locate_definitions(&child, _module, first);
+                    Ok(())
+                }
+                "complexType" => {
+                    if let Some(n) = self.get_some_attribute(&child, "name") {
+                        let module = self.pick_section(TYPES_MOD);
+                        let mut _module = &mut *module.deref().borrow_mut();
+                        let first = _module.children.len();
+                        self.print_complex_element(&child, n, false, _module)?;
+                        self.locate_definitions(&child, _module, first);
+                        Ok(())
+                    } else {
+                        Ok(())
+                    }
+                }
+                "simpleType" => {
+                    if let Some(n) = self.get_some_attribute(&child, "name") {
+                        let module = self.pick_section(TYPES_MOD);
+                        let mut _module = &mut *module.deref().borrow_mut();
+                        let first = _module.children.len();
+                        self.print_simplex_element(&child, n, _module)?;
+                        self.document_type(n, &child, _module);
+                        self.locate_definitions(&child, _module, first);
+                        Ok(())
+                    } else {
+                        Ok(())
+                    }
+                }
+                _ => Ok(()),
+            })?;
+
+        self.record_type_namespaces();
+        self.leave_namespace(outer_prefix);
+        self.form_defaults.pop();
+        self.namespaces = outer_namespaces;
+
+        Ok(())
+    }
+
+    /// Records the position of a global definition on the types generated for it, which are
+    /// those added to the module since the first index
+    fn locate_definitions(&self, node: &Node, module: &Element, first: usize) {
+        let position = self.position(node);
+        for element in module.children.iter().skip(first) {
+            element
+                .borrow_mut()
+                .position
+                .get_or_insert_with(|| position.clone());
+        }
+    }
+
+    /// Registers a global definition of the current schema. Returns false when the schema was
+    /// processed before, through another import, and the definition is generated already.
+    fn first_definition(&mut self, node: &Node) -> bool {
+        let name = match node.attribute("name") {
+            None => return true,
+            Some(n) => n.to_string(),
+        };
+        let namespace = self.target_namespace().cloned().unwrap_or_default();
+        self.defined_types
+            .insert((namespace, node.tag_name().name().to_string(), name))
+    }
+
+    /// Attributes the types generated since the last call to the namespace of the current schema.
+    /// Imported schemas are done before the schema that imports them.
+    fn record_type_namespaces(&mut self) {
+        let namespace = self.target_namespace().cloned().unwrap_or_default();
+        let module = self.pick_section(TYPES_MOD);
+        for element in &module.borrow().children {
+            self.type_namespaces
+                .entry(element.borrow().name.clone())
+                .or_insert_with(|| namespace.clone());
+        }
+    }
+
+    /// Moves the types into a module per namespace, within the types module. Types of no
+    /// namespace stay where they are. A type takes back its local name in its module, as the
+    /// module tells it apart from the types of other namespaces, and references to it are
+    /// qualified by the module.
+    fn split_namespace_modules(&mut self) {
+        let types = self.pick_section(TYPES_MOD);
+        let mut types = types.borrow_mut();
+        let local_names: HashMap<&String, String> = self
+            .type_names
+            .iter()
+            .map(|((_, local_name), name)| (name, self.type_ident(local_name)))
+            .collect();
+
+        // the module and local name of every type that moves
+        let mut modules: Vec<(String, String)> = vec![];
+        let mut paths: HashMap<String, (String, String)> = HashMap::new();
+        let mut unmoved: HashSet<String> = HashSet::new();
+        for element in &types.children {
+            let name = element.borrow().name.clone();
+            let namespace = match self.type_namespaces.get(&name) {
+                Some(ns) if !ns.is_empty() => ns,
+                _ => {
+                    unmoved.insert(name);
+                    continue;
                 }
-                _ => (String::new(), String::new(), String::new(), false),
             };
 
-        let (_fault_type, _fault_xml_type, fault_soap_name, has_fault) = match &port_type.fault_type
-        {
-            Some((fault_name, Some(fault_type))) => {
-                let soap_name = format!("Soap{}", fault_type);
-                (
-                    fault_type.to_string(),
-                    fault_name.to_string(),
-                    soap_name,
-                    true,
+            let module = match modules.iter().find(|(ns, _)| ns == namespace) {
+                Some((_, module)) => module.clone(),
+                None => {
+                    let module = self.namespace_module_name(namespace, &modules);
+                    modules.push((namespace.clone(), module.clone()));
+                    module
+                }
+            };
+            let local_name = local_names
+                .get(&name)
+                .cloned()
+                .unwrap_or_else(|| name.clone());
+            paths.insert(name, (module, local_name));
+        }
+
+        let mut namespace_modules: Vec<Element> = modules
+            .iter()
+            .map(|(_, module)| Element::new_module(module))
+            .collect();
+
+        let children = std::mem::take(&mut types.children);
+        types.children_idx.clear();
+        for child in children {
+            let mut element = child.borrow().clone();
+            let current = paths.get(&element.name).map(|(module, _)| module.clone());
+
+            // a reference within the types module is relative to the module of the type
+            qualify_types(&mut element, &|identifier, before| {
+                if before.ends_with("::") {
+                    return None;
+                }
+                match (paths.get(identifier), &current) {
+                    (Some((module, local_name)), Some(current)) if module == current => {
+                        Some(local_name.clone())
+                    }
+                    (Some((module, local_name)), Some(_)) => {
+                        Some(format!("super::{}::{}", module, local_name))
+                    }
+                    (Some((module, local_name)), None) => {
+                        Some(format!("{}::{}", module, local_name))
+                    }
+                    (None, Some(_)) if unmoved.contains(identifier) => {
+                        Some(format!("super::{}", identifier))
+                    }
+                    _ => None,
+                }
+            });
+
+            match paths.get(&element.name) {
+                None => types.add(element),
+                Some((module, local_name)) => {
+                    element.name = local_name.clone();
+                    if let Some(m) = namespace_modules.iter_mut().find(|m| &m.name == module) {
+                        m.add(element);
+                    }
+                }
+            }
+        }
+        namespace_modules.into_iter().for_each(|m| types.add(m));
+        drop(types);
+
+        // the other modules refer to the types by their path in the types module
+        let prefix = format!("{}::", self.module_name(TYPES_MOD));
+        for child in &self.root.children {
+            let mut module = child.borrow_mut();
+            if module.name == TYPES_MOD || !matches!(module.element_type, ElementType::Module) {
+                continue;
+            }
+
+            qualify_types(&mut module, &|identifier, before| {
+                let (module, local_name) = paths.get(identifier)?;
+                let start = before.strip_suffix(&prefix)?;
+                if start.ends_with(|c: char| c.is_alphanumeric() || c == '_') {
+                    return None;
+                }
+                Some(format!("{}::{}", module, local_name))
+            });
+        }
+    }
+
+    /// Adds the round trip tests of the structs to the types module, those of the namespace
+    /// modules included. The types do not derive PartialEq, so a value is compared by the XML it
+    /// serializes to. The default value of a restricted type need not satisfy its facets, so the
+    /// structs that require one are left untested.
+    fn print_round_trip_tests(&mut self) {
+        let types = self.pick_section(TYPES_MOD);
+        let mut paths = vec![];
+        struct_paths(&types.borrow(), &[], &mut paths);
+        if paths.is_empty() {
+            return;
+        }
+
+        let invalid_defaults = invalid_defaults(&types.borrow());
+        let tests: String = paths
+            .iter()
+            .map(|path| {
+                let test_name = path
+                    .iter()
+                    .map(|segment| to_snake_case(unraw(segment)))
+                    .collect::<Vec<_>>()
+                    .join("_");
+                if path.last().is_some_and(|name| invalid_defaults.contains(name)) {
+                    return format!(
+                        "\t// {} is not tested, its default value may not satisfy the facets of its fields\n",
+                        path.join("::")
+                    );
+                }
+                format!(
+                    "\t#[test]\n\tfn round_trip_{0}() {{\n\t\tlet xml = to_string(&{1}::default()).expect(\"can not serialize\");\n\t\tlet value: {1} = from_str(&xml).expect(\"can not deserialize\");\n\t\tassert_eq!(to_string(&value), Ok(xml));\n\t}}\n",
+                    test_name,
+                    path.join("::")
                 )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let mut module = Element::new("round_trip_tests", ElementType::Static);
+        module.set_content(&format!(
+            "#[cfg(test)]\nmod round_trip_tests {{\n\tuse super::*;\n\n{}}}\n",
+            tests
+        ));
+        types.borrow_mut().add(module);
+    }
+
+    /// The name of the module of a namespace: the configured one, or else the last segment of the
+    /// namespace URI, numbered when another namespace ends alike.
+    fn namespace_module_name(&self, namespace: &str, modules: &[(String, String)]) -> String {
+        if let Some(module) = self.namespace_module_names.get(namespace) {
+            return module.clone();
+        }
+
+        let separators: &[char] = &['/', ':', '#'];
+        let segment = namespace
+            .trim_end_matches(separators)
+            .rsplit(separators)
+            .next()
+            .unwrap_or_default();
+        let mut name: String = to_snake_case(segment)
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '_')
+            .collect();
+        if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+            name = format!("ns_{}", name);
+        }
+        let name = self.shield_reserved_names(&name);
+
+        let mut module = name.clone();
+        let mut count = 1;
+        while modules.iter().any(|(_, m)| *m == module) {
+            count += 1;
+            module = format!("{}_{}", name, count);
+        }
+        module
+    }
+
+    /// Makes the target namespace of a schema or WSDL document current, returning the prefix of
+    /// the enclosing document. The first namespace gets the configured prefix, any other
+    /// namespace a prefix of its own.
+    fn enter_namespace(&mut self, target_namespace: Option<&str>) -> String {
+        self.target_name_space
+            .push(target_namespace.map(|tns| tns.to_string()));
+        let outer_prefix = self.ns_prefix.clone();
+
+        if let Some(tns) = target_namespace {
+            self.ns_prefix = self.namespace_prefix(tns);
+        }
+
+        outer_prefix
+    }
+
+    /// The prefix of a namespace. The first namespace gets the configured prefix, any other
+    /// namespace a prefix of its own.
+    fn namespace_prefix(&mut self, namespace: &str) -> String {
+        let prefix = match self.namespace_prefixes.get(namespace) {
+            Some(prefix) => prefix.clone(),
+            None if self.namespace_prefixes.is_empty() => self.ns_prefix.clone(),
+            None => {
+                self.import_count += 1;
+                format!("{}{}", IMPORT_PREFIX, self.import_count)
             }
-            _ => (String::new(), String::new(), String::new(), false),
         };
 
-        let soap_fault = if has_fault {
-            format!(
-                r#"     #[yaserde(rename = "Fault", default)]
-                            pub fault: Option<{1}::{0}>,
-                            "#,
-                fault_soap_name, PORTS_MOD,
-            )
-        } else {
-            r#"     #[yaserde(rename = "Fault", default)]
-                            pub fault: Option<SoapFault>,
-                            "#
-            .to_string()
-        };
+        self.namespace_prefixes
+            .insert(namespace.to_string(), prefix.clone());
+        prefix
+    }
 
-        let soap_wrapper_out = if has_output {
-            if !self.have_seen_type(&output_soap_name, parent) {
-                Option::Some(format!(
-                    r#"#[derive(Debug, Default, YaSerialize, YaDeserialize)]
-                    pub struct {0} {{
-                    #[yaserde(rename = "{3}", default)]
-                    pub body: {2}::{1},
-                    {4}
-                }}
-                {5}
-                "#,
-                    output_soap_name,
-                    output_type,
-                    PORTS_MOD,
-                    output_xml_type,
-                    soap_fault,
-                    self.construct_soap_wrapper(output_type.as_str(), output_soap_name.as_str()),
-                ))
-            } else {
-                Option::None
+    fn leave_namespace(&mut self, outer_prefix: String) {
+        self.target_name_space.pop();
+        self.ns_prefix = outer_prefix;
+    }
+
+    /// The target namespace of the document being processed
+    fn target_namespace(&self) -> Option<&String> {
+        self.target_name_space.last().and_then(|tns| tns.as_ref())
+    }
+
+    fn find_namespaces(&mut self, node: &Node) {
+        node.namespaces().iter().for_each(|ns| {
+            if let Some(name) = ns.name() {
+                self.namespaces
+                    .insert(name.to_string(), ns.uri().to_string());
             }
-        } else {
-            Option::None
+        });
+    }
+
+    fn import_file(&mut self, node: &Node) -> ZeepResult<()> {
+        let namespace = match self.get_some_attribute(node, "namespace") {
+            None => self.target_namespace().cloned().unwrap_or_default(),
+            Some(n) => n.to_string(),
         };
 
-        let output_template = if has_output {
-            if has_fault {
-                format!(
-                    "-> SoapResult<{2}::{0}, Option<{2}::{1}>>",
-                    output_type, fault_soap_name, PORTS_MOD,
-                )
-            } else {
-                format!(
-                    "-> SoapResult<{}::{}, Option<SoapFault>>",
-                    PORTS_MOD, output_type
-                )
+        // the swaRef of the WS-I Attachments Profile, the only type of its namespace, is built in
+        if namespace == WSI_SWA_NS && !self.serde_backend {
+            return Ok(());
+        }
+
+        let name = match self.get_some_attribute(node, "schemaLocation") {
+            Some(n) => self.resolve_location(n),
+            None => match self.namespace_catalog.get(&namespace) {
+                Some(location) => location.clone(),
+                None => {
+                    // schemas in the same document are processed on their own
+                    let is_local = node.document().descendants().any(|c| {
+                        c.has_tag_name("schema")
+                            && c.attribute("targetNamespace") == Some(namespace.as_str())
+                    });
+                    if !is_local {
+                        let reason = format!(
+                            "no schema location or catalog entry for namespace {}",
+                            namespace
+                        );
+                        self.report_construct(Coverage::Skipped, node, &reason);
+                    }
+                    return Ok(());
+                }
+            },
+        };
+
+        // the imported schema declares its own namespace, which is the one imported
+        let imported = self.get_some_attribute(node, "namespace");
+        self.process_file_in_path(&name, imported)
+    }
+
+    /// Processes a schema of the same namespace, with the definitions in the redefine element
+    /// replacing those of the schema. A redefinition refers to the original by its own name.
+    fn redefine_file(&mut self, node: &Node) -> ZeepResult<()> {
+        let name = match self.get_some_attribute(node, "schemaLocation") {
+            None => {
+                self.report_construct(
+                    Coverage::Skipped,
+                    node,
+                    "redefine without a schema location",
+                );
+                return Ok(());
             }
-        } else {
-            String::new()
+            Some(n) => self.resolve_location(n),
         };
 
-        let some_soap_action = node
-            .children()
-            .find(|c| c.has_tag_name("operation"))
-            .map(|opp| opp.attribute("soapAction"))
-            .unwrap_or_default();
+        let xml = self.read_to_string(&name)?;
+        let doc = roxmltree::Document::parse(&xml).map_err(|e| ZeepError::xml(&name, e))?;
+        self.record_uncovered(&doc, &name);
+        let schema = doc.root_element();
+
+        // groups are inlined where they are used, so they are redefined before any type uses them
+        self.find_namespaces(&schema);
+        self.collect_attribute_groups(&schema);
+        self.collect_element_groups(&schema)?;
+        for group in node.children().filter(|c| c.is_element()) {
+            match group.tag_name().name() {
+                "attributeGroup" => self.redefine_attribute_group(&group),
+                "group" => self.redefine_element_group(&group)?,
+                _ => {}
+            }
+        }
+
+        self.documents.push(name.clone());
+        let result = self.print(&schema);
+        self.documents.pop();
+        result?;
+
+        let module = self.pick_section(TYPES_MOD);
+        let module = &mut *module.deref().borrow_mut();
+        for definition in node.children().filter(|c| c.is_element()) {
+            let name = match self.get_some_attribute(&definition, "name") {
+                None => continue,
+                Some(n) => n,
+            };
+
+            match definition.tag_name().name() {
+                "simpleType" => {
+                    let original_base = schema
+                        .children()
+                        .find(|c| c.has_tag_name("simpleType") && c.attribute("name") == Some(name))
+                        .and_then(|simple| self.deconstruct_simplex_element(&simple).ok());
+                    if let Some(base) = original_base {
+                        self.redefined_bases.insert(name.to_string(), base);
+                    }
+
+                    let type_name = self.type_ident(name);
+                    let original = module.child(&type_name).map(|t| t.borrow().clone());
+                    module.retain(|t| t.name != type_name);
+                    self.print_simplex_element(&definition, name, module)?;
+                    self.document_type(name, &definition, module);
+
+                    // the facets of the original still apply
+                    if let (Some(original), Some(redefined)) = (original, module.child(&type_name))
+                    {
+                        let mut redefined = redefined.borrow_mut();
+                        if matches!(original.element_type, ElementType::Restriction)
+                            && matches!(redefined.element_type, ElementType::Restriction)
+                        {
+                            let facets = std::mem::take(&mut redefined.facets);
+                            redefined.facets = original.facets;
+                            redefined.facets.extend(facets);
+                        }
+                    }
+                }
+                "complexType" => self.redefine_complex_type(&definition, name, module)?,
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Replaces a complex type. An extension of the original adds to its fields, a restriction
+    /// repeats all the content it keeps.
+    fn redefine_complex_type(
+        &mut self,
+        node: &Node,
+        name: &str,
+        module: &mut Element,
+    ) -> ZeepResult<()> {
+        let type_name = self.type_ident(name);
+        let original = module.child(&type_name).map(|t| t.borrow().clone());
+        module.retain(|t| t.name != type_name);
+
+        let extension = node
+            .children()
+            .filter(|c| c.has_tag_name("complexContent"))
+            .flat_map(|c| c.children())
+            .find(|c| {
+                c.has_tag_name("extension")
+                    && c.attribute("base").map(|b| self.split_type(b)) == Some(name)
+            });
+
+        match (extension, original) {
+            (Some(extension), Some(original)) => {
+                let mut element = self.init_element(name, false);
+                element.documentation = documentation(node).or(original.documentation);
+                original
+                    .children
+                    .iter()
+                    .for_each(|c| element.add(c.borrow().clone()));
+                self.print_derived_content(&extension, &mut Some(&mut element), module)?;
+                module.add(element);
+                Ok(())
+            }
+            _ => self.print_complex_element(node, name, false, module),
+        }
+    }
+
+    /// Replaces the attributes of an attribute group, where a reference to the group itself
+    /// stands for its original attributes
+    fn redefine_attribute_group(&mut self, group: &Node) {
+        let name = match self.get_some_attribute(group, "name") {
+            None => return,
+            Some(n) => n.to_string(),
+        };
+
+        let mut element = Element::new(&self.type_ident(&name), ElementType::Struct);
+        self.print_attributes(group, &mut element);
+
+        let attributes = element
+            .children
+            .iter()
+            .map(|c| c.borrow().clone())
+            .collect();
+        self.attribute_groups.insert(name, attributes);
+    }
+
+    /// Replaces the fields of an element group, where a reference to the group itself stands for
+    /// its original fields
+    fn redefine_element_group(&mut self, group: &Node) -> ZeepResult<()> {
+        let name = match self.get_some_attribute(group, "name") {
+            None => return Ok(()),
+            Some(n) => n.to_string(),
+        };
+
+        let fields = self.print_element_group(group)?;
+        self.element_groups.insert(name, fields);
+        Ok(())
+    }
+
+    /// Whether a local element is namespace qualified, which is assumed outside of a schema. The
+    /// form of the element overrides the default of the schema.
+    fn element_qualified(&self, node: &Node) -> bool {
+        match node.attribute("form") {
+            Some("qualified") => true,
+            Some("unqualified") => false,
+            _ => self
+                .form_defaults
+                .last()
+                .is_none_or(|forms| forms.elements_qualified),
+        }
+    }
+
+    /// Whether a local attribute is namespace qualified
+    fn attribute_qualified(&self, node: &Node) -> bool {
+        match node.attribute("form") {
+            Some("qualified") => true,
+            Some("unqualified") => false,
+            _ => self
+                .form_defaults
+                .last()
+                .is_some_and(|forms| forms.attributes_qualified),
+        }
+    }
+
+    fn on_default_namespace(&self) -> bool {
+        if let (Some(default_namespace), Some(namespace)) =
+            (&self.default_namespace, self.target_namespace())
+        {
+            return default_namespace == namespace;
+        }
+
+        false
+    }
+
+    fn print_element(
+        &mut self,
+        node: &Node,
+        is_top_level: bool,
+        parent: &mut Option<&mut Element>,
+        module: &mut Element,
+    ) -> ZeepResult<()> {
+        let as_enum = node.has_tag_name("choice");
+        if as_enum {
+            let groups: Vec<Node> = node
+                .children()
+                .filter(|child| is_group_particle(child))
+                .collect();
+
+            for node in groups {
+                if let Some(p) = parent {
+                    self.print_nested_group(&node, p, module)?
+                }
+            }
+
+            let elements: Vec<Node> = node
+                .children()
+                .filter(|child| child.has_tag_name("element"))
+                .collect();
+
+            for node in elements {
+                self.print_element(&node, false, parent, module)?
+            }
+
+            if let Some(p) = parent {
+                if node.children().any(|child| child.has_tag_name("any")) {
+                    self.print_any(p);
+                }
+            }
+        }
+
+        let element_name = match self.get_some_attribute(node, "name") {
+            Some(n) => n,
+            None => {
+                if let (Some(reference), Some(p)) = (self.get_some_attribute(node, "ref"), parent) {
+                    self.print_element_ref(node, reference, p, module);
+                }
+                return Ok(());
+            }
+        };
+
+        let max_occurs = self.max_occurs(node);
+        if max_occurs == Some(0) {
+            // the element is prohibited
+            return Ok(());
+        }
+
+        let (as_option, as_vec) = self.occurrence(node);
+
+        let maybe_complex = node
+            .children()
+            .find(|child| child.has_tag_name("complexType"));
+
+        let maybe_simplex = node
+            .children()
+            .find(|child| child.has_tag_name("simpleType"));
+
+        // anonymous types of nested elements are named after their parent, so they can not clash
+        let owner = parent.as_ref().map(|p| p.name.clone()).unwrap_or_default();
+        let anonymous_name = if is_top_level {
+            self.type_name(element_name)
+        } else {
+            format!("{}{}", owner, to_pascal_case(element_name))
+        };
+
+        let mut type_name = match self.get_some_attribute(node, "type") {
+            // an element without any type information is of xs:anyType
+            None if maybe_complex.is_none() && maybe_simplex.is_none() => "anyType".to_string(),
+            None => anonymous_name.clone(),
+            Some(t) => t.to_string(),
+        };
+
+        if is_top_level {
+            // top-level == type alias
+            let top_level_name = self.type_name(element_name);
+            let alias = self.fetch_referenced_type(node, &type_name, &top_level_name, element_name);
+
+            if top_level_name != alias {
+                let mut alias_element = Element::new(top_level_name.as_str(), ElementType::Alias);
+                alias_element.field_type = Option::Some(alias);
+                alias_element.documentation = documentation(node);
+                module.add(alias_element);
+                return Ok(());
+            }
+        } else {
+            let snake_name = to_snake_case(element_name);
+            let field_name = self.shield_reserved_names(&snake_name);
+
+            // fields
+            let mut element = if let Some(_tns) = self.target_namespace() {
+                if is_top_level {
+                    let mut e = Element::new(&field_name, ElementType::Field);
+                    e.xml_name = Option::Some(element_name.to_string());
+                    e.add_ns("xsi", "http://www.w3.org/2001/XMLSchema-instance");
+                    e
+                } else if self.on_default_namespace() || !self.element_qualified(node) {
+                    let mut e = Element::new(&field_name, ElementType::Field);
+                    e.xml_name = Option::Some(element_name.to_string());
+                    e
+                } else {
+                    let mut e = Element::new(&field_name, ElementType::Field);
+                    e.xml_name = Option::Some(element_name.to_string());
+                    e.prefix = Option::Some(self.ns_prefix.to_string());
+                    e
+                }
+            } else {
+                Element::new(&field_name, ElementType::Field)
+            };
+
+            if let Some(simple) = maybe_simplex {
+                if let Some(union) = simple.children().find(|c| c.has_tag_name("union")) {
+                    self.print_union(&anonymous_name, &union, module);
+                }
+
+                if let Some(list) = simple.children().find(|c| c.has_tag_name("list")) {
+                    self.print_list(&anonymous_name, &list, module);
+                }
+
+                type_name = match self.deconstruct_simplex_element(&simple) {
+                    Ok(tn) => tn,
+                    Err(_) => type_name,
+                };
+            }
+
+            // add the element to the owning structure
+            let mut field_type = self.fetch_referenced_type(node, &type_name, &owner, element_name);
+            if self.is_xop_binary(node, &field_type) {
+                field_type = "XopBinary".to_string();
+            }
+            element.field_type = Option::Some(field_type);
+            element.vector = as_vec;
+            element.optional = as_option;
+            element.documentation = documentation(node);
+            element.position = Option::Some(self.position(node));
+
+            if let Some(max) = max_occurs.filter(|max| *max > 1) {
+                element.comment = Option::Some(format!("at most {} items", max));
+            }
+
+            // resolved into an expression once all types are known
+            element.default_value = self.get_some_attribute_as_string(node, "default");
+            if let Some(fixed) = self.get_some_attribute_as_string(node, "fixed") {
+                element.default_value = Option::Some(fixed);
+            }
+
+            if let Some(p) = parent {
+                p.add(element);
+            }
+        }
+
+        if let Some(complex) = maybe_complex {
+            let mut element = self.init_element(element_name, is_top_level);
+            element.name = anonymous_name;
+            element.documentation = documentation(node);
+            self.print_complex_type(&complex, element, module)?
+        }
+
+        Ok(())
+    }
+
+    /// Collects the global elements of a schema, so references to them can be resolved.
+    fn collect_global_elements(&mut self, schema: &Node) {
+        schema
+            .children()
+            .filter(|c| c.has_tag_name("element"))
+            .for_each(|element| {
+                let name = match element.attribute("name") {
+                    None => return,
+                    Some(n) => n,
+                };
+
+                let type_name = match element.attribute("type") {
+                    Some(t) => self.fetch_type(t),
+                    None if element.children().any(|c| c.is_element()) => to_pascal_case(name),
+                    None => self.fetch_type("anyType"),
+                };
+
+                self.global_elements.insert(
+                    name.to_string(),
+                    GlobalElement {
+                        type_name,
+                        is_abstract: element.attribute("abstract") == Some("true"),
+                    },
+                );
+
+                if let Some(head) = element.attribute("substitutionGroup") {
+                    let members = self
+                        .substitution_groups
+                        .entry(self.split_type(head).to_string())
+                        .or_default();
+                    members.push(name.to_string());
+                }
+            });
+    }
+
+    /// Adds a field for a reference to a global element. When other elements can substitute
+    /// the referenced one, the field is an enum over all of them.
+    fn print_element_ref(
+        &mut self,
+        node: &Node,
+        reference: &str,
+        parent: &mut Element,
+        module: &mut Element,
+    ) {
+        let element_name = self.split_type(reference);
+        let (as_option, as_vec) = self.occurrence(node);
+
+        let substitutes = self.substitutes(element_name);
+        if substitutes.len() > 1 && !as_vec {
+            let enum_name = self.print_substitution_group(element_name, &substitutes, module);
+
+            let mut field = Element::new(
+                &self.shield_reserved_names(&to_snake_case(element_name)),
+                ElementType::Field,
+            );
+            field.field_type = Option::Some(enum_name);
+            field.flatten = true;
+            field.optional = as_option;
+            parent.add(field);
+            return;
+        }
+
+        // yaserde can not flatten a vector of enums, so repeating substitutes get a field each
+        for name in substitutes {
+            let type_name = match self.global_elements.get(&name) {
+                Some(element) => element.type_name.clone(),
+                None => self.fetch_referenced_type(node, &name, &parent.name, &name),
+            };
+
+            let mut field = Element::new(
+                &self.shield_reserved_names(&to_snake_case(&name)),
+                ElementType::Field,
+            );
+            field.xml_name = Option::Some(name.to_string());
+            if !self.on_default_namespace() {
+                field.prefix = Option::Some(self.ns_prefix.to_string());
+            }
+            field.field_type = Option::Some(type_name);
+            field.vector = as_vec;
+            field.optional = as_option;
+            parent.add(field);
+        }
+    }
+
+    /// Returns the element itself, unless it is abstract, followed by all the elements that can
+    /// substitute it.
+    fn substitutes(&self, element_name: &str) -> Vec<String> {
+        let mut result = vec![];
+        let mut pending = vec![element_name.to_string()];
+
+        while let Some(name) = pending.pop() {
+            if result.contains(&name) {
+                continue;
+            }
+
+            if let Some(members) = self.substitution_groups.get(&name) {
+                pending.extend(members.iter().rev().cloned());
+            }
+
+            let is_abstract = self
+                .global_elements
+                .get(&name)
+                .map(|e| e.is_abstract)
+                .unwrap_or(false);
+            if !is_abstract {
+                result.push(name);
+            }
+        }
+
+        result
+    }
+
+    /// Generates an enum with a variant per element of a substitution group, dispatching on the
+    /// element name.
+    fn print_substitution_group(
+        &mut self,
+        head: &str,
+        substitutes: &[String],
+        module: &mut Element,
+    ) -> String {
+        let enum_name = format!("{}Group", to_pascal_case(head));
+        if self.have_seen_type(&enum_name, module) {
+            return enum_name;
+        }
+
+        let mut element = self.init_element(&enum_name, false);
+        element.element_type = ElementType::Enum;
+
+        for name in substitutes {
+            let mut variant = Element::new(&self.variant_name(name), ElementType::Variant);
+            variant.xml_name = Option::Some(name.to_string());
+            if !self.on_default_namespace() {
+                variant.prefix = Option::Some(self.ns_prefix.to_string());
+            }
+            variant.field_type = self.global_elements.get(name).map(|e| e.type_name.clone());
+            element.add(variant);
+        }
+
+        module.add(element);
+        enum_name
+    }
+
+    fn get_some_attribute<'a>(&self, node: &'a Node, attr_name: &str) -> Option<&'a str> {
+        match node.attributes().iter().find(|a| a.name() == attr_name) {
+            None => None,
+            Some(a) => Some(a.value()),
+        }
+    }
+
+    fn get_some_attribute_as_string(&self, node: &Node, attr_name: &str) -> Option<String> {
+        node.attributes()
+            .iter()
+            .find(|a| a.name() == attr_name)
+            .map(|a| a.value().to_string())
+    }
+
+    fn fetch_type(&self, node_type: &str) -> String {
+        self.qname_type(&self.qualified_name(node_type))
+    }
+
+    /// Resolves a prefixed name with the namespaces of the documents. Names without a prefix are
+    /// in the target namespace.
+    fn qualified_name(&self, node_type: &str) -> QName {
+        let (namespace, local_name) = match node_type.split_once(':') {
+            Some((prefix, local_name)) => (self.namespaces.get(prefix), local_name),
+            None => (self.target_namespace(), node_type),
+        };
+
+        QName {
+            namespace: namespace.cloned(),
+            name: local_name.to_string(),
+        }
+    }
+
+    /// The Rust type of a qualified name, a built-in type or a generated one
+    fn qname_type(&self, name: &QName) -> String {
+        match self.builtin_type(&name.name) {
+            Some(t) => t.to_string(),
+            None => self.rust_name(name.namespace.as_ref(), &name.name),
+        }
+    }
+
+    /// The Rust name of a global definition. Definitions of namespaces that share local names are
+    /// named apart.
+    fn rust_name(&self, namespace: Option<&String>, local_name: &str) -> String {
+        namespace
+            .and_then(|ns| self.type_names.get(&(ns.clone(), local_name.to_string())))
+            .cloned()
+            .unwrap_or_else(|| self.type_ident(local_name))
+    }
+
+    /// Whether a type is the swaRef of the WS-I Attachments Profile
+    fn is_swa_ref(&self, name: &QName) -> bool {
+        !self.serde_backend
+            && name.name == "swaRef"
+            && name.namespace.as_deref() == Some(WSI_SWA_NS)
+    }
+
+    /// Whether a binary element goes in a part of its own of MTOM messages, as its schema expects a
+    /// content type for it
+    fn is_xop_binary(&self, node: &Node, field_type: &str) -> bool {
+        self.mtom
+            && !self.serde_backend
+            && field_type == "Base64Binary"
+            && node.attribute((XMIME_NS, "expectedContentTypes")).is_some()
+    }
+
+    /// Maps XSD built-in types to their Rust counterpart
+    fn builtin_type(&self, node_type: &str) -> Option<&str> {
+        if let Some(rust_type) = self.type_mappings.get(self.split_type(node_type)) {
+            return Some(rust_type);
+        }
+        let t = match self.split_type(node_type) {
+            "string" | "normalizedString" | "anyURI" => "String",
+            "base64Binary" => "Base64Binary",
+            "hexBinary" => "HexBinary",
+            "QName" => "Qname",
+            "decimal" | "double" => "f64",
+            "float" => "f32",
+            // the unbounded integer types are limited to 64 bits
+            "integer" | "negativeInteger" | "nonPositiveInteger" => "i64",
+            "nonNegativeInteger" | "positiveInteger" => "u64",
+            "long" => "i64",
+            "int" => "i32",
+            "short" => "i16",
+            "byte" => "i8",
+            "unsignedLong" => "u64",
+            "unsignedInt" => "u32",
+            "unsignedShort" => "u16",
+            "unsignedByte" => "u8",
+            "boolean" => "bool",
+            "dateTime" if self.chrono_dates => "DateTime",
+            "date" if self.chrono_dates => "Date",
+            "time" if self.chrono_dates => "Time",
+            // use String for date types
+            "date" | "dateTime" | "time" => "String",
+            "duration" if self.typed_durations => "Duration",
+            "duration" => "String",
+            "anySimpleType" => "String",
+            "anyType" => "XmlValue",
+            _ => return None,
+        };
+
+        Some(t)
+    }
+
+    /// Same as `fetch_type`, but remembers references to generated types, so they can be
+    /// verified once all files have been processed.
+    fn fetch_referenced_type(
+        &mut self,
+        node: &Node,
+        node_type: &str,
+        owner: &str,
+        member: &str,
+    ) -> String {
+        let name = self.qualified_name(node_type);
+        self.fetch_referenced_qname(self.position(node), &name, owner, member)
+    }
+
+    /// Same as `qname_type`, but remembers references to generated types, along with the position
+    /// of the reference
+    fn fetch_referenced_qname(
+        &mut self,
+        position: String,
+        name: &QName,
+        owner: &str,
+        member: &str,
+    ) -> String {
+        // a WS-I swaRef is a cid: reference to an attachment of the message
+        if self.is_swa_ref(name) {
+            self.print_swa_ref();
+            return "SwaRef".to_string();
+        }
+
+        let type_name = self.qname_type(name);
+
+        if self.builtin_type(&name.name).is_none() {
+            self.type_references.push(TypeReference {
+                type_name: type_name.clone(),
+                owner: owner.to_string(),
+                member: member.to_string(),
+                position,
+            });
+        }
+
+        type_name
+    }
+
+    /// Removes the messages and types that can not be reached from the generated operations.
+    fn prune_unreferenced(&mut self) {
+        let messages = self.pick_section(MESSAGES_MOD);
+        let messages = &mut *messages.deref().borrow_mut();
+        let types = self.pick_section(TYPES_MOD);
+        let types = &mut *types.deref().borrow_mut();
+
+        let used_messages: HashSet<String> = self
+            .port_types
+            .values()
+            .flat_map(|pt| {
+                vec![&pt.input_type, &pt.output_type]
+                    .into_iter()
+                    .flatten()
+                    .chain(pt.fault_types.iter())
+            })
+            .filter_map(|(_name, msg)| msg.clone())
+            .collect();
+
+        messages.retain(|m| used_messages.contains(&m.name));
+
+        let mut pending: Vec<String> = messages
+            .children
+            .iter()
+            .flat_map(|m| referenced_names(&m.borrow()))
+            .collect();
+        let mut used_types = HashSet::new();
+
+        while let Some(name) = pending.pop() {
+            if used_types.contains(&name) {
+                continue;
+            }
+
+            if let Some(t) = types.child(&name) {
+                pending.extend(referenced_names(&t.borrow()));
+                used_types.insert(name);
+            }
+        }
+
+        types.retain(|t| used_types.contains(&t.name));
+        self.type_references
+            .retain(|r| used_types.contains(&r.owner) || used_messages.contains(&r.owner));
+    }
+
+    /// Replaces the type of every field holding a type that others extend with an enum over the
+    /// whole hierarchy, so values of derived types, tagged with xsi:type, are accepted.
+    fn resolve_polymorphic_types(&mut self) {
+        let module = self.pick_section(TYPES_MOD);
+        let module = &mut *module.deref().borrow_mut();
+
+        let mut hierarchies = BTreeSet::new();
+        for element in &module.children {
+            let element = element.borrow();
+            if !matches!(element.element_type, ElementType::Struct) {
+                continue;
+            }
+
+            for field in &element.children {
+                let mut field = field.borrow_mut();
+                if field.flatten || !matches!(field.element_type, ElementType::Field) {
+                    continue;
+                }
+
+                let base = match &field.field_type {
+                    Some(t) if self.derived_types.contains_key(t) => t.clone(),
+                    _ => continue,
+                };
+
+                let enum_name = format!("{}Kind", base);
+                if let Some(existing) = module
+                    .child(&enum_name)
+                    .filter(|_| !hierarchies.contains(&base))
+                {
+                    warn!(
+                        "{}: {} already exists, {} is not polymorphic",
+                        existing.borrow().position.as_deref().unwrap_or_default(),
+                        enum_name,
+                        base
+                    );
+                    continue;
+                }
+
+                field.field_type = Option::Some(enum_name);
+                hierarchies.insert(base);
+            }
+        }
+
+        for base in hierarchies {
+            let mut kind = Element::new(&format!("{}Kind", base), ElementType::Polymorphic);
+            kind.documentation = Option::Some(format!(
+                "{} or any type derived from it, told apart by xsi:type",
+                base
+            ));
+
+            let mut pending = vec![base.clone()];
+            while let Some(type_name) = pending.pop() {
+                if kind.has_child(&type_name) {
+                    continue;
+                }
+
+                // the base type is the fallback, without xsi:type
+                let mut variant = Element::new(&type_name, ElementType::Variant);
+                variant.field_type = Option::Some(type_name.clone());
+                let derived = module.child(&type_name);
+                let path = Self::xsi_type_path(&type_name, module);
+                if let (true, Some(derived), Some(path)) = (kind.has_children(), derived, path) {
+                    let derived = derived.borrow();
+                    let xml_name = derived.xml_name.clone().unwrap_or_default();
+                    variant.xml_name = Option::Some(match &derived.prefix {
+                        None => xml_name,
+                        Some(prefix) => format!("{}:{}", prefix, xml_name),
+                    });
+
+                    // the attribute to write the xsi:type to
+                    variant.add(Element::new(&path, ElementType::Attribute));
+                }
+                kind.add(variant);
+
+                if let Some(derived) = self.derived_types.get(&type_name) {
+                    pending.extend(derived.iter().rev().cloned());
+                }
+            }
+
+            if let Some(namespace) = self.type_namespaces.get(&base).cloned() {
+                self.type_namespaces.insert(kind.name.clone(), namespace);
+            }
+            module.add(kind);
+        }
+    }
+
+    /// Warns about every referenced type that has not been generated and, when enabled,
+    /// adds a `String` placeholder for it, so the output still compiles.
+    fn resolve_type_references(&mut self) {
+        let module = self.pick_section(TYPES_MOD);
+        let module = &mut *module.deref().borrow_mut();
+
+        // the referrers of every unresolved type, with the position of the reference
+        let mut unresolved: BTreeMap<String, Vec<(String, String)>> = BTreeMap::new();
+        for reference in self.type_references.drain(..) {
+            if module.has_child(&reference.type_name) || self.root.has_child(&reference.type_name) {
+                continue;
+            }
+
+            let referrer = (
+                format!("{}.{}", reference.owner, reference.member),
+                reference.position,
+            );
+            let referrers = unresolved.entry(reference.type_name).or_default();
+            if !referrers.contains(&referrer) {
+                referrers.push(referrer);
+            }
+        }
+
+        for (type_name, referrers) in unresolved {
+            for (referrer, position) in referrers {
+                let message = format!("unresolved type {} referenced by {}", type_name, referrer);
+                let problem = Problem::new(&position, &message);
+                warn!("{}", problem);
+                self.report.unresolved.push(problem);
+            }
+
+            if self.stub_unresolved_types {
+                let mut stub = Element::new(&type_name, ElementType::Alias);
+                stub.field_type = Option::Some("String".to_string());
+                module.add(stub);
+            }
+        }
+    }
+
+    /// Turns the default and fixed values of all fields into Rust expressions. Values that can
+    /// not be expressed are dropped, leaving the field at the default of its type.
+    fn resolve_default_values(&mut self) {
+        let module = self.pick_section(TYPES_MOD);
+        let module = &*module.deref().borrow();
+
+        for element in &module.children {
+            let element = element.borrow();
+
+            // the variants of numeric enumerations carry their discriminants
+            if let ElementType::Enum = element.element_type {
+                continue;
+            }
+
+            for field in &element.children {
+                let mut field = field.borrow_mut();
+                let value = match field.default_value.take() {
+                    None => continue,
+                    Some(v) => v,
+                };
+
+                let field_type = field.field_type.clone().unwrap_or_default();
+                let expression = match self.default_expression(&value, &field_type, module) {
+                    Some(e) if field.vector => {
+                        warn!(
+                            "{}: ignoring default value {} of the list {}.{}",
+                            field.position.as_deref().unwrap_or_default(),
+                            e,
+                            element.name,
+                            field.name
+                        );
+                        None
+                    }
+                    Some(e) if field.optional => Option::Some(format!("Some({})", e)),
+                    Some(e) => Option::Some(e),
+                    None => {
+                        warn!(
+                            "{}: can not express default value {} of {}.{}",
+                            field.position.as_deref().unwrap_or_default(),
+                            value,
+                            element.name,
+                            field.name
+                        );
+                        None
+                    }
+                };
+
+                field.default_value = expression;
+            }
+        }
+    }
+
+    /// Sets the extra derives and the builders of the types and messages
+    fn apply_type_options(&mut self) {
+        let mut unused: BTreeSet<String> = self.type_derives.keys().cloned().collect();
+        for section in [TYPES_MOD, MESSAGES_MOD] {
+            let module = self.pick_section(section);
+            for element in &module.borrow().children {
+                let mut element = element.borrow_mut();
+                unused.remove(&element.name);
+                element.builder = self.builders;
+                element.derives = self
+                    .type_derives
+                    .get(&element.name)
+                    .unwrap_or(&self.extra_derives)
+                    .clone();
+            }
+        }
+
+        for type_name in unused {
+            warn!(
+                "ignoring the type-derives of {}, no such type is generated",
+                type_name
+            );
+        }
+    }
+
+    /// quick-xml hands the values of a flattened struct to serde as strings, which only
+    /// deserialize into strings, so the serde backend copies the fields of a base type into the
+    /// types that extend it. Choices, repeating or not, are told apart by the name of the element
+    /// instead, and wildcards are left to serde, which skips unknown elements.
+    fn lower_flattened_fields(&mut self) {
+        let module = self.pick_section(TYPES_MOD);
+        let module = &*module.deref().borrow();
+        let mut lowered = HashSet::new();
+        for element in &module.children {
+            Self::lower_element(element, module, &mut lowered);
+        }
+    }
+
+    fn lower_element(
+        element: &Rc<RefCell<Element>>,
+        module: &Element,
+        lowered: &mut HashSet<String>,
+    ) {
+        if !lowered.insert(element.borrow().name.clone()) {
+            return;
+        }
+        if !matches!(element.borrow().element_type, ElementType::Struct) {
+            return;
+        }
+
+        let mut fields: Vec<Element> = vec![];
+        for field in &element.borrow().children {
+            let mut field = field.borrow().clone();
+            if !field.flatten {
+                fields.push(field);
+                continue;
+            }
+
+            let field_type = field.field_type.clone().unwrap_or_default();
+            match module.child(&field_type) {
+                Some(base) if matches!(base.borrow().element_type, ElementType::Struct) => {
+                    Self::lower_element(&base, module, lowered);
+                    for base_field in &base.borrow().children {
+                        let base_field = base_field.borrow();
+                        if !fields.iter().any(|f| f.name == base_field.name) {
+                            fields.push(base_field.clone());
+                        }
+                    }
+                }
+                // the variants of a choice carry the types of its elements
+                Some(choice)
+                    if choice
+                        .borrow()
+                        .children
+                        .iter()
+                        .any(|v| v.borrow().field_type.is_some()) =>
+                {
+                    field.flatten = false;
+                    field.xml_name = Option::Some("$value".to_string());
+                    fields.push(field);
+                }
+                None if field_type == "RawXml" => {}
+                _ => {
+                    field.flatten = false;
+                    field.text_field = true;
+                    fields.push(field);
+                }
+            }
+        }
+
+        // quick-xml hands every element that is no field by its qualified name to a choice, so the
+        // fields are read by the name they are written with as well
+        if fields
+            .iter()
+            .any(|f| f.xml_name.as_deref() == Some("$value"))
+        {
+            for field in fields
+                .iter_mut()
+                .filter(|f| matches!(f.element_type, ElementType::Field) && !f.text_field)
+            {
+                if let (Some(prefix), Some(xml_name)) = (&field.prefix, &field.xml_name) {
+                    if xml_name != "$value" {
+                        field.aliases = vec![format!("{}:{}", prefix, xml_name)];
+                    }
+                }
+            }
+        }
+
+        let mut element = element.borrow_mut();
+        element.children.clear();
+        element.children_idx.clear();
+        for field in fields {
+            element.add(field);
+        }
+    }
+
+    /// Returns the Rust expression for an XSD value of a type
+    fn default_expression(
+        &self,
+        value: &str,
+        field_type: &str,
+        module: &Element,
+    ) -> Option<String> {
+        let expression = match field_type {
+            "String" => format!("{:?}.to_string()", value),
+            "bool" => match value.trim() {
+                "true" | "1" => "true".to_string(),
+                "false" | "0" => "false".to_string(),
+                _ => return None,
+            },
+            "f32" | "f64" => match value.trim().parse::<f64>() {
+                Ok(v) if v.is_finite() => format!("{:?}", v),
+                _ => return None,
+            },
+            "i8" | "i16" | "i32" | "i64" | "u8" | "u16" | "u32" | "u64" => {
+                match value.trim().parse::<i128>() {
+                    Ok(v) if v >= 0 || field_type.starts_with('i') => v.to_string(),
+                    _ => return None,
+                }
+            }
+            _ => {
+                let enumeration = module.child(field_type)?;
+                let enumeration = enumeration.borrow();
+
+                // restricted types wrap their base type
+                if let ElementType::Restriction = enumeration.element_type {
+                    let base_type = enumeration.field_type.as_deref()?;
+                    let base_value = self.default_expression(value, base_type, module)?;
+                    return Some(format!("{}({})", field_type, base_value));
+                }
+
+                // enumerations
+                if !matches!(enumeration.element_type, ElementType::Enum) {
+                    return None;
+                }
+
+                // numeric enumerations compare the numbers
+                let discriminant = match &enumeration.field_type {
+                    None => None,
+                    Some(base_type) => {
+                        Option::Some(self.default_expression(value, base_type, module)?)
+                    }
+                };
+
+                let variant = enumeration.children.iter().find(|v| {
+                    let v = v.borrow();
+                    match &discriminant {
+                        None => v.xml_name.as_deref() == Some(value),
+                        Some(d) => v.default_value.as_ref() == Some(d),
+                    }
+                })?;
+                let variant = variant.borrow();
+                if variant.field_type.is_some() {
+                    return None;
+                }
+
+                format!("{}::{}", field_type, variant.name)
+            }
+        };
+
+        Some(expression)
+    }
+
+    fn split_type<'a>(&self, node_type: &'a str) -> &'a str {
+        node_type.split(':').next_back().unwrap_or("String")
+    }
+
+    fn init_element(&self, name: &str, is_top_level: bool) -> Element {
+        let some_tns = self.target_namespace();
+
+        if let Some(tns) = some_tns {
+            let element_name = self.type_name(name);
+            let mut e = Element::new(&element_name, ElementType::Struct);
+
+            if is_top_level {
+                e.prefix = Option::Some(self.ns_prefix.to_string());
+                e.xml_name = Option::Some(name.to_string());
+
+                // declare all namespaces
+                e.add_ns("tns", tns);
+                e.add_ns("xsi", "http://www.w3.org/2001/XMLSchema-instance");
+                e
+            } else if self.on_default_namespace() {
+                e.xml_name = Option::Some(name.to_string());
+                e
+            } else {
+                e.prefix = Option::Some(self.ns_prefix.to_string());
+                e.xml_name = Option::Some(name.to_string());
+                e.add_ns(&self.ns_prefix, tns);
+                e
+            }
+        } else {
+            let element_name = self.type_ident(name);
+            let mut e = Element::new(&element_name, ElementType::Struct);
+            e.xml_name = Option::Some(name.to_string());
+            e
+        }
+    }
+
+    fn print_simplex_element(
+        &mut self,
+        node: &Node,
+        name: &str,
+        module: &mut Element,
+    ) -> ZeepResult<()> {
+        if self.have_seen_type(name, module) {
+            return Ok(());
+        }
+
+        if let Some(union) = node.children().find(|c| c.has_tag_name("union")) {
+            self.print_union(name, &union, module);
+            return Ok(());
+        }
+
+        if let Some(list) = node.children().find(|c| c.has_tag_name("list")) {
+            self.print_list(name, &list, module);
+            return Ok(());
+        }
+
+        let enumeration = self.find_enumeration(node);
+        if !enumeration.is_empty() {
+            self.print_enumeration(name, &enumeration, module);
+            return Ok(());
+        }
+
+        if let Some(restriction) = node.children().find(|c| c.has_tag_name("restriction")) {
+            if self.print_restriction(name, &restriction, module) {
+                return Ok(());
+            }
+        }
+
+        let mut parent_element = self.init_element(name, false);
+        let mut type_name = match self.deconstruct_simplex_element(node) {
+            Ok(tn) => tn,
+            Err(_) => self.type_ident(name),
+        };
+        if self.split_type(&type_name) == name {
+            if let Some(base) = self.redefined_bases.get(name) {
+                type_name = base.clone();
+            }
+        }
+
+        let mut field = Element::new("body", ElementType::Field);
+        let field_type = self.fetch_referenced_type(node, &type_name, &parent_element.name, "body");
+        field.text_field = field_type == "String";
+        field.field_type = Option::Some(field_type);
+        field.xml_name = None;
+
+        // text fields are automatically "flattened"
+        field.flatten = !field.text_field;
+
+        parent_element.add(field);
+
+        if !self.have_seen_type(&parent_element.name, module) {
+            module.add(parent_element);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the enumeration facets of a simple type restriction
+    fn find_enumeration<'a, 'input>(&self, node: &Node<'a, 'input>) -> Vec<Node<'a, 'input>> {
+        node.children()
+            .filter(|c| c.has_tag_name("restriction"))
+            .flat_map(|r| r.children())
+            .filter(|c| c.has_tag_name("enumeration") && c.has_attribute("value"))
+            .collect()
+    }
+
+    fn print_enumeration(&mut self, name: &str, facets: &[Node], module: &mut Element) {
+        let enum_name = self.type_name(name);
+        if self.have_seen_type(&enum_name, module) {
+            return;
+        }
+
+        let mut element = self.init_element(name, false);
+        element.element_type = ElementType::Enum;
+
+        // enumerations of integers use the values as discriminants
+        let base_type = facets
+            .first()
+            .and_then(|f| f.parent())
+            .and_then(|r| r.attribute("base").map(|b| self.base_type(&r, b)))
+            .filter(|b| {
+                matches!(
+                    b.as_str(),
+                    "i8" | "i16" | "i32" | "i64" | "u8" | "u16" | "u32" | "u64"
+                )
+            });
+        let base_type = base_type.as_deref();
+        let discriminants: Option<Vec<String>> = base_type.and_then(|b| {
+            facets
+                .iter()
+                .map(|f| self.default_expression(f.attribute("value")?, b, module))
+                .collect()
+        });
+        if let (Some(base_type), None) = (base_type, &discriminants) {
+            if let Some(restriction) = facets.first().and_then(|f| f.parent()) {
+                let reason = format!(
+                    "the values of enumeration {} are no valid {}, generated as strings",
+                    name, base_type
+                );
+                self.report_construct(Coverage::Approximated, &restriction, &reason);
+            }
+        }
+        if discriminants.is_some() {
+            element.field_type = base_type.map(|b| b.to_string());
+        }
+
+        let mut seen = HashSet::new();
+        for (i, facet) in facets.iter().enumerate() {
+            let value = facet.attribute("value").unwrap_or_default();
+            let discriminant = discriminants.as_ref().map(|d| d[i].clone());
+
+            // the same number can be written in several ways
+            if let Some(discriminant) = &discriminant {
+                if !seen.insert(discriminant.clone()) {
+                    continue;
+                }
+            }
+
+            let mut variant_name = self.variant_name(discriminant.as_deref().unwrap_or(value));
+            if element.has_child(&variant_name) {
+                variant_name = format!("{}{}", variant_name, element.children.len());
+            }
+
+            let mut variant = Element::new(&variant_name, ElementType::Variant);
+            variant.xml_name = Option::Some(value.to_string());
+            variant.default_value = discriminant;
+            variant.documentation = documentation(facet);
+            element.add(variant);
+        }
+
+        module.add(element);
+    }
+
+    /// Generates a newtype that validates the range facets of a restriction of a numeric type, or
+    /// the pattern facets of a restriction of a string when pattern validation is enabled.
+    /// Returns false if there is no such restriction.
+    fn print_restriction(&mut self, name: &str, restriction: &Node, module: &mut Element) -> bool {
+        let base_type = match restriction.attribute("base") {
+            None => return false,
+            Some(base) => self.base_type(restriction, base),
+        };
+        let base_type = base_type.as_str();
+
+        let is_numeric = matches!(
+            base_type,
+            "i8" | "i16" | "i32" | "i64" | "u8" | "u16" | "u32" | "u64" | "f32" | "f64"
+        );
+
+        // the patterns of a single restriction are alternatives
+        let patterns: Vec<&str> = restriction
+            .children()
+            .filter(|c| c.has_tag_name("pattern"))
+            .filter_map(|c| c.attribute("value"))
+            .collect();
+        let validate_patterns =
+            self.pattern_validation && base_type == "String" && !patterns.is_empty();
+
+        if !is_numeric {
+            for facet in restriction.children().filter(|c| is_range_facet(c)) {
+                self.report_construct(
+                    Coverage::Approximated,
+                    &facet,
+                    "range facets are only validated for numeric types",
+                );
+            }
+        }
+
+        if !is_numeric && !validate_patterns {
+            return false;
+        }
+
+        let mut facets = vec![];
+        if validate_patterns {
+            facets.push(Facet::Pattern(to_regex(&patterns)));
+        }
+
+        for facet in restriction
+            .children()
+            .filter(|c| c.is_element() && is_numeric)
+        {
+            let value = facet.attribute("value").unwrap_or_default();
+            let to_facet = match facet.tag_name().name() {
+                "minInclusive" => Facet::MinInclusive,
+                "maxInclusive" => Facet::MaxInclusive,
+                "minExclusive" => Facet::MinExclusive,
+                "maxExclusive" => Facet::MaxExclusive,
+                _ => continue,
+            };
+
+            match self.default_expression(value, base_type, module) {
+                Some(literal) => facets.push(to_facet(literal)),
+                None => {
+                    let reason = format!("{} is not a valid {}", value, base_type);
+                    self.report_construct(Coverage::Approximated, &facet, &reason);
+                }
+            }
+        }
+
+        let mut element = Element::new(&self.type_name(name), ElementType::Restriction);
+        element.field_type = Option::Some(base_type.to_string());
+        element.facets = facets;
+        module.add(element);
+        true
+    }
+
+    /// Generates an enum with a variant per member type of a union. Members that are not
+    /// built-in types are carried as the built-in type they are derived from.
+    fn print_union(&mut self, name: &str, node: &Node, module: &mut Element) {
+        let union_name = self.type_name(name);
+        if self.have_seen_type(&union_name, module) {
+            return;
+        }
+
+        let mut members: Vec<(String, String)> = node
+            .attribute("memberTypes")
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(|m| (to_pascal_case(self.split_type(m)), self.base_type(node, m)))
+            .collect();
+
+        let inline_members: Vec<String> = node
+            .children()
+            .filter(|c| c.has_tag_name("simpleType"))
+            .filter_map(|simple| self.deconstruct_simplex_element(&simple).ok())
+            .collect();
+
+        inline_members.iter().for_each(|base| {
+            let member = (
+                to_pascal_case(self.split_type(base)),
+                self.base_type(node, base),
+            );
+            members.push(member);
+        });
+
+        let mut element = Element::new(&union_name, ElementType::Union);
+        for (member_name, member_type) in members {
+            let mut variant_name = self.variant_name(&member_name);
+            if element.has_child(&variant_name) {
+                variant_name = format!("{}{}", variant_name, element.children.len());
+            }
+
+            let mut variant = Element::new(&variant_name, ElementType::Variant);
+            variant.field_type = Option::Some(member_type.to_string());
+            element.add(variant);
+        }
+
+        if element.has_children() {
+            module.add(element);
+        }
+    }
+
+    /// Generates a newtype over a vector of the item type of a list. Like union members, the
+    /// items are carried as the built-in type they are derived from.
+    fn print_list(&mut self, name: &str, node: &Node, module: &mut Element) {
+        let list_name = self.type_name(name);
+        if self.have_seen_type(&list_name, module) {
+            return;
+        }
+
+        let item_type = match node.attribute("itemType") {
+            Some(item) => self.base_type(node, item),
+            None => match node.children().find(|c| c.has_tag_name("simpleType")) {
+                Some(simple) => match self.deconstruct_simplex_element(&simple) {
+                    Ok(base) => self.base_type(node, &base),
+                    Err(_) => "String".to_string(),
+                },
+                None => "String".to_string(),
+            },
+        };
+
+        let mut element = Element::new(&list_name, ElementType::List);
+        element.field_type = Option::Some(item_type.to_string());
+        module.add(element);
+    }
+
+    /// Follows the restrictions of the named simple types in a document down to a built-in
+    /// type. Anything that can not be resolved is treated as a string.
+    fn base_type(&self, node: &Node, type_name: &str) -> String {
+        let mut type_name = type_name.to_string();
+        let mut seen = HashSet::new();
+
+        loop {
+            if let Some(t) = self.builtin_type(&type_name) {
+                return t.to_string();
+            }
+
+            let local_name = self.split_type(&type_name).to_string();
+            if !seen.insert(local_name.clone()) {
+                return "String".to_string();
+            }
+
+            // a redefinition restricts the original definition, which is in another document
+            if let Some(base) = self.redefined_bases.get(&local_name) {
+                type_name = base.clone();
+                continue;
+            }
+
+            let base = node
+                .document()
+                .descendants()
+                .find(|c| {
+                    c.has_tag_name("simpleType") && c.attribute("name") == Some(local_name.as_str())
+                })
+                .and_then(|simple| simple.children().find(|c| c.has_tag_name("restriction")))
+                .and_then(|restriction| restriction.attribute("base"));
+
+            match base {
+                None => return "String".to_string(),
+                Some(b) => type_name = b.to_string(),
+            }
+        }
+    }
+
+    /// Turns an enumeration value into a valid variant name
+    fn variant_name(&self, value: &str) -> String {
+        if let Some(number) = value.strip_prefix('-') {
+            if number.starts_with(|c: char| c.is_ascii_digit()) {
+                return format!("ValueMinus{}", to_pascal_case(number));
+            }
+        }
+
+        let name = to_pascal_case(value);
+
+        match name.chars().next() {
+            None => "Empty".to_string(),
+            Some(c) if c.is_ascii_digit() => format!("Value{}", name),
+            _ if name == "Self" => "SelfValue".to_string(),
+            _ => name,
+        }
+    }
+
+    fn print_complex_element(
+        &mut self,
+        node: &Node,
+        name: &str,
+        is_top_level: bool,
+        module: &mut Element,
+    ) -> ZeepResult<()> {
+        let element = self.init_element(name, is_top_level);
+        self.print_complex_type(node, element, module)
+    }
+
+    /// Adds the content of a complex type to an initialized struct
+    fn print_complex_type(
+        &mut self,
+        node: &Node,
+        mut element: Element,
+        module: &mut Element,
+    ) -> ZeepResult<()> {
+        if self.have_seen_type(&element.name, module) {
+            return Ok(());
+        }
+
+        if element.documentation.is_none() {
+            element.documentation = documentation(node);
+        }
+
+        let maybe_sequence = node.children().find(|child| child.has_tag_name("sequence"));
+
+        let maybe_complex = node
+            .children()
+            .find(|child| child.has_tag_name("complexContent"));
+
+        self.print_attributes(node, &mut element);
+
+        if let Some(sequence) = maybe_sequence {
+            if self.occurrence(&sequence).1 {
+                self.print_repeated_group(&sequence, &mut element, module)?;
+            } else {
+                self.print_sequence(&sequence, &mut Some(&mut element), module)?;
+            }
+        }
+
+        node.children()
+            .filter(|c| c.has_tag_name("choice"))
+            .try_for_each(|c| self.print_choice(&c, &mut element, module))?;
+
+        node.children()
+            .filter(|c| c.has_tag_name("all"))
+            .try_for_each(|c| self.print_all(&c, &mut element, module))?;
+
+        node.children()
+            .filter(|c| c.has_tag_name("group"))
+            .try_for_each(|c| self.print_nested_group(&c, &mut element, module))?;
+
+        if let Some(complex) = maybe_complex {
+            self.print_complex_content(&complex, &mut Some(&mut element), module)?;
+        }
+
+        if let Some(simple) = node.children().find(|c| c.has_tag_name("simpleContent")) {
+            self.print_simple_content(&simple, &mut element);
+        }
+
+        let is_mixed = node.attribute("mixed") == Some("true")
+            || maybe_complex.and_then(|c| c.attribute("mixed")) == Some("true");
+        if is_mixed {
+            self.print_mixed_text(&mut element);
+            self.report_construct(
+                Coverage::Approximated,
+                node,
+                "only the last text of mixed content is captured",
+            );
+        }
+
+        self.check_any(node, &mut element);
+
+        if !self.have_seen_type(&element.name, module) {
+            module.add(element);
+        }
+
+        Ok(())
+    }
+
+    /// Collects the global attribute groups of a schema, so they can be expanded wherever they
+    /// are referenced.
+    fn collect_attribute_groups(&mut self, schema: &Node) {
+        schema
+            .children()
+            .filter(|c| c.has_tag_name("attributeGroup"))
+            .for_each(|group| self.collect_attribute_group(schema, &group));
+    }
+
+    fn collect_attribute_group(&mut self, schema: &Node, group: &Node) {
+        let name = match self.get_some_attribute(group, "name") {
+            None => return,
+            Some(n) => n,
+        };
+
+        if self.attribute_groups.contains_key(name) {
+            return;
+        }
+
+        // guards against circular references
+        self.attribute_groups.insert(name.to_string(), vec![]);
+
+        // the groups this group refers to need to be known first
+        let dependencies: Vec<Node> = group
+            .children()
+            .filter(|c| c.has_tag_name("attributeGroup"))
+            .filter_map(|c| c.attribute("ref").map(|r| self.split_type(r)))
+            .filter_map(|r| {
+                schema
+                    .children()
+                    .find(|c| c.has_tag_name("attributeGroup") && c.attribute("name") == Some(r))
+            })
+            .collect();
+
+        dependencies
+            .iter()
+            .for_each(|definition| self.collect_attribute_group(schema, definition));
+
+        let mut element = Element::new(&self.type_ident(name), ElementType::Struct);
+        self.print_attributes(group, &mut element);
+
+        let attributes = element
+            .children
+            .iter()
+            .map(|c| c.borrow().clone())
+            .collect();
+        self.attribute_groups.insert(name.to_string(), attributes);
+    }
+
+    /// Collects the global element groups of a schema, so their content can be inlined into
+    /// every type that references them.
+    fn collect_element_groups(&mut self, schema: &Node) -> ZeepResult<()> {
+        schema
+            .children()
+            .filter(|c| c.has_tag_name("group"))
+            .try_for_each(|group| self.collect_element_group(schema, &group))
+    }
+
+    fn collect_element_group(&mut self, schema: &Node, group: &Node) -> ZeepResult<()> {
+        let name = match self.get_some_attribute(group, "name") {
+            None => return Ok(()),
+            Some(n) => n,
+        };
+
+        if self.element_groups.contains_key(name) {
+            return Ok(());
+        }
+
+        // guards against circular references
+        self.element_groups.insert(name.to_string(), vec![]);
+
+        // the groups this group refers to need to be known first
+        let dependencies: Vec<Node> = group
+            .descendants()
+            .filter(|c| c.has_tag_name("group"))
+            .filter_map(|c| c.attribute("ref").map(|r| self.split_type(r)))
+            .filter_map(|r| {
+                schema
+                    .children()
+                    .find(|c| c.has_tag_name("group") && c.attribute("name") == Some(r))
+            })
+            .collect();
+
+        dependencies
+            .iter()
+            .try_for_each(|definition| self.collect_element_group(schema, definition))?;
+
+        let fields = self.print_element_group(group)?;
+        self.element_groups.insert(name.to_string(), fields);
+
+        Ok(())
+    }
+
+    /// Generates the fields of an element group
+    fn print_element_group(&mut self, group: &Node) -> ZeepResult<Vec<Element>> {
+        let name = group.attribute("name").unwrap_or_default();
+        let module = self.pick_section(TYPES_MOD);
+        let module = &mut *module.deref().borrow_mut();
+        let mut element = Element::new(&self.type_ident(name), ElementType::Struct);
+
+        group
+            .children()
+            .try_for_each(|c| match c.tag_name().name() {
+                "sequence" => self.print_sequence(&c, &mut Some(&mut element), module),
+                "choice" => self.print_choice(&c, &mut element, module),
+                "all" => self.print_all(&c, &mut element, module),
+                _ => Ok(()),
+            })?;
+
+        Ok(element
+            .children
+            .iter()
+            .map(|c| c.borrow().clone())
+            .collect())
+    }
+
+    fn print_element_group_ref(&mut self, node: &Node, parent: &mut Element) {
+        let name = match self.get_some_attribute(node, "ref") {
+            None => return,
+            Some(r) => self.split_type(r),
+        };
+
+        match self.element_groups.get(name) {
+            None => {
+                let reason = format!("unknown group {} used by {}", name, parent.name);
+                self.report_construct(Coverage::Skipped, node, &reason);
+            }
+            Some(fields) => fields.iter().for_each(|f| parent.add(f.clone())),
+        }
+    }
+
+    /// Generates the attributes of a type, expanding attribute group references
+    fn print_attributes(&mut self, node: &Node, parent: &mut Element) {
+        node.children().for_each(|c| match c.tag_name().name() {
+            "attribute" => self.print_attribute(&c, parent),
+            "attributeGroup" => self.print_attribute_group_ref(&c, parent),
+            _ => {}
+        });
+    }
+
+    fn print_attribute_group_ref(&mut self, node: &Node, parent: &mut Element) {
+        let name = match self.get_some_attribute(node, "ref") {
+            None => return,
+            Some(r) => self.split_type(r),
+        };
+
+        match self.attribute_groups.get(name) {
+            None => {
+                let reason = format!("unknown attribute group {} used by {}", name, parent.name);
+                self.report_construct(Coverage::Skipped, node, &reason);
+            }
+            Some(attributes) => attributes.iter().for_each(|a| parent.add(a.clone())),
+        }
+    }
+
+    fn print_attribute(&mut self, node: &Node, parent: &mut Element) {
+        let element_name = match self.get_some_attribute(node, "name") {
+            None => return,
+            Some(n) => n,
+        };
+
+        // attributes without a type are xs:anySimpleType
+        let type_name = match self.get_some_attribute(node, "type") {
+            Some(t) => t.to_string(),
+            None => node
+                .children()
+                .find(|c| c.has_tag_name("simpleType"))
+                .and_then(|simple| self.deconstruct_simplex_element(&simple).ok())
+                .unwrap_or_else(|| "string".to_string()),
+        };
+        let element_type = self.fetch_referenced_type(node, &type_name, &parent.name, element_name);
+
+        // attributes are optional unless stated otherwise
+        let optional = self.get_some_attribute(node, "use") != Some("required");
+
+        let snake_name = to_snake_case(element_name);
+        let mut element = Element::new(
+            &self.shield_reserved_names(&snake_name),
+            ElementType::Attribute,
+        );
+
+        element.xml_name = Option::Some(element_name.to_string());
+        element.field_type = Option::Some(element_type);
+        element.optional = optional;
+        element.documentation = documentation(node);
+        element.position = Option::Some(self.position(node));
+
+        if self.attribute_qualified(node) && !self.on_default_namespace() {
+            element.prefix = Option::Some(self.ns_prefix.to_string());
+        }
+
+        // resolved into an expression once all types are known, fixed values become constants
+        element.default_value = self.get_some_attribute_as_string(node, "default");
+        element.fixed_value = self.get_some_attribute_as_string(node, "fixed");
+        parent.add(element)
+    }
+
+    /// Attaches the documentation of a named simple type to whatever was generated for it
+    fn document_type(&self, name: &str, node: &Node, module: &mut Element) {
+        if let Some(generated) = module.child(&self.type_name(name)) {
+            let mut generated = generated.borrow_mut();
+            if generated.documentation.is_none() {
+                generated.documentation = documentation(node);
+            }
+        }
+    }
+
+    fn deconstruct_simplex_element(&mut self, node: &Node) -> ZeepResult<String> {
+        let restriction = match node.children().find(|c| c.has_tag_name("restriction")) {
+            None => {
+                return Err(ZeepError::Unsupported {
+                    position: self.position(node),
+                    message: "simple content without a restriction".to_string(),
+                })
+            }
+            Some(b) => b,
+        };
+
+        let base = match self.get_some_attribute(&restriction, "base") {
+            None => {
+                return Err(ZeepError::Unsupported {
+                    position: self.position(&restriction),
+                    message: "restriction of simple content without a base type".to_string(),
+                })
+            }
+            Some(b) => b,
+        };
+
+        Ok(base.to_string())
+    }
+
+    fn print_sequence(
+        &mut self,
+        node: &Node,
+        parent: &mut Option<&mut Element>,
+        module: &mut Element,
+    ) -> ZeepResult<()> {
+        node.children().try_for_each(|child| {
+            if let Some(p) = parent {
+                if child.has_tag_name("choice") {
+                    self.print_choice(&child, p, module)
+                } else if is_group_particle(&child) {
+                    self.print_nested_group(&child, p, module)
+                } else if child.has_tag_name("any") {
+                    self.print_any(p);
+                    Ok(())
+                } else {
+                    self.print_element(&child, false, &mut Some(p), module)
+                }
+            } else {
+                Ok(())
+            }
+        })?;
+        Ok(())
+    }
+
+    /// The members of an `all` group may appear in any order. Unless a member is explicitly
+    /// required with minOccurs="1", it is generated as an optional field.
+    fn print_all(
+        &mut self,
+        node: &Node,
+        parent: &mut Element,
+        module: &mut Element,
+    ) -> ZeepResult<()> {
+        let (group_optional, _) = self.occurrence(node);
+
+        node.children()
+            .filter(|c| c.has_tag_name("element"))
+            .try_for_each(|c| {
+                let first_field = parent.children.len();
+                self.print_element(&c, false, &mut Some(parent), module)?;
+
+                let as_option =
+                    group_optional || self.get_some_attribute(&c, "minOccurs") != Some("1");
+                parent.children.iter().skip(first_field).for_each(|field| {
+                    field.borrow_mut().optional |= as_option;
+                });
+
+                Ok(())
+            })
+    }
+
+    /// Flattens a nested grouping construct (sequence, choice or group reference) into the parent
+    /// struct. An optional group makes every field it contributes optional, a repeating group gets
+    /// a type of its own.
+    fn print_nested_group(
+        &mut self,
+        node: &Node,
+        parent: &mut Element,
+        module: &mut Element,
+    ) -> ZeepResult<()> {
+        let (as_option, as_vec) = self.occurrence(node);
+        if as_vec {
+            return self.print_repeated_group(node, parent, module);
+        }
+
+        let first_field = parent.children.len();
+
+        if node.has_tag_name("sequence") {
+            self.print_sequence(node, &mut Some(parent), module)?;
+        } else if node.has_tag_name("group") {
+            self.print_element_group_ref(node, parent);
+        } else {
+            self.print_element(node, false, &mut Some(parent), module)?;
+        }
+
+        parent.children.iter().skip(first_field).for_each(|field| {
+            field.borrow_mut().optional |= as_option;
+        });
+
+        Ok(())
+    }
+
+    /// Generates a type holding one occurrence of a repeating group, and a flattened field with
+    /// all occurrences in the parent. A choice of elements becomes an enum, any other group a
+    /// struct. With serde, only a choice of elements repeats, as a list of the enum.
+    fn print_repeated_group(
+        &mut self,
+        node: &Node,
+        parent: &mut Element,
+        module: &mut Element,
+    ) -> ZeepResult<()> {
+        let is_choice = node.has_tag_name("choice");
+        let of_elements = is_choice && !node.children().any(|c| is_group_particle(&c));
+        if self.serde_backend && !of_elements {
+            return Err(ZeepError::Unsupported {
+                position: self.position(node),
+                message: format!(
+                    "repeating sequences and groups with the serde backend, as in {}",
+                    parent.name
+                ),
+            });
+        }
+
+        let kind = match node.tag_name().name() {
+            "choice" => "Choice",
+            "group" => "Group",
+            _ => "Sequence",
+        };
+        let (type_name, field_name) = self.group_names(parent, kind, module);
+
+        if of_elements {
+            if !self.print_choice_enum(node, &type_name, module)? {
+                return Ok(());
+            }
+
+            // a list of the choice, which the serde backend lowers like the other choices
+            if self.serde_backend {
+                let mut field = Element::new(&field_name, ElementType::Field);
+                field.field_type = Option::Some(type_name);
+                field.vector = true;
+                field.flatten = true;
+                parent.add(field);
+                return Ok(());
+            }
+
+            if let Some(choice) = module.child(&type_name) {
+                let mut choice = choice.borrow_mut();
+                choice.group_members = choice
+                    .children
+                    .iter()
+                    .map(|variant| {
+                        let variant = variant.borrow();
+                        GroupMember {
+                            xml_name: variant.xml_name.clone().unwrap_or_default(),
+                            position: 0,
+                            repeats: variant.vector,
+                        }
+                    })
+                    .collect();
+            }
+        } else {
+            let mut occurrence = self.init_element(&type_name, false);
+            match kind {
+                "Sequence" => self.print_sequence(node, &mut Some(&mut occurrence), module)?,
+                "Group" => self.print_element_group_ref(node, &mut occurrence),
+                _ => {
+                    // the branches of a choice are alternatives
+                    self.print_element(node, false, &mut Some(&mut occurrence), module)?;
+                    occurrence.children.iter().for_each(|field| {
+                        let mut field = field.borrow_mut();
+                        field.optional |= !field.vector;
+                    });
+                }
+            }
+
+            if !occurrence.has_children() {
+                return Ok(());
+            }
+
+            occurrence.group_members = self.group_members(&occurrence, is_choice, module);
+            module.add(occurrence);
+        }
+
+        let mut field = Element::new(&field_name, ElementType::Field);
+        field.field_type = Option::Some(format!("Repeated<{}>", type_name));
+        field.flatten = true;
+        parent.add(field);
+
+        Ok(())
+    }
+
+    /// Lists the elements of an occurrence of a repeating group, looking into the flattened
+    /// choices it contains
+    fn group_members(
+        &self,
+        occurrence: &Element,
+        is_choice: bool,
+        module: &Element,
+    ) -> Vec<GroupMember> {
+        let mut members = vec![];
+
+        for (position, field) in occurrence.children.iter().enumerate() {
+            let field = field.borrow();
+            let position = if is_choice { 0 } else { position };
+
+            if !field.flatten {
+                members.push(GroupMember {
+                    xml_name: field.xml_name.clone().unwrap_or_else(|| field.name.clone()),
+                    position,
+                    repeats: field.vector,
+                });
+                continue;
+            }
+
+            let choice = match field.field_type.as_deref().and_then(|t| module.child(t)) {
+                None => continue,
+                Some(c) => c,
+            };
+            choice.borrow().children.iter().for_each(|variant| {
+                let variant = variant.borrow();
+                members.push(GroupMember {
+                    xml_name: variant.xml_name.clone().unwrap_or_default(),
+                    position,
+                    repeats: variant.vector,
+                });
+            });
+        }
+
+        members
+    }
+
+    /// Picks the names of the type and field generated for a group, unique within the parent
+    fn group_names(&self, parent: &Element, kind: &str, module: &Element) -> (String, String) {
+        let mut type_name = format!("{}{}", parent.name, kind);
+        let mut field_name = to_snake_case(kind);
+        let mut idx = 1;
+        while self.have_seen_type(&type_name, module) || parent.has_child(&field_name) {
+            idx += 1;
+            type_name = format!("{}{}{}", parent.name, kind, idx);
+            field_name = format!("{}{}", to_snake_case(kind), idx);
+        }
+
+        (type_name, field_name)
+    }
+
+    /// Generates an enum with a variant per branch of a choice, and a flattened field of that enum
+    /// in the parent. yaserde can not express a group as a variant, so choices with nested groups
+    /// are flattened instead. An optional choice is flattened too: yaserde deserializes a missing
+    /// enum to its default variant, so an absent choice would come back as its first branch.
+    fn print_choice(
+        &mut self,
+        node: &Node,
+        parent: &mut Element,
+        module: &mut Element,
+    ) -> ZeepResult<()> {
+        let (as_option, as_vec) = self.occurrence(node);
+
+        if as_vec {
+            return self.print_repeated_group(node, parent, module);
+        }
+
+        if as_option || node.children().any(|c| is_group_particle(&c)) {
+            return self.print_nested_group(node, parent, module);
+        }
+
+        if node.children().any(|c| c.has_tag_name("any")) {
+            self.print_any(parent);
+        }
+
+        let (enum_name, field_name) = self.group_names(parent, "Choice", module);
+        if !self.print_choice_enum(node, &enum_name, module)? {
+            return Ok(());
+        }
+
+        let mut field = Element::new(&field_name, ElementType::Field);
+        field.field_type = Option::Some(enum_name);
+        field.flatten = true;
+        parent.add(field);
+
+        Ok(())
+    }
+
+    /// Generates an enum with a variant per element of a choice. Returns false if there are none.
+    fn print_choice_enum(
+        &mut self,
+        node: &Node,
+        enum_name: &str,
+        module: &mut Element,
+    ) -> ZeepResult<bool> {
+        // collect the branches as fields, then turn them into variants
+        let mut branches = Element::new(enum_name, ElementType::Struct);
+        node.children()
+            .filter(|c| c.has_tag_name("element"))
+            .try_for_each(|c| self.print_element(&c, false, &mut Some(&mut branches), module))?;
+
+        let mut choice = self.init_element(enum_name, false);
+        choice.element_type = ElementType::Enum;
+
+        for branch in &branches.children {
+            let branch = branch.borrow();
+            let xml_name = branch
+                .xml_name
+                .clone()
+                .unwrap_or_else(|| branch.name.clone());
+
+            let mut variant = Element::new(&self.variant_name(&xml_name), ElementType::Variant);
+            variant.xml_name = Option::Some(xml_name);
+            variant.prefix = branch.prefix.clone();
+            variant.field_type = branch.field_type.clone();
+            variant.vector = branch.vector;
+            variant.optional = branch.optional;
+            choice.add(variant);
+        }
+
+        if !choice.has_children() {
+            return Ok(false);
+        }
+
+        module.add(choice);
+        Ok(true)
+    }
+
+    /// Adds a field capturing the text of a type with mixed content. yaserde keeps a single text
+    /// field, so when text is interleaved with elements, only its last part is captured.
+    fn print_mixed_text(&mut self, parent: &mut Element) {
+        let mut name = "text".to_string();
+        while parent.has_child(&name) {
+            name.push('_');
+        }
+
+        let mut field = Element::new(&name, ElementType::Field);
+        field.field_type = Option::Some("String".to_string());
+        field.text_field = true;
+        parent.add(field);
+    }
+
+    /// Adds a field capturing the elements matched by a wildcard. All the unmatched children of
+    /// an element end up in this one field, so it is only added once.
+    fn print_any(&mut self, parent: &mut Element) {
+        if parent.has_child(ANY_FIELD) {
+            return;
+        }
+
+        let mut field = Element::new(ANY_FIELD, ElementType::Field);
+        field.field_type = Option::Some("RawXml".to_string());
+        field.flatten = true;
+        parent.add(field);
+    }
+
+    /// yaserde hands the same unmatched elements to every flattened field, so a wildcard can
+    /// only be captured when it is the only flattened field of a type.
+    fn check_any(&mut self, node: &Node, element: &mut Element) {
+        let flattened = element
+            .children
+            .iter()
+            .filter(|c| c.borrow().flatten)
+            .count();
+
+        if element.has_child(ANY_FIELD) && flattened > 1 {
+            self.report_construct(
+                Coverage::Approximated,
+                node,
+                "the elements matched by the wildcard can not be captured",
+            );
+            element.retain(|c| c.name != ANY_FIELD);
+        }
+    }
+
+    /// Returns the maximum number of occurrences of a particle, or None when it is unbounded.
+    fn max_occurs(&self, node: &Node) -> Option<u32> {
+        match self.get_some_attribute(node, "maxOccurs") {
+            None => Some(1),
+            Some("unbounded") => None,
+            // an invalid number is reported as approximated with the rest of the document
+            Some(n) => Some(n.trim().parse().unwrap_or(1)),
+        }
+    }
+
+    /// Returns whether a particle is optional and whether it repeats, as (optional, vector).
+    /// A repeating particle is never optional as well, an empty vector covers that case.
+    fn occurrence(&self, node: &Node) -> (bool, bool) {
+        let as_vec = !matches!(self.max_occurs(node), Some(0) | Some(1));
+
+        let as_option = self.get_some_attribute(node, "minOccurs") == Some("0")
+            || self.get_some_attribute(node, "nillable") == Some("true");
+
+        (as_option && !as_vec, as_vec)
+    }
+
+    /// A type with simple content holds a value of its base type as text, plus attributes.
+    fn print_simple_content(&mut self, node: &Node, parent: &mut Element) {
+        let derivation = match node
+            .children()
+            .find(|c| c.has_tag_name("extension") || c.has_tag_name("restriction"))
+        {
+            None => return,
+            Some(d) => d,
+        };
+
+        let base = derivation.attribute("base").unwrap_or("string");
+        if let Some(base_type) = self.builtin_type(base) {
+            // yaserde only writes text fields of type String
+            let mut field = Element::new("body", ElementType::Field);
+            field.field_type = Option::Some("String".to_string());
+            field.text_field = true;
+            if base_type != "String" {
+                field.comment = Option::Some(base.to_string());
+                let reason = format!("the {} value is kept as a string", base);
+                self.report_construct(Coverage::Approximated, &derivation, &reason);
+            }
+            parent.add(field);
+        } else {
+            // the base type is generated as well, and brings its own value
+            let owner = parent.name.clone();
+            let type_name = self.fetch_referenced_type(&derivation, base, &owner, base);
+
+            let mut field = Element::new(
+                &self.shield_reserved_names(&to_snake_case(&type_name)),
+                ElementType::Field,
+            );
+            field.field_type = Option::Some(type_name);
+            field.flatten = true;
+            parent.add(field);
+        }
+
+        self.print_attributes(&derivation, parent);
+    }
+
+    fn print_complex_content(
+        &mut self,
+        node: &Node,
+        parent: &mut Option<&mut Element>,
+        module: &mut Element,
+    ) -> ZeepResult<()> {
+        if let Some(extension) = node
+            .children()
+            .find(|child| child.has_tag_name("extension"))
+        {
+            self.print_extension(&extension, parent);
+            self.print_derived_content(&extension, parent, module)?;
+        }
+
+        // a restriction repeats all the content it keeps of its base type
+        if let Some(restriction) = node
+            .children()
+            .find(|child| child.has_tag_name("restriction"))
+        {
+            self.print_derived_content(&restriction, parent, module)?;
+        }
+
+        self.print_sequence(node, parent, module)
+    }
+
+    /// Generates the particles and attributes declared by an extension or restriction
+    fn print_derived_content(
+        &mut self,
+        derivation: &Node,
+        parent: &mut Option<&mut Element>,
+        module: &mut Element,
+    ) -> ZeepResult<()> {
+        if let Some(p) = parent {
+            self.print_attributes(derivation, p);
+        }
+
+        let maybe_sequence = derivation
+            .children()
+            .find(|child| child.has_tag_name("sequence"));
+
+        match (maybe_sequence, parent.as_deref_mut()) {
+            (Some(sequence), Some(p)) if self.occurrence(&sequence).1 => {
+                self.print_repeated_group(&sequence, p, module)?
+            }
+            (Some(sequence), _) => self.print_sequence(&sequence, parent, module)?,
+            _ => {}
+        }
+
+        if let Some(p) = parent {
+            derivation
+                .children()
+                .filter(|c| c.has_tag_name("choice"))
+                .try_for_each(|c| self.print_choice(&c, p, module))?;
+
+            derivation
+                .children()
+                .filter(|c| c.has_tag_name("all"))
+                .try_for_each(|c| self.print_all(&c, p, module))?;
+
+            derivation
+                .children()
+                .filter(|c| c.has_tag_name("group"))
+                .try_for_each(|c| self.print_nested_group(&c, p, module))?;
+        }
+
+        Ok(())
+    }
+
+    fn print_extension(&mut self, node: &Node, parent: &mut Option<&mut Element>) {
+        if let Some(p) = parent {
+            let base = match self.get_some_attribute(node, "base") {
+                None => return,
+                Some(n) => n,
+            };
+
+            let owner = p.name.clone();
+            let type_name = self.fetch_referenced_type(node, base, &owner, base);
+            self.derived_types
+                .entry(type_name.clone())
+                .or_default()
+                .push(owner);
+
+            let mut element = Element::new(
+                &self.shield_reserved_names(&to_snake_case(&type_name)),
+                ElementType::Field,
+            );
+            // yaserde matches flattened fields by name too, which must not catch an element named
+            // like the base type, so the name is no valid XML name
+            element.xml_name = Option::Some("$base".to_string());
+            element.flatten = true;
+            element.field_type = Option::Some(type_name.clone());
+            p.add(element);
+
+            // the attribute is carried by the first type of a hierarchy that extends another
+            let local_name = self.split_type(base);
+            let base_is_derived = node.document().descendants().any(|c| {
+                c.has_tag_name("complexType")
+                    && c.attribute("name") == Some(local_name)
+                    && c.children()
+                        .filter(|content| content.has_tag_name("complexContent"))
+                        .any(|content| content.children().any(|e| e.has_tag_name("extension")))
+            });
+            if base_is_derived {
+                return;
+            }
+
+            let mut xsi = Element::new("xsi_type", ElementType::Attribute);
+            xsi.field_type = Option::Some("String".to_string());
+            xsi.prefix = Option::Some("xsi".to_string());
+            xsi.xml_name = Option::Some("type".to_string());
+            xsi.optional = true;
+            xsi.comment = Option::Some(type_name);
+            p.add(xsi);
+
+            if !p.namespaces.iter().any(|ns| ns.starts_with("xsi:")) {
+                p.add_ns("xsi", "http://www.w3.org/2001/XMLSchema-instance");
+            }
+        }
+    }
+
+    /// Returns the path to the xsi:type attribute of a derived type, which is carried by the
+    /// first type of the hierarchy that extends another
+    fn xsi_type_path(type_name: &str, module: &Element) -> Option<String> {
+        let element = module.child(type_name)?;
+        let element = element.borrow();
+        if element.has_child("xsi_type") {
+            return Option::Some("xsi_type".to_string());
+        }
+
+        let base = element.children.iter().find(|c| {
+            let c = c.borrow();
+            c.flatten && c.xml_name.as_deref() == Some("$base")
+        })?;
+        let base = base.borrow();
+        let path = Self::xsi_type_path(base.field_type.as_deref()?, module)?;
+        Option::Some(format!("{}.{}", base.name, path))
+    }
+
+    /// Keeps a name that is a Rust keyword from being taken for one: a raw identifier, or the
+    /// name with the configured suffix. The keywords of paths can only take a suffix.
+    fn shield_reserved_names(&self, name: &str) -> String {
+        if !RUST_KEYWORDS.contains(&name) {
+            return name.to_string();
+        }
+        match &self.keyword_suffix {
+            Some(suffix) => format!("{}{}", name, suffix),
+            None if PATH_KEYWORDS.contains(&name) => format!("{}_", name),
+            None => format!("r#{}", name),
+        }
+    }
+
+    /// The Rust name of a type, trait or struct defined after an XML name
+    fn type_ident(&self, name: &str) -> String {
+        let type_name = to_pascal_case(name);
+        match self
+            .type_renames
+            .get(name)
+            .or_else(|| self.type_renames.get(&type_name))
+        {
+            Some(rust_name) => rust_name.clone(),
+            None => self.shield_reserved_names(&type_name),
+        }
+    }
+
+    fn pick_section(&mut self, target: &str) -> Rc<RefCell<Element>> {
+        self.root
+            .child(target)
+            .expect("modules have not been initialized properly")
+    }
+
+    // WSDL Messages
+
+    /// Generates the struct of a message, with a field per part of the body. The messages of
+    /// WSDL 2.0 are made up of the element of an input, output or fault.
+    fn print_message(&mut self, message: &Message, definitions: &Definitions) {
+        let parent = self.pick_section(MESSAGES_MOD);
+        let mut _parent = &mut *parent.deref().borrow_mut();
+
+        let name = message.name.name.as_str();
+        let mut element = Element::new(to_pascal_case(name).as_str(), ElementType::Struct);
+        element.xml_name = Option::Some(name.to_string());
+        element.position = Option::Some(message.position.to_string());
+
+        // parts bound to a SOAP header travel in the header, and attachments of a MIME binding
+        // apart from the envelope, neither is part of the body. Nor is any part a soap:body
+        // leaves out.
+        let body_parts = definitions.body_parts(&message.name);
+        let outside_parts = definitions.outside_parts(&message.name);
+
+        let parts = message.parts.iter().filter(|part| {
+            !outside_parts.contains(part.name.as_str())
+                && body_parts
+                    .as_ref()
+                    .is_none_or(|body_parts| body_parts.contains(part.name.as_str()))
+        });
+
+        for (index, part) in parts.enumerate() {
+            match (&part.type_name, &part.element) {
+                // simple type
+                (Some(type_name), _) => self.print_simple_part(name, part, type_name, &mut element),
+                (None, Some(element_ref)) => {
+                    self.print_element_field(name, part, element_ref, index == 0, &mut element)
+                }
+                (None, None) => {}
+            }
+        }
+
+        if !self.have_seen_type(&element.name, _parent) {
+            _parent.add(element);
+        }
+    }
+
+    /// The field of a message for a part that refers to an element. The first element part makes
+    /// up the message, further parts follow as elements of their own.
+    fn print_element_field(
+        &mut self,
+        message_name: &str,
+        part: &model::Part,
+        element_ref: &QName,
+        first: bool,
+        parent: &mut Element,
+    ) {
+        let type_name = self.fetch_referenced_qname(
+            part.position.to_string(),
+            element_ref,
+            &to_pascal_case(message_name),
+            &part.name,
+        );
+
+        let field_name = self.shield_reserved_names(&to_snake_case(&part.name));
+        let field_type = format!("{}::{}", self.module_name(TYPES_MOD), type_name);
+
+        if !first {
+            let field = Element::new_field(&field_name, &element_ref.name, &field_type, false);
+            parent.add(field);
+            return;
+        }
+
+        let mut element = Element::new(&field_name, ElementType::Field);
+        element.flatten = true;
+        element.field_type = Option::Some(field_type);
+        parent.add(element);
+
+        self.message_types
+            .insert(message_name.to_string(), type_name.clone());
+    }
+
+    fn print_simple_part(
+        &mut self,
+        message_name: &str,
+        part: &model::Part,
+        type_name: &QName,
+        parent: &mut Element,
+    ) {
+        let field_type = self.fetch_referenced_qname(
+            part.position.to_string(),
+            type_name,
+            &to_pascal_case(message_name),
+            &part.name,
+        );
+        // the messages live apart from the generated types
+        let field_type = match self.builtin_type(&type_name.name) {
+            None => format!("{}::{}", self.module_name(TYPES_MOD), field_type),
+            Some(_) => field_type,
+        };
+        let element = Element::new_field(
+            &self.shield_reserved_names(&to_snake_case(&part.name)),
+            &part.name,
+            field_type.as_str(),
+            false,
+        );
+
+        parent.add(element);
+
+        self.message_types
+            .entry(message_name.to_string())
+            .or_insert_with(|| type_name.name.clone());
+    }
+
+    // WSDL Port Types
+    fn print_port_type(&mut self, port_type: &model::PortType, definitions: &Definitions) {
+        let parent = self.pick_section(PORTS_MOD);
+        let mut _parent = &mut *parent.deref().borrow_mut();
+
+        let element_name = port_type.name.name.as_str();
+        let struct_name = self.type_ident(element_name);
+        let mut element = Element::new(struct_name.as_str(), ElementType::Trait);
+        element.documentation = port_type.documentation.clone();
+        element.position = Option::Some(port_type.position.to_string());
+
+        port_type.operations.iter().for_each(|operation| {
+            self.print_operation(
+                to_pascal_case(element_name).as_str(),
+                operation,
+                definitions,
+                &mut element,
+                _parent,
+            )
+        });
+
+        if self.mocks {
+            let mock = self.print_mock(&element);
+            _parent.add(mock);
+        }
+        _parent.add(element);
+    }
+
+    /// Generates a mock of a port type, which answers the calls of every operation with a
+    /// closure set by the tests. It is only compiled for tests, or with the mocks feature.
+    fn print_mock(&self, port_type: &Element) -> Element {
+        let mock_name = format!("Mock{}", port_type.name);
+        let mut fields = String::new();
+        let mut setters = String::new();
+        let mut functions = String::new();
+
+        for child in &port_type.children {
+            let function = child.borrow();
+            let args = match &function.function_args {
+                None => continue,
+                Some(a) => a,
+            };
+
+            let types: Vec<&str> = std::iter::once(args.input_type.as_str())
+                .chain(args.attachments.iter().map(|_| "Attachment"))
+                .collect();
+            let names: Vec<&str> = std::iter::once(args.input_name.as_str())
+                .chain(args.attachments.iter().map(String::as_str))
+                .collect();
+            let answer = format!(
+                "Fn({}) {} + Send + Sync",
+                types.join(", "),
+                args.render_result()
+            );
+
+            fields.push_str(&format!(
+                "\t/// Answers the calls of {0}\n\tpub {0}: Option<Box<dyn {1}>>,\n",
+                function.name, answer
+            ));
+            // the setters are named after the operation, not the raw identifier of its function
+            setters.push_str(&format!(
+                "\t/// Answers the calls of {0} with a closure\n\tpub fn on_{2}<F: {1} + 'static>(mut self, answer: F) -> Self {{\n\t\tself.{0} = Option::Some(Box::new(answer));\n\t\tself\n\t}}\n",
+                function.name, answer, unraw(&function.name)
+            ));
+            if let Some(output) = &args.output_type {
+                // the attachments of the response are given in memory, and read like the ones
+                // of a response
+                let (output, answer) = if args.output_attachments {
+                    (
+                        format!("({}, Vec<Attachment>)", output),
+                        format!(
+                            "(output.0.clone(), {}::ResponseParts::from(output.1.clone()))",
+                            TRANSPORT_MOD
+                        ),
+                    )
+                } else {
+                    (output.clone(), "output.clone()".to_string())
+                };
+                setters.push_str(&format!(
+                    "\t/// Answers every call of {0} with the same output\n\tpub fn returning_{3}(self, output: {1}) -> Self {{\n\t\tself.on_{3}(move |{2}| Ok({4}))\n\t}}\n",
+                    function.name,
+                    output,
+                    vec!["_"; types.len()].join(", "),
+                    unraw(&function.name),
+                    answer
+                ));
+            }
+            functions.push_str(&format!(
+                "\tasync fn {0} (&self, {1}) {2} {{\n\t\tmatch &self.{0} {{\n\t\t\tSome(answer) => answer({3}),\n\t\t\tNone => panic!(\"{4} has no answer to {0}\"),\n\t\t}}\n\t}}\n",
+                function.name,
+                args.render_parameters(),
+                args.render_result(),
+                names.join(", "),
+                mock_name
+            ));
+        }
+
+        let mut e = Element::new(&mock_name, ElementType::Static);
+        e.set_content(&format!(
+            r#"/// A {1} that answers with the closures set by the tests
+#[cfg(any(test, feature = "mocks"))]
+#[derive(Default)]
+pub struct {0} {{
+{2}}}
+
+#[cfg(any(test, feature = "mocks"))]
+impl {0} {{
+{3}}}
+
+#[cfg(any(test, feature = "mocks"))]
+#[async_trait]
+impl {1} for {0} {{
+{4}}}
+"#,
+            mock_name, port_type.name, fields, setters, functions,
+        ));
+        e
+    }
+
+    // WSDL bindings
+
+    fn print_binding(&mut self, binding: &Binding, definitions: &Definitions) -> ZeepResult<()> {
+        let parent = self.pick_section(BINDINGS_MOD);
+        let mut _parent = &mut *parent.deref().borrow_mut();
+
+        // plain HTTP bindings send the parts of the messages without an envelope
+        let http_verb = match &binding.protocol {
+            Protocol::Soap(_) => Option::None,
+            Protocol::Http(verb) => Option::Some(verb.as_str()),
+            // other bindings are reported as skipped with the rest of the document
+            Protocol::Other(_) => return Ok(()),
+        };
+        let version = binding.protocol.soap_version();
+
+        let struct_name = self.type_ident(&binding.name.name);
+        let trait_name = self.qname_type(&binding.port_type);
+
+        // plain HTTP bindings have no envelope to carry the headers
+        let headers = EnvelopeHeaders {
+            security: self.ws_security && http_verb.is_none(),
+            signature: self.ws_signature && http_verb.is_none(),
+            addressing: self.ws_addressing && http_verb.is_none(),
+        };
+        if headers.security {
+            self.print_ws_security();
+        }
+        if headers.addressing {
+            self.print_ws_addressing();
+        }
+        if version == SoapVersion::Soap12 && http_verb.is_none() {
+            self.print_soap12_fault();
+        }
+
+        if !self.have_seen_type(&struct_name, _parent) {
+            match http_verb {
+                Some(verb) => self.print_http_helpers(binding, &struct_name, verb, _parent),
+                None => self.print_transport(),
+            }
+
+            let multipart = binding
+                .operations
+                .iter()
+                .any(|o| o.input.multipart || o.output.multipart);
+            // operations with MIME attachments send them instead of MTOM messages
+            let mtom = self.mtom
+                && http_verb.is_none()
+                && binding
+                    .operations
+                    .iter()
+                    .any(|o| !o.input.multipart && !o.output.multipart);
+            if multipart || mtom {
+                self.print_attachment();
+                self.print_response_parts();
+                self.print_multipart_helpers(&struct_name, version, multipart, mtom, _parent);
+            }
+        }
+
+        let mut client = Element::new(&struct_name, ElementType::Static);
+        client.documentation = binding.documentation.clone();
+        // plain HTTP bindings send no envelopes, so they post their requests themselves
+        let envelope_version = Option::Some(version).filter(|_| http_verb.is_none());
+        client.set_content(
+            format!(
+                r#"#[derive(Debug, Clone)]
+                pub struct {0} {{
+                {1}
+                url: String{2}
+                }}
+                "#,
+                struct_name,
+                self.client_fields(envelope_version, "").0,
+                headers.fields()
+            )
+            .as_str(),
+        );
+
+        let mut t_impl = Element::new(&struct_name, ElementType::TraitImpl);
+        t_impl.field_type =
+            Option::Some(format!("{1}::{0}", trait_name, self.module_name(PORTS_MOD)));
+
+        // the SOAPAction or location of every operation as associated constants of the client,
+        // next to the calls that take SOAP headers
+        let mut inherent = Element::new(&format!("{}Inherent", struct_name), ElementType::Static);
+        inherent.set_content(&format!("impl {} {{\n", struct_name));
+
+        let mut dispatches = String::new();
+        for operation in &binding.operations {
+            match http_verb {
+                Some(_) => self.print_http_operation(
+                    &trait_name,
+                    operation,
+                    definitions,
+                    &mut t_impl,
+                    &mut inherent,
+                )?,
+                None => {
+                    let dispatch = self.print_binding_operation(
+                        binding,
+                        operation,
+                        definitions,
+                        &mut t_impl,
+                        &mut inherent,
+                        _parent,
+                    )?;
+                    dispatches.push_str(&dispatch.unwrap_or_default());
+                }
+            }
+        }
+
+        inherent.append_content("}\n");
+
+        // plain HTTP bindings carry no envelopes to dispatch
+        if self.server_dispatchers && http_verb.is_none() {
+            self.print_dispatcher(&struct_name, &trait_name, &dispatches, _parent);
+        }
+
+        self.print_default_constructor(struct_name.as_str(), envelope_version, headers, _parent);
+        self.print_constructor(struct_name.as_str(), envelope_version, headers, _parent);
+
+        _parent.add(client);
+        _parent.add(inherent);
+        _parent.add(t_impl);
+        Ok(())
+    }
+
+    /// Generates the transport of the SOAP bindings, which posts their envelopes with reqwest
+    fn print_transport(&mut self) {
+        if self.have_seen_type(TRANSPORT_MOD, &self.root) {
+            return;
+        }
+
+        let mut module = Element::new_module(TRANSPORT_MOD);
+        let mut e = Element::new("HttpTransport", ElementType::Static);
+        e.set_content(
+            r##"/// The response to a request, with the HTTP status it came back with
+#[derive(Debug, Clone)]
+pub struct Response {
+    pub status: reqwest::StatusCode,
+    /// The content type of the body, which tells a multipart/related package from an envelope
+    pub content_type: String,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    /// The body as text, which is the envelope of the response unless it came with attachments
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+/// Sends the envelopes of the SOAP bindings over HTTP, with the SOAPAction of the operation as a
+/// header for SOAP 1.1 and in the content type for SOAP 1.2
+#[derive(Debug, Clone, Default)]
+pub struct HttpTransport {
+    client: reqwest::Client,
+    credentials: Option<(String, String)>,
+    soap12: bool,
+}
+
+impl HttpTransport {
+    pub fn new(client: reqwest::Client) -> Self {
+        HttpTransport {
+            client,
+            credentials: Option::None,
+            soap12: false,
+        }
+    }
+
+    /// The user and password of the basic authentication of every request
+    pub fn with_credentials(mut self, credentials: Option<(String, String)>) -> Self {
+        self.credentials = credentials;
+        self
+    }
+
+    /// Sends the envelopes of SOAP 1.2 instead of SOAP 1.1
+    pub fn with_soap12(mut self) -> Self {
+        self.soap12 = true;
+        self
+    }
+
+    /// Posts an envelope to an endpoint, for the action of an operation
+    pub async fn call<E>(&self, endpoint: &str, action: &str, body: String) -> Result<Response, SoapError<E>> {
+        debug!("SOAP Request: {}", body);
+        let content_type = if self.soap12 {
+            format!("application/soap+xml; charset=utf-8; action=\"{}\"", action)
+        } else {
+            "text/xml; charset=utf-8".to_string()
+        };
+        let response = self.post(endpoint, action, content_type, body.into_bytes()).await?;
+        debug!("SOAP Response: {}", response.text());
+        Ok(response)
+    }
+
+    /// Posts a body of any content type to an endpoint, for the action of an operation, like the
+    /// multipart/related package of an envelope and its attachments
+    pub async fn post<E>(
+        &self,
+        endpoint: &str,
+        action: &str,
+        content_type: String,
+        body: Vec<u8>,
+    ) -> Result<Response, SoapError<E>> {
+        let res = self.send(endpoint, action, content_type, body).await?;
+        let status = res.status();
+        let content_type = res
+            .headers()
+            .get("Content-Type")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let body = res.bytes().await.map_err(SoapError::RequestError)?.to_vec();
+        Ok(Response {
+            status,
+            content_type,
+            body,
+        })
+    }
+
+    /// Posts a body, and gives back the response before its body is read
+    async fn send<E>(
+        &self,
+        endpoint: &str,
+        action: &str,
+        content_type: String,
+        body: Vec<u8>,
+    ) -> Result<reqwest::Response, SoapError<E>> {
+        let mut req = self
+            .client
+            .post(endpoint)
+            .body(body)
+            .header("Content-Type", content_type);
+        // SOAP 1.2 has the action in the content type
+        if !self.soap12 {
+            req = req.header("Soapaction", action);
+        }
+        if let Some((user, password)) = &self.credentials {
+            req = req.basic_auth(user, Option::Some(password));
+        }
+        let res = req.send().await.map_err(SoapError::RequestError)?;
+        debug!("SOAP Status: {}", res.status());
+        Ok(res)
+    }
+}
+"##,
+        );
+        module.add(e);
+        self.root.add(module);
+    }
+
+    /// Generates the reading of multipart/related responses, whose envelope is read before the
+    /// operation returns and whose attachments are read from the body as they are asked for
+    fn print_response_parts(&mut self) {
+        self.print_transport();
+        let module = match self.root.child(TRANSPORT_MOD) {
+            Some(module) => module,
+            None => return,
+        };
+        let mut module = module.borrow_mut();
+        if self.have_seen_type("ResponseParts", &module) {
+            return;
+        }
+
+        let mut body = Element::new("ResponseBody", ElementType::Static);
+        body.set_content(
+            r##"/// The body of a response, read as it comes in
+#[derive(Debug)]
+struct ResponseBody(reqwest::Response);
+
+impl ResponseBody {
+    /// The next bytes of the body, none at its end
+    async fn chunk(&mut self) -> std::io::Result<Option<Vec<u8>>> {
+        let chunk = self
+            .0
+            .chunk()
+            .await
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        Ok(chunk.map(|chunk| chunk.to_vec()))
+    }
+}
+"##,
+        );
+        // the blocking response is a reader, and so are the parts read from it
+        body.blocking_content = Option::Some(
+            r##"/// The body of a response, read as it comes in
+#[derive(Debug)]
+struct ResponseBody(reqwest::blocking::Response);
+
+impl ResponseBody {
+    /// The next bytes of the body, none at its end
+    fn chunk(&mut self) -> std::io::Result<Option<Vec<u8>>> {
+        let mut chunk = vec![0; 8192];
+        let read = loop {
+            match std::io::Read::read(&mut self.0, &mut chunk) {
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                read => break read?,
+            }
+        };
+        chunk.truncate(read);
+        Ok(Some(chunk).filter(|chunk| !chunk.is_empty()))
+    }
+}
+
+impl std::io::Read for Part<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.data.is_empty() {
+            if let Some(data) = self.chunk()? {
+                self.data = data;
+            }
+        }
+        let read = buf.len().min(self.data.len());
+        buf[..read].copy_from_slice(&self.data[..read]);
+        self.data.drain(..read);
+        Ok(read)
+    }
+}
+"##
+            .to_string(),
+        );
+        module.add(body);
+
+        let mut parts = Element::new("ResponseParts", ElementType::Static);
+        parts.set_content(
+            r##"
+/// The attachments of a multipart/related response, which are read from the body of the response
+/// as they are asked for, after its envelope
+#[derive(Debug, Default)]
+pub struct ResponseParts {
+    body: Option<ResponseBody>,
+    /// What is read of the body and not yet given out
+    buffer: Vec<u8>,
+    /// The line break and the boundary that go before every part
+    delimiter: Vec<u8>,
+    /// Whether the data of the current part is read up to the next delimiter
+    part_read: bool,
+    /// Whether the closing delimiter is read
+    closed: bool,
+    /// The parts that are read already, like the ones before the envelope
+    read_ahead: std::collections::VecDeque<Attachment>,
+}
+
+impl From<Vec<Attachment>> for ResponseParts {
+    /// Parts that are all in memory, like the ones the mocks answer with
+    fn from(attachments: Vec<Attachment>) -> Self {
+        ResponseParts {
+            part_read: true,
+            closed: true,
+            read_ahead: attachments.into(),
+            ..Default::default()
+        }
+    }
+}
+
+impl ResponseParts {
+    /// The next part of the response, none after the last one. What is left of the part before
+    /// is skipped.
+    pub async fn next_part(&mut self) -> std::io::Result<Option<Part<'_>>> {
+        if let Some(attachment) = self.read_ahead.pop_front() {
+            return Ok(Option::Some(Part {
+                content_id: attachment.content_id,
+                content_type: attachment.content_type,
+                data: attachment.data,
+                parts: Option::None,
+            }));
+        }
+        Ok(self.next_headers().await?.map(move |part| Part {
+            content_id: part.content_id,
+            content_type: part.content_type,
+            data: Vec::new(),
+            parts: Option::Some(self),
+        }))
+    }
+
+    /// Reads the parts that are left into memory, to find them by their Content-ID
+    pub async fn collect(mut self) -> std::io::Result<Vec<Attachment>> {
+        let mut attachments = Vec::new();
+        while let Some(part) = self.next_part().await? {
+            attachments.push(part.into_attachment().await?);
+        }
+        Ok(attachments)
+    }
+
+    /// Reads a response up to its envelope: the root part of a multipart/related body, or else
+    /// the whole body. The parts after the envelope are left to be read.
+    async fn read(response: reqwest::Response) -> std::io::Result<(String, ResponseParts)> {
+        let content_type = response
+            .headers()
+            .get("Content-Type")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let parameter = |name: &str| {
+            content_type
+                .split(';')
+                .map(str::trim)
+                .find_map(|p| p.strip_prefix(name))
+                .map(|v| v.trim_matches('"').trim_start_matches('<').trim_end_matches('>'))
+        };
+        let mut parts = ResponseParts {
+            body: Option::Some(ResponseBody(response)),
+            ..Default::default()
+        };
+
+        let multipart = content_type
+            .trim_start()
+            .to_ascii_lowercase()
+            .starts_with("multipart/related");
+        let boundary = match parameter("boundary=").filter(|_| multipart) {
+            Some(boundary) => boundary,
+            None => {
+                // any other body is the envelope
+                while parts.fill().await? {}
+                let envelope = String::from_utf8_lossy(&parts.buffer).into_owned();
+                return Ok((envelope, ResponseParts::from(Vec::new())));
+            }
+        };
+        parts.delimiter = format!("\r\n--{}", boundary).into_bytes();
+        // the preamble before the first delimiter is skipped like the data of a part
+        parts.buffer = b"\r\n".to_vec();
+
+        let start = parameter("start=");
+        while let Some(mut part) = parts.next_headers().await? {
+            while let Some(data) = parts.part_data().await? {
+                part.data.extend_from_slice(&data);
+            }
+            // the root part is the one of the start parameter, else the first one
+            if start.map_or(true, |start| part.content_id == start) {
+                let envelope = String::from_utf8_lossy(&part.data).into_owned();
+                return Ok((envelope, parts));
+            }
+            parts.read_ahead.push_back(part);
+        }
+        Err(invalid_body("the multipart/related body has no root part"))
+    }
+
+    /// Reads more of the body into the buffer, false at its end
+    async fn fill(&mut self) -> std::io::Result<bool> {
+        let chunk = match self.body.as_mut() {
+            Some(body) => body.chunk().await?,
+            None => Option::None,
+        };
+        match chunk {
+            Some(chunk) => {
+                self.buffer.extend_from_slice(&chunk);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// The next data of the current part, none at its end. What may be the start of the next
+    /// delimiter is held back until more of the body is read.
+    async fn part_data(&mut self) -> std::io::Result<Option<Vec<u8>>> {
+        while !self.part_read {
+            let end = match find(&self.buffer, &self.delimiter) {
+                Some(end) => {
+                    self.part_read = true;
+                    end
+                }
+                // only an end of the buffer that starts like a delimiter may be one
+                None => {
+                    let from = self.buffer.len().saturating_sub(self.delimiter.len() - 1);
+                    (from..self.buffer.len())
+                        .find(|&i| self.delimiter.starts_with(&self.buffer[i..]))
+                        .unwrap_or(self.buffer.len())
+                }
+            };
+            if end > 0 {
+                return Ok(Option::Some(self.buffer.drain(..end).collect()));
+            }
+            if !self.part_read && !self.fill().await? {
+                return Err(invalid_body("the multipart/related body ends within a part"));
+            }
+        }
+        Ok(Option::None)
+    }
+
+    /// Skips what is left of the current part, and reads the headers of the next one. Gives
+    /// nothing after the closing delimiter.
+    async fn next_headers(&mut self) -> std::io::Result<Option<Attachment>> {
+        while self.part_data().await?.is_some() {}
+        if self.closed {
+            return Ok(Option::None);
+        }
+
+        // the delimiter is followed by -- when it closes the body, else by the headers of a part
+        while self.buffer.len() < self.delimiter.len() + 2 {
+            if !self.fill().await? {
+                return Err(invalid_body("the multipart/related body is not closed"));
+            }
+        }
+        if self.buffer[self.delimiter.len()..].starts_with(b"--") {
+            // the epilogue after the closing delimiter is ignored
+            self.closed = true;
+            self.buffer.clear();
+            self.body = Option::None;
+            return Ok(Option::None);
+        }
+        let header_end = loop {
+            if let Some(end) = find(&self.buffer[self.delimiter.len()..], b"\r\n\r\n") {
+                break self.delimiter.len() + end;
+            }
+            if !self.fill().await? {
+                return Err(invalid_body("the headers of a part do not end"));
+            }
+        };
+
+        let mut part = Attachment::default();
+        for line in String::from_utf8_lossy(&self.buffer[self.delimiter.len()..header_end]).lines() {
+            if let Some((name, value)) = line.split_once(':') {
+                let value = value.trim();
+                match name.trim().to_ascii_lowercase().as_str() {
+                    "content-type" => part.content_type = value.to_string(),
+                    "content-id" => {
+                        part.content_id = value.trim_start_matches('<').trim_end_matches('>').to_string()
+                    }
+                    _ => {}
+                }
+            }
+        }
+        self.buffer.drain(..header_end + 4);
+        self.part_read = false;
+        Ok(Option::Some(part))
+    }
+}
+
+/// A part of a multipart/related response, whose data is read from the response as it comes in
+#[derive(Debug)]
+pub struct Part<'a> {
+    pub content_id: String,
+    pub content_type: String,
+    /// The data that is read and not yet given out
+    data: Vec<u8>,
+    /// The parts of the response that the rest of the data is read from
+    parts: Option<&'a mut ResponseParts>,
+}
+
+impl Part<'_> {
+    /// Whether a cid: URL, like the href of an element or a SwaRef, references the part
+    pub fn is_referenced_by(&self, href: &str) -> bool {
+        content_id(href).map_or(false, |content_id| content_id == self.content_id)
+    }
+
+    /// The next bytes of the data of the part, none at its end
+    pub async fn chunk(&mut self) -> std::io::Result<Option<Vec<u8>>> {
+        if !self.data.is_empty() {
+            return Ok(Option::Some(std::mem::take(&mut self.data)));
+        }
+        match self.parts.as_mut() {
+            Some(parts) => parts.part_data().await,
+            None => Ok(Option::None),
+        }
+    }
+
+    /// Reads the rest of the data of the part into memory
+    pub async fn into_attachment(mut self) -> std::io::Result<Attachment> {
+        let mut data = Vec::new();
+        while let Some(chunk) = self.chunk().await? {
+            data.extend_from_slice(&chunk);
+        }
+        Ok(Attachment {
+            content_id: self.content_id,
+            content_type: self.content_type,
+            data,
+        })
+    }
+}
+
+impl HttpTransport {
+    /// Posts a body like `post`, and reads the response up to its envelope. The parts after the
+    /// envelope of a multipart/related response are read as they are asked for.
+    pub async fn post_related<E>(
+        &self,
+        endpoint: &str,
+        action: &str,
+        content_type: String,
+        body: Vec<u8>,
+    ) -> Result<(reqwest::StatusCode, String, ResponseParts), SoapError<E>> {
+        let res = self.send(endpoint, action, content_type, body).await?;
+        let status = res.status();
+        let (envelope, parts) = ResponseParts::read(res)
+            .await
+            .map_err(|e| SoapError::XmlError(format!("The response can not be read: {}", e)))?;
+        debug!("SOAP Response: {}", envelope);
+        Ok((status, envelope, parts))
+    }
+}
+
+fn find(data: &[u8], pattern: &[u8]) -> Option<usize> {
+    data.windows(pattern.len()).position(|w| w == pattern)
+}
+
+fn invalid_body(message: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+"##,
+        );
+        module.add(parts);
+    }
+
+    /// The fields of a binding that send its requests, and their values given the credentials: the
+    /// transport of the envelopes of a SOAP binding, or the client of a plain HTTP binding
+    fn client_fields(&self, version: Option<SoapVersion>, credentials: &str) -> (String, String) {
+        match version {
+            Some(version) => (
+                format!("transport: {}::HttpTransport,", TRANSPORT_MOD),
+                format!(
+                    "transport: {}::HttpTransport::new(reqwest::Client::new()){}.with_credentials({}),",
+                    TRANSPORT_MOD,
+                    if version == SoapVersion::Soap12 {
+                        ".with_soap12()"
+                    } else {
+                        ""
+                    },
+                    credentials
+                ),
+            ),
+            None => (
+                "client: reqwest::Client,\n                credentials: Option<(String,String)>,"
+                    .to_string(),
+                format!(
+                    "client: reqwest::Client::new(),\n                        credentials: {},",
+                    credentials
+                ),
+            ),
+        }
+    }
+
+    /// Generates the requests of a binding with MIME attachments, which sends a multipart/related
+    /// body and reads the attachments of the response, and of a binding that sends MTOM messages,
+    /// whose binary values go in the parts of an XOP package
+    fn print_multipart_helpers(
+        &mut self,
+        struct_name: &str,
+        version: SoapVersion,
+        multipart: bool,
+        mtom: bool,
+        parent: &mut Element,
+    ) {
+        let (root_type, xop_root_type, xop_content_type) = match version {
+            SoapVersion::Soap11 => (
+                r#""text/xml; charset=utf-8""#,
+                r#""application/xop+xml; charset=utf-8; type=\"text/xml\"""#,
+                r#"format!("{}; start-info=\"text/xml\"", content_type)"#,
+            ),
+            SoapVersion::Soap12 => (
+                r#"format!("application/soap+xml; charset=utf-8; action=\"{}\"", action)"#,
+                r#""application/xop+xml; charset=utf-8; type=\"application/soap+xml\"""#,
+                r#"format!("{}; start-info=\"application/soap+xml\"; action=\"{}\"", content_type, action)"#,
+            ),
+        };
+
+        let mut requests = String::new();
+        if multipart {
+            requests.push_str(&format!(
+                r#"
+                async fn send_multipart_request<E>(
+                    &self,
+                    envelope: String,
+                    attachments: Vec<(&str, Attachment)>,
+                    action: &str,
+                ) -> Result<(reqwest::StatusCode, String, {1}::ResponseParts), SoapError<E>> {{
+                    debug!("SOAP Request: {{}}", envelope);
+                    // without attachments the envelope goes as it is
+                    let (content_type, body) = if attachments.is_empty() {{
+                        ({0}.to_string(), envelope.into_bytes())
+                    }} else {{
+                        multipart_related(&envelope, &{0}, &attachments)
+                    }};
+                    self.transport.post_related(&self.url, action, content_type, body).await
+                }}
+"#,
+                root_type, TRANSPORT_MOD
+            ));
+        }
+        if mtom {
+            requests.push_str(&format!(
+                r#"
+                async fn send_mtom_request<E>(
+                    &self,
+                    envelope: String,
+                    parts: Vec<Attachment>,
+                    action: &str,
+                ) -> Result<(reqwest::StatusCode, String, Vec<Attachment>), SoapError<E>> {{
+                    debug!("SOAP Request: {{}}", envelope);
+                    // without binary values the envelope goes as it is, else as the root of an XOP package
+                    let (content_type, body) = if parts.is_empty() {{
+                        ({0}.to_string(), envelope.into_bytes())
+                    }} else {{
+                        let parts: Vec<(&str, Attachment)> = parts.into_iter().map(|part| ("", part)).collect();
+                        let (content_type, body) = multipart_related(&envelope, {1}, &parts);
+                        ({2}, body)
+                    }};
+                    let (status, txt, parts) = self.transport.post_related(&self.url, action, content_type, body).await?;
+                    // the binary values are resolved along with the rest of the envelope
+                    let parts = parts.collect().await.map_err(|e| {{
+                        SoapError::XmlError(format!("The parts of the response can not be read: {{}}", e))
+                    }})?;
+                    Ok((status, txt, parts))
+                }}
+"#,
+                root_type,
+                xop_root_type,
+                xop_content_type
+            ));
+        }
+
+        let mut e = Element::new(&format!("{}Multipart", struct_name), ElementType::Static);
+        e.set_content(
+            format!(
+                r#"
+            impl {0} {{{1}            }}
+            "#,
+                struct_name, requests
+            )
+            .as_str(),
+        );
+
+        parent.add(e);
+    }
+
+    /// Generates the request of a plain HTTP binding, which sends the parameters of an operation
+    /// in the query of a GET or the form of a POST to the location of the operation
+    fn print_http_helpers(
+        &mut self,
+        binding: &Binding,
+        struct_name: &str,
+        verb: &str,
+        parent: &mut Element,
+    ) {
+        let request = match verb {
+            "GET" => ".get(&url).query(parameters)",
+            "POST" => ".post(&url).form(parameters)",
+            _ => {
+                let reason = format!("unsupported HTTP verb {}, sending a POST", verb);
+                self.report_at(Coverage::Approximated, &binding.position, &reason);
+                ".post(&url).form(parameters)"
+            }
+        };
+
+        let mut e = Element::new(struct_name, ElementType::Static);
+        e.set_content(format!(r#"
+            impl {0} {{
+                async fn send_http_request(&self, location: &str, parameters: &[(&str, String)]) -> SoapResponse {{
+                    let url = format!("{{}}/{{}}", self.url.trim_end_matches('/'), location.trim_start_matches('/'));
+                    debug!("HTTP Request: {{}} {{:?}}", url, parameters);
+                    let mut req = self
+                        .client
+                        {1};
+                    if let Some(credentials) = &self.credentials {{
+                        req = req.basic_auth(
+                            credentials.0.to_string(),
+                            Option::Some(credentials.1.to_string()),
+                        );
+                    }}
+                    let res = req.send().await?;
+                    let status = res.status();
+                    debug!("HTTP Status: {{}}", status);
+                    let txt = res.text().await.unwrap_or_default();
+                    debug!("HTTP Response: {{}}", txt);
+                    Ok((status, txt))
+                }}
+            }}
+            "#, struct_name, request).as_str());
+
+        parent.add(e);
+    }
+
+    /// Generates the WS-Security headers, that the SOAP bindings add to the envelopes they send
+    fn print_ws_security(&mut self) {
+        if self.have_seen_type(SECURITY_MOD, &self.root) {
+            return;
+        }
+
+        self.print_envelope_helpers();
+        let mut module = Element::new_module(SECURITY_MOD);
+        module.add(self.security_header());
+
+        let mut tokens = Element::new("tokens", ElementType::Static);
+        tokens.set_content(
+            r##"const WSSE_NAMESPACE: &str =
+    "http://docs.oasis-open.org/wss/2004/01/oasis-200401-wss-wssecurity-secext-1.0.xsd";
+const WSU_NAMESPACE: &str =
+    "http://docs.oasis-open.org/wss/2004/01/oasis-200401-wss-wssecurity-utility-1.0.xsd";
+const USERNAME_TOKEN_PROFILE: &str =
+    "http://docs.oasis-open.org/wss/2004/01/oasis-200401-wss-username-token-profile-1.0";
+const BASE64_BINARY: &str =
+    "http://docs.oasis-open.org/wss/2004/01/oasis-200401-wss-soap-message-security-1.0#Base64Binary";
+
+/// A WS-Security Timestamp: the time a message is created, and the time it expires after its
+/// time to live. Clocks that are off by less than the clock skew are not held against a response.
+#[derive(Debug, Clone)]
+pub struct Timestamp {
+    time_to_live: std::time::Duration,
+    clock_skew: std::time::Duration,
+}
+
+impl Timestamp {
+    pub fn new(time_to_live: std::time::Duration) -> Self {
+        Timestamp {
+            time_to_live,
+            clock_skew: std::time::Duration::from_secs(300),
+        }
+    }
+
+    pub fn with_clock_skew(mut self, clock_skew: std::time::Duration) -> Self {
+        self.clock_skew = clock_skew;
+        self
+    }
+
+    /// The timestamp of a message created at a time
+    pub fn to_xml(&self, created: std::time::SystemTime) -> String {
+        format!(
+            r#"<wsu:Timestamp wsu:Id="Timestamp"><wsu:Created>{}</wsu:Created><wsu:Expires>{}</wsu:Expires></wsu:Timestamp>"#,
+            utc_timestamp(created),
+            utc_timestamp(created + self.time_to_live)
+        )
+    }
+
+    /// Checks the timestamp in the Security header of a message, if it has one: it may not be
+    /// created later than now, nor expire before now
+    pub fn check(&self, envelope: &str, now: std::time::SystemTime) -> Result<(), String> {
+        let (created, expires) = match header_timestamp(envelope)? {
+            Some(times) => times,
+            None => return Ok(()),
+        };
+        if let Some(created) = created.as_deref() {
+            let time = parse_utc_timestamp(created)
+                .ok_or_else(|| format!("invalid creation time {} in the timestamp", created))?;
+            if time > now + self.clock_skew {
+                return Err(format!("the message is created in the future, at {}", created));
+            }
+        }
+        if let Some(expires) = expires.as_deref() {
+            let time = parse_utc_timestamp(expires)
+                .ok_or_else(|| format!("invalid expiry time {} in the timestamp", expires))?;
+            if time + self.clock_skew < now {
+                return Err(format!("the message expired at {}", expires));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How the password of a UsernameToken is sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasswordType {
+    /// The password as it is, for connections that are encrypted anyway
+    Text,
+    /// The Base64 of the SHA-1 of the nonce, the creation time and the password
+    Digest,
+}
+
+/// The credentials of a WS-Security UsernameToken, sent with a nonce and the time it was created
+#[derive(Debug, Clone)]
+pub struct UsernameToken {
+    username: String,
+    password: String,
+    password_type: PasswordType,
+}
+
+impl UsernameToken {
+    /// A token that sends its password as text
+    pub fn new(username: &str, password: &str) -> Self {
+        UsernameToken {
+            username: username.to_string(),
+            password: password.to_string(),
+            password_type: PasswordType::Text,
+        }
+    }
+
+    pub fn with_password_type(mut self, password_type: PasswordType) -> Self {
+        self.password_type = password_type;
+        self
+    }
+
+    pub fn to_xml(&self) -> String {
+        let nonce: [u8; 16] = rand::random();
+        let created = utc_timestamp(std::time::SystemTime::now());
+        let (password_type, password) = match self.password_type {
+            PasswordType::Text => ("PasswordText", self.password.clone()),
+            PasswordType::Digest => (
+                "PasswordDigest",
+                password_digest(&nonce, &created, &self.password),
+            ),
+        };
+        format!(
+            r#"<wsse:UsernameToken><wsse:Username>{}</wsse:Username><wsse:Password Type="{}#{}">{}</wsse:Password><wsse:Nonce EncodingType="{}">{}</wsse:Nonce><wsu:Created>{}</wsu:Created></wsse:UsernameToken>"#,
+            escape(&self.username),
+            USERNAME_TOKEN_PROFILE,
+            password_type,
+            escape(&password),
+            BASE64_BINARY,
+            base64::encode(nonce),
+            created
+        )
+    }
+}
+
+/// The password digest of a UsernameToken: Base64(SHA-1(nonce + created + password))
+pub fn password_digest(nonce: &[u8], created: &str, password: &str) -> String {
+    use sha1::Digest;
+    let mut hasher = sha1::Sha1::new();
+    hasher.update(nonce);
+    hasher.update(created.as_bytes());
+    hasher.update(password.as_bytes());
+    base64::encode(hasher.finalize())
+}
+
+/// A point in time as an xs:dateTime in UTC, with milliseconds, like 2021-08-01T12:00:00.000Z
+pub fn utc_timestamp(time: std::time::SystemTime) -> String {
+    chrono::DateTime::<chrono::Utc>::from(time).to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
+
+/// The point in time of an xs:dateTime, in UTC or with an offset from it. Without either, it is
+/// taken to be in UTC.
+pub fn parse_utc_timestamp(text: &str) -> Option<std::time::SystemTime> {
+    let text = text.trim();
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(text) {
+        return Some(time.into());
+    }
+    use chrono::TimeZone;
+    chrono::NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S%.f")
+        .ok()
+        .map(|time| chrono::Utc.from_utc_datetime(&time).into())
+}
+
+/// The creation and expiry times of the wsu:Timestamp in the wsse:Security header of an
+/// envelope, if it has one. Elements of the same names elsewhere, like in the body, are not it.
+fn header_timestamp(envelope: &str) -> Result<Option<(Option<String>, Option<String>)>, String> {
+    use yaserde::xml::reader::{EventReader, XmlEvent};
+
+    // the path to the element being read, by namespace and local name
+    let mut path: Vec<(String, String)> = vec![];
+    let mut times: Option<(Option<String>, Option<String>)> = None;
+    let mut text = String::new();
+    for event in EventReader::from_str(envelope) {
+        match event.map_err(|e| e.to_string())? {
+            XmlEvent::StartElement { name, .. } => {
+                path.push((name.namespace.unwrap_or_default(), name.local_name));
+                text.clear();
+                if is_header_timestamp(&path) {
+                    times = Some((None, None));
+                }
+                // the header comes before the body
+                if path.len() == 2 && path[1].1 == "Body" {
+                    break;
+                }
+            }
+            XmlEvent::Characters(characters) => text.push_str(&characters),
+            XmlEvent::EndElement { .. } => {
+                if is_header_timestamp(&path) {
+                    break;
+                }
+                if let (Some((created, expires)), true) =
+                    (times.as_mut(), path.len() == 5 && is_header_timestamp(&path[..4]))
+                {
+                    match (path[4].0.as_str(), path[4].1.as_str()) {
+                        (WSU_NAMESPACE, "Created") => *created = Some(text.trim().to_string()),
+                        (WSU_NAMESPACE, "Expires") => *expires = Some(text.trim().to_string()),
+                        _ => {}
+                    }
+                }
+                path.pop();
+            }
+            _ => {}
+        }
+    }
+    Ok(times)
+}
+
+/// Whether a path is that of Envelope/Header/wsse:Security/wsu:Timestamp
+fn is_header_timestamp(path: &[(String, String)]) -> bool {
+    path.len() == 4
+        && path[0].1 == "Envelope"
+        && path[1].1 == "Header"
+        && path[2] == (WSSE_NAMESPACE.to_string(), "Security".to_string())
+        && path[3] == (WSU_NAMESPACE.to_string(), "Timestamp".to_string())
+}
+"##,
+        );
+        module.add(tokens);
+
+        if self.ws_signature {
+            module.add(Self::security_signature());
+        }
+
+        self.root.add(module);
+    }
+
+    fn print_ws_addressing(&mut self) {
+        if self.have_seen_type(ADDRESSING_MOD, &self.root) {
+            return;
+        }
+
+        // the headers go with the others in the header of the envelopes
+        if let Some(header) = self.root.child("Header") {
+            header.borrow_mut().add(Self::addressing_field());
+        }
+
+        let mut module = Element::new_module(ADDRESSING_MOD);
+        let mut e = Element::new("Addressing", ElementType::Static);
+        e.set_content(
+            r##"const WSA_NAMESPACE: &str = "http://www.w3.org/2005/08/addressing";
+
+/// The WS-Addressing headers of the requests. The destination, the action and the id of a
+/// message that are not set are the URL of the binding, the SOAPAction of the operation and a
+/// new UUID for every request.
+#[derive(Debug, Default, Clone)]
+pub struct Addressing {
+    to: Option<String>,
+    action: Option<String>,
+    message_id: Option<String>,
+    reply_to: Option<String>,
+    relates_to: Option<String>,
+}
+
+impl Addressing {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn with_to(mut self, to: &str) -> Self {
+        self.to = Option::Some(to.to_string());
+        self
+    }
+
+    pub fn with_action(mut self, action: &str) -> Self {
+        self.action = Option::Some(action.to_string());
+        self
+    }
+
+    /// The id of the message, instead of a new UUID for every request
+    pub fn with_message_id(mut self, message_id: &str) -> Self {
+        self.message_id = Option::Some(message_id.to_string());
+        self
+    }
+
+    /// The address that the replies go to
+    pub fn with_reply_to(mut self, address: &str) -> Self {
+        self.reply_to = Option::Some(address.to_string());
+        self
+    }
+
+    /// The id of the message that the requests reply to
+    pub fn with_relates_to(mut self, message_id: &str) -> Self {
+        self.relates_to = Option::Some(message_id.to_string());
+        self
+    }
+
+    /// A new id of a message, as a URN of a random UUID
+    pub fn new_message_id() -> String {
+        format!("urn:uuid:{}", uuid::Uuid::new_v4())
+    }
+
+    /// The headers of a request to a URL, for the action of an operation
+    pub fn headers(&self, url: &str, action: &str) -> AddressingHeaders {
+        let message_id = self
+            .message_id
+            .clone()
+            .unwrap_or_else(Self::new_message_id);
+        AddressingHeaders {
+            to: Option::Some(AttributedUri::new(self.to.as_deref().unwrap_or(url))),
+            action: Option::Some(AttributedUri::new(
+                self.action.as_deref().unwrap_or(action),
+            )),
+            message_id: Option::Some(AttributedUri::new(&message_id)),
+            reply_to: self.reply_to.as_ref().map(|address| EndpointReference {
+                address: AttributedUri::new(address),
+            }),
+            relates_to: self.relates_to.as_deref().map(AttributedUri::new),
+        }
+    }
+}
+
+impl AttributedUri {
+    pub fn new(value: &str) -> Self {
+        AttributedUri {
+            value: value.to_string(),
+        }
+    }
+}
+
+// the headers are read from the header of the envelope, whose other headers are skipped
+impl YaDeserialize for AddressingHeaders {
+    fn deserialize<R: std::io::Read>(
+        reader: &mut yaserde::de::Deserializer<R>,
+    ) -> Result<Self, String> {
+        use yaserde::xml::reader::XmlEvent;
+        let mut headers = AddressingHeaders::default();
+        loop {
+            let name = match reader.peek()? {
+                XmlEvent::EndDocument => return Ok(headers),
+                XmlEvent::StartElement { name, .. }
+                    if name.namespace.as_deref() == Some(WSA_NAMESPACE) =>
+                {
+                    name.local_name.clone()
+                }
+                _ => {
+                    reader.next_event()?;
+                    continue;
+                }
+            };
+            match name.as_str() {
+                "To" => headers.to = Option::Some(AttributedUri::deserialize(reader)?),
+                "Action" => headers.action = Option::Some(AttributedUri::deserialize(reader)?),
+                "MessageID" => {
+                    headers.message_id = Option::Some(AttributedUri::deserialize(reader)?)
+                }
+                "ReplyTo" => {
+                    headers.reply_to = Option::Some(EndpointReference::deserialize(reader)?)
+                }
+                "RelatesTo" => {
+                    headers.relates_to = Option::Some(AttributedUri::deserialize(reader)?)
+                }
+                _ => {
+                    reader.next_event()?;
+                }
+            }
+        }
+    }
+}
+"##,
+        );
+        module.add(e);
+        for header in Self::addressing_header_types() {
+            module.add(header);
+        }
+        self.root.add(module);
+    }
+
+    /// The types of the WS-Addressing headers: a URI, the address of an endpoint and the headers
+    /// of an envelope, which are read by hand from among the other headers
+    fn addressing_header_types() -> Vec<Element> {
+        let wsa_element = |name: &str, documentation: &str| {
+            let mut e = Element::new(name, ElementType::Struct);
+            e.add_ns("wsa", WSA_NS);
+            e.prefix = Option::Some("wsa".to_string());
+            e.documentation = Option::Some(documentation.to_string());
+            e.derives = vec!["PartialEq".to_string()];
+            e
+        };
+        let wsa_field = |name: &str, xml_name: &str, field_type: &str| {
+            let mut field = Element::new_field(name, xml_name, field_type, true);
+            field.prefix = Option::Some("wsa".to_string());
+            field
+        };
+
+        let mut uri = wsa_element("AttributedUri", "A header of which the value is a URI");
+        let mut value = Element::new_field("value", "value", "String", false);
+        value.xml_name = Option::None;
+        value.text_field = true;
+        uri.add(value);
+
+        let mut endpoint = wsa_element(
+            "EndpointReference",
+            "The address of an endpoint, that the replies go to",
+        );
+        let mut address = wsa_field("address", "Address", "AttributedUri");
+        address.optional = false;
+        endpoint.add(address);
+
+        let mut headers = wsa_element(
+            "AddressingHeaders",
+            "The WS-Addressing headers of an envelope, which are among the other headers of its\nsoapenv:Header",
+        );
+        headers.namespaces.clear();
+        headers.prefix = Option::None;
+        headers.custom_deserialize = true;
+        headers.add(wsa_field("to", "To", "AttributedUri"));
+        headers.add(wsa_field("action", "Action", "AttributedUri"));
+        headers.add(wsa_field("message_id", "MessageID", "AttributedUri"));
+        headers.add(wsa_field("reply_to", "ReplyTo", "EndpointReference"));
+        headers.add(wsa_field("relates_to", "RelatesTo", "AttributedUri"));
+
+        vec![uri, endpoint, headers]
+    }
+
+    /// The field of the header of the envelopes with the WS-Addressing headers, which are
+    /// written among the other headers
+    fn addressing_field() -> Element {
+        let mut field = Element::new_field(
+            "addressing",
+            "addressing",
+            &format!("{}::AddressingHeaders", ADDRESSING_MOD),
+            true,
+        );
+        field.xml_name = Option::None;
+        field.flatten = true;
+        field
+    }
+
+    /// The functions that the modules of the WS-* headers share to add their headers to envelopes
+    fn print_envelope_helpers(&mut self) {
+        if self.have_seen_type(ENVELOPE_HELPERS, &self.root) {
+            return;
+        }
+        let mut e = Element::new(ENVELOPE_HELPERS, ElementType::Static);
+        e.set_content(
+            r#"/// Adds a header to the soapenv:Header of an envelope, which gets one if it has none
+fn add_header(envelope: String, header: &str) -> String {
+    if let Some(start) = envelope.find("<soapenv:Header") {
+        if let Some(end) = envelope[start..].find('>').map(|end| start + end) {
+            let tag = &envelope[..end];
+            return match tag.strip_suffix('/') {
+                Some(tag) => format!(
+                    "{}>{}</soapenv:Header>{}",
+                    tag.trim_end(),
+                    header,
+                    &envelope[end + 1..]
+                ),
+                None => format!("{}>{}{}", tag, header, &envelope[end + 1..]),
+            };
+        }
+    }
+    match envelope.find("<soapenv:Body") {
+        Some(body) => format!(
+            "{}<soapenv:Header>{}</soapenv:Header>{}",
+            &envelope[..body],
+            header,
+            &envelope[body..]
+        ),
+        None => envelope,
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+"#,
+        );
+        self.root.add(e);
+    }
+
+    /// The X.509 signature of the requests, with the exclusive canonicalization of the elements
+    /// it signs
+    fn security_signature() -> Element {
+        let mut e = Element::new("Signature", ElementType::Static);
+        e.set_content(
+            r###"const DSIG_NAMESPACE: &str = "http://www.w3.org/2000/09/xmldsig#";
+const EXC_C14N: &str = "http://www.w3.org/2001/10/xml-exc-c14n#";
+const X509_TOKEN: &str =
+    "http://docs.oasis-open.org/wss/2004/01/oasis-200401-wss-x509-token-profile-1.0#X509v3";
+/// The ids of the elements that are signed, when an envelope has them
+const SIGNED_IDS: [&str; 2] = ["Body", "Timestamp"];
+const TOKEN_ID: &str = "X509Token";
+
+/// An X.509 signature of the body and the timestamp of the requests, by the private key of a
+/// certificate that is sent along as a BinarySecurityToken
+#[derive(Debug, Clone)]
+pub struct Signature {
+    private_key: rsa::RsaPrivateKey,
+    certificate: Vec<u8>,
+}
+
+impl Signature {
+    /// A signature by an RSA key, with the DER encoded certificate of its public key
+    pub fn new(private_key: rsa::RsaPrivateKey, certificate: Vec<u8>) -> Self {
+        Signature {
+            private_key,
+            certificate,
+        }
+    }
+
+    /// A signature by a PEM encoded RSA key, in PKCS#8 or PKCS#1, with its PEM encoded
+    /// certificate
+    pub fn from_pem(private_key: &str, certificate: &str) -> Result<Self, String> {
+        use rsa::pkcs1::DecodeRsaPrivateKey;
+        use rsa::pkcs8::DecodePrivateKey;
+        let private_key = rsa::RsaPrivateKey::from_pkcs8_pem(private_key)
+            .or_else(|_| rsa::RsaPrivateKey::from_pkcs1_pem(private_key))
+            .map_err(|e| format!("invalid private key: {}", e))?;
+        let certificate: String = certificate
+            .lines()
+            .filter(|line| !line.starts_with("-----"))
+            .flat_map(|line| line.split_whitespace())
+            .collect();
+        let certificate =
+            base64::decode(certificate).map_err(|e| format!("invalid certificate: {}", e))?;
+        Ok(Signature::new(private_key, certificate))
+    }
+
+    fn token_xml(&self) -> String {
+        format!(
+            r#"<wsse:BinarySecurityToken EncodingType="{}" ValueType="{}" wsu:Id="{}">{}</wsse:BinarySecurityToken>"#,
+            BASE64_BINARY,
+            X509_TOKEN,
+            TOKEN_ID,
+            base64::encode(&self.certificate)
+        )
+    }
+
+    /// Signs the elements of an envelope that are signed, and adds the signature to its
+    /// security header
+    pub fn sign(&self, envelope: String) -> Result<String, String> {
+        use sha2::Digest;
+        let mut references = String::new();
+        for id in SIGNED_IDS {
+            let element = match canonicalize(&envelope, id)? {
+                Some(element) => element,
+                None => continue,
+            };
+            references.push_str(&format!(
+                r##"<ds:Reference URI="#{}"><ds:Transforms><ds:Transform Algorithm="{}"></ds:Transform></ds:Transforms><ds:DigestMethod Algorithm="http://www.w3.org/2001/04/xmlenc#sha256"></ds:DigestMethod><ds:DigestValue>{}</ds:DigestValue></ds:Reference>"##,
+                id,
+                EXC_C14N,
+                base64::encode(sha2::Sha256::digest(element.as_bytes()))
+            ));
+        }
+        // the signed info is written in its canonical form, which is what is signed
+        let signed_info = format!(
+            r#"<ds:SignedInfo xmlns:ds="{}"><ds:CanonicalizationMethod Algorithm="{}"></ds:CanonicalizationMethod><ds:SignatureMethod Algorithm="http://www.w3.org/2001/04/xmldsig-more#rsa-sha256"></ds:SignatureMethod>{}</ds:SignedInfo>"#,
+            DSIG_NAMESPACE, EXC_C14N, references
+        );
+        let signature_value = self
+            .private_key
+            .sign(
+                rsa::Pkcs1v15Sign::new::<sha2::Sha256>(),
+                &sha2::Sha256::digest(signed_info.as_bytes()),
+            )
+            .map_err(|e| format!("can not sign the envelope: {}", e))?;
+        let signature = format!(
+            r##"<ds:Signature xmlns:ds="{}">{}<ds:SignatureValue>{}</ds:SignatureValue><ds:KeyInfo><wsse:SecurityTokenReference><wsse:Reference URI="#{}" ValueType="{}"/></wsse:SecurityTokenReference></ds:KeyInfo></ds:Signature>"##,
+            DSIG_NAMESPACE,
+            signed_info,
+            base64::encode(signature_value),
+            TOKEN_ID,
+            X509_TOKEN
+        );
+        match envelope.find("</wsse:Security>") {
+            Some(end) => Ok(format!("{}{}{}", &envelope[..end], signature, &envelope[end..])),
+            None => Err("no security header for the signature".to_string()),
+        }
+    }
+}
+
+/// Gives the soapenv:Body of an envelope the id by which it is signed
+fn with_body_id(envelope: String) -> String {
+    match envelope.find("<soapenv:Body") {
+        Some(start) => {
+            let end = start + "<soapenv:Body".len();
+            format!(
+                r#"{} xmlns:wsu="{}" wsu:Id="{}"{}"#,
+                &envelope[..end],
+                WSU_NAMESPACE,
+                SIGNED_IDS[0],
+                &envelope[end..]
+            )
+        }
+        None => envelope,
+    }
+}
+
+/// The exclusive XML canonicalization, without comments, of the element of a document with an
+/// id, if there is one
+pub fn canonicalize(xml: &str, id: &str) -> Result<Option<String>, String> {
+    use yaserde::xml::name::OwnedName;
+    use yaserde::xml::reader::{ParserConfig, XmlEvent};
+
+    fn qualified(name: &OwnedName) -> String {
+        match &name.prefix {
+            Some(prefix) => format!("{}:{}", prefix, name.local_name),
+            None => name.local_name.clone(),
+        }
+    }
+
+    let reader = ParserConfig::new()
+        .trim_whitespace(false)
+        .whitespace_to_characters(true)
+        .cdata_to_characters(true)
+        .ignore_comments(true)
+        .coalesce_characters(true)
+        .create_reader(xml.as_bytes());
+    let mut output = String::new();
+    // the namespaces declared in the output by every open element, innermost last
+    let mut rendered: Vec<Vec<(String, String)>> = Vec::new();
+    for event in reader {
+        match event.map_err(|e| e.to_string())? {
+            XmlEvent::StartElement {
+                name,
+                attributes,
+                namespace,
+            } => {
+                let is_apex = attributes
+                    .iter()
+                    .any(|a| a.name.local_name == "Id" && a.value == id);
+                if rendered.is_empty() && !is_apex {
+                    continue;
+                }
+                // the namespaces of the prefixes the element and its attributes use, that are
+                // not in the output yet
+                let prefixes = std::iter::once(name.prefix.clone().unwrap_or_default())
+                    .chain(attributes.iter().filter_map(|a| a.name.prefix.clone()));
+                let mut declarations: Vec<(String, String)> = Vec::new();
+                for prefix in prefixes {
+                    if prefix == "xml" || declarations.iter().any(|(p, _)| *p == prefix) {
+                        continue;
+                    }
+                    let uri = namespace.get(&prefix).unwrap_or_default();
+                    let in_output = rendered
+                        .iter()
+                        .rev()
+                        .flatten()
+                        .find(|(p, _)| *p == prefix)
+                        .map(|(_, uri)| uri.as_str())
+                        .unwrap_or_default();
+                    if uri != in_output {
+                        declarations.push((prefix, uri.to_string()));
+                    }
+                }
+                declarations.sort();
+                let mut attributes: Vec<(String, &str, String, &str)> = attributes
+                    .iter()
+                    .map(|a| {
+                        (
+                            a.name.namespace.clone().unwrap_or_default(),
+                            a.name.local_name.as_str(),
+                            qualified(&a.name),
+                            a.value.as_str(),
+                        )
+                    })
+                    .collect();
+                attributes.sort();
+
+                output.push('<');
+                output.push_str(&qualified(&name));
+                for (prefix, uri) in &declarations {
+                    let name = match prefix.as_str() {
+                        "" => "xmlns".to_string(),
+                        prefix => format!("xmlns:{}", prefix),
+                    };
+                    output.push_str(&format!(r#" {}="{}""#, name, escape_attribute(uri)));
+                }
+                for (_, _, name, value) in attributes {
+                    output.push_str(&format!(r#" {}="{}""#, name, escape_attribute(value)));
+                }
+                output.push('>');
+                rendered.push(declarations);
+            }
+            XmlEvent::EndElement { name } if !rendered.is_empty() => {
+                output.push_str(&format!("</{}>", qualified(&name)));
+                rendered.pop();
+                if rendered.is_empty() {
+                    return Ok(Some(output));
+                }
+            }
+            XmlEvent::Characters(text) if !rendered.is_empty() => {
+                output.push_str(
+                    &text
+                        .replace('&', "&amp;")
+                        .replace('<', "&lt;")
+                        .replace('>', "&gt;")
+                        .replace('\r', "&#xD;"),
+                );
+            }
+            _ => {}
+        }
+    }
+    Ok(None)
+}
+
+fn escape_attribute(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('"', "&quot;")
+        .replace('\t', "&#x9;")
+        .replace('\n', "&#xA;")
+        .replace('\r', "&#xD;")
+}
+"###,
+        );
+        e
+    }
+
+    /// The wsse:Security header of the bindings, with the tokens that are generated
+    fn security_header(&self) -> Element {
+        let signing = self.ws_signature;
+        let mut e = Element::new("Security", ElementType::Static);
+        e.set_content(&format!(
+            r##"/// The wsse:Security header of the requests of a binding. It is written anew for every request,
+/// so the nonces and timestamps in it are fresh.
+#[derive(Debug, Clone, Default)]
+pub struct Security {{
+    pub timestamp: Option<Timestamp>,
+    pub username_token: Option<UsernameToken>,{0}
+}}
+
+impl Security {{
+    /// Whether there is nothing to send in the header
+    pub fn is_empty(&self) -> bool {{
+        self.timestamp.is_none() && self.username_token.is_none(){1}
+    }}
+
+    pub fn to_xml(&self) -> String {{
+        let mut xml = format!(
+            r#"<wsse:Security xmlns:wsse="{{}}" xmlns:wsu="{{}}" soapenv:mustUnderstand="1">"#,
+            WSSE_NAMESPACE, WSU_NAMESPACE
+        );{2}
+        if let Some(timestamp) = &self.timestamp {{
+            xml.push_str(&timestamp.to_xml(std::time::SystemTime::now()));
+        }}
+        if let Some(token) = &self.username_token {{
+            xml.push_str(&token.to_xml());
+        }}
+        xml.push_str("</wsse:Security>");
+        xml
+    }}
+
+    /// Adds the header to the soapenv:Header of an envelope, which gets one if it has none{3}
+    pub fn add_to(&self, envelope: String) -> Result<String, String> {{
+        if self.is_empty() {{
+            return Ok(envelope);
+        }}
+{4}    }}
+
+    /// Checks the security header of a response, which is only there to check when the requests
+    /// have a timestamp
+    pub fn check_response(&self, envelope: &str) -> Result<(), String> {{
+        match &self.timestamp {{
+            Some(timestamp) => timestamp.check(envelope, std::time::SystemTime::now()),
+            None => Ok(()),
+        }}
+    }}
+}}
+"##,
+            if signing {
+                "\n    pub signature: Option<Signature>,"
+            } else {
+                ""
+            },
+            if signing {
+                " && self.signature.is_none()"
+            } else {
+                ""
+            },
+            if signing {
+                r#"
+        if let Some(signature) = &self.signature {
+            xml.push_str(&signature.token_xml());
+        }"#
+            } else {
+                ""
+            },
+            if signing {
+                ".\n    /// With a signature, the body and the timestamp are signed."
+            } else {
+                ""
+            },
+            if signing {
+                r#"        match &self.signature {
+            Some(signature) => signature.sign(add_header(with_body_id(envelope), &self.to_xml())),
+            None => Ok(add_header(envelope, &self.to_xml())),
+        }
+"#
+            } else {
+                "        Ok(add_header(envelope, &self.to_xml()))\n"
+            }
+        ));
+        e
+    }
+
+    /// Generates the function that fills the placeholders in the location of an operation bound
+    /// with http:urlReplacement
+    fn print_url_replacement(&mut self) {
+        if self.have_seen_type("url_replacement", &self.root) {
+            return;
+        }
+
+        let mut e = Element::new("url_replacement", ElementType::Static);
+        e.set_content(
+            r#"/// Replaces the (part) placeholders of the location of an HTTP operation with the
+/// percent-encoded values of the parts
+fn url_replacement(location: &str, parts: &[(&str, String)]) -> String {
+    parts.iter().fold(location.to_string(), |location, (name, value)| {
+        let encoded: String = value
+            .bytes()
+            .map(|b| match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                    (b as char).to_string()
+                }
+                _ => format!("%{:02X}", b),
+            })
+            .collect();
+        location.replace(&format!("({})", name), &encoded)
+    })
+}
+"#,
+        );
+
+        self.root.add(e);
+    }
+
+    /// Generates what the dispatchers of all bindings share: the error of a request that can not
+    /// be dispatched, and finding the element in the body of a request
+    fn print_dispatch_helpers(&mut self) {
+        if self.have_seen_type("DispatchError", &self.root) {
+            return;
+        }
+
+        let mut e = Element::new("DispatchError", ElementType::Static);
+        e.set_content(
+            r#"/// Why a SOAP request could not be handed to a service
+#[derive(Debug)]
+pub enum DispatchError {
+    /// No operation has the SOAPAction or the body element of the request, if it has either
+    UnknownOperation(Option<String>),
+    XmlError(std::string::String),
+    /// The service failed with something other than a fault
+    ServiceError(std::string::String),
+}
+
+/// The local name of the first element in the body of a SOAP envelope
+fn body_element(envelope: &str) -> Option<String> {
+    let mut in_body = false;
+    for event in yaserde::xml::reader::EventReader::from_str(envelope) {
+        if let yaserde::xml::reader::XmlEvent::StartElement { name, .. } = event.ok()? {
+            if in_body {
+                return Option::Some(name.local_name);
+            }
+            in_body = name.local_name == "Body";
+        }
+    }
+    Option::None
+}
+"#,
+        );
+
+        self.root.add(e);
+    }
+
+    /// Generates the server side of a SOAP binding: a dispatcher that hands incoming requests to
+    /// an implementation of the port type, given the branches of the operations
+    fn print_dispatcher(
+        &mut self,
+        struct_name: &str,
+        trait_name: &str,
+        operations: &str,
+        parent: &mut Element,
+    ) {
+        self.print_dispatch_helpers();
+
+        let mut e = Element::new(&format!("{}Dispatcher", struct_name), ElementType::Static);
+        e.set_content(&format!(
+            r#"/// Serves the operations of the {0} binding with an implementation of {2}::{1}
+pub struct {0}Dispatcher<T> {{
+    service: T,
+}}
+
+impl<T: {2}::{1} + Send + Sync> {0}Dispatcher<T> {{
+    pub fn new(service: T) -> Self {{
+        {0}Dispatcher {{ service }}
+    }}
+
+    /// Calls the operation named by the SOAPAction of a request, or else by the element in its
+    /// body, and answers with the HTTP status and the response envelope
+    pub async fn dispatch(&self, action: Option<&str>, envelope: &str) -> Result<(reqwest::StatusCode, String), DispatchError> {{
+        let element = body_element(envelope);
+        let action = action.map(|a| a.trim_matches('"')).filter(|a| !a.is_empty());
+{3}        Err(DispatchError::UnknownOperation(action.map(str::to_string).or(element)))
+    }}
+}}
+"#,
+            struct_name,
+            trait_name,
+            self.module_name(PORTS_MOD),
+            operations,
+        ));
+
+        parent.add(e);
+    }
+
+    fn print_default_constructor(
+        &mut self,
+        struct_name: &str,
+        version: Option<SoapVersion>,
+        headers: EnvelopeHeaders,
+        parent: &mut Element,
+    ) {
+        let url = match self.target_namespace() {
+            None => "String::new()".to_string(),
+            Some(tns) => tns.to_string(),
+        };
+
+        let mut e = Element::new(struct_name, ElementType::Static);
+        e.set_content(
+            format!(
+                r#"impl Default for {0} {{
+                fn default() -> Self {{
+                    {0} {{
+                        {2}
+                        url: "{1}".to_string(),{3}
+                     }}
+                }}
+            }}
+            "#,
+                struct_name,
+                url,
+                self.client_fields(version, "Option::None").1,
+                headers.initial_values()
+            )
+            .as_str(),
+        );
+
+        parent.add(e);
+    }
+
+    fn print_constructor(
+        &mut self,
+        struct_name: &str,
+        version: Option<SoapVersion>,
+        headers: EnvelopeHeaders,
+        parent: &mut Element,
+    ) {
+        let transport_setter = if version.is_some() {
+            format!(
+                r#"
+                /// Sends the requests with another transport, like one sharing its client
+                pub fn with_transport(mut self, transport: {0}::HttpTransport) -> Self {{
+                    self.transport = transport;
+                    self
+                }}
+"#,
+                TRANSPORT_MOD
+            )
+        } else {
+            String::new()
+        };
+        let mut e = Element::new(struct_name, ElementType::Static);
+        e.set_content(
+            format!(
+                r#"impl {0} {{
+                pub fn new(url: &str, credentials: Option<(String,String)>) -> Self {{
+                    {0} {{
+                        {1}
+                        url: url.to_string(),{2}
+                    }}
+                }}
+{3}{4}
+        }}
+        "#,
+                struct_name,
+                self.client_fields(version, "credentials").1,
+                headers.initial_values(),
+                transport_setter,
+                headers.setters()
+            )
+            .as_str(),
+        );
+
+        parent.add(e);
+    }
+
+    /// The name of an input, output or fault of an operation, after its message unless it is
+    /// given one, along with the Rust name of the message
+    fn map_name_message(&self, name: Option<&str>, message: &QName) -> (String, Option<String>) {
+        let msg = self.qname_type(message);
+        let name = match name {
+            None => to_pascal_case(msg.as_str()),
+            Some(name) => name.to_string(),
+        };
+
+        (name, Option::Some(msg))
+    }
+
+    /// Names the alias in the ports of a message of an operation. An alias of the same name for
+    /// another message, of another port type, keeps the name, this one is prefixed with the name of
+    /// its port type.
+    fn port_alias(
+        &self,
+        port_type_name: &str,
+        message: (String, Option<String>),
+        module: &Element,
+    ) -> (String, Option<String>) {
+        let (name, msg) = message;
+        let name = to_pascal_case(&name);
+
+        let taken = match (&msg, module.child(&name)) {
+            (Some(msg), Some(alias)) => {
+                alias.borrow().field_type
+                    != Option::Some(format!("{}::{}", self.module_name(MESSAGES_MOD), msg))
+            }
+            _ => false,
+        };
+
+        if taken {
+            (format!("{}{}", port_type_name, name), msg)
+        } else {
+            (name, msg)
+        }
+    }
+
+    fn print_operation(
+        &mut self,
+        port_type_name: &str,
+        operation: &model::Operation,
+        definitions: &Definitions,
+        parent: &mut Element,
+        module: &mut Element,
+    ) {
+        let element_name = operation.name.as_str();
+        if !self.is_operation_selected(element_name) {
+            return;
+        }
+
+        let func_name = self.shield_reserved_names(&to_snake_case(element_name));
+
+        let some_documentation = operation.documentation.clone();
+
+        let alias = |m: &model::OperationMessage| {
+            self.port_alias(
+                port_type_name,
+                self.map_name_message(m.name.as_deref(), &m.message),
+                module,
+            )
+        };
+        let some_input = operation.input.as_ref().map(alias);
+        let some_output = operation.output.as_ref().map(alias);
+
+        let faults = operation
+            .faults
+            .iter()
+            .map(|f| {
+                let (_, msg) = self.map_name_message(None, &f.message);
+                self.port_alias(port_type_name, (f.name.clone(), msg), module)
+            })
+            .collect();
+
+        let mut port_type = PortType {
+            name: format!("{}::{}", port_type_name, element_name),
+            input_type: some_input,
+            output_type: some_output,
+            fault_types: faults,
+            fault_error: None,
+        };
+
+        let input_type_element = match &port_type.input_type {
+            Some((type_name, Some(message_type_name))) => {
+                let mut e = Element::new(to_pascal_case(type_name).as_str(), ElementType::Alias);
+                e.field_type = Option::Some(format!(
+                    "{0}::{1}",
+                    self.module_name(MESSAGES_MOD),
+                    message_type_name,
+                ));
+
+                Option::Some(e)
+            }
+            _ => None,
+        };
+
+        let mut function_element = match &port_type.input_type {
+            Some((name, Some(_msg))) => Element::new_function(
+                &func_name,
+                &self.shield_reserved_names(&to_snake_case(name.as_str())),
+                to_pascal_case(name.as_str()).as_str(),
+            ),
+            _ => return,
+        };
+
+        let (output_type_element, fault_type_element) = match &port_type.output_type {
+            Some((type_name, Some(msg))) => {
+                let mut e = Element::new(to_pascal_case(type_name).as_str(), ElementType::Alias);
+                e.field_type = Option::Some(format!("{}::{}", self.module_name(MESSAGES_MOD), msg));
+
+                if port_type.fault_types.len() > 1 {
+                    let fault_error = self.print_operation_error(
+                        port_type_name,
+                        operation,
+                        definitions,
+                        &port_type,
+                        module,
+                    );
+
+                    if let Some(mut args) = function_element.function_args.take() {
+                        args.output_type = Option::Some(to_pascal_case(type_name));
+                        args.fault_type = Option::Some(fault_error.0.clone());
+                        function_element.function_args.replace(args);
+                    }
+                    port_type.fault_error = Option::Some(fault_error);
+
+                    (Option::Some(e), Option::None)
+                } else if let Some((fault_name, Some(fault_type))) = port_type.fault_types.first() {
+                    let mut f = Element::new(fault_name.as_str(), ElementType::Alias);
+                    f.field_type = Option::Some(format!(
+                        "{}::{}",
+                        self.module_name(MESSAGES_MOD),
+                        fault_type,
+                    ));
+
+                    if !self.have_seen_type(fault_name, module) {
+                        self.fault_soap_wrapper(fault_name, fault_type, module);
+                    }
+                    self.fault_message_error(fault_type, module);
+
+                    if let Some(mut args) = function_element.function_args.take() {
+                        args.output_type = Option::Some(to_pascal_case(type_name));
+                        args.fault_type =
+                            Option::Some(format!("Option<Soap{}>", to_pascal_case(fault_name)));
+                        function_element.function_args.replace(args);
+                    }
+
+                    (Option::Some(e), Option::Some(f))
+                } else {
+                    if let Some(mut args) = function_element.function_args.take() {
+                        args.output_type = Option::Some(to_pascal_case(type_name));
+                        args.fault_type = Option::Some("Option<SoapFault>".to_string());
+                        function_element.function_args.replace(args);
+                    }
+
+                    (Option::Some(e), Option::None)
+                }
+            }
+            _ => {
+                // one-way operations only learn whether the request went through
+                if let Some(mut args) = function_element.function_args.take() {
+                    args.output_type = Option::Some("()".to_string());
+                    args.fault_type = Option::Some("Option<SoapFault>".to_string());
+                    function_element.function_args.replace(args);
+                }
+
+                (Option::None, Option::None)
+            }
+        };
+
+        // a MIME binding of the operation sends and receives attachments along with the messages
+        if let Some(binding_operation) = definitions.binding_operation(element_name) {
+            if let Some(mut args) = function_element.function_args.take() {
+                args.attachments = binding_operation
+                    .input
+                    .attachments
+                    .iter()
+                    .map(|part| self.shield_reserved_names(&to_snake_case(part)))
+                    .collect();
+                args.output_attachments =
+                    binding_operation.output.multipart && args.output_type.is_some();
+                function_element.function_args.replace(args);
+            }
+        }
+
+        if let Some(input_type_element) = input_type_element {
+            if !self.have_seen_type(&input_type_element.name, module) {
+                module.add(input_type_element);
+            }
+        }
+
+        if let Some(output_type_element) = output_type_element {
+            if !self.have_seen_type(&output_type_element.name, module) {
+                module.add(output_type_element);
+            }
+        }
+
+        if let Some(fault_type_element) = fault_type_element {
+            if !self.have_seen_type(&fault_type_element.name, module) {
+                module.add(fault_type_element);
+            }
+        }
+
+        function_element.documentation = some_documentation;
+
+        parent.add(function_element);
+        self.port_types.insert(port_type.name.clone(), port_type);
+    }
+
+    /// Generates the error of an operation that declares several faults: a variant per fault,
+    /// told by the element in the detail of the SOAP fault, and one for any other fault. Returns
+    /// the name of the error, along with the SOAP fault that carries the faults.
+    fn print_operation_error(
+        &mut self,
+        port_type_name: &str,
+        operation: &model::Operation,
+        definitions: &Definitions,
+        port_type: &PortType,
+        module: &mut Element,
+    ) -> (String, String) {
+        let operation_name = operation.name.as_str();
+        // an operation of the same name in another port type has an error of its own
+        let scope =
+            if self.have_seen_type(&format!("{}Error", to_pascal_case(operation_name)), module) {
+                port_type_name
+            } else {
+                ""
+            };
+        let error_name = format!("{}{}Error", scope, to_pascal_case(operation_name));
+        let faults_name = format!("Soap{}{}Faults", scope, to_pascal_case(operation_name));
+        let detail_name = format!("{}{}FaultDetail", scope, to_pascal_case(operation_name));
+
+        // the fault elements of the port type, next to the messages they name
+        let faults: Vec<(&String, &String, Option<&str>)> = operation
+            .faults
+            .iter()
+            .zip(port_type.fault_types.iter())
+            .filter_map(|(fault, (name, msg))| {
+                let element = definitions
+                    .message(&fault.message)
+                    .and_then(|message| message.parts.first())
+                    .and_then(|part| part.element.as_ref())
+                    .map(|element| element.name.as_str());
+                msg.as_ref().map(|msg| (name, msg, element))
+            })
+            .collect();
+
+        let mut wrapper = Element::new(&faults_name, ElementType::Struct);
+        wrapper.xml_name = Option::Some("Fault".to_string());
+        wrapper.add_ns("soapenv", "http://schemas.xmlsoap.org/soap/envelope/");
+        wrapper.prefix = Option::Some("soapenv".to_string());
+        wrapper.add(Element::new_field(
+            "fault_code",
+            "faultcode",
+            "String",
+            true,
+        ));
+        wrapper.add(Element::new_field(
+            "fault_string",
+            "faultstring",
+            "String",
+            true,
+        ));
+        wrapper.add(Element::new_field(
+            "fault_actor",
+            "faultactor",
+            "String",
+            true,
+        ));
+        wrapper.add(Element::new_field("detail", "detail", &detail_name, true));
+
+        let mut detail = Element::new(&detail_name, ElementType::Struct);
+        detail.xml_name = Option::Some("detail".to_string());
+
+        let mut variants = String::new();
+        let mut conversions = String::new();
+        let mut details = String::new();
+        let mut displays = String::new();
+        let mut from_faults = String::new();
+        for (fault_name, fault_type, element) in faults.iter() {
+            let variant = to_pascal_case(fault_name);
+            let field = self.shield_reserved_names(&to_snake_case(fault_name));
+            let element_name = element.unwrap_or(fault_type);
+            detail.add(Element::new_field(&field, element_name, &variant, true));
+
+            let mut alias = Element::new(&variant, ElementType::Alias);
+            alias.field_type = Option::Some(format!(
+                "{}::{}",
+                self.module_name(MESSAGES_MOD),
+                fault_type
+            ));
+            if !self.have_seen_type(&alias.name, module) {
+                module.add(alias);
+            }
+
+            variants.push_str(&format!("\t{0}({0}),\n", variant));
+            conversions.push_str(&format!(
+                "\t\tif let Some(fault) = detail.{} {{\n\t\t\treturn {}::{}(fault);\n\t\t}}\n",
+                field, error_name, variant
+            ));
+            details.push_str(&format!(
+                "\t\t\t{}::{}(fault) => detail.{} = Option::Some(fault),\n",
+                error_name, variant, field
+            ));
+            displays.push_str(&format!(
+                "\t\t\t{}::{}(fault) => write!(f, \"{{}}\", fault),\n",
+                error_name, variant
+            ));
+
+            // a message can only be converted when it stands for one of the faults
+            if faults.iter().filter(|(_, t, _)| t == fault_type).count() == 1 {
+                from_faults.push_str(&format!(
+                    "\nimpl From<{0}> for {1} {{\n\tfn from(fault: {0}) -> Self {{\n\t\t{1}::{0}(fault)\n\t}}\n}}\n",
+                    variant, error_name
+                ));
+            }
+        }
+
+        if self.have_seen_type(&error_name, module) {
+            return (error_name, faults_name);
+        }
+        module.add(wrapper);
+        module.add(Self::soap_fault_error(&faults_name));
+        module.add(detail);
+        for (_, fault_type, _) in faults.iter() {
+            self.fault_message_error(fault_type, module);
+        }
+
+        let mut error = Element::new(&error_name, ElementType::Static);
+        error.set_content(&format!(
+            r#"/// The faults of the {0} operation
+#[derive(Debug, Clone)]
+pub enum {1} {{
+{2}	/// A fault of none of the declared kinds
+	Other(SoapFault),
+}}
+
+impl From<{3}> for {1} {{
+	fn from(fault: {3}) -> Self {{
+		if let Some(detail) = fault.detail {{
+{4}		}}
+		{1}::Other(SoapFault {{
+			fault_code: fault.fault_code,
+			fault_string: fault.fault_string,
+			fault_actor: fault.fault_actor,
+			detail: Option::None,
+		}})
+	}}
+}}
+
+impl std::fmt::Display for {1} {{
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {{
+		match self {{
+{5}			{1}::Other(fault) => write!(f, "{{}}", fault),
+		}}
+	}}
+}}
+
+impl std::error::Error for {1} {{}}
+{6}"#,
+            operation_name, error_name, variants, faults_name, conversions, displays, from_faults
+        ));
+
+        // a service answers with the fault of the error
+        if self.server_dispatchers {
+            error.append_content(&format!(
+                r#"
+impl From<{0}> for {1} {{
+	fn from(error: {0}) -> Self {{
+		let mut detail = {2}::default();
+		match error {{
+{3}			{0}::Other(fault) => {{
+				return {1} {{
+					fault_code: fault.fault_code,
+					fault_string: fault.fault_string,
+					fault_actor: fault.fault_actor,
+					detail: Option::None,
+				}}
+			}}
+		}}
+		{1} {{
+			fault_code: Option::Some("soapenv:Server".to_string()),
+			fault_string: Option::None,
+			fault_actor: Option::None,
+			detail: Option::Some(detail),
+		}}
+	}}
+}}
+"#,
+                error_name, faults_name, detail_name, details
+            ));
+        }
+        module.add(error);
+
+        (error_name, faults_name)
+    }
+
+    fn fault_soap_wrapper(&self, fault_name: &str, fault_type: &str, parent: &mut Element) {
+        let soap_fault_name = format!("Soap{}", fault_name);
+
+        let mut e = Element::new(&soap_fault_name, ElementType::Struct);
+        e.xml_name = Option::Some("Fault".to_string());
+        e.add_ns("soapenv", "http://schemas.xmlsoap.org/soap/envelope/");
+        e.prefix = Option::Some("soapenv".to_string());
+
+        let fault_code = Element::new_field("fault_code", "faultcode", "String", true);
+        let fault_string = Element::new_field("fault_string", "faultstring", "String", true);
+        let fault_actor = Element::new_field("fault_actor", "faultactor", "String", true);
+        let detail = Element::new_field("detail", fault_type, fault_name, true);
+
+        e.add(fault_code);
+        e.add(fault_string);
+        e.add(fault_actor);
+        e.add(detail);
+
+        if !self.have_seen_type(&e.name, parent) {
+            parent.add(e);
+            parent.add(Self::soap_fault_error(&soap_fault_name));
+        }
+    }
+
+    /// The Display and Error implementations of a SOAP fault, which show its code and string
+    fn soap_fault_error(fault_name: &str) -> Element {
+        let mut e = Element::new(&format!("{}Display", fault_name), ElementType::Static);
+        e.set_content(&format!(
+            r#"
+impl std::fmt::Display for {0} {{
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {{
+		match (&self.fault_code, &self.fault_string) {{
+			(Some(code), Some(string)) => write!(f, "{{}}: {{}}", code, string),
+			(Some(text), None) | (None, Some(text)) => write!(f, "{{}}", text),
+			(None, None) => write!(f, "SOAP fault"),
+		}}
+	}}
+}}
+
+impl std::error::Error for {0} {{}}
+
+"#,
+            fault_name
+        ));
+        e
+    }
+
+    /// Generates the fault of the SOAP 1.2 envelopes, of which the code and the reason are
+    /// elements with the value and the text, where SOAP 1.1 has a faultcode and a faultstring
+    fn print_soap12_fault(&mut self) {
+        if self.have_seen_type("Soap12Fault", &self.root) {
+            return;
+        }
+
+        let mut code = Self::soap12_element("Soap12FaultCode", "Code");
+        code.add(Self::soap12_field("value", "Value", "String"));
+        let mut code_of = Element::new("Soap12FaultCodeOf", ElementType::Static);
+        code_of.set_content(
+            r#"
+impl Soap12FaultCode {
+	/// The code of a SOAP 1.1 fault, of which the Client and Server codes are the Sender and
+	/// Receiver codes of SOAP 1.2
+	pub fn of_soap11(code: String) -> Self {
+		let value = match code.split_once(':') {
+			Some((prefix, "Client")) => format!("{}:Sender", prefix),
+			Some((prefix, "Server")) => format!("{}:Receiver", prefix),
+			_ => code,
+		};
+		Soap12FaultCode {
+			value: Option::Some(value),
+		}
+	}
+}
+
+"#,
+        );
+
+        // the reason has a text in every language it is given in
+        let mut reason = Self::soap12_element("Soap12FaultReason", "Reason");
+        let mut text = Self::soap12_field("text", "Text", "String");
+        text.vector = true;
+        reason.add(text);
+
+        self.root.add(code);
+        self.root.add(code_of);
+        self.root.add(reason);
+        self.root
+            .add(Self::soap12_fault("Soap12Fault", Option::Some("XmlValue")));
+        self.root.add(self.soap12_fault_conversions(
+            "Soap12Fault",
+            "SoapFault",
+            Option::Some(("fault.detail", "fault.detail")),
+        ));
+    }
+
+    /// A struct of an element of the SOAP 1.2 envelope namespace
+    fn soap12_element(name: &str, xml_name: &str) -> Element {
+        let mut e = Element::new(name, ElementType::Struct);
+        e.xml_name = Option::Some(xml_name.to_string());
+        e.add_ns("soapenv", SoapVersion::Soap12.envelope_namespace());
+        e.prefix = Option::Some("soapenv".to_string());
+        e
+    }
+
+    /// An optional field of an element of the SOAP 1.2 envelope namespace
+    fn soap12_field(field_name: &str, xml_name: &str, field_type: &str) -> Element {
+        let mut field = Element::new_field(field_name, xml_name, field_type, true);
+        field.prefix = Option::Some("soapenv".to_string());
+        field
+    }
+
+    /// A SOAP 1.2 fault, with a detail of the type given, if any
+    fn soap12_fault(name: &str, detail_type: Option<&str>) -> Element {
+        let mut e = Self::soap12_element(name, "Fault");
+        e.add(Self::soap12_field("code", "Code", "Soap12FaultCode"));
+        e.add(Self::soap12_field("reason", "Reason", "Soap12FaultReason"));
+        e.add(Self::soap12_field("node", "Node", "String"));
+        e.add(Self::soap12_field("role", "Role", "String"));
+        if let Some(detail_type) = detail_type {
+            e.add(Self::soap12_field("detail", "Detail", detail_type));
+        }
+        e
+    }
+
+    /// Generates the SOAP 1.2 fault of an operation, which carries the detail of the SOAP fault of
+    /// the operation when it declares faults, a fault message or the faults of its error. Returns
+    /// the name of the fault.
+    fn print_soap12_operation_fault(
+        &self,
+        has_fault: bool,
+        fault_soap_name: &str,
+        single: Option<(&str, &str)>,
+        module: &mut Element,
+    ) -> String {
+        if !has_fault {
+            return "Soap12Fault".to_string();
+        }
+
+        let name = fault_soap_name.replacen("Soap", "Soap12", 1);
+        if self.have_seen_type(&name, module) {
+            return name;
+        }
+
+        let ports = self.module_name(PORTS_MOD);
+        let (detail_type, detail_in, detail_out) = match single {
+            // the detail holds the fault message
+            Some((fault_name, fault_type)) => {
+                let detail_name = format!("{}Detail", name);
+                let mut detail = Self::soap12_element(&detail_name, "Detail");
+                detail.add(Element::new_field(
+                    "fault",
+                    fault_type,
+                    &format!("{}::{}", ports, fault_name),
+                    true,
+                ));
+                module.add(detail);
+                (
+                    detail_name.clone(),
+                    "fault.detail.and_then(|detail| detail.fault)".to_string(),
+                    format!(
+                        "fault.detail.map(|fault| {} {{ fault: Option::Some(fault) }})",
+                        detail_name
+                    ),
+                )
+            }
+            // the detail of the faults of an error is read as it is
+            None => {
+                let detail_name = fault_soap_name
+                    .strip_prefix("Soap")
+                    .and_then(|name| name.strip_suffix("Faults"))
+                    .unwrap_or(fault_soap_name);
+                (
+                    format!("{}::{}FaultDetail", ports, detail_name),
+                    "fault.detail".to_string(),
+                    "fault.detail".to_string(),
+                )
+            }
+        };
+
+        module.add(Self::soap12_fault(&name, Option::Some(&detail_type)));
+        module.add(self.soap12_fault_conversions(
+            &name,
+            &format!("{}::{}", ports, fault_soap_name),
+            Option::Some((&detail_in, &detail_out)),
+        ));
+        name
+    }
+
+    /// The conversion of a SOAP 1.2 fault to the SOAP 1.1 fault the operations answer with, and
+    /// back for a service, given the conversions of the detail
+    fn soap12_fault_conversions(
+        &self,
+        name: &str,
+        fault_name: &str,
+        detail: Option<(&str, &str)>,
+    ) -> Element {
+        let (detail_in, detail_out) = match detail {
+            Some((detail_in, detail_out)) => (
+                format!("\n\t\t\tdetail: {},", detail_in),
+                format!("\n\t\t\tdetail: {},", detail_out),
+            ),
+            None => (String::new(), String::new()),
+        };
+
+        let mut e = Element::new(&format!("{}Conversions", name), ElementType::Static);
+        e.set_content(&format!(
+            r#"
+impl From<{0}> for {1} {{
+	fn from(fault: {0}) -> Self {{
+		{1} {{
+			fault_code: fault.code.and_then(|code| code.value),
+			fault_string: fault.reason.and_then(|reason| reason.text.into_iter().next()),
+			fault_actor: fault.role,{2}
+		}}
+	}}
+}}
+
+"#,
+            name, fault_name, detail_in
+        ));
+
+        // a service answers with the SOAP 1.2 fault of its error
+        if self.server_dispatchers {
+            e.append_content(&format!(
+                r#"impl From<{1}> for {0} {{
+	fn from(fault: {1}) -> Self {{
+		{0} {{
+			code: fault.fault_code.map(Soap12FaultCode::of_soap11),
+			reason: fault.fault_string.map(|text| Soap12FaultReason {{ text: vec![text] }}),
+			node: Option::None,
+			role: fault.fault_actor,{2}
+		}}
+	}}
+}}
+
+"#,
+                name, fault_name, detail_out
+            ));
+        }
+        e
+    }
+
+    /// The Display and Error implementations of a fault message, of which the content is up to
+    /// the schema, so it shows as its Debug representation
+    fn fault_message_error(&self, fault_type: &str, module: &mut Element) {
+        let name = format!("{}Display", fault_type);
+        if module.has_child(&name) {
+            return;
+        }
+
+        let mut e = Element::new(&name, ElementType::Static);
+        e.set_content(&format!(
+            r#"
+impl std::fmt::Display for {0}::{1} {{
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {{
+		write!(f, "{{:?}}", self)
+	}}
+}}
+
+impl std::error::Error for {0}::{1} {{}}
+
+"#,
+            self.module_name(MESSAGES_MOD),
+            fault_type
+        ));
+        module.add(e);
+    }
+
+    /// Generates the SOAP header of an operation, with a field per header part. The parts refer
+    /// to global elements through their message.
+    fn print_soap_header(
+        &mut self,
+        header_type: &str,
+        headers: &[Header],
+        definitions: &Definitions,
+    ) -> Element {
+        let mut element = Element::new(header_type, ElementType::Struct);
+
+        for header in headers {
+            let part = header.part.as_str();
+            let element_name = definitions
+                .message(&header.message)
+                .and_then(|m| m.parts.iter().find(|p| p.name == part))
+                .and_then(|p| p.element.as_ref());
+
+            let element_name = match element_name {
+                None => {
+                    let reason = format!(
+                        "header part {} of message {} is no element",
+                        part, header.message.name
+                    );
+                    self.report_at(Coverage::Skipped, &header.position, &reason);
+                    continue;
+                }
+                Some(e) => e,
+            };
+
+            // the element is qualified by the prefix generated for its namespace
+            let prefix = element_name
+                .namespace
+                .as_ref()
+                .and_then(|ns| self.namespace_prefixes.get(ns))
+                .cloned()
+                .unwrap_or_else(|| self.ns_prefix.clone());
+
+            let field_type = self.fetch_referenced_qname(
+                header.position.to_string(),
+                element_name,
+                header_type,
+                part,
+            );
+            // the WS-Addressing headers are sent without the others, which are left out then
+            let mut field = Element::new_field(
+                &self.shield_reserved_names(&to_snake_case(part)),
+                &element_name.name,
+                &format!("{}::{}", self.module_name(TYPES_MOD), field_type),
+                self.ws_addressing,
+            );
+            field.prefix = Option::Some(prefix);
+            element.add(field);
+        }
+        if self.ws_addressing {
+            element.add(Self::addressing_field());
+        }
+
+        element
+    }
+
+    /// The envelope of a body, and the constructor of the envelope
+    fn construct_soap_wrapper(
+        &self,
+        soap_name: &str,
+        body_type: &str,
+        header_type: &str,
+        version: SoapVersion,
+        encoding_style: Option<&str>,
+    ) -> (Element, Element) {
+        let tns = match self.target_namespace() {
+            None => "Option::None".to_string(),
+            Some(t) => format!("Option::Some(\"{}\".to_string())", t),
+        };
+        let name = format!("{}{}", soap_name, version.envelope_suffix());
+
+        let mut envelope = Element::new(&name, ElementType::Struct);
+        envelope.xml_name = Option::Some("Envelope".to_string());
+        envelope.add_ns("soapenv", version.envelope_namespace());
+        envelope.prefix = Option::Some("soapenv".to_string());
+        for (field_name, xml_name, prefix) in [
+            ("encoding_style", "encodingStyle", "soapenv"),
+            ("tnsattr", self.ns_prefix.as_str(), "xmlns"),
+            ("urnattr", "urn", "xmlns"),
+            ("xsiattr", "xsi", "xmlns"),
+        ] {
+            let mut attribute = Element::new_field(field_name, xml_name, "String", true);
+            attribute.element_type = ElementType::Attribute;
+            attribute.prefix = Option::Some(prefix.to_string());
+            envelope.add(attribute);
+        }
+        for (field_name, xml_name, field_type, optional) in [
+            ("header", "Header", header_type, true),
+            ("body", "Body", body_type, false),
+        ] {
+            let mut field = Element::new_field(field_name, xml_name, field_type, optional);
+            field.prefix = Option::Some("soapenv".to_string());
+            envelope.add(field);
+        }
+
+        let mut constructor = Element::new(&format!("{}Inherent", name), ElementType::Static);
+        constructor.set_content(&format!(
+            r#"
+        impl {0} {{
+            pub fn new(body: {1}) -> Self {{
+                {0} {{
+                    encoding_style: {3},
+                    tnsattr: {2},
+                    body,
+                    urnattr: None,
+                    xsiattr: None,
+                    header: None,
+                }}
+            }}
+        }}        
+        "#,
+            name,
+            body_type,
+            tns,
+            // literal bodies declare no encoding
+            encoding_style
+                .map(|es| format!("Option::Some({:?}.to_string())", es))
+                .unwrap_or_else(|| "Option::None".to_string()),
+        ));
+
+        (envelope, constructor)
+    }
+
+    fn print_binding_operation(
+        &mut self,
+        binding: &Binding,
+        operation: &BindingOperation,
+        definitions: &Definitions,
+        parent: &mut Element,
+        inherent: &mut Element,
+        module: &mut Element,
+    ) -> ZeepResult<Option<String>> {
+        let operation_name = operation.name.as_str();
+        let bind_type_name = self.qname_type(&binding.port_type);
+        let version = binding.protocol.soap_version();
+        if !self.is_operation_selected(operation_name) {
+            return Ok(None);
+        }
+
+        // rpc style names the body after the operation, document style after the message
+        let rpc = operation.style == Style::Rpc;
+        let message_type_name = match self.message_types.get(operation_name) {
+            Some(mt) if !rpc => self.split_type(mt).to_string(),
+            _ => operation_name.to_string(),
+        };
+        let body_namespace = match &operation.input.namespace {
+            Some(ns) if rpc => Option::Some(ns.clone()),
+            _ => self.target_namespace().cloned(),
+        };
+        // the parts of an rpc operation are unqualified, so its wrapper declares a prefix of its
+        // own instead of the default namespace
+        let (body_prefix, xmlns_attribute) = if rpc {
+            (
+                Option::Some(RPC_PREFIX),
+                (RPC_PREFIX, Option::Some("xmlns")),
+            )
+        } else {
+            (Option::None, ("xmlns", Option::None))
+        };
+
+        let port_type_name = format!("{}::{}", bind_type_name, operation_name);
+
+        let port_type = match self.port_types.get(&port_type_name) {
+            None => {
+                return Err(ZeepError::Unresolved {
+                    position: operation.position.to_string(),
+                    message: format!(
+                        "binding operation {} has no matching port type operation {}",
+                        operation_name, port_type_name
+                    ),
+                })
+            }
+            Some(pt) => pt.clone(),
+        };
+
+        let func_name = self.shield_reserved_names(&to_snake_case(operation_name));
+
+        let (input_name, input_type, input_soap_name, has_input) = match &port_type.input_type {
+            Some((input_type, Some(_))) => {
+                let soap_name = format!("Soap{}", input_type);
+
+                (
+                    self.shield_reserved_names(&to_snake_case(input_type)),
+                    input_type.clone(),
+                    soap_name,
+                    true,
+                )
+            }
+            _ => (String::new(), String::new(), String::new(), false),
+        };
+
+        // the attachments of a MIME binding are parameters of their own
+        let attachments: Vec<String> = operation
+            .input
+            .attachments
+            .iter()
+            .map(|part| self.shield_reserved_names(&to_snake_case(part)))
+            .collect();
+        let output_attachments = operation.output.multipart;
+
+        let input_template = if has_input {
+            format!(
+                "{}: {}::{}",
+                input_name,
+                self.module_name(PORTS_MOD),
+                input_type
+            ) + &attachments
+                .iter()
+                .map(|a| format!(", {}: Attachment", a))
+                .collect::<String>()
+        } else {
+            String::new()
+        };
+
+        let soap_wrapper_in = if has_input {
+            if !self.have_seen_type(&input_soap_name, parent) {
+                let mut wrapper = Element::new(&input_soap_name, ElementType::Struct);
+                let mut body = Element::new_field(
+                    "body",
+                    &message_type_name,
+                    &format!("{}::{}", self.module_name(PORTS_MOD), input_type),
+                    false,
+                );
+                body.prefix = body_prefix.map(str::to_string);
+                wrapper.add(body);
+                let mut xmlns = Element::new_field("xmlns", xmlns_attribute.0, "String", true);
+                xmlns.element_type = ElementType::Attribute;
+                xmlns.prefix = xmlns_attribute.1.map(str::to_string);
+                wrapper.add(xmlns);
+                Option::Some(wrapper)
+            } else {
+                Option::None
+            }
+        } else {
+            Option::None
+        };
+
+        let (output_type, output_soap_name, output_xml_type, has_output) =
+            match &port_type.output_type {
+                Some((output_type, Some(output_message))) => {
+                    let soap_name = format!("Soap{}", output_type);
+                    let output_xml_name = if rpc {
+                        format!("{}Response", operation_name)
+                    } else {
+                        self.message_types
+                            .get(output_message)
+                            .cloned()
+                            .unwrap_or_else(|| output_message.clone())
+                    };
+
+                    (output_type.clone(), soap_name, output_xml_name, true)
+                }
+                _ => (String::new(), String::new(), String::new(), false),
+            };
+
+        let (fault_type, fault_name, fault_soap_name, has_fault) =
+            match port_type.fault_types.first() {
+                // several faults are told apart by the error of the operation
+                Some(_) if port_type.fault_types.len() > 1 => (
+                    String::new(),
+                    String::new(),
+                    port_type
+                        .fault_error
+                        .as_ref()
+                        .map(|(_, faults)| faults.clone())
+                        .unwrap_or_default(),
+                    true,
+                ),
+                Some((fault_name, Some(fault_type))) => {
+                    let soap_name = format!("Soap{}", fault_name);
+                    (
+                        fault_type.to_string(),
+                        fault_name.to_string(),
+                        soap_name,
+                        true,
+                    )
+                }
+                _ => (String::new(), String::new(), String::new(), false),
+            };
+
+        // the faults of SOAP 1.2 are read into faults of their own, which convert to those the
+        // operation answers with
+        let (output_soap_name, fault_wrapper) = match version {
+            SoapVersion::Soap11 if has_fault => (
+                output_soap_name,
+                format!("{}::{}", self.module_name(PORTS_MOD), fault_soap_name),
+            ),
+            SoapVersion::Soap11 => (output_soap_name, "SoapFault".to_string()),
+            SoapVersion::Soap12 => {
+                let single = Option::Some((fault_name.as_str(), fault_type.as_str()))
+                    .filter(|(name, _)| has_fault && !name.is_empty());
+                (
+                    format!("Soap12{}", output_type),
+                    self.print_soap12_operation_fault(has_fault, &fault_soap_name, single, module),
+                )
+            }
+        };
+        let soap_wrapper_out = if has_output {
+            if !self.have_seen_type(&output_soap_name, parent) {
+                let mut wrapper = Element::new(&output_soap_name, ElementType::Struct);
+                wrapper.add(Element::new_field(
+                    "body",
+                    &output_xml_type,
+                    &format!("{}::{}", self.module_name(PORTS_MOD), output_type),
+                    false,
+                ));
+                wrapper.add(Element::new_field("fault", "Fault", &fault_wrapper, true));
+                Option::Some(wrapper)
+            } else {
+                Option::None
+            }
+        } else {
+            Option::None
+        };
+
+        let several_faults = port_type.fault_types.len() > 1;
+        let output_with_attachments = if output_attachments {
+            format!(
+                "({}::{}, {}::ResponseParts)",
+                self.module_name(PORTS_MOD),
+                output_type,
+                TRANSPORT_MOD
+            )
+        } else {
+            format!("{}::{}", self.module_name(PORTS_MOD), output_type)
+        };
+        let output_template = if has_output {
+            if let Some((error, _)) = &port_type.fault_error {
+                format!(
+                    "-> SoapResult<{0}, {1}::{2}>",
+                    output_with_attachments,
+                    self.module_name(PORTS_MOD),
+                    error,
+                )
+            } else if has_fault {
+                format!(
+                    "-> SoapResult<{0}, Option<{2}::{1}>>",
+                    output_with_attachments,
+                    fault_soap_name,
+                    self.module_name(PORTS_MOD),
+                )
+            } else {
+                format!(
+                    "-> SoapResult<{}, Option<SoapFault>>",
+                    output_with_attachments
+                )
+            }
+        } else if has_input {
+            "-> SoapResult<(), Option<SoapFault>>".to_string()
+        } else {
+            String::new()
+        };
+
+        let action = match &operation.action {
+            None => match self.target_namespace() {
+                None => "undefined".to_string(),
+                Some(tns) => format!("{}/{}", tns, operation_name),
+            },
+            Some(sa) => sa.to_string(),
+        };
+
+        let action_name = format!("{}_ACTION", unraw(&func_name).to_uppercase());
+        inherent.append_content(&format!(
+            "\t/// The SOAPAction of the {} operation\n\tpub const {}: &'static str = {:?};\n",
+            operation_name, action_name, action
+        ));
+
+        // todo: convert this to function
+        let mut e = Element::new(&func_name, ElementType::Static);
+        e.documentation = operation.documentation.clone();
+        e.set_content(
+            format!(
+                "\tasync fn {} (&self, {}) {} {{\n",
+                func_name, input_template, output_template,
+            )
+            .as_str(),
+        );
+
+        // operations with SOAP headers take them in a call of their own, the trait leaves them out
+        let headers = &operation.input.headers;
+        let header_type = if has_input && !headers.is_empty() {
+            let header_type = format!("{}Header", input_type);
+            if !self.have_seen_type(&header_type, module) {
+                let header = self.print_soap_header(&header_type, headers, definitions);
+                module.add(header);
+            }
+            Option::Some(header_type)
+        } else {
+            Option::None
+        };
+
+        let fault = match version {
+            SoapVersion::Soap11 if several_faults => {
+                "r.body.fault.unwrap_or_default().into()".to_string()
+            }
+            SoapVersion::Soap11 => "r.body.fault".to_string(),
+            SoapVersion::Soap12 if several_faults => format!(
+                "r.body.fault.map({}::{}::from).unwrap_or_default().into()",
+                self.module_name(PORTS_MOD),
+                fault_soap_name
+            ),
+            SoapVersion::Soap12 => "r.body.fault.map(Into::into)".to_string(),
+        };
+        let output = if has_output {
+            Option::Some((output_type.as_str(), fault.as_str()))
+        } else {
+            Option::None
+        };
+
+        let mut call = SoapCall {
+            input_variable: &input_name,
+            input_type: &input_type,
+            output,
+            action_name: &action_name,
+            namespace: body_namespace.as_deref(),
+            header_variable: None,
+            attachments: &attachments,
+            output_attachments,
+            version,
+        };
+
+        if has_input {
+            match &header_type {
+                None => self.print_reqwest_body(&call, &mut e),
+                Some(header_type) => {
+                    e.append_content(&format!(
+                        "\tself.{}_with_header({}{}, None).await\n",
+                        unraw(&func_name),
+                        input_name,
+                        attachments
+                            .iter()
+                            .map(|a| format!(", {}", a))
+                            .collect::<String>(),
+                    ));
+
+                    let mut with_header = Element::new(&func_name, ElementType::Static);
+                    with_header.set_content(&format!(
+                        "\t/// Calls {} with the SOAP headers declared by the binding\n\tpub async fn {}_with_header(&self, {}, header: Option<{}>) {} {{\n",
+                        operation_name,
+                        unraw(&func_name),
+                        input_template,
+                        header_type,
+                        output_template,
+                    ));
+                    call.header_variable = Option::Some("header");
+                    self.print_reqwest_body(&call, &mut with_header);
+                    with_header.append_content("}\n");
+                    inherent.append_content(&with_header.render());
+                }
+            }
+        }
+
+        e.append_content("}\n");
+
+        if !self.have_seen_type(&e.name, parent) {
+            parent.add(e);
+        }
+
+        if let Some(soap_wrapper_in) = soap_wrapper_in {
+            if !self.have_seen_type(&input_soap_name, module) {
+                module.add(soap_wrapper_in);
+            }
+        }
+
+        if let Some(soap_wrapper_out) = soap_wrapper_out {
+            if !self.have_seen_type(&output_soap_name, module) {
+                module.add(soap_wrapper_out);
+            }
+        }
+
+        // the bindings of a port type share the messages, but not the envelopes
+        let input_header_type = header_type.as_deref().unwrap_or("Header");
+        let envelopes = [
+            (
+                has_input,
+                input_type.as_str(),
+                &input_soap_name,
+                input_header_type,
+                operation.input.encoding_style.as_deref(),
+            ),
+            (
+                has_output,
+                output_type.as_str(),
+                &output_soap_name,
+                "Header",
+                operation.output.encoding_style.as_deref(),
+            ),
+        ];
+        for (_, body_type, soap_name, header_type, encoding_style) in
+            envelopes.iter().filter(|(has, ..)| *has)
+        {
+            let envelope_name = format!("{}{}", body_type, version.envelope_suffix());
+            if !self.have_seen_type(&envelope_name, module) {
+                let (envelope, constructor) = self.construct_soap_wrapper(
+                    body_type,
+                    soap_name,
+                    header_type,
+                    version,
+                    *encoding_style,
+                );
+                module.add(envelope);
+                module.add(constructor);
+            }
+        }
+
+        // the dispatcher hands the requests to the service, except those with attachments
+        if !self.server_dispatchers || !has_input || !attachments.is_empty() || output_attachments {
+            return Ok(None);
+        }
+        let call = format!(
+            "let request: {}{} = from_str(envelope).map_err(DispatchError::XmlError)?;\n\t\t\tlet result = self.service.{}(request.body.body).await;\n",
+            input_type,
+            version.envelope_suffix(),
+            func_name,
+        );
+        let reply = if has_output {
+            format!(
+                r#"let (status, body) = match result {{
+				Ok(output) => (reqwest::StatusCode::OK, {0} {{ body: output, fault: None }}),
+				Err(SoapError::Fault(fault)) => (
+					reqwest::StatusCode::INTERNAL_SERVER_ERROR,
+					{0} {{ body: Default::default(), fault: {1} }},
+				),
+				Err(err) => return Err(DispatchError::ServiceError(format!("{{:?}}", err))),
+			}};
+			let response = to_string(&{2}{3}::new(body)).map_err(DispatchError::XmlError)?;
+			return Ok((status, response));
+"#,
+                output_soap_name,
+                match version {
+                    SoapVersion::Soap11 if several_faults =>
+                        "Option::Some(fault.into())".to_string(),
+                    SoapVersion::Soap11 => "fault".to_string(),
+                    SoapVersion::Soap12 if several_faults => format!(
+                        "Option::Some({}::{}::from(fault).into())",
+                        self.module_name(PORTS_MOD),
+                        fault_soap_name
+                    ),
+                    SoapVersion::Soap12 => "fault.map(Into::into)".to_string(),
+                },
+                output_type,
+                version.envelope_suffix(),
+            )
+        } else {
+            r#"result.map_err(|err| DispatchError::ServiceError(format!("{:?}", err)))?;
+			return Ok((reqwest::StatusCode::ACCEPTED, String::new()));
+"#
+            .to_string()
+        };
+        Ok(Option::Some(format!(
+            "\t\tif action.map_or(element.as_deref() == Some({:?}), |a| a == {}::{}) {{\n\t\t\t{}\t\t\t{}\t\t}}\n",
+            message_type_name, parent.name, action_name, call, reply
+        )))
+    }
+
+    /// The statements that set the WS-Addressing headers of the envelope of a request, which are
+    /// serialized with the envelope
+    fn addressing_headers(&self, action_name: &str) -> String {
+        if !self.ws_addressing {
+            return String::new();
+        }
+        format!(
+            "if let Some(addressing) = &self.addressing {{\n            __request.header.get_or_insert_with(Default::default).addressing =\n                Option::Some(addressing.headers(&self.url, Self::{}));\n        }}\n        ",
+            action_name
+        )
+    }
+
+    /// The statements that add the WS-Security header to the envelope of a request, once it is
+    /// serialized, as its signature covers the other headers
+    fn envelope_headers(&self) -> String {
+        if !self.ws_security {
+            return String::new();
+        }
+        "let __body = self.security.add_to(__body).map_err(SoapError::SecurityError)?;\n        "
+            .to_string()
+    }
+
+    fn print_reqwest_body(&self, call: &SoapCall, parent: &mut Element) {
+        let SoapCall {
+            input_variable,
+            input_type,
+            output,
+            action_name,
+            namespace,
+            header_variable,
+            attachments,
+            output_attachments,
+            version,
+        } = *call;
+
+        let xmlns = match namespace {
+            None => "Option::None".to_string(),
+            Some(tns) => format!("Option::Some(\"{}\".to_string())", tns),
+        };
+
+        // a MIME binding sends the envelope in a multipart/related body, along with the attachments,
+        // and with MTOM the binary values of the messages go in parts of their own
+        let mtom = self.mtom && attachments.is_empty() && !output_attachments;
+        let sent = if mtom {
+            format!(
+                "let (status, {}) = self.send_mtom_request(__body, parts, Self::{}).await?;",
+                if output.is_some() {
+                    "response, parts"
+                } else {
+                    "_, _"
+                },
+                action_name,
+            )
+        } else if !attachments.is_empty() || output_attachments {
+            format!(
+                "let (status, {}) = self.send_multipart_request(__body, vec![{}], Self::{}).await?;",
+                match (output.is_some(), output_attachments) {
+                    (true, true) => "response, attachments",
+                    (true, false) => "response, _",
+                    _ => "_, _",
+                },
+                attachments
+                    .iter()
+                    .map(|a| format!("({:?}, {})", a, a))
+                    .collect::<Vec<String>>()
+                    .join(", "),
+                action_name,
+            )
+        } else if output.is_some() {
+            format!(
+                "let __response = self.transport.call(&self.url, Self::{}, __body).await?;\n        let (status, response) = (__response.status, __response.text());",
+                action_name,
+            )
+        } else {
+            format!(
+                "let status = self.transport.call(&self.url, Self::{}, __body).await?.status;",
+                action_name
+            )
+        };
+
+        parent.append_content(
+            format!(
+                r#"
+        let {5}__request = {1}{4}::new(Soap{1} {{
+            body: {0},
+            xmlns: {3},
+        }});            
+        {6}
+        let {8} = {9}(&__request).map_err(SoapError::XmlError)?;
+        {7}{2}
+        "#,
+                input_variable,
+                input_type,
+                sent,
+                xmlns,
+                version.envelope_suffix(),
+                if header_variable.is_some() || self.ws_addressing {
+                    "mut "
+                } else {
+                    ""
+                },
+                header_variable
+                    .map(|h| format!("__request.header = {};\n        ", h))
+                    .unwrap_or_default()
+                    + &self.addressing_headers(action_name),
+                self.envelope_headers(),
+                if mtom { "(__body, parts)" } else { "__body" },
+                if mtom {
+                    "to_string_with_parts"
+                } else {
+                    "to_string"
+                },
+            )
+            .as_str(),
+        );
+
+        let (output_type, fault) = match output {
+            Some(output) => output,
+            None => {
+                // nothing comes back from a one-way operation, not even a fault to read
+                parent.append_content(
+                    r#"if status.is_success() {
+            Ok(())
+        } else {
+            Err(SoapError::Fault(Option::None))
+        }"#,
+                );
+                return;
+            }
+        };
+
+        if self.ws_security {
+            parent.append_content(
+                "self.security.check_response(&response).map_err(SoapError::SecurityError)?;\n        ",
+            );
+        }
+        parent.append_content(
+            format!(
+                r#"let r: {0}{1} = {4}.map_err(|err| {{
+                        SoapError::XmlError(err)
+                    }})?;
+        // a fault may come back with any status, so it is looked for in the body first
+        if status.is_success() && r.body.fault.is_none() {{
+            Ok({3})
+        }} else {{
+            Err(SoapError::Fault({2}))
+        }}"#,
+                output_type,
+                version.envelope_suffix(),
+                fault,
+                if output_attachments {
+                    "(r.body.body, attachments)"
+                } else {
+                    "r.body.body"
+                },
+                if mtom {
+                    "from_str_with_parts(&response, parts)"
+                } else {
+                    "from_str(&response)"
+                },
+            )
+            .as_str(),
+        );
+    }
+
+    /// Generates the call of an operation of a plain HTTP binding. The parts of the input are sent
+    /// as parameters, or take the place of their placeholders in the location with
+    /// http:urlReplacement, and the response is the XML of the output.
+    fn print_http_operation(
+        &mut self,
+        bind_type_name: &str,
+        operation: &BindingOperation,
+        definitions: &Definitions,
+        parent: &mut Element,
+        inherent: &mut Element,
+    ) -> ZeepResult<()> {
+        let operation_name = operation.name.as_str();
+        if !self.is_operation_selected(operation_name) {
+            return Ok(());
+        }
+
+        let port_type_name = format!("{}::{}", bind_type_name, operation_name);
+        let port_type = match self.port_types.get(&port_type_name) {
+            None => {
+                return Err(ZeepError::Unresolved {
+                    position: operation.position.to_string(),
+                    message: format!(
+                        "binding operation {} has no matching port type operation {}",
+                        operation_name, port_type_name
+                    ),
+                })
+            }
+            Some(pt) => pt.clone(),
+        };
+
+        let (input_name, input_type, input_message) = match &port_type.input_type {
+            Some((input_type, Some(input_message))) => (
+                self.shield_reserved_names(&to_snake_case(input_type)),
+                input_type.clone(),
+                input_message.clone(),
+            ),
+            _ => return Ok(()),
+        };
+
+        let func_name = self.shield_reserved_names(&to_snake_case(operation_name));
+
+        let location = operation.location.as_deref().unwrap_or_default();
+        let location_name = format!("{}_LOCATION", unraw(&func_name).to_uppercase());
+        inherent.append_content(&format!(
+            "\t/// The location of the {} operation, relative to the address of the port\n\tpub const {}: &'static str = {:?};\n",
+            operation_name, location_name, location
+        ));
+
+        // only parts of simple types make for parameters
+        let (parts, dropped): (Vec<_>, Vec<_>) = message_parts(definitions, &input_message)
+            .filter_map(|part| Some((part.name.as_str(), &part.type_name.as_ref()?.name)))
+            .partition(
+                |(_, type_name)| matches!(self.builtin_type(type_name), Some(t) if t != "XmlValue"),
+            );
+        for (part, _) in dropped {
+            let reason = format!(
+                "part {} of message {} cannot be sent as an HTTP parameter",
+                part, input_message
+            );
+            self.report_at(Coverage::Approximated, &operation.position, &reason);
+        }
+        let parameters: Vec<String> = parts
+            .into_iter()
+            .map(|(part, _)| {
+                format!(
+                    "({:?}, {}.{}.to_string())",
+                    part,
+                    input_name,
+                    self.shield_reserved_names(&to_snake_case(part))
+                )
+            })
+            .collect();
+        // an input without parameters goes unused
+        let input_variable = if parameters.is_empty() {
+            format!("_{}", input_name)
+        } else {
+            input_name.clone()
+        };
+        let parameters = format!("&[{}]", parameters.join(", "));
+
+        let (location, parameters) = if operation.input.url_replacement {
+            self.print_url_replacement();
+            (
+                format!("&url_replacement(Self::{}, {})", location_name, parameters),
+                "&[]".to_string(),
+            )
+        } else {
+            (format!("Self::{}", location_name), parameters)
+        };
+
+        // there is no fault to read from a plain HTTP response, only its status
+        let fault = if let Some((error, _)) = &port_type.fault_error {
+            format!(
+                "{}::{}::Other(SoapFault {{ fault_code: Option::Some(status.to_string()), fault_string: Option::Some(response), fault_actor: Option::None, detail: Option::None }})",
+                self.module_name(PORTS_MOD), error
+            )
+        } else if port_type.fault_types.is_empty() {
+            "Option::Some(SoapFault { fault_code: Option::Some(status.to_string()), fault_string: Option::Some(response), fault_actor: Option::None, detail: Option::None })".to_string()
+        } else {
+            "Option::None".to_string()
+        };
+        let fault_type = match (&port_type.fault_error, port_type.fault_types.first()) {
+            (Some((error, _)), _) => format!("{}::{}", self.module_name(PORTS_MOD), error),
+            (None, Some((fault_name, Some(_)))) => {
+                format!(
+                    "Option<{}::Soap{}>",
+                    self.module_name(PORTS_MOD),
+                    fault_name
+                )
+            }
+            _ => "Option<SoapFault>".to_string(),
+        };
+        // the response is the element of the output, which the message wraps
+        let (output_type, output) = match &port_type.output_type {
+            Some((output_type, Some(output_message))) => {
+                let parts: Vec<&model::Part> = message_parts(definitions, output_message).collect();
+                let part = parts
+                    .iter()
+                    .find(|p| p.element.is_some())
+                    .map(|p| p.name.as_str());
+                let output = match (part, self.message_types.get(output_message.as_str())) {
+                    (Some(part), Some(element_type)) => format!(
+                        r#"let {4}: {0}::{1} = from_str(&response).map_err(|err| {{
+                        SoapError::XmlError(err)
+                    }})?;
+        Ok({2}::{3} {{
+            {4},{5}
+        }})"#,
+                        self.module_name(TYPES_MOD),
+                        element_type,
+                        self.module_name(PORTS_MOD),
+                        output_type,
+                        self.shield_reserved_names(&to_snake_case(part)),
+                        if parts.len() > 1 {
+                            "\n            ..Default::default()"
+                        } else {
+                            ""
+                        },
+                    ),
+                    _ => r#"from_str(&response).map_err(|err| {
+                        SoapError::XmlError(err)
+                    })"#
+                    .to_string(),
+                };
+                (
+                    format!("{}::{}", self.module_name(PORTS_MOD), output_type),
+                    output,
+                )
+            }
+            _ => ("()".to_string(), "Ok(())".to_string()),
+        };
+
+        let mut e = Element::new(&func_name, ElementType::Static);
+        e.documentation = operation.documentation.clone();
+        e.set_content(&format!(
+            r#"	async fn {0} (&self, {1}: {2}::{3}) -> SoapResult<{4}, {5}> {{
+        let (status, response) = self.send_http_request({6}, {7}).await
+                    .map_err(|err| {{
+                        SoapError::RequestError(err)
+                    }})?;
+        if !status.is_success() {{
+            return Err(SoapError::Fault({8}));
+        }}
+        {9}
+    }}
+"#,
+            func_name,
+            input_variable,
+            self.module_name(PORTS_MOD),
+            input_type,
+            output_type,
+            fault_type,
+            location,
+            parameters,
+            fault,
+            output,
+        ));
+
+        if !self.have_seen_type(&e.name, parent) {
+            parent.add(e);
+        }
+        Ok(())
+    }
+
+    // WSDL Services
+
+    fn print_service(&mut self, service: &Service) {
+        let parent = self.pick_section(SERVICES_MOD);
+        let mut _parent = &mut *parent.deref().borrow_mut();
+
+        let element_name = service.name.name.as_str();
+        let struct_name = self.type_ident(element_name);
+
+        if self.have_seen_type(&struct_name, _parent) {
+            return;
+        }
+
+        // every port with its binding and the location of its address, or the WSDL 2.0 endpoints
+        let ports: Vec<(&String, String, &String, &Option<String>)> = service
+            .ports
+            .iter()
+            .map(|port| {
+                let binding = self.rust_name(port.binding.namespace.as_ref(), &port.binding.name);
+                (&port.name, binding, &port.address, &port.documentation)
+            })
+            .collect();
+
+        let (first_port, first_binding, ..) = match ports.first() {
+            None => return,
+            Some(p) => p,
+        };
+
+        let mut e = Element::new(&struct_name, ElementType::Static);
+        e.documentation = service.documentation.clone();
+
+        e.set_content(
+            format!(
+                r#"pub struct {0} {{}}
+               impl {0} {{
+                /// The ports of the service, each with the binding it uses
+                pub const PORTS: &'static [(&'static str, &'static str)] = &[{1}];
+                "#,
+                struct_name,
+                ports
+                    .iter()
+                    .map(|(name, binding, ..)| format!("({:?}, {:?}), ", name, binding))
+                    .collect::<String>(),
+            )
+            .as_str(),
+        );
+
+        e.append_content(
+            format!(
+                r#"
+            pub fn new_client(credentials: Option<(String, String)>) -> {2}::{1} {{
+                {0}::new_client(credentials)
+            }}
+        "#,
+                self.type_ident(first_port),
+                first_binding,
+                self.module_name(BINDINGS_MOD),
+            )
+            .as_str(),
+        );
+
+        e.append_content("}\n");
+
+        _parent.add(e);
+
+        for (name, binding, location, port_documentation) in ports.iter() {
+            let port_name = self.type_ident(name);
+            if self.have_seen_type(&port_name, _parent) {
+                continue;
+            }
+
+            let summary = format!(
+                "The {} port of {}, bound to {}::{}",
+                name,
+                element_name,
+                self.module_name(BINDINGS_MOD),
+                binding
+            );
+            let mut port = Element::new(&port_name, ElementType::Static);
+            port.documentation = match port_documentation {
+                None => Option::Some(summary),
+                Some(d) => Option::Some(format!("{}\n\n{}", summary, d)),
+            };
+            port.set_content(
+                format!(
+                    r#"pub struct {1} {{}}
+                impl {1} {{
+                    /// The location of the port
+                    pub const DEFAULT_ENDPOINT: &'static str = {3:?};
+
+                    pub fn new_client(credentials: Option<(String, String)>) -> {2}::{0} {{
+                        {2}::{0}::new(Self::DEFAULT_ENDPOINT, credentials)
+                    }}
+                }}
+                "#,
+                    binding,
+                    port_name,
+                    self.module_name(BINDINGS_MOD),
+                    location,
+                )
+                .as_str(),
+            );
+
+            _parent.add(port);
+        }
+    }
+}
+
+/// Whether a node is a particle that contributes a group of fields, rather than a single one.
+fn is_range_facet(node: &Node) -> bool {
+    [
+        "minInclusive",
+        "maxInclusive",
+        "minExclusive",
+        "maxExclusive",
+    ]
+    .iter()
+    .any(|facet| node.has_tag_name(*facet))
+}
+
+fn is_group_particle(node: &Node) -> bool {
+    node.has_tag_name("sequence") || node.has_tag_name("choice") || node.has_tag_name("group")
+}
+
+/// The parts of the messages of a local name, which the port types know them by
+fn message_parts<'a>(
+    definitions: &'a Definitions,
+    message: &'a str,
+) -> impl Iterator<Item = &'a model::Part> {
+    definitions
+        .messages
+        .iter()
+        .filter(move |m| m.name.name == message)
+        .flat_map(|m| m.parts.iter())
+}
+
+/// Translates XSD patterns into a single regular expression matching any of them. XSD patterns
+/// match the entire value, know the name character escapes, and have no anchors.
+fn to_regex(patterns: &[&str]) -> String {
+    let mut regex = String::new();
+    let mut class_depth = 0;
+
+    for (i, pattern) in patterns.iter().enumerate() {
+        if i > 0 {
+            regex.push('|');
+        }
+
+        let mut chars = pattern.chars().peekable();
+        let mut class_start = false;
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some('i') => regex.push_str("[_:A-Za-z]"),
+                    Some('I') => regex.push_str("[^_:A-Za-z]"),
+                    Some('c') => regex.push_str("[-._:A-Za-z0-9]"),
+                    Some('C') => regex.push_str("[^-._:A-Za-z0-9]"),
+                    Some(escaped) => {
+                        regex.push('\\');
+                        regex.push(escaped);
+                    }
+                    None => regex.push_str("\\\\"),
+                },
+                '[' => {
+                    class_depth += 1;
+                    regex.push(c);
+                    class_start = true;
+                    continue;
+                }
+                ']' if class_depth > 0 => {
+                    class_depth -= 1;
+                    regex.push(c);
+                }
+                // character class subtraction
+                '-' if class_depth > 0 && chars.peek() == Some(&'[') => regex.push_str("--"),
+                '^' if class_start => regex.push(c),
+                '^' | '$' => {
+                    regex.push('\\');
+                    regex.push(c);
+                }
+                _ => regex.push(c),
+            }
+            class_start = false;
+        }
+    }
+
+    format!("^(?:{})$", regex)
+}
+
+/// Formats the generated code. Code that does not parse is a bug of the generator and is
+/// returned as is, so it can be inspected.
+fn format_source(source: String) -> String {
+    match syn::parse_file(&source) {
+        Ok(file) => prettyplease::unparse(&file),
+        Err(e) => {
+            let start = e.span().start();
+            warn!(
+                "unable to format the generated code, at {}:{}: {}",
+                start.line,
+                start.column + 1,
+                e
+            );
+            source
+        }
+    }
+}
+
+/// Turns the inner doc comments and attributes of code into plain comments, which code that is
+/// included can not have
+fn includable(code: &str) -> String {
+    code.split_inclusive('\n')
+        .map(|line| {
+            if let Some(comment) = line.strip_prefix("//!") {
+                format!("//{}", comment)
+            } else if line.starts_with("#![") {
+                format!("// {}", line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect()
+}
+
+/// The name of an identifier without the prefix of raw identifiers, to build other names from
+fn unraw(identifier: &str) -> &str {
+    identifier.trim_start_matches("r#")
+}
+
+/// The name of the extension file of a module file
+fn extension_file_name(module_file: &str) -> String {
+    format!("{}_ext.rs", module_file.trim_end_matches(".rs"))
+}
+
+/// The initial content of the extension file of a module
+/// The trait of a port type, in any of the modules
+fn find_trait(module: &Element, name: &str) -> Option<Rc<RefCell<Element>>> {
+    module.children.iter().find_map(|child| {
+        let element = child.borrow();
+        match element.element_type {
+            ElementType::Trait if element.name == name => Some(child.clone()),
+            ElementType::Module => find_trait(&element, name),
+            _ => None,
+        }
+    })
+}
+
+fn extension_file(module: &str) -> String {
+    format!(
+        "// Hand-written code of the {0} module, included at the end of {0}.rs.\n// zeep creates this file once and never overwrites it.\n",
+        module
+    )
+}
+
+/// Collects the descriptions of the types in a module and its submodules, named by their path
+/// relative to the module
+fn describe_types(module: &Element, path: &str, types: &mut Vec<serde_json::Value>) {
+    for child in &module.children {
+        let child = child.borrow();
+        if let ElementType::Module = child.element_type {
+            describe_types(&child, &format!("{}{}::", path, child.name), types);
+        } else if let Some(mut description) = child.to_json() {
+            description["name"] = serde_json::Value::from(format!("{}{}", path, child.name));
+            types.push(description);
+        }
+    }
+}
+
+/// Collects the paths of the structs in a module and its submodules, relative to the module
+fn is_url(location: &str) -> bool {
+    location.starts_with("http://") || location.starts_with("https://")
+}
+
+#[cfg(feature = "remote")]
+fn fetch_url(url: &str) -> ZeepResult<String> {
+    let io_error = |e| ZeepError::Io {
+        path: url.to_string(),
+        source: std::io::Error::other(e),
+    };
+    reqwest::blocking::get(url)
+        .and_then(|response| response.error_for_status())
+        .map_err(io_error)?
+        .text()
+        .map_err(io_error)
+}
+
+#[cfg(not(feature = "remote"))]
+fn fetch_url(url: &str) -> ZeepResult<String> {
+    Err(ZeepError::Io {
+        path: url.to_string(),
+        source: std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "fetching documents needs the remote feature of zeep-lib",
+        ),
+    })
+}
+
+/// Whether an element defines a type, of which a module can only have one by a name
+fn is_definition(element: &Element) -> bool {
+    matches!(
+        element.element_type,
+        ElementType::Struct
+            | ElementType::Alias
+            | ElementType::Trait
+            | ElementType::Enum
+            | ElementType::Union
+            | ElementType::List
+            | ElementType::Restriction
+            | ElementType::Polymorphic
+    )
+}
+
+/// The names of the types whose default value may be rejected when deserialized: the restricted
+/// types with a facet it does not satisfy, and the structs with a required field of such a type
+fn invalid_defaults(module: &Element) -> HashSet<String> {
+    fn collect(
+        module: &Element,
+        invalid: &mut HashSet<String>,
+        structs: &mut Vec<(String, Vec<String>)>,
+    ) {
+        for child in &module.children {
+            let child = child.borrow();
+            match child.element_type {
+                ElementType::Restriction if !child.facets.iter().all(Facet::admits_default) => {
+                    invalid.insert(child.name.clone());
+                }
+                ElementType::Struct => {
+                    let required = child
+                        .children
+                        .iter()
+                        .map(|field| field.borrow())
+                        .filter(|field| !field.optional && !field.vector)
+                        .filter(|field| field.default_value.is_none())
+                        .filter_map(|field| field.field_type.clone())
+                        .map(|t| t.rsplit("::").next().unwrap_or_default().to_string())
+                        .collect();
+                    structs.push((child.name.clone(), required));
+                }
+                ElementType::Module => collect(&child, invalid, structs),
+                _ => {}
+            }
+        }
+    }
+
+    let mut invalid = HashSet::new();
+    let mut structs = vec![];
+    collect(module, &mut invalid, &mut structs);
+
+    // a struct is invalid through the structs it requires, repeat until none is added
+    loop {
+        let before = invalid.len();
+        for (name, required) in &structs {
+            if required.iter().any(|t| invalid.contains(t)) {
+                invalid.insert(name.clone());
+            }
+        }
+        if invalid.len() == before {
+            return invalid;
+        }
+    }
+}
+
+fn struct_paths(module: &Element, path: &[String], paths: &mut Vec<Vec<String>>) {
+    for child in &module.children {
+        let child = child.borrow();
+        let mut child_path = path.to_vec();
+        child_path.push(child.name.clone());
+        match child.element_type {
+            ElementType::Struct => paths.push(child_path),
+            ElementType::Module => struct_paths(&child, &child_path, paths),
+            _ => {}
+        }
+    }
+}
+
+/// Rewrites the references to types in an element and its children, in the field types, default
+/// values, function signatures and static content. `qualify` gets every identifier with the code
+/// in front of it, and returns what replaces the identifier, if anything.
+fn qualify_types(element: &mut Element, qualify: &dyn Fn(&str, &str) -> Option<String>) {
+    for code in element
+        .field_type
+        .iter_mut()
+        .chain(element.default_value.iter_mut())
+    {
+        *code = replace_identifiers(code, qualify);
+    }
+
+    if let Some(args) = &mut element.function_args {
+        args.input_type = replace_identifiers(&args.input_type, qualify);
+        for code in args
+            .output_type
+            .iter_mut()
+            .chain(args.fault_type.iter_mut())
+        {
+            *code = replace_identifiers(code, qualify);
+        }
+    }
+
+    if let Some(content) = element.content() {
+        let content = replace_identifiers(content, qualify);
+        element.set_content(&content);
+    }
+
+    for child in &element.children {
+        qualify_types(&mut child.borrow_mut(), qualify);
+    }
+}
+
+/// Replaces the identifiers in Rust code with what `replace` returns for them
+fn replace_identifiers(code: &str, replace: &dyn Fn(&str, &str) -> Option<String>) -> String {
+    let is_identifier = |c: char| c.is_alphanumeric() || c == '_';
+    let mut result = String::with_capacity(code.len());
+    let mut rest = code;
+    while let Some(start) = rest.find(is_identifier) {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest.find(|c: char| !is_identifier(c)).unwrap_or(rest.len());
+        let identifier = &rest[..end];
+        match replace(identifier, &result) {
+            Some(replacement) => result.push_str(&replacement),
+            None => result.push_str(identifier),
+        }
+        rest = &rest[end..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Collects the identifiers used in the field types of an element and its children.
+fn referenced_names(element: &Element) -> Vec<String> {
+    let mut names: Vec<String> = element
+        .field_type
+        .iter()
+        .flat_map(|t| t.split(|c: char| !c.is_alphanumeric() && c != '_'))
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_string())
+        .collect();
+
+    element
+        .children
+        .iter()
+        .for_each(|c| names.extend(referenced_names(&c.borrow())));
+
+    names
+}
+
+#[cfg(test)]
+mod test_xsd {
+    use super::*;
+    use crate::debug::DebugBuffer;
+    use std::io::Read;
+
+    const RESOURCES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../resources/smgr");
+
+    fn prepare_output(ns_prefix: Option<String>, default_ns: Option<String>) -> String {
+        let mut buffer = DebugBuffer::default();
+        let mut fw = FileWriter::new_buffer(ns_prefix, default_ns, buffer.clone());
+        fw.process_file(RESOURCES, "agentCommProfile.xsd")
+            .expect("can not open xsd");
+
+        let mut result = String::new();
+        buffer
+            .read_to_string(&mut result)
+            .expect("failed to get content");
+        result
+    }
+
+    #[test]
+    fn test_attributes() {
+        let result = prepare_output(
+            None,
+            Some("http://xml.avaya.com/schema/import_csm_agent".to_string()),
+        );
+        assert!(
+            result.contains(r#"#[yaserde(rename="createTenantIfNotAlreadyPresent", attribute)]"#)
+        );
+    }
+
+    #[test]
+    fn test_default_namespace() {
+        let result = prepare_output(
+            None,
+            Some("http://xml.avaya.com/schema/import_csm_agent".to_string()),
+        );
+
+        // no prefix for default namespace
+        assert!(result.contains(
+            r#"#[yaserde(
+	rename = "xmlAgentProfile",
+	namespace = "xsi: http://www.w3.org/2001/XMLSchema-instance",
+)]"#
+        ));
+    }
+
+    #[test]
+    fn test_no_default_namespace() {
+        let result = prepare_output(None, None);
+
+        assert!(result.contains(
+            r#"#[yaserde(
+	rename = "xmlAgentProfile",
+	namespace = "tns: http://xml.avaya.com/schema/import_csm_agent",
+	namespace = "xsi: http://www.w3.org/2001/XMLSchema-instance",
+	prefix = "tns",
+)]"#
+        ));
+    }
+
+    #[test]
+    fn test_ns_prefix() {
+        let result = prepare_output(Some("ns2".to_string()), None);
+
+        assert!(result.contains(
+            r#"#[yaserde(
+	rename = "xmlAgentProfile",
+	namespace = "ns2: http://xml.avaya.com/schema/import_csm_agent",
+	namespace = "xsi: http://www.w3.org/2001/XMLSchema-instance",
+	prefix = "ns2",
+)]"#
+        ));
+    }
+
+    #[test]
+    fn test_import() {
+        let result = prepare_output(None, None);
+        assert!(result.contains(r#"xmlContact"#));
+    }
+}
+
+#[cfg(test)]
+mod test_wsdl {
+    use super::*;
+    use std::io::Read;
+
+    const RESOURCES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../resources/temp_converter");
+
+    fn prepare_output(ns_prefix: Option<String>, default_ns: Option<String>) -> String {
+        let mut buffer = DebugBuffer::default();
+        let mut fw = FileWriter::new_buffer(ns_prefix, default_ns, buffer.clone());
+        fw.process_file(RESOURCES, "tempconverter.wsdl")
+            .expect("can not open wsdl");
+
+        let mut result = String::new();
+        buffer
+            .read_to_string(&mut result)
+            .expect("failed to get content");
+        result
+    }
+
+    #[test]
+    fn test_service() {
+        let result = prepare_output(None, None);
+        assert!(result.contains(r#"pub const DEFAULT_ENDPOINT: &'static str = "http://www.learnwebservices.com/services/tempconverter";"#));
+        assert!(result.contains(r#"bindings::TempConverterEndpointServiceSoapBinding::new(Self::DEFAULT_ENDPOINT, credentials)"#));
+    }
+}
+
+#[cfg(test)]
+mod test_fixtures {
+    use super::*;
+    use std::io::Read;
+
+    const RESOURCES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../resources/fixtures");
+
+    fn prepare_output(file_name: &str) -> String {
+        let mut buffer = DebugBuffer::default();
+        let mut fw = FileWriter::new_buffer(None, None, buffer.clone());
+        fw.process_file(RESOURCES, file_name)
+            .expect("can not open fixture");
+
+        let mut result = String::new();
+        buffer
+            .read_to_string(&mut result)
+            .expect("failed to get content");
+        result
+    }
+
+    /// A directory of a test and of this run, so runs at the same time do not write over each
+    /// other's files. What is left of an earlier run is removed.
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("zeep_test_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_generate_to_string() {
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default());
+        let first = fw
+            .generate_to_string(RESOURCES, "nested_sequence.xsd")
+            .expect("can not generate");
+        let second = fw
+            .generate_to_string(RESOURCES, "nested_sequence.xsd")
+            .expect("can not generate");
+
+        assert!(first.starts_with(SIGNATURE));
+        assert_eq!(first, second);
+        assert_eq!(first, prepare_output("nested_sequence.xsd"));
+    }
+
+    #[test]
+    fn test_nested_sequence() {
+        let result = prepare_output("nested_sequence.xsd");
+
+        assert!(result.contains("pub first_name: String,"));
+        assert!(result.contains("pub street: String,"));
+        assert!(result.contains("pub city: String,"));
+        assert!(result.contains("pub phone: Option<String>,"));
+
+        // the branches of a repeating choice are alternatives within an occurrence
+        assert!(result.contains("pub choice: Repeated<PersonChoice>,"));
+        assert!(result.contains("pub email: Option<String>,"));
+        assert!(result.contains("pub fax: Option<String>,"));
+    }
+
+    #[test]
+    fn test_enumeration() {
+        let result = prepare_output("enumeration.xsd");
+
+        assert!(result.contains("pub enum OrderStatus {"));
+        assert!(result.contains("\t#[yaserde(rename = \"in-progress\")]\n\tInProgress,"));
+        assert!(result.contains("\t#[yaserde(rename = \"2nd-line\")]\n\tValue2NdLine,"));
+        assert!(result.contains("\t#[yaserde(rename = \"new\")]\n\tNew3,"));
+        assert!(result.contains("impl Default for OrderStatus"));
+        assert!(result.contains("pub status: OrderStatus,"));
+    }
+
+    #[test]
+    fn test_choice() {
+        let result = prepare_output("choice.xsd");
+
+        assert!(result.contains("pub enum ContactChoice {"));
+        assert!(result.contains("\tPhone(Vec<String>),"));
+        assert!(result.contains("\tAddress(Address),"));
+        assert!(result.contains("#[yaserde(flatten, default)]\n\tpub choice: ContactChoice,"));
+
+        // optional choices are flattened, as an absent enum would deserialize to its first branch
+        assert!(!result.contains("PreferenceChoice"));
+        assert!(result.contains("pub email: Option<bool>, \n\t#[yaserde(rename = \"post\", prefix = \"tns\", default)]\n\tpub post: Option<bool>,"));
+
+        // repeating choices are repeated enums
+        assert!(result.contains("pub enum ContactsChoice {"));
+        assert!(result
+            .contains("#[yaserde(flatten, default)]\n\tpub choice: Repeated<ContactsChoice>,"));
+
+        // choices nested in a sequence keep their position
+        assert!(!result.contains("PaymentChoice"));
+        assert!(result.contains("pub amount: f64, \n\t#[yaserde(rename = \"card\", prefix = \"tns\", default)]\n\tpub card: Option<String>,"));
+        assert!(result.contains("pub iban: Option<String>, \n\t#[yaserde(rename = \"note\", prefix = \"tns\", default)]\n\tpub note: String,"));
+    }
+
+    #[test]
+    fn test_attributes() {
+        let result = prepare_output("attributes.xsd");
+
+        assert!(result.contains("#[yaserde(rename=\"id\", attribute)]\npub id: i32,"));
+        assert!(result.contains("pub label: Option<String>,"));
+        assert!(
+            result.contains("#[yaserde(rename=\"type\", attribute)]\npub r#type: Option<String>,")
+        );
+        assert!(result.contains("pub weight: Option<f64>,"));
+        assert!(result.contains("#[yaserde(rename=\"isbn\", attribute)]\npub isbn: String,"));
+    }
+
+    #[test]
+    fn test_all() {
+        let result = prepare_output("all.xsd");
+
+        assert!(result.contains("pub language: String,"));
+        assert!(result.contains("pub timezone: Option<String>,"));
+        assert!(result.contains("pub page_size: Option<i32>,"));
+    }
+
+    #[test]
+    fn test_attribute_group() {
+        let result = prepare_output("attribute_group.xsd");
+
+        assert!(
+            result.contains("#[yaserde(rename=\"createdBy\", attribute)]\npub created_by: String,")
+        );
+        assert!(
+            result.contains("#[yaserde(rename=\"version\", attribute)]\npub version: Option<i32>,")
+        );
+        assert!(result.contains("pub id: String,"));
+    }
+
+    #[test]
+    fn test_group() {
+        let result = prepare_output("group.xsd");
+
+        let customer = &result[result.find("pub struct Customer").expect("no Customer")..];
+        assert!(customer.contains("pub street: Option<String>,"));
+        assert!(customer.contains("pub zip: Option<String>,"));
+
+        let supplier = &result[result.find("pub struct Supplier").expect("no Supplier")..];
+        assert!(supplier.contains("pub street: String,"));
+        assert!(supplier.contains("pub city: String,"));
+        assert!(!result.contains("pub struct Address"));
+    }
+
+    #[test]
+    fn test_union() {
+        let result = prepare_output("union.xsd");
+
+        assert!(result
+            .contains("pub enum Size {\n\tInt(i32),\n\tShortName(String),\n\tDecimal(f64),\n}"));
+        assert!(result.contains("pub enum ShirtFit {\n\tBoolean(bool),\n\tString(String),\n}"));
+        assert!(result.contains("impl_text_value!(Size);"));
+        assert!(result.contains("pub size: Size,"));
+        assert!(result.contains("pub fit: ShirtFit,"));
+    }
+
+    #[test]
+    fn test_list() {
+        let result = prepare_output("list.xsd");
+
+        assert!(result.contains("pub struct Sizes(pub Vec<i32>);"));
+        assert!(result.contains("pub struct OrderTags(pub Vec<String>);"));
+        assert!(result.contains("impl_text_value!(Sizes);"));
+        assert!(result.contains("pub tags: OrderTags,"));
+    }
+
+    #[test]
+    fn test_any() {
+        let result = prepare_output("any.xsd");
+
+        let envelope = &result[result.find("pub struct Envelope").expect("no Envelope")..];
+        assert!(envelope.contains("#[yaserde(flatten, default)]\n\tpub any: RawXml,"));
+
+        // the base type already claims the unmatched elements
+        let extended = &result[result.find("pub struct ExtendedEnvelope").expect("no type")..];
+        assert!(!extended.contains("pub any: RawXml,"));
+    }
+
+    #[test]
+    fn test_any_type() {
+        let result = prepare_output("any_type.xsd");
+
+        assert!(result.contains("pub struct XmlValue {"));
+        assert!(result.contains("pub value: XmlValue,"));
+        assert!(result.contains("pub default: XmlValue,"));
+        assert!(!result.contains("AnyType"));
+    }
+
+    #[test]
+    fn test_substitution_group() {
+        let result = prepare_output("substitution_group.xsd");
+
+        assert!(result.contains(
+            "pub enum ShapeGroup {\n\t#[yaserde(prefix = \"tns\", rename = \"circle\")]\n\tCircle(Circle),\n\t#[yaserde(prefix = \"tns\", rename = \"square\")]\n\tSquare(Square),\n}"
+        ));
+
+        let drawing = &result[result.find("pub struct Drawing").expect("no Drawing")..];
+        assert!(drawing.contains(
+            "#[yaserde(rename = \"label\", prefix = \"tns\", default)]\n\tpub label: String,"
+        ));
+        assert!(drawing.contains("#[yaserde(flatten, default)]\n\tpub shape: ShapeGroup,"));
+
+        let layers = &result[result.find("pub struct Layers").expect("no Layers")..];
+        assert!(layers.contains("pub circle: Vec<Circle>,"));
+        assert!(layers.contains("pub square: Vec<Square>,"));
+    }
+
+    #[test]
+    fn test_occurrence() {
+        let result = prepare_output("occurrence.xsd");
+
+        assert!(result.contains("pub required: String,"));
+        assert!(result.contains("pub optional: Option<String>,"));
+        assert!(result.contains("pub nillable: Option<String>,"));
+        assert!(result.contains("pub not_nillable: String,"));
+        assert!(result.contains("pub optional_list: Vec<String>,"));
+        assert!(result.contains("pub single: String,"));
+        assert!(result.contains("pub bounded: Vec<String>, // at most 3 items"));
+        assert!(!result.contains("pub prohibited"));
+    }
+
+    #[test]
+    fn test_mixed() {
+        let result = prepare_output("mixed.xsd");
+
+        let note = &result[result.find("pub struct Note").expect("no Note")..];
+        assert!(note.contains("pub author: String,"));
+        assert!(note.contains("#[yaserde(text, default)]\n\tpub text: String,"));
+
+        let plain = &result[result.find("pub struct Plain").expect("no Plain")..];
+        assert!(!plain.contains("text"));
+    }
+
+    #[test]
+    fn test_anonymous_types() {
+        let result = prepare_output("anonymous_types.xsd");
+
+        assert!(result.contains("pub address: CustomerAddress,"));
+        assert!(result.contains("pub address: WarehouseAddress,"));
+
+        let customer = &result[result.find("pub struct CustomerAddress").expect("no type")..];
+        assert!(
+            customer.starts_with("pub struct CustomerAddress {\n\t#[yaserde(rename = \"street\"")
+        );
+        let warehouse = &result[result.find("pub struct WarehouseAddress").expect("no type")..];
+        assert!(
+            warehouse.starts_with("pub struct WarehouseAddress {\n\t#[yaserde(rename = \"dock\"")
+        );
+        assert!(result.contains("rename = \"address\""));
+    }
+
+    #[test]
+    fn test_simple_content() {
+        let result = prepare_output("simple_content.xsd");
+
+        let price = &result[result.find("pub struct Price").expect("no Price")..];
+        assert!(price.contains("#[yaserde(text, default)]\n\tpub body: String, // xs:decimal"));
+        assert!(price.contains("#[yaserde(rename=\"currency\", attribute)]\npub currency: String,"));
+
+        let discounted = &result[result.find("pub struct DiscountedPrice").expect("no type")..];
+        assert!(discounted.contains("#[yaserde(flatten, default)]\n\tpub price: Price,"));
+        assert!(discounted.contains("pub discount: Option<i32>,"));
+    }
+
+    #[test]
+    fn test_complex_restriction() {
+        let result = prepare_output("complex_restriction.xsd");
+
+        let restricted = &result[result.find("pub struct LocalAddress").expect("no type")..];
+        let restricted = &restricted[..restricted.find('}').expect("no end")];
+        assert!(restricted.contains("pub street: String,"));
+        assert!(restricted.contains("pub kind: String,"));
+        assert!(!restricted.contains("country"));
+        assert!(!restricted.contains("flatten"));
+    }
+
+    #[test]
+    fn test_default_values() {
+        let result = prepare_output("default_values.xsd");
+
+        assert!(result.contains("#[derive(Debug, YaSerialize, YaDeserialize, Clone)]"));
+        assert!(result.contains("\t\t\tretries: 3,\n"));
+        assert!(result.contains("\t\t\tratio: Some(0.5),\n"));
+        assert!(result.contains("\t\t\tenabled: true,\n"));
+        assert!(result.contains("\t\t\tstatus: Status::Active,\n"));
+        assert!(result.contains("\t\t\tfixed_attributes: Default::default(),\n"));
+        assert!(result.contains("\t\t\tname: Default::default(),\n"));
+    }
+
+    #[test]
+    fn test_documentation() {
+        let result = prepare_output("documentation.xsd");
+
+        assert!(result.contains("/// A customer of the shop\n///\n/// Customers are identified by their number.\n#[derive("));
+        assert!(result.contains("\t/// The number of the customer\n\t#[yaserde("));
+        assert!(result.contains("/// Whether the customer is active\n#[yaserde("));
+        assert!(result.contains("/// The level of a customer\n#[derive("));
+        assert!(result.contains("\t/// The default level\n\t#[yaserde(rename = \"BASIC\")]"));
+        assert!(result.contains("/// An order placed by a customer\n#[derive("));
+    }
+
+    #[test]
+    fn test_keywords() {
+        let result = prepare_output("keywords.xsd");
+
+        assert!(result.contains("pub struct Self_ {"));
+        assert!(result.contains("pub r#ref: String,"));
+        assert!(result.contains("pub r#match: String,"));
+        assert!(result.contains("pub r#impl: String,"));
+        assert!(
+            result.contains("#[yaserde(rename=\"use\", attribute)]\npub r#use: Option<String>,")
+        );
+        assert!(result.contains("pub r#async: Option<bool>,"));
+        assert!(result.contains("pub self_: Self_,"));
+
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default())
+            .with_keyword_suffix("_field");
+        let result = fw
+            .generate_to_string(RESOURCES, "keywords.xsd")
+            .expect("can not generate");
+        assert!(result.contains("pub struct Self_field {"));
+        assert!(result.contains("pub ref_field: String,"));
+        assert!(result.contains("pub use_field: Option<String>,"));
+        assert!(result.contains("pub self_field: Self_field,"));
+        assert!(!result.contains("r#"));
+    }
+
+    #[test]
+    fn test_strict() {
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default());
+        assert!(fw.generate_to_string(RESOURCES, "any.xsd").is_ok());
+
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default()).with_strict(true);
+        match fw.generate_to_string(RESOURCES, "any.xsd") {
+            Err(ZeepError::Skipped(nodes)) => assert_eq!(
+                nodes,
+                vec![Construct {
+                    coverage: Coverage::Skipped,
+                    tag: "xs:anyAttribute".to_string(),
+                    file: "any.xsd".to_string(),
+                    line: 12,
+                    reason: "not supported in xs:complexType".to_string(),
+                }]
+            ),
+            other => panic!("expected any.xsd to skip a construct, got {:?}", other),
+        }
+
+        for file in [
+            "attributes.xsd",
+            "redefine.xsd",
+            "multiple_schemas.wsdl",
+            "wsdl2.wsdl",
+        ] {
+            if let Err(err) = fw.generate_to_string(RESOURCES, file) {
+                panic!("expected {} to be fully supported, got {}", file, err);
+            }
+        }
+    }
+
+    #[test]
+    fn test_generation_report() {
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default());
+        fw.generate_to_string(RESOURCES, "any.xsd")
+            .expect("can not generate");
+        let report = fw.report();
+        assert_eq!(report.skipped().count(), 1);
+        let wildcard = report.approximated().next().expect("no approximation");
+        assert_eq!(wildcard.tag, "xs:complexType");
+        assert_eq!(wildcard.line, 15);
+
+        let json = report.to_json();
+        assert!(json.contains("\"skipped\": [\n"));
+        assert!(json.contains("\"approximated\": [\n"));
+        assert!(json.contains("\"tag\": \"xs:anyAttribute\""));
+        assert!(json.contains("\"file\": \"any.xsd\""));
+
+        // the facets of imported schemas are reported in the schema they are in
+        fw.generate_to_string(RESOURCES, "diamond.xsd")
+            .expect("can not generate");
+        let facet = fw.report().approximated().next().expect("no approximation");
+        assert_eq!(facet.tag, "xs:maxLength");
+        assert_eq!(facet.file, "diamond_shared.xsd");
+
+        let reasons = |file: &str, fw: &mut FileWriter| -> Vec<String> {
+            fw.generate_to_string(RESOURCES, file)
+                .expect("can not generate");
+            fw.report()
+                .approximated()
+                .map(|c| c.reason.clone())
+                .collect()
+        };
+        assert_eq!(
+            reasons("mixed.xsd", &mut fw),
+            vec!["only the last text of mixed content is captured"]
+        );
+        assert_eq!(
+            reasons("simple_content.xsd", &mut fw),
+            vec!["the xs:decimal value is kept as a string"]
+        );
+        assert!(!reasons("pattern.xsd", &mut fw).is_empty());
+
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default())
+            .with_pattern_validation(true);
+        assert!(reasons("pattern.xsd", &mut fw).is_empty());
+    }
+
+    #[test]
+    fn test_namespace_catalog() {
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default());
+        let result = fw
+            .generate_to_string(RESOURCES, "catalog.xsd")
+            .expect("can not generate");
+        assert!(!result.contains("pub struct Address"));
+
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default())
+            .with_catalog_entry("http://example.com/fixtures/address", "catalog_address.xsd");
+        let result = fw
+            .generate_to_string(RESOURCES, "catalog.xsd")
+            .expect("can not generate");
+        assert!(result.contains("pub struct Address"));
+        assert!(result.contains("pub address: Address"));
+    }
+
+    #[test]
+    fn test_namespace_modules() {
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default())
+            .with_namespace_modules(true)
+            .with_namespace_module("http://example.com/fixtures", "shop");
+        let result = fw
+            .generate_to_string(RESOURCES, "multiple_schemas.wsdl")
+            .expect("can not generate");
+
+        // the types of both namespaces keep their local names, in a module of their own
+        let shop = &result[result.find("pub mod shop {").expect("no shop module")..];
+        let billing = &shop[shop.find("pub mod billing {").expect("no billing module")..];
+        assert!(shop[..shop.len() - billing.len()].contains("pub struct Address {"));
+        assert!(billing.contains("pub struct Address {"));
+        assert!(!result.contains("AddressNsi1"));
+
+        // references across namespaces and from the other modules go through the modules
+        assert!(result.contains("pub shipping: Address,"));
+        assert!(result.contains("pub billing: super::billing::Address,"));
+        assert!(billing.contains("pub type Invoice = Address;"));
+        assert!(result.contains("pub order: types::shop::Order,"));
+        assert!(result.contains("pub invoice: types::billing::Invoice,"));
+    }
+
+    #[test]
+    fn test_form_defaults() {
+        let result = prepare_output("form_defaults.xsd");
+
+        // unqualified elements and attributes
+        assert!(result.contains("#[yaserde(rename = \"name\", default)]\n\tpub name: String,"));
+        assert!(result.contains("#[yaserde(rename=\"id\", attribute)]\npub id: Option<String>,"));
+
+        // qualified elements and attributes of the imported schema
+        assert!(result.contains(
+            "#[yaserde(rename = \"street\", prefix = \"nsi1\", default)]\n\tpub street: String,"
+        ));
+        assert!(result.contains(
+            "#[yaserde(prefix = \"nsi1\", rename=\"kind\", attribute)]\npub kind: Option<String>,"
+        ));
+    }
+
+    #[test]
+    fn test_form_overrides() {
+        let result = prepare_output("form_overrides.xsd");
+
+        assert!(result.contains("#[yaserde(rename = \"owner\", prefix = \"tns\", default)]"));
+        assert!(result.contains("#[yaserde(rename = \"note\", default)]\n\tpub note: String,"));
+        assert!(result.contains("#[yaserde(rename=\"id\", attribute)]\npub id: Option<String>,"));
+        assert!(result.contains(
+            "#[yaserde(prefix = \"tns\", rename=\"currency\", attribute)]\npub currency: Option<String>,"
+        ));
+    }
+
+    #[test]
+    fn test_typed_durations() {
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default());
+        let result = fw
+            .generate_to_string(RESOURCES, "duration.xsd")
+            .expect("can not generate");
+        assert!(result.contains("pub timeout: String,"));
+        assert!(!result.contains("pub struct Duration"));
+
+        let mut fw =
+            FileWriter::new_buffer(None, None, DebugBuffer::default()).with_typed_durations(true);
+        let result = fw
+            .generate_to_string(RESOURCES, "duration.xsd")
+            .expect("can not generate");
+        assert!(result.contains("pub timeout: Duration,"));
+        assert!(result.contains("pub struct Duration {"));
+        assert!(result.contains("impl_text_value!(Duration);"));
+    }
+
+    #[test]
+    fn test_chrono_dates() {
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default());
+        let result = fw
+            .generate_to_string(RESOURCES, "dates.xsd")
+            .expect("can not generate");
+        assert!(result.contains("pub created: String,"));
+        assert!(!result.contains("chrono"));
+
+        let mut fw =
+            FileWriter::new_buffer(None, None, DebugBuffer::default()).with_chrono_dates(true);
+        let result = fw
+            .generate_to_string(RESOURCES, "dates.xsd")
+            .expect("can not generate");
+        assert!(result.contains("pub created: DateTime,"));
+        assert!(result.contains("pub day: Date,"));
+        assert!(result.contains("pub opens: Option<Time>,"));
+        assert!(result.contains("pub struct DateTime(pub chrono::DateTime<chrono::Utc>);"));
+    }
+
+    #[test]
+    fn test_binary_types() {
+        let result = prepare_output("binary.xsd");
+
+        assert!(result.contains("pub content: Base64Binary,"));
+        assert!(result.contains("pub checksum: Option<HexBinary>,"));
+        assert!(result.contains("pub struct Base64Binary(pub Vec<u8>);"));
+        assert!(result.contains("f.write_str(&base64::encode(&self.0))"));
+        assert!(result.contains("pub struct HexBinary(pub Vec<u8>);"));
+    }
+
+    #[test]
+    fn test_qname() {
+        let result = prepare_output("qname.xsd");
+
+        assert!(result.contains("pub code: Qname,"));
+        assert!(result.contains("pub struct Qname {"));
+        // a default Qname serializes to an empty text, which must read back
+        assert!(result.contains("if s.is_empty() {\n            return Ok(Qname::default());"));
+    }
+
+    #[test]
+    fn test_namespaces() {
+        let result = prepare_output("namespaces.xsd");
+
+        assert!(result.contains(
+            "#[yaserde(\n\trename = \"shipment\",\n\tnamespace = \"tns: http://example.com/fixtures\","
+        ));
+        assert!(result.contains(
+            "#[yaserde(\n\trename = \"parcel\",\n\tnamespace = \"nsi1: http://example.com/fixtures/other\","
+        ));
+
+        // a schema without a target namespace does not inherit the one of the importer
+        assert!(result.contains("#[yaserde(\n\trename = \"label\",\n)]\npub struct Label {"));
+    }
+
+    #[test]
+    fn test_range_facets() {
+        let result = prepare_output("range_facets.xsd");
+
+        assert!(result.contains("pub struct Age(pub u8);"));
+        assert!(result.contains("\t\tif self.0 > 150 {\n"));
+        assert!(result.contains("pub struct Ratio(pub f64);"));
+        assert!(result.contains("\t\tif self.0 <= 0.0 {\n"));
+        assert!(result.contains("\t\tif self.0 >= 1.0 {\n"));
+        assert!(result.contains("pub age: Age,"));
+        assert!(result.contains("\t\t\tratio: Ratio(0.5),\n"));
+
+        // without range facets, any value is valid
+        assert!(result.contains("pub struct Plain(pub i32);"));
+    }
+
+    #[test]
+    fn test_pattern_validation() {
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default());
+        let result = fw
+            .generate_to_string(RESOURCES, "pattern.xsd")
+            .expect("can not generate");
+        assert!(!result.contains("regex::Regex"));
+
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default())
+            .with_pattern_validation(true);
+        let result = fw
+            .generate_to_string(RESOURCES, "pattern.xsd")
+            .expect("can not generate");
+        assert!(result.contains("pub struct ProductCode(pub String);"));
+        assert!(result.contains("regex::Regex::new(\"^(?:[A-Z]{2}[0-9]{4})$\")"));
+        assert!(result.contains("pub code: ProductCode,"));
+    }
+
+    #[test]
+    fn test_numeric_enumeration() {
+        let result = prepare_output("numeric_enum.xsd");
+        assert!(result.contains("#[repr(i32)]\npub enum StatusCode {"));
+        assert!(result.contains("\tValue1 = 1,\n\tValue2 = 2,\n\tValueMinus1 = -1,\n}"));
+        assert!(result.contains("#[repr(u8)]\npub enum Level {"));
+        assert!(result.contains("pub status: StatusCode,"));
+        assert!(result.contains("status: StatusCode::Value2,"));
+
+        // decimals can not be discriminants
+        assert!(result.contains("#[yaserde(rename = \"1.5\")]"));
+    }
+
+    #[test]
+    fn test_repeated_groups() {
+        let result = prepare_output("repeated_groups.xsd");
+
+        assert!(result.contains("pub struct OrderSequence {"));
+        assert!(result
+            .contains("#[yaserde(flatten, default)]\n\tpub sequence: Repeated<OrderSequence>,"));
+        assert!(result.contains("impl RepeatedGroup for OrderSequence {"));
+        assert!(result.contains(
+            "\t\t\t\"quantity\" => Some((1, false)),\n\t\t\t\"note\" => Some((2, true)),"
+        ));
+
+        // a repeating sequence may be the content of the type
+        assert!(result.contains("pub sequence: Repeated<PointsSequence>,"));
+
+        // every element of a repeating choice starts an occurrence
+        assert!(result.contains("impl RepeatedGroup for LogChoice {"));
+        assert!(result.contains("\t\t\t\"error\" => Some((0, false)),"));
+        assert!(
+            result.contains("pub choice: Repeated<LogChoice>, \n\t#[yaserde(rename = \"total\"")
+        );
+    }
+
+    #[test]
+    fn test_xsi_type() {
+        let result = prepare_output("xsi_type.xsd");
+
+        assert!(result.contains("pub star: AnimalKind,"));
+        assert!(result.contains("pub animal: Vec<AnimalKind>,"));
+        assert!(result.contains(
+            "pub enum AnimalKind {\n\tAnimal(Animal),\n\tDog(Dog),\n\tPuppy(Puppy),\n\tCat(Cat),\n}"
+        ));
+        assert!(result.contains(
+            "\t\t\tSome(\"puppy\") => Puppy::deserialize(reader).map(AnimalKind::Puppy),"
+        ));
+        assert!(result.contains("\t\t\t_ => Animal::deserialize(reader).map(AnimalKind::Animal),"));
+
+        // the attribute is carried by the first derived type of the hierarchy
+        assert!(result.contains("value.dog.xsi_type = Some(\"tns:puppy\".to_string());"));
+        assert!(result.contains("value.xsi_type = Some(\"tns:cat\".to_string());"));
+        assert!(result.contains("pub xsi_type: Option<String>,"));
+        assert_eq!(result.matches("pub xsi_type:").count(), 2);
+    }
+
+    #[test]
+    fn test_fixed_attributes() {
+        let result = prepare_output("fixed_attributes.xsd");
+
+        assert!(!result.contains("pub version:"));
+        assert!(result.contains("#[yaserde(rename=\"id\", attribute)]\npub id: Option<String>,"));
+        assert!(result.contains(
+            "\t#[yaserde(rename = \"$fixed\", flatten, default)]\n\tpub fixed_attributes: Fixed<Message>,\n"
+        ));
+        assert!(result.contains("\tpub const VERSION: &'static str = \"1.2\";\n"));
+        assert!(result.contains("\tpub const MUST_UNDERSTAND: &'static str = \"1\";\n"));
+        assert!(result.contains("impl FixedAttributes for Message {"));
+        assert!(result.contains("\t\t(\"mustUnderstand\", Message::MUST_UNDERSTAND),\n"));
+    }
+
+    #[test]
+    fn test_redefine() {
+        let result = prepare_output("redefine.xsd");
+
+        // an extension of the original adds to its fields
+        assert_eq!(result.matches("pub struct Person {").count(), 1);
+        assert!(result.contains("pub struct Person {\n\t#[yaserde(rename = \"name\", prefix = \"tns\", default)]\n\tpub name: String, \n\t#[yaserde(rename = \"email\""));
+        assert!(!result.contains("pub person: Person, \n}"));
+
+        // a restriction replaces them
+        assert_eq!(result.matches("pub struct Address {").count(), 1);
+        assert!(!result.contains("pub city:"));
+
+        // the facets of both definitions apply
+        assert_eq!(result.matches("pub struct Rating(pub i32);").count(), 1);
+        assert!(result.contains("is less than the minimum of 0"));
+        assert!(result.contains("is greater than the maximum of 5"));
+
+        // types of the redefined schema use the redefined groups
+        assert!(result.contains("pub id: Option<String>,"));
+        assert!(result.contains("pub revision: Option<i32>,"));
+    }
+
+    #[test]
+    fn test_multiple_schemas() {
+        let result = prepare_output("multiple_schemas.wsdl");
+
+        // the first namespace keeps the plain name
+        assert!(result.contains("pub struct Address {\n\t#[yaserde(rename = \"street\""));
+        assert!(result.contains("pub struct AddressNsi1 {\n\t#[yaserde(rename = \"iban\""));
+        assert!(result.contains("namespace = \"nsi1: http://example.com/fixtures/billing\","));
+
+        // references resolve the prefix of the type
+        assert!(result.contains("pub shipping: Address, "));
+        assert!(result.contains("pub billing: AddressNsi1, "));
+        assert!(result.contains("pub type Invoice = AddressNsi1;"));
+    }
+
+    #[test]
+    fn test_soap12_binding() {
+        let result = prepare_output("soap12.wsdl");
+
+        // both bindings share the messages, but each has its own envelopes
+        assert_eq!(result.matches("pub struct SoapPingRequest {").count(), 1);
+        assert!(result.contains("pub struct PingRequestSoapEnvelope {"));
+        assert!(result.contains("pub struct PingRequestSoap12Envelope {"));
+        assert!(
+            result.contains("namespace = \"soapenv: http://schemas.xmlsoap.org/soap/envelope/\",")
+        );
+        assert!(
+            result.contains("namespace = \"soapenv: http://www.w3.org/2003/05/soap-envelope\",")
+        );
+        assert!(result.contains("let __request = PingRequestSoap12Envelope::new(SoapPingRequest {"));
+        assert!(result.contains("let r: PingResponseSoap12Envelope = from_str(&response)"));
+
+        // the action moves into the content type
+        assert!(result.contains("transport: transport::HttpTransport::new(reqwest::Client::new()).with_soap12().with_credentials(credentials),"));
+        assert!(result
+            .contains("if !self.soap12 {\n            req = req.header(\"Soapaction\", action);"));
+        assert!(result.contains(
+            "format!(\"application/soap+xml; charset=utf-8; action=\\\"{}\\\"\", action)"
+        ));
+    }
+
+    #[test]
+    fn test_soap12_fault() {
+        let result = prepare_output("soap12.wsdl");
+
+        // the SOAP 1.2 responses read the fault of their version, which converts to the SoapFault
+        assert!(result.contains("pub struct SoapPingResponse {"));
+        assert!(result.contains("pub struct Soap12PingResponse {"));
+        assert!(result.contains("pub fault: Option<Soap12Fault>,"));
+        assert!(result.contains("pub body: Soap12PingResponse,"));
+        assert!(result.contains("#[yaserde(rename = \"Value\", prefix = \"soapenv\", default)]"));
+        assert!(result.contains("#[yaserde(rename = \"Text\", prefix = \"soapenv\", default)]\n\tpub text: Vec<String>,"));
+        assert!(result.contains("impl From<Soap12Fault> for SoapFault {"));
+        // the detail of the faults that the operations do not declare is kept as it is
+        assert!(result
+            .contains("#[yaserde(rename = \"detail\", default)]\n\tpub detail: Option<XmlValue>,"));
+        assert!(result.contains("#[yaserde(rename = \"Detail\", prefix = \"soapenv\", default)]\n\tpub detail: Option<XmlValue>,"));
+        assert!(result.contains("\t\t\tfault_actor: fault.role,\n\t\t\tdetail: fault.detail,\n"));
+        assert!(result.contains("Err(SoapError::Fault(r.body.fault.map(Into::into)))"));
+        assert!(result.contains("Err(SoapError::Fault(r.body.fault))"));
+    }
+
+    #[test]
+    fn test_soap_actions() {
+        let result = prepare_output("soap12.wsdl");
+
+        assert!(result.contains("impl EchoSoap {\n\t/// The SOAPAction of the Ping operation\n\tpub const PING_ACTION: &'static str = \"http://example.com/fixtures/Ping\";\n}"));
+        assert!(result.contains("impl EchoSoap12 {\n\t/// The SOAPAction of the Ping operation\n"));
+        assert!(
+            result.contains("self.transport.call(&self.url, Self::PING_ACTION, __body).await?;")
+        );
+    }
+
+    #[test]
+    fn test_soap_headers() {
+        let result = prepare_output("soap_header.wsdl");
+
+        assert!(result.contains("pub struct PingRequestHeader {\n\t#[yaserde(rename = \"session\", prefix = \"tns\", default)]\n\tpub session: types::Session, \n}"));
+        assert!(result.contains("pub header: Option<PingRequestHeader>,"));
+        assert!(result.contains("pub header: Option<Header>,"));
+
+        // the trait leaves the headers out
+        assert!(result.contains("\tself.ping_with_header(ping_request, None).await\n"));
+        assert!(result.contains("pub async fn ping_with_header(&self, ping_request: ports::PingRequest, header: Option<PingRequestHeader>)"));
+        assert!(result.contains("__request.header = header;\n"));
+        // serialization failures are reported, rather than panicking
+        assert!(
+            result.contains("let __body = to_string(&__request).map_err(SoapError::XmlError)?;")
+        );
+        assert!(!result.contains("failed to generate xml"));
+    }
+
+    #[test]
+    fn test_rpc_encoded_binding() {
+        let result = prepare_output("rpc.wsdl");
+
+        // the part sits unqualified in an element named after the operation
+        assert!(result.contains("#[yaserde(rename = \"Greet\", prefix = \"rpc\", default)]"));
+        assert!(result.contains(
+            "#[yaserde(prefix = \"xmlns\", rename=\"rpc\", attribute)]\npub xmlns: Option<String>,"
+        ));
+        assert!(result.contains("#[yaserde(rename = \"GreetResponse\", default)]"));
+        assert!(result.contains("xmlns: Option::Some(\"urn:example:greeter\".to_string()),"));
+        assert!(result.contains(
+            "encoding_style: Option::Some(\"http://schemas.xmlsoap.org/soap/encoding/\".to_string()),"
+        ));
+    }
+
+    #[test]
+    fn test_service_ports() {
+        let result = prepare_output("soap12.wsdl");
+
+        assert!(result.contains("pub const PORTS: &'static [(&'static str, &'static str)] = &[(\"EchoSoap12Port\", \"EchoSoap12\"), (\"EchoSoapPort\", \"EchoSoap\"), ];"));
+        assert!(result.contains("pub struct EchoSoapPort {}"));
+        assert!(result
+            .contains("pub const DEFAULT_ENDPOINT: &'static str = \"http://localhost/echo11\";"));
+        assert!(result.contains("bindings::EchoSoap::new(Self::DEFAULT_ENDPOINT, credentials)"));
+        // the service still hands out a client of its first port
+        assert!(result.contains("EchoSoap12Port::new_client(credentials)"));
+    }
+
+    #[test]
+    fn test_one_way_operation() {
+        let result = prepare_output("one_way.wsdl");
+
+        assert!(result.contains("async fn notify (&self, notify_request: NotifyRequest) -> SoapResult<(), Option<SoapFault>>;"));
+        assert!(result.contains(
+            "let status = self.transport.call(&self.url, Self::NOTIFY_ACTION, __body).await?.status;"
+        ));
+        assert!(result.contains("Ok(())"));
+        // there is no response to read
+        assert!(!result.contains("from_str(&response)"));
+    }
+
+    #[test]
+    fn test_several_faults() {
+        let result = prepare_output("faults.wsdl");
+
+        assert!(result.contains("pub enum WithdrawError {\n\tInsufficientFunds(InsufficientFunds),\n\tAccountLocked(AccountLocked),\n"));
+        assert!(result.contains("\tOther(SoapFault),\n"));
+        // the faults are told apart by the element in the detail
+        assert!(result.contains("#[yaserde(rename = \"accountLocked\", default)]\n\tpub account_locked: Option<AccountLocked>,"));
+        assert!(result.contains("impl From<SoapWithdrawFaults> for WithdrawError {"));
+        assert!(result.contains(
+            "async fn withdraw (&self, withdraw_request: WithdrawRequest) -> SoapResult<WithdrawResponse, WithdrawError>;"
+        ));
+        assert!(result.contains("pub fault: Option<ports::SoapWithdrawFaults>,"));
+        assert!(result.contains("Err(SoapError::Fault(r.body.fault.unwrap_or_default().into()))"));
+        // a fault is a fault whatever the status it comes with
+        assert!(result.contains("if status.is_success() && r.body.fault.is_none() {"));
+        assert!(result.contains(
+            "#[yaserde(rename = \"faultactor\", default)]\n\tpub fault_actor: Option<String>, \n\t#[yaserde(rename = \"detail\", default)]\n\tpub detail: Option<WithdrawFaultDetail>,"
+        ));
+        assert!(result.contains("\t\t\tfault_actor: fault.fault_actor,\n"));
+
+        // the faults and errors work with the usual error handling
+        assert!(result.contains("impl<E: std::fmt::Debug> std::error::Error for SoapError<E> {"));
+        assert!(result.contains("impl<E> From<reqwest::Error> for SoapError<E> {"));
+        assert!(result.contains("impl std::error::Error for SoapFault {}"));
+        assert!(result.contains("impl std::error::Error for SoapWithdrawFaults {}"));
+        assert!(result.contains("impl std::error::Error for messages::AccountLockedFault {}"));
+        assert!(result
+            .contains("\t\t\tWithdrawError::AccountLocked(fault) => write!(f, \"{}\", fault),\n"));
+        assert!(result.contains("impl std::error::Error for WithdrawError {}"));
+        assert!(result.contains("impl From<AccountLocked> for WithdrawError {"));
+    }
+
+    #[test]
+    fn test_wsdl_import() {
+        let result = prepare_output("wsdl_import.wsdl");
+
+        // the imported document declares the port type, the importing one binds it
+        assert_eq!(result.matches("pub struct GetQuote {").count(), 1);
+        assert_eq!(result.matches("pub trait Stock {").count(), 1);
+        assert!(result.contains("impl ports::Stock for StockSoap {"));
+        assert_eq!(result.matches("pub struct StockSoap {").count(), 1);
+    }
+
+    #[test]
+    fn test_wsdl_import_cycle() {
+        let result = prepare_output("wsdl_import_interface.wsdl");
+
+        // the imported document binds the port type of the importing one
+        assert_eq!(result.matches("pub struct GetQuote {").count(), 1);
+        assert_eq!(result.matches("pub trait Stock {").count(), 1);
+        assert!(result.contains("impl ports::Stock for StockSoap {"));
+        assert_eq!(result.matches("pub struct StockSoap {").count(), 1);
+        assert!(result.contains("pub struct StockService {"));
+    }
+
+    #[test]
+    fn test_message_parts() {
+        let result = prepare_output("rpc.wsdl");
+
+        assert!(result.contains("pub struct AddRequest {\n\t#[yaserde(rename = \"a\", default)]\n\tpub a: i32, \n\t#[yaserde(rename = \"b\", default)]\n\tpub b: i32, \n}"));
+    }
+
+    #[test]
+    fn test_type_parts() {
+        let result = prepare_output("rpc.wsdl");
+
+        assert!(result.contains("#[yaserde(rename = \"name\", default)]\n\tpub name: String, \n"));
+        assert!(result
+            .contains("#[yaserde(rename = \"person\", default)]\n\tpub person: types::Person, \n"));
+    }
+
+    #[test]
+    fn test_mime_attachments() {
+        let result = prepare_output("mime.wsdl");
+
+        assert!(result.contains("async fn upload (&self, upload_request: UploadRequest, content: Attachment) -> SoapResult<UploadResponse, Option<SoapFault>>;"));
+        assert!(result.contains("async fn download (&self, download_request: DownloadRequest) -> SoapResult<(DownloadResponse, transport::ResponseParts), Option<SoapFault>>;"));
+        // the attachments are no part of the body
+        assert!(result.contains(
+            "pub struct UploadRequest {\n\t#[yaserde(flatten, default)]\n\tpub parameters: types::Upload, \n}"
+        ));
+        assert!(result.contains("self.send_multipart_request(__body, vec![(\"content\", content)], Self::UPLOAD_ACTION)"));
+        assert!(result.contains("let (status, response, attachments) = self.send_multipart_request(__body, vec![], Self::DOWNLOAD_ACTION)"));
+        assert!(result.contains("Ok((r.body.body, attachments))"));
+        assert!(result.contains("pub struct Attachment {"));
+        // the attachments of the response are read from its body as they are asked for
+        assert!(result.contains("pub struct ResponseParts {"));
+        assert!(result.contains("self.transport.post_related(&self.url, action, content_type, body).await"));
+    }
+
+    #[test]
+    fn test_swa_references() {
+        let result = prepare_output("swa_ref.wsdl");
+
+        assert!(result.contains("pub photo: SwaRef, "));
+        assert!(result.contains("pub thumbnails: Vec<SwaRef>, "));
+        assert!(result.contains("pub struct SwaRef(pub String);"));
+        assert!(result.contains(
+            "pub fn resolve<'a>(&self, attachments: &'a [Attachment]) -> Option<&'a Attachment> {"
+        ));
+        assert!(result.contains("pub fn is_referenced_by(&self, href: &str) -> bool {"));
+        assert!(result.contains("pub async fn chunk(&mut self) -> std::io::Result<Option<Vec<u8>>> {"));
+        assert!(!result.contains("impl std::io::Read for Part<'_> {"));
+        // a multipart/related output comes with its attachments, even when the WSDL binds none
+        assert!(result.contains("async fn get_photo (&self, get_photo_request: GetPhotoRequest) -> SoapResult<(GetPhotoResponse, transport::ResponseParts), Option<SoapFault>>;"));
+        assert!(result.contains("let (status, response, attachments) = self.send_multipart_request(__body, vec![], Self::GET_PHOTO_ACTION)"));
+
+        // the parts of the blocking clients are read from the response like any reader
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default())
+            .with_blocking_clients(true);
+        let result = fw.generate_to_string(RESOURCES, "swa_ref.wsdl").unwrap();
+        assert!(result.contains("struct ResponseBody(reqwest::blocking::Response);"));
+        assert!(result.contains("impl std::io::Read for Part<'_> {"));
+        assert!(result.contains("pub fn chunk(&mut self) -> std::io::Result<Option<Vec<u8>>> {"));
+
+        // the namespace of the swaRef needs no schema
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default()).with_strict(true);
+        assert!(fw.generate_to_string(RESOURCES, "swa_ref.wsdl").is_ok());
+    }
+
+    #[test]
+    fn test_mtom() {
+        let result = prepare_output("mtom.wsdl");
+        assert!(result.contains("pub content: Base64Binary, "));
+        assert!(!result.contains("XopBinary"));
+
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default()).with_mtom(true);
+        let result = fw
+            .generate_to_string(RESOURCES, "mtom.wsdl")
+            .expect("can not generate");
+        // only the binary values with an expected content type go in parts of their own
+        assert!(result.contains("pub content: XopBinary, "));
+        assert!(result.contains("pub thumbnail: Option<Base64Binary>, "));
+        assert!(result.contains("pub struct XopBinary {"));
+        assert!(result.contains("pub struct Attachment {"));
+        assert!(result.contains(
+            "let (__body, parts) = to_string_with_parts(&__request).map_err(SoapError::XmlError)?;"
+        ));
+        assert!(result.contains("let (status, response, parts) = self.send_mtom_request(__body, parts, Self::GET_DOCUMENT_ACTION).await"));
+        assert!(result.contains(
+            "let r: GetDocumentResponseSoapEnvelope = from_str_with_parts(&response, parts)"
+        ));
+        assert!(result.contains("format!(\"{}; start-info=\\\"text/xml\\\"\", content_type)"));
+        assert!(!result.contains("send_multipart_request"));
+
+        // the operations with MIME attachments send them as they are
+        let result = fw
+            .generate_to_string(RESOURCES, "mime.wsdl")
+            .expect("can not generate");
+        assert!(result.contains("self.send_multipart_request(__body, vec![(\"content\", content)], Self::UPLOAD_ACTION)"));
+        assert!(result.contains("let (status, response, attachments) = self.send_multipart_request(__body, vec![], Self::DOWNLOAD_ACTION)"));
+        assert!(!result.contains("send_mtom_request"));
+    }
+
+    #[test]
+    fn test_http_bindings() {
+        let result = prepare_output("http_binding.wsdl");
+
+        assert!(result.contains(".get(&url).query(parameters);"));
+        assert!(result.contains(".post(&url).form(parameters);"));
+        assert!(result.contains(
+            "pub const GET_QUOTE_ON_DAY_LOCATION: &'static str = \"/quotes/(symbol)/(day)\";"
+        ));
+        assert!(result.contains("self.send_http_request(Self::GET_QUOTE_LOCATION, &[(\"symbol\", get_quote_in.symbol.to_string())])"));
+        // the parts of a url replacement take the place of their placeholders
+        assert!(result.contains("self.send_http_request(&url_replacement(Self::GET_QUOTE_ON_DAY_LOCATION, &[(\"symbol\", get_quote_on_day_in.symbol.to_string()), (\"day\", get_quote_on_day_in.day.to_string())]), &[])"));
+        assert!(result
+            .contains("fn url_replacement(location: &str, parts: &[(&str, String)]) -> String {"));
+        assert!(result.contains("let body: types::Quote = from_str(&response)"));
+        // no envelopes for plain HTTP
+        assert!(!result.contains("pub mod transport"));
+        assert!(!result.contains("SoapEnvelope"));
+    }
+
+    #[test]
+    fn test_wsdl2_description() {
+        let result = prepare_output("wsdl2.wsdl");
+
+        // the interface makes up the trait, with messages of the elements of the operations
+        assert!(result.contains("pub struct ReserveInput {\n\t#[yaserde(flatten, default)]\n\tpub reserve: types::Reserve, \n}"));
+        assert!(result.contains("pub type RoomUnavailableFault = messages::RoomUnavailableFault;"));
+        assert!(result.contains("\t/// Books a room for a number of nights\n\tasync fn reserve (&self, reserve_input: ReserveInput) -> SoapResult<ReserveOutput, Option<SoapRoomUnavailableFault>>;"));
+        // wsoap:version tells the envelope, SOAP 1.2 unless stated otherwise
+        assert!(result.contains("impl ports::Reservation for ReservationSoap {"));
+        assert!(result.contains("pub struct ReserveInputSoapEnvelope {"));
+        assert!(result.contains("impl ports::Reservation for ReservationSoap12 {"));
+        assert!(result.contains("pub struct ReserveInputSoap12Envelope {"));
+        assert!(result.contains(
+            "pub const RESERVE_ACTION: &'static str = \"http://example.com/fixtures/Reserve\";"
+        ));
+        // endpoints take the place of ports
+        assert!(result.contains(
+            "pub const DEFAULT_ENDPOINT: &'static str = \"http://localhost/reservation12\";"
+        ));
+    }
+
+    #[test]
+    fn test_port_aliases() {
+        let result = prepare_output("port_aliases.wsdl");
+
+        // the first port type keeps the names, the aliases of another message are prefixed
+        assert!(result.contains("pub type LookupOutput = messages::ProductResponse;"));
+        assert!(result.contains("pub type InventoryLookupOutput = messages::StockResponse;"));
+        assert!(result.contains("pub type InventoryLookupFault = messages::OutOfStockFault;"));
+        // aliases of the same message are shared
+        assert!(!result.contains("InventoryLookupInput"));
+        assert!(result.contains("async fn lookup (&self, lookup_input: ports::LookupInput) -> SoapResult<ports::InventoryLookupOutput, Option<ports::SoapInventoryLookupFault>> {"));
+        assert!(result.contains("pub enum InventoryRemoveError {"));
+        assert!(result.contains("pub fault: Option<ports::SoapInventoryRemoveFaults>,"));
+        assert!(
+            result.contains("-> SoapResult<ports::StockResponse, ports::InventoryRemoveError> {")
+        );
+    }
+
+    #[test]
+    fn test_wsdl_documentation() {
+        let result = prepare_output("documentation.wsdl");
+
+        assert!(result
+            .contains("/// Checks whether the service is up\n#[async_trait]\npub trait Monitor {"));
+        assert!(result.contains("\t/// Answers with the message sent\n\tasync fn ping (&self"));
+        assert!(result.contains("/// The monitor over SOAP 1.1\n#[derive(Debug, Clone)]"));
+        assert!(result.contains("/// Pings over SOAP\n\tasync fn ping (&self"));
+        assert!(result.contains(
+            "/// Monitoring of the shop.\n/// Run by operations.\npub struct MonitorService {}"
+        ));
+        assert!(result.contains("/// The MonitorSoapPort port of MonitorService, bound to bindings::MonitorSoap\n///\n/// The production endpoint\npub struct MonitorSoapPort {}"));
+    }
+
+    #[test]
+    fn test_body_parts() {
+        let result = prepare_output("body_parts.wsdl");
+
+        // the trace goes in the header, the audit nowhere
+        assert!(result.contains(
+            "pub struct PlaceRequest {\n\t#[yaserde(flatten, default)]\n\tpub body: types::Order, \n}"
+        ));
+        assert!(result.contains("pub struct PlaceRequestHeader {"));
+        assert!(!result.contains("pub audit: "));
+        assert!(result.contains("pub encoding_style: Option<String>,"));
+        assert!(result.contains("encoding_style: Option::None,"));
+    }
+
+    #[test]
+    fn test_imports() {
+        // the types of a schema do not log, nor implement async traits
+        let result = prepare_output("attributes.xsd");
+        assert!(
+            result.contains("use yaserde::{YaSerialize, YaDeserialize};\nuse yaserde_derive::*;\n")
+        );
+        assert!(!result.contains("use log::"));
+        assert!(!result.contains("async_trait"));
+        assert!(!result.contains("use yaserde::de::from_str;"));
+        assert!(result.contains("pub mod messages {\nuse super::*;\n"));
+
+        let result = prepare_output("faults.wsdl");
+        assert!(result.contains("use log::debug;\n"));
+        assert!(result.contains(
+            "pub mod bindings {\nuse yaserde::{YaSerialize, YaDeserialize};\nuse yaserde::de::from_str;\nuse async_trait::async_trait;\nuse yaserde::ser::to_string;\nuse super::*;\n"
+        ));
+        assert!(result.contains("pub mod services {\nuse super::*;\n"));
+    }
+
+    #[test]
+    fn test_blocking_clients() {
+        let result = prepare_output("soap_header.wsdl");
+        assert!(result.contains("use async_trait::async_trait;"));
+        assert!(result.contains("pub struct HttpTransport {\n    client: reqwest::Client,"));
+
+        let mut fw =
+            FileWriter::new_buffer(None, None, DebugBuffer::default()).with_blocking_clients(true);
+        let result = fw
+            .generate_to_string(RESOURCES, "soap_header.wsdl")
+            .expect("can not generate");
+        assert!(!result.contains("async"));
+        assert!(!result.contains(".await"));
+        assert!(result.contains("client: reqwest::blocking::Client,"));
+        assert!(result.contains("pub fn call<E>(&self, endpoint: &str, action: &str, body: String) -> Result<Response, SoapError<E>> {"));
+        assert!(result.contains("\tself.ping_with_header(ping_request, None)\n"));
+    }
+
+    #[test]
+    fn test_blocking_feature() {
+        let mut fw =
+            FileWriter::new_buffer(None, None, DebugBuffer::default()).with_blocking_feature(true);
+        let result = fw
+            .generate_to_string(RESOURCES, "faults.wsdl")
+            .expect("can not generate");
+        let (async_clients, blocking_clients) = result
+            .split_once("#[cfg(feature = \"blocking\")]\npub mod blocking {\n")
+            .expect("no blocking module");
+        assert!(async_clients.contains("async fn withdraw (&self, withdraw_request: WithdrawRequest) -> SoapResult<WithdrawResponse, WithdrawError>;"));
+        assert!(blocking_clients.contains("\tfn withdraw (&self, withdraw_request: WithdrawRequest) -> SoapResult<WithdrawResponse, WithdrawError>;"));
+        assert!(blocking_clients.contains("client: reqwest::blocking::Client,"));
+        assert!(blocking_clients.contains("pub mod transport {"));
+        assert!(!blocking_clients.contains(".await"));
+        assert!(blocking_clients.contains("pub mod services {"));
+
+        // the faults of the port types are shared
+        assert!(blocking_clients.contains("pub mod ports {\n"));
+        assert!(blocking_clients.contains("pub use super::super::ports::*;\n"));
+        assert_eq!(result.matches("pub enum WithdrawError {").count(), 1);
+
+        // schemas have no clients
+        let result = fw
+            .generate_to_string(RESOURCES, "attributes.xsd")
+            .expect("can not generate");
+        assert!(!result.contains("pub mod blocking"));
+    }
+
+    #[test]
+    fn test_server_dispatchers() {
+        let result = prepare_output("faults.wsdl");
+        assert!(!result.contains("Dispatcher"));
+
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default())
+            .with_server_dispatchers(true);
+        let result = fw
+            .generate_to_string(RESOURCES, "faults.wsdl")
+            .expect("can not generate");
+        assert!(result.contains("pub enum DispatchError {"));
+        assert!(result.contains("fn body_element(envelope: &str) -> Option<String> {"));
+        assert!(result.contains("impl<T: ports::Account + Send + Sync> AccountSoapDispatcher<T> {"));
+        assert!(result.contains("if action.map_or(element.as_deref() == Some(\"Withdraw\"), |a| a == AccountSoap::WITHDRAW_ACTION) {"));
+        assert!(result.contains("let result = self.service.withdraw(request.body.body).await;"));
+        // the error of the service goes back as the faults of the operation
+        assert!(result.contains("impl From<WithdrawError> for SoapWithdrawFaults {"));
+        assert!(result.contains("WithdrawError::InsufficientFunds(fault) => detail.insufficient_funds = Option::Some(fault),"));
+        assert!(result.contains("fault: Option::Some(fault.into())"));
+    }
+
+    #[test]
+    fn test_mocks() {
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default()).with_mocks(true);
+        let result = fw
+            .generate_to_string(RESOURCES, "mime.wsdl")
+            .expect("can not generate");
+
+        assert!(result.contains(
+            "#[cfg(any(test, feature = \"mocks\"))]\n#[derive(Default)]\npub struct MockFiles {"
+        ));
+        assert!(result.contains("\tpub upload: Option<Box<dyn Fn(UploadRequest, Attachment) -> SoapResult<UploadResponse, Option<SoapFault>> + Send + Sync>>,"));
+        assert!(result.contains("\tpub fn returning_upload(self, output: UploadResponse) -> Self {\n\t\tself.on_upload(move |_, _| Ok(output.clone()))\n\t}"));
+        // the attachments of the output are given in memory
+        assert!(result.contains("pub fn returning_download(self, output: (DownloadResponse, Vec<Attachment>)) -> Self {\n\t\tself.on_download(move |_| Ok((output.0.clone(), transport::ResponseParts::from(output.1.clone()))))\n\t}"));
+        assert!(result.contains("#[async_trait]\nimpl Files for MockFiles {"));
+        assert!(result.contains("\t\t\tSome(answer) => answer(upload_request, content),"));
+    }
+
+    #[test]
+    fn test_ws_security() {
+        let result = prepare_output("soap_header.wsdl");
+        assert!(!result.contains("pub mod security {"));
+        assert!(!result.contains("self.security"));
+
+        let mut fw =
+            FileWriter::new_buffer(None, None, DebugBuffer::default()).with_ws_security(true);
+        let result = fw
+            .generate_to_string(RESOURCES, "soap_header.wsdl")
+            .expect("can not generate");
+        assert!(result.contains("pub mod security {\nuse super::*;\n"));
+        assert!(result.contains("pub struct UsernameToken {"));
+        assert!(result.contains("url: String,\n                security: security::Security\n"));
+        assert!(result.contains(
+            "pub fn with_username_token(mut self, token: security::UsernameToken) -> Self {"
+        ));
+        // the header goes into every envelope the binding sends
+        assert!(result.contains("let __body = self.security.add_to(__body).map_err(SoapError::SecurityError)?;\n        let __response = self.transport.call(&self.url, Self::PING_ACTION, __body).await?;"));
+
+        // the timestamps of responses are checked before they are read
+        assert!(result
+            .contains("pub fn with_timestamp(mut self, timestamp: security::Timestamp) -> Self {"));
+        assert!(result.contains("pub struct Timestamp {"));
+        assert!(result.contains("\n    SecurityError(std::string::String),\n"));
+        assert!(result.contains(
+            "self.security.check_response(&response).map_err(SoapError::SecurityError)?;\n        let r: PingResponseSoapEnvelope = from_str(&response)"
+        ));
+
+        assert!(!result.contains("pub struct Signature {"));
+
+        // the signature signs the envelopes once the header is in
+        let mut fw =
+            FileWriter::new_buffer(None, None, DebugBuffer::default()).with_ws_signature(true);
+        let result = fw
+            .generate_to_string(RESOURCES, "soap_header.wsdl")
+            .expect("can not generate");
+        assert!(result.contains("pub struct Signature {"));
+        assert!(result.contains("\n    pub signature: Option<Signature>,\n}"));
+        assert!(result
+            .contains("pub fn with_signature(mut self, signature: security::Signature) -> Self {"));
+        assert!(result.contains(
+            "Some(signature) => signature.sign(add_header(with_body_id(envelope), &self.to_xml())),"
+        ));
+        assert!(result.contains(
+            "pub fn canonicalize(xml: &str, id: &str) -> Result<Option<String>, String> {"
+        ));
+
+        // plain HTTP bindings have no envelope for the header
+        let result = fw
+            .generate_to_string(RESOURCES, "http_binding.wsdl")
+            .expect("can not generate");
+        assert!(!result.contains("security"));
+    }
 
-        // todo: convert this to function
-        let mut e = Element::new(&func_name, ElementType::Static);
-        e.set_content(
-            format!(
-                "\tasync fn {} (&self, {}) {} {{\n",
-                func_name, input_template, output_template,
-            )
-            .as_str(),
+    #[test]
+    fn test_ws_addressing() {
+        let result = prepare_output("soap_header.wsdl");
+        assert!(!result.contains("pub mod addressing {"));
+
+        let mut fw =
+            FileWriter::new_buffer(None, None, DebugBuffer::default()).with_ws_addressing(true);
+        let result = fw
+            .generate_to_string(RESOURCES, "soap_header.wsdl")
+            .expect("can not generate");
+        assert!(result.contains(
+            "pub mod addressing {\nuse yaserde::{YaSerialize, YaDeserialize};\nuse super::*;\n"
+        ));
+        assert!(result.contains("pub struct Addressing {"));
+        assert!(!result.contains("fn add_header(envelope: String, header: &str) -> String {"));
+        assert!(result.contains(
+            "pub fn with_addressing(mut self, addressing: addressing::Addressing) -> Self {"
+        ));
+        // the headers are typed, and go with the others in the header of the envelopes
+        assert!(result.contains("#[yaserde(rename = \"MessageID\", prefix = \"wsa\", default)]\n\tpub message_id: Option<AttributedUri>, \n"));
+        // they are read by hand from among the other headers
+        assert!(result.contains("#[derive(Debug, Default, YaSerialize, Clone, PartialEq)]\npub struct AddressingHeaders {"));
+        assert!(result.contains("pub struct Header {\n\t#[yaserde(flatten, default)]\n\tpub addressing: Option<addressing::AddressingHeaders>, \n}"));
+        assert!(result.contains("pub session: Option<types::Session>, \n\t#[yaserde(flatten, default)]\n\tpub addressing: Option<addressing::AddressingHeaders>, \n}"));
+        // the action of the headers is the SOAPAction of the operation
+        assert!(result.contains("let mut __request = PingRequestSoapEnvelope::new("));
+        assert!(result.contains("__request.header = header;\n        if let Some(addressing) = &self.addressing {\n            __request.header.get_or_insert_with(Default::default).addressing =\n                Option::Some(addressing.headers(&self.url, Self::PING_ACTION));\n        }"));
+        assert!(!result.contains("pub mod security {"));
+
+        // the headers are in before the signature is made
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default())
+            .with_ws_addressing(true)
+            .with_ws_signature(true);
+        let result = fw
+            .generate_to_string(RESOURCES, "soap_header.wsdl")
+            .expect("can not generate");
+        assert_eq!(
+            result
+                .matches("fn add_header(envelope: String, header: &str) -> String {")
+                .count(),
+            1
         );
+        assert!(result.contains(
+            "Option::Some(addressing.headers(&self.url, Self::PING_ACTION));\n        }\n        \n        let __body = to_string(&__request).map_err(SoapError::XmlError)?;\n        let __body = self.security.add_to(__body)"
+        ));
 
-        if has_input && has_output {
-            self.print_reqwest_body(
-                input_name.as_str(),
-                input_type.as_str(),
-                output_type.as_str(),
-                &operation_name,
-                some_soap_action,
-                &mut e,
-            )
-        }
-
-        e.append_content("}");
+        let result = fw
+            .generate_to_string(RESOURCES, "http_binding.wsdl")
+            .expect("can not generate");
+        assert!(!result.contains("addressing"));
+    }
 
-        if !self.have_seen_type(&e.name, parent) {
-            parent.add(e);
+    #[test]
+    fn test_errors() {
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default());
+        match fw.generate_to_string(RESOURCES, "missing.xsd") {
+            Err(ZeepError::Io { path, .. }) => assert!(path.ends_with("missing.xsd")),
+            other => panic!("expected an io error, got {:?}", other),
         }
 
-        if let Some(soap_wrapper_in) = soap_wrapper_in {
-            if !self.have_seen_type(&input_soap_name, module) {
-                let mut e_in = Element::new(&input_soap_name, ElementType::Static);
-                e_in.set_content(&soap_wrapper_in);
-                module.add(e_in);
-            }
+        match fw.generate_to_string(RESOURCES, "malformed.xsd") {
+            Err(ZeepError::Xml { line, .. }) => assert_eq!(line, 4),
+            other => panic!("expected an xml error, got {:?}", other),
         }
 
-        if let Some(soap_wrapper_out) = soap_wrapper_out {
-            if !self.have_seen_type(&output_soap_name, module) {
-                let mut e_out = Element::new(&output_soap_name, ElementType::Static);
-                e_out.set_content(&soap_wrapper_out);
-                module.add(e_out);
-            }
+        match fw.generate_to_string(RESOURCES, "unresolved_operation.wsdl") {
+            Err(err @ ZeepError::Unresolved { .. }) => assert!(err
+                .to_string()
+                .starts_with("unresolved_operation.wsdl:26: unresolved binding operation Pong")),
+            other => panic!("expected an unresolved reference, got {:?}", other),
         }
     }
 
-    fn print_reqwest_body(
-        &mut self,
-        input_variable: &str,
-        input_type: &str,
-        output_type: &str,
-        operation_name: &str,
-        soap_action: Option<&str>,
-        parent: &mut Element,
-    ) {
-        let action = match soap_action {
-            None => match self.target_name_space.last() {
-                None => "undefined".to_string(),
-                Some(tns) => format!("{}/{}", tns, operation_name),
-            },
-            Some(sa) => sa.to_string(),
-        };
+    #[test]
+    fn test_formatted_output() {
+        let mut fw =
+            FileWriter::new_buffer(None, None, DebugBuffer::default()).with_formatted_output(true);
+        let result = fw
+            .generate_to_string(RESOURCES, "faults.wsdl")
+            .expect("can not generate");
+        assert!(!result.contains('\t'));
+        assert!(result.contains("use yaserde::{YaSerialize, YaDeserialize};\n"));
+        assert!(result
+            .contains("pub struct SoapFault {\n    #[yaserde(rename = \"faultcode\", default)]\n"));
+        assert_eq!(
+            result,
+            fw.generate_to_string(RESOURCES, "faults.wsdl")
+                .expect("can not generate")
+        );
+    }
 
-        let xmlns = match self.target_name_space.last() {
-            None => "Option::None".to_string(),
-            Some(tns) => format!("Option::Some(\"{}\".to_string())", tns),
+    #[test]
+    fn test_module_order() {
+        let generate = || {
+            FileWriter::new_buffer(None, None, DebugBuffer::default())
+                .generate_to_string(RESOURCES, "faults.wsdl")
+                .expect("can not generate")
         };
+        let result = generate();
+        let positions: Vec<usize> = [
+            "pub mod types {",
+            "pub mod messages {",
+            "pub mod ports {",
+            "pub mod bindings {",
+            "pub mod services {",
+        ]
+        .iter()
+        .map(|module| result.find(module).expect("no module"))
+        .collect();
+        assert!(positions.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(result, generate());
+    }
 
-        parent.append_content(
-            format!(
-                r#"
-        let __request = {1}SoapEnvelope::new(Soap{1} {{
-            body: {0},
-            xmlns: {4},
-        }});            
-        
-        let (status, response) = self.send_soap_request(&__request, "{3}")
-                    .await
-                    .map_err(|err| {{
-                        SoapError::RequestError(err)
-                    }})?;
-
-        let r: {2}SoapEnvelope = from_str(&response).map_err(|err| {{
-                        SoapError::XmlError(err)
-                    }})?;
-        "#,
-                input_variable, input_type, output_type, action, xmlns
-            )
-            .as_str(),
-        );
-
-        parent.append_content(
-            r#"if status.is_success() {
-            Ok(r.body.body)
-        } else {
-            Err(SoapError::Fault(r.body.fault))
-        }"#,
+    #[test]
+    fn test_output_dir() {
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default());
+        let files = fw
+            .generate_to_files(RESOURCES, "faults.wsdl")
+            .expect("can not generate");
+        let names: Vec<&str> = files.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "mod.rs",
+                "types.rs",
+                "messages.rs",
+                "ports.rs",
+                "bindings.rs",
+                "services.rs",
+                "transport.rs"
+            ]
         );
+        assert!(files[0].1.contains("pub struct SoapFault {"));
+        assert!(files[0].1.contains("pub mod types;\npub mod messages;\n"));
+        assert!(!files[0].1.contains("pub mod types {"));
+        assert!(files[3].1.contains("pub trait Account {"));
+
+        let dir = test_dir("output_dir");
+        let mut fw = FileWriter::new_dir(&dir, None, None);
+        fw.process_file(RESOURCES, "faults.wsdl")
+            .expect("can not generate");
+        for (name, content) in files {
+            let written = std::fs::read_to_string(dir.join(name)).expect("can not read module");
+            assert_eq!(written, content);
+        }
+        std::fs::remove_dir_all(dir).expect("can not clean up");
     }
 
-    // WSDL Services
-
-    fn print_service(&mut self, node: &Node) {
-        let parent = self.pick_section(SERVICES_MOD);
-        let mut _parent = &mut *parent.deref().borrow_mut();
-
-        let element_name = match self.get_some_attribute(node, "name") {
-            None => return,
-            Some(n) => n,
-        };
+    #[test]
+    fn test_scaffold() {
+        let dir = test_dir("scaffold");
+        // the crate is named after the directory
+        let name = format!("zeep_test_scaffold_{}", std::process::id());
+        let mut fw = FileWriter::new_scaffold(&dir, None, None).with_chrono_dates(true);
+        fw.process_file(RESOURCES, "faults.wsdl")
+            .expect("can not generate");
+
+        let lib = std::fs::read_to_string(dir.join("src/lib.rs")).expect("can not read lib.rs");
+        assert!(lib.contains("pub mod types;\npub mod messages;\n"));
+        assert!(dir.join("src/bindings.rs").exists() && !dir.join("src/mod.rs").exists());
+        let manifest =
+            std::fs::read_to_string(dir.join("Cargo.toml")).expect("can not read manifest");
+        assert!(manifest.starts_with(&format!(
+            "[package]\nname = \"{}\"\n",
+            name.replace('_', "-")
+        )));
+        assert!(manifest.contains("yaserde = \"0.7.1\"\n"));
+        assert!(manifest.contains("chrono = \"0.4\"\n"));
+        assert!(manifest.contains("base64 = \"0.13\"\n"));
+        assert!(manifest.contains("\n[dev-dependencies]\ntokio = "));
+        let example = std::fs::read_to_string(dir.join("examples").join(EXAMPLE_FILE))
+            .expect("can not read example");
+        assert!(example.contains(&format!("use {}::ports::Account;\n", name)));
+        assert!(example.contains("let client = AccountService::new_client(None);\n"));
+        assert!(example.contains("client.withdraw(Default::default()).await;\n"));
+
+        // the manifest and the example are edited by hand, so they are not written again
+        std::fs::write(dir.join("Cargo.toml"), "edited").expect("can not edit manifest");
+        let mut fw = FileWriter::new_scaffold(&dir, None, None).with_blocking_clients(true);
+        fw.process_file(RESOURCES, "faults.wsdl")
+            .expect("can not generate");
+        let manifest =
+            std::fs::read_to_string(dir.join("Cargo.toml")).expect("can not read manifest");
+        assert_eq!(manifest, "edited");
+        std::fs::remove_dir_all(dir).expect("can not clean up");
+    }
 
-        let some_documentation = node
-            .children()
-            .find(|c| c.has_tag_name("documentation"))
-            .map(|c| c.text().unwrap_or_default());
+    #[test]
+    fn test_extension_files() {
+        let dir = test_dir("extension_files");
+        let mut fw = FileWriter::new_dir(&dir, None, None).with_extension_files(true);
+        fw.process_file(RESOURCES, "faults.wsdl")
+            .expect("can not generate");
+        let types = std::fs::read_to_string(dir.join("types.rs")).expect("can not read module");
+        assert!(types.ends_with("\ninclude!(\"types_ext.rs\");\n"));
+        let mod_rs = std::fs::read_to_string(dir.join("mod.rs")).expect("can not read module");
+        assert!(!mod_rs.contains("include!"));
+        assert!(!dir.join("mod_ext.rs").exists());
+
+        // the extension files are created once, and kept on the next run
+        let extension = dir.join("ports_ext.rs");
+        assert!(std::fs::read_to_string(&extension)
+            .expect("can not read extension")
+            .starts_with("// Hand-written code of the ports module"));
+        std::fs::write(&extension, "impl Account for () {}\n").expect("can not write extension");
+        fw.process_file(RESOURCES, "faults.wsdl")
+            .expect("can not generate");
+        assert_eq!(
+            std::fs::read_to_string(&extension).expect("can not read extension"),
+            "impl Account for () {}\n"
+        );
+        std::fs::remove_dir_all(dir).expect("can not clean up");
+    }
 
-        let some_port = node.children().find(|c| c.has_tag_name("port"));
+    #[test]
+    fn test_model_json() {
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default());
+        fw.generate_to_string(RESOURCES, "faults.wsdl")
+            .expect("can not generate");
+        let model: serde_json::Value =
+            serde_json::from_str(&fw.model_json()).expect("the model is no JSON");
+
+        let operation = &model["port_types"][0]["operations"][0];
+        assert_eq!(operation["name"], "Withdraw");
+        assert_eq!(operation["input"]["name"], "WithdrawRequest");
+        assert_eq!(
+            operation["input"]["namespace"],
+            "http://example.com/fixtures"
+        );
+        assert_eq!(operation["faults"][1]["name"], "AccountLocked");
+        assert_eq!(model["bindings"][0]["protocol"]["soap"], "1.1");
+        assert_eq!(model["messages"].as_array().map(Vec::len), Some(4));
+        assert!(model["services"][0]["ports"][0]["address"].is_string());
+
+        let withdraw = &model["types"][0];
+        assert_eq!(withdraw["name"], "Withdraw");
+        assert_eq!(withdraw["kind"], "struct");
+        assert!(withdraw["fields"][0]["type"].is_string());
+        assert_eq!(withdraw["fields"][0]["attribute"], false);
+    }
 
-        let port = match some_port {
-            None => return,
-            Some(p) => p,
-        };
+    #[test]
+    fn test_serde_backend() {
+        let mut fw =
+            FileWriter::new_buffer(None, None, DebugBuffer::default()).with_serde_backend(true);
+        let result = fw
+            .generate_to_string(RESOURCES, "attributes.xsd")
+            .expect("can not generate");
+        assert!(!result.contains("yaserde"));
+        assert!(result.contains("pub fn from_str<T: serde::de::DeserializeOwned>"));
+        // the names of the qualified schema are written with their prefix, and read without it
+        assert!(result.contains(
+            "#[derive(Debug, Default, Serialize, Deserialize, Clone)]\n#[serde(rename(serialize = \"tns:book\", deserialize = \"book\"))]\n"
+        ));
+        assert!(result.contains("#[serde(rename = \"@id\", default)]\npub id: i32,\n"));
+        assert!(result.contains(
+            "#[serde(rename(serialize = \"@xsi:type\", deserialize = \"@type\"), default, skip_serializing_if = \"Option::is_none\")]\npub xsi_type: Option<String>,\n"
+        ));
 
-        let some_binding = port
-            .attributes()
-            .iter()
-            .find(|a| a.name() == "binding")
-            .map(|a| self.fetch_type(a.value()));
+        // the element declares the namespaces of the prefixes
+        assert!(result.contains("pub struct Xmlns<T, const N: usize>"));
+        assert!(result.contains(
+            "pub struct Book {\n\t#[serde(rename = \"@xmlns:tns\", default)]\n\tpub xmlns_tns: Xmlns<Book, 0>,\n\t#[serde(rename = \"@xmlns:xsi\", default)]\n\tpub xmlns_xsi: Xmlns<Book, 1>,\n"
+        ));
+        assert!(result.contains(
+            "impl XmlNamespaces for Book {\n\tconst NAMESPACES: &'static [(&'static str, &'static str)] = &[\n\t\t(\"tns\", \"http://example.com/fixtures\"),\n\t\t(\"xsi\", \"http://www.w3.org/2001/XMLSchema-instance\"),\n\t];\n}\n"
+        ));
 
-        let binding = match some_binding {
-            None => return,
-            Some(b) => b,
-        };
+        // the fields of the base type are copied into the extension
+        let book = &result[result.find("pub struct Book {").unwrap()..];
+        let book = &book[..book.find('}').unwrap()];
+        assert!(!book.contains("flatten"));
+        assert!(book.contains(
+            "\t#[serde(rename(serialize = \"tns:name\", deserialize = \"name\"), default)]\n\tpub name: String, \n"
+        ));
+        assert!(book.contains("pub weight: Option<f64>,\n"));
 
-        let some_address = port.children().find(|c| c.has_tag_name("address"));
+        // the local elements of an unqualified schema have no prefix
+        let result = fw
+            .generate_to_string(RESOURCES, "form_defaults.xsd")
+            .expect("can not generate");
+        assert!(result.contains("\t#[serde(rename = \"name\", default)]\n\tpub name: String, \n"));
+        assert!(result.contains(
+            "\t#[serde(rename(serialize = \"nsi1:street\", deserialize = \"street\"), default)]\n"
+        ));
 
-        let address = match some_address {
-            None => return,
-            Some(a) => a,
-        };
+        // the occurrences of a repeating choice are a list of its enum
+        let result = fw
+            .generate_to_string(RESOURCES, "choice.xsd")
+            .expect("can not generate");
+        assert!(result.contains(
+            "\t#[serde(rename = \"$value\", default)]\n\tpub choice: Vec<ContactsChoice>, \n"
+        ));
+        assert!(result.contains(
+            "\t#[serde(rename(serialize = \"tns:phone\", deserialize = \"phone\"))]\n\tPhone(String),\n"
+        ));
+        assert!(!result.contains("Repeated"));
 
-        let location = address
-            .attributes()
-            .iter()
-            .find(|a| a.name() == "location")
-            .map(|a| a.value())
-            .unwrap_or_default();
+        // the elements next to a choice are read by their prefixed names as well, which quick-xml
+        // hands to the choice otherwise
+        let result = fw
+            .generate_to_string(RESOURCES, "substitution_group.xsd")
+            .expect("can not generate");
+        assert!(result.contains(
+            "\t#[serde(rename(serialize = \"tns:label\", deserialize = \"label\"), default, alias = \"tns:label\")]\n\tpub label: String, \n"
+        ));
 
-        let struct_name = to_pascal_case(element_name);
+        let result = fw
+            .generate_to_string(RESOURCES, "any.xsd")
+            .expect("can not generate");
+        assert!(!result.contains("RawXml"));
 
-        if self.have_seen_type(&struct_name, _parent) {
-            return;
+        for file in ["repeated_groups.xsd", "faults.wsdl"] {
+            match fw.generate_to_string(RESOURCES, file) {
+                Err(ZeepError::Unsupported { .. }) => {}
+                other => panic!("expected {} to be unsupported, got {:?}", file, other),
+            }
         }
+    }
 
-        let mut e = Element::new(&struct_name, ElementType::Static);
-
-        if let Some(doc) = some_documentation {
-            e.comment = Option::Some(doc.to_string());
-        }
+    #[test]
+    fn test_extra_derives() {
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default())
+            .with_derives(&["PartialEq", "Default", "Clone"])
+            .with_type_derives("Item", &["PartialEq", "PartialOrd"]);
+        let result = fw
+            .generate_to_string(RESOURCES, "attributes.xsd")
+            .expect("can not generate");
+        assert!(result.contains(
+            "#[derive(Debug, Default, YaSerialize, YaDeserialize, Clone, PartialEq, PartialOrd)]\n#[yaserde(\n\trename = \"item\""
+        ));
+        assert!(result.contains(
+            "#[derive(Debug, Default, YaSerialize, YaDeserialize, Clone, PartialEq)]\n#[yaserde(\n\trename = \"book\""
+        ));
+    }
 
-        e.set_content(
-            format!(
-                r#"pub struct {0} {{}}
-               impl {0} {{
-                "#,
-                struct_name,
-            )
-            .as_str(),
-        );
+    #[test]
+    fn test_renames_and_type_mappings() {
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default())
+            .with_type_rename("item", "Article")
+            .with_type_rename("Book", "Volume")
+            .with_type_mapping("int", "u16")
+            .with_type_mapping("double", "f32");
+        let result = fw
+            .generate_to_string(RESOURCES, "attributes.xsd")
+            .expect("can not generate");
+
+        // the documents keep their names, the Rust code uses the new ones everywhere
+        assert!(result.contains("#[yaserde(\n\trename = \"item\""));
+        assert!(result.contains("pub struct Article {"));
+        assert!(result.contains("pub struct Volume {"));
+        assert!(result.contains("\tpub article: Article, \n"));
+        assert!(!result.contains("Item"));
+        assert!(result.contains("pub id: u16,"));
+        assert!(result.contains("pub weight: Option<f32>,"));
+    }
 
-        e.append_content(
-            format!(
-                r#"
-            pub fn new_client(credentials: Option<(String, String)>) -> {2}::{1} {{
-                {2}::{1}::new("{0}", credentials)
-            }}
-        "#,
-                location,
-                to_pascal_case(binding.as_str()),
-                BINDINGS_MOD,
-            )
-            .as_str(),
+    #[test]
+    fn test_builders() {
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default()).with_builders(true);
+        let result = fw
+            .generate_to_string(RESOURCES, "attributes.xsd")
+            .expect("can not generate");
+        assert!(
+            result.contains("\tpub fn builder() -> BookBuilder {\n\t\tBookBuilder::default()\n")
         );
+        assert!(result.contains("pub struct BookBuilder {\n\tvalue: Book,\n}\n"));
+        assert!(result.contains(
+            "\tpub fn item(mut self, value: impl Into<Item>) -> Self {\n\t\tself.value.item = value.into();\n"
+        ));
+        assert!(result.contains(
+            "\tpub fn weight(mut self, value: impl Into<f64>) -> Self {\n\t\tself.value.weight = Some(value.into());\n"
+        ));
+        assert!(result.contains("impl From<ItemBuilder> for Item {\n"));
 
-        e.append_content("}\n");
+        // a field named like the function of Default gets a setter of another name
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default()).with_builders(true);
+        let result = fw
+            .generate_to_string(RESOURCES, "any_type.xsd")
+            .expect("can not generate");
+        assert!(result.contains(
+            "\tpub fn set_default(mut self, value: impl Into<XmlValue>) -> Self {\n\t\tself.value.default = value.into();\n"
+        ));
+        assert!(!result.contains("pub fn default(mut self"));
 
-        _parent.add(e);
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default());
+        let result = fw
+            .generate_to_string(RESOURCES, "attributes.xsd")
+            .expect("can not generate");
+        assert!(!result.contains("Builder"));
     }
-}
 
-#[cfg(test)]
-mod test_xsd {
-    use super::*;
-    use crate::debug::DebugBuffer;
-    use std::io::Read;
+    #[test]
+    fn test_round_trip_tests() {
+        let mut fw =
+            FileWriter::new_buffer(None, None, DebugBuffer::default()).with_round_trip_tests(true);
+        let result = fw
+            .generate_to_string(RESOURCES, "attributes.xsd")
+            .expect("can not generate");
+        assert!(result.contains("#[cfg(test)]\nmod round_trip_tests {\n\tuse super::*;\n"));
+        assert!(result.contains(
+            "\t#[test]\n\tfn round_trip_book() {\n\t\tlet xml = to_string(&Book::default()).expect(\"can not serialize\");\n\t\tlet value: Book = from_str(&xml).expect(\"can not deserialize\");\n\t\tassert_eq!(to_string(&value), Ok(xml));\n\t}\n"
+        ));
 
-    fn prepare_output(ns_prefix: Option<String>, default_ns: Option<String>) -> String {
-        let mut buffer = DebugBuffer::default();
-        let mut fw = FileWriter::new_buffer(ns_prefix, default_ns, buffer.clone());
-        fw.process_file("resources/smgr/", "agentCommProfile.xsd")
-            .expect("can not open xsd");
+        // the structs of the namespace modules are tested by their path
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default())
+            .with_round_trip_tests(true)
+            .with_namespace_modules(true)
+            .with_catalog_entry("http://example.com/fixtures/address", "catalog_address.xsd");
+        let result = fw
+            .generate_to_string(RESOURCES, "catalog.xsd")
+            .expect("can not generate");
+        assert!(result.contains("\tfn round_trip_address_address() {\n\t\tlet xml = to_string(&address::Address::default())"));
+
+        // a default value that does not match a pattern would fail to deserialize
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default())
+            .with_round_trip_tests(true)
+            .with_pattern_validation(true);
+        let result = fw
+            .generate_to_string(RESOURCES, "pattern.xsd")
+            .expect("can not generate");
+        assert!(result.contains("\t// Product is not tested, its default value may not satisfy the facets of its fields\n"));
+        assert!(!result.contains("fn round_trip_product()"));
+
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default());
+        let result = fw
+            .generate_to_string(RESOURCES, "attributes.xsd")
+            .expect("can not generate");
+        assert!(!result.contains("round_trip"));
+    }
 
-        let mut result = String::new();
-        buffer
-            .read_to_string(&mut result)
-            .expect("failed to get content");
-        result
+    #[test]
+    fn test_definition_positions() {
+        // the passes over the generated elements warn at the position of the definitions
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default());
+        fw.generate_to_string(RESOURCES, "attributes.xsd")
+            .expect("can not generate");
+        let types = fw.pick_section(TYPES_MOD);
+        let item = types.borrow().child("Item").expect("no Item");
+        assert_eq!(item.borrow().position.as_deref(), Some("attributes.xsd:7"));
+        let id = item.borrow().child("id").expect("no id");
+        assert_eq!(id.borrow().position.as_deref(), Some("attributes.xsd:11"));
     }
 
     #[test]
-    fn test_attributes() {
-        let result = prepare_output(
-            None,
-            Some("http://xml.avaya.com/schema/import_csm_agent".to_string()),
-        );
-        assert!(
-            result.contains(r#"#[yaserde(rename="createTenantIfNotAlreadyPresent", attribute)]"#)
+    fn test_diamond_imports() {
+        let result = prepare_output("diamond.xsd");
+
+        // both imported schemas import the shared one, of which the definitions appear once
+        assert_eq!(result.matches("pub struct Address {").count(), 1);
+        assert_eq!(result.matches("pub struct PostalAddress {").count(), 1);
+        assert_eq!(result.matches("pub enum Country {").count(), 1);
+        assert_eq!(result.matches("pub enum LocationGroup {").count(), 1);
+        assert_eq!(result.matches("pub type Village = String;").count(), 1);
+        assert_eq!(result.matches("impl_text_value!(HouseNumbers);").count(), 1);
+        assert_eq!(
+            result.matches("\tPostalAddress(PostalAddress),\n").count(),
+            1
         );
+        assert!(result.contains("pub struct Billing {"));
+        assert!(result.contains("pub struct Shipping {"));
     }
 
+    #[cfg(feature = "remote")]
     #[test]
-    fn test_default_namespace() {
-        let result = prepare_output(
-            None,
-            Some("http://xml.avaya.com/schema/import_csm_agent".to_string()),
+    fn test_remote_documents() {
+        use std::io::{BufRead, BufReader, Write};
+
+        // serves the fixtures, and records the paths that were requested
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("can not listen");
+        let address = listener.local_addr().expect("no address");
+        let requested = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = requested.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let mut request_line = String::new();
+                if BufReader::new(&stream)
+                    .read_line(&mut request_line)
+                    .is_err()
+                {
+                    continue;
+                }
+                let path = request_line
+                    .split(' ')
+                    .nth(1)
+                    .unwrap_or_default()
+                    .to_string();
+                let file_name = path.split('?').next().unwrap_or_default();
+                let file_name = file_name.trim_start_matches("/schemas/");
+                let body = std::fs::read_to_string(format!("{}/{}", RESOURCES, file_name))
+                    .unwrap_or_default();
+                let _ = write!(
+                    &stream,
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                log.lock().expect("poisoned").push(path);
+            }
+        });
+
+        let url = format!("http://{}/schemas/diamond.xsd?xsd", address);
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default());
+        let result = fw.generate_to_string(".", &url).expect("can not generate");
+
+        // imports are fetched relative to the document importing them
+        let mut requested = requested.lock().expect("poisoned").clone();
+        requested.sort();
+        requested.dedup();
+        assert_eq!(
+            requested,
+            vec![
+                "/schemas/diamond.xsd?xsd",
+                "/schemas/diamond_left.xsd",
+                "/schemas/diamond_right.xsd",
+                "/schemas/diamond_shared.xsd"
+            ]
         );
+        assert_eq!(result, prepare_output("diamond.xsd"));
+        assert!(fw.sources().is_empty());
+    }
 
-        // no prefix for default namespace
-        assert!(result.contains(
-            r#"#[yaserde(
-	rename = "xmlAgentProfile",
-)]"#
-        ));
+    #[test]
+    fn test_cached_documents() {
+        let dir = std::env::temp_dir().join("zeep_test_cached_documents");
+        let _ = std::fs::remove_dir_all(&dir);
+        let cache = DocumentCache::new(&dir);
+        // nothing listens on the discard port, so the documents can only come from the cache
+        let base = "http://127.0.0.1:9/schemas";
+        for file_name in &[
+            "diamond.xsd",
+            "diamond_left.xsd",
+            "diamond_right.xsd",
+            "diamond_shared.xsd",
+        ] {
+            let content = std::fs::read_to_string(format!("{}/{}", RESOURCES, file_name))
+                .expect("can not read fixture");
+            cache
+                .put(&format!("{}/{}", base, file_name), &content)
+                .expect("can not cache");
+        }
+        assert_eq!(
+            cache.get(&format!("{}/diamond.xsd", base)),
+            std::fs::read_to_string(format!("{}/diamond.xsd", RESOURCES)).ok()
+        );
+        assert_eq!(cache.get(&format!("{}/missing.xsd", base)), None);
+
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default())
+            .with_cache_dir(&dir)
+            .with_offline(true);
+        let result = fw
+            .generate_to_string(".", &format!("{}/diamond.xsd", base))
+            .expect("can not generate");
+        assert_eq!(result, prepare_output("diamond.xsd"));
+
+        match fw.generate_to_string(".", &format!("{}/missing.xsd", base)) {
+            Err(ZeepError::Io { path, .. }) => assert!(path.ends_with("missing.xsd")),
+            other => panic!("expected an io error, got {:?}", other),
+        }
+        std::fs::remove_dir_all(dir).expect("can not clean up");
     }
 
     #[test]
-    fn test_no_default_namespace() {
-        let result = prepare_output(None, None);
+    fn test_multiple_inputs() {
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default())
+            .with_additional_inputs(&[
+                "multiple_inputs_billing.wsdl",
+                "multiple_inputs_orders.wsdl",
+            ]);
+        let result = fw
+            .generate_to_string(RESOURCES, "multiple_inputs_orders.wsdl")
+            .expect("can not generate");
+
+        // the schema both documents import is generated once, the services of both are generated
+        assert_eq!(result.matches("pub struct Money {").count(), 1);
+        assert!(result.contains("pub struct PlaceOrder {"));
+        assert!(result.contains("pub struct Charge {"));
+        assert!(result.contains("pub trait Orders {"));
+        assert!(result.contains("pub trait Billing {"));
+        assert_eq!(result.matches("pub struct OrdersService {}").count(), 1);
+        assert!(result.contains("pub struct BillingService {}"));
+        assert_eq!(fw.model().services.len(), 2);
+    }
 
+    #[test]
+    fn test_module_layout() {
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default())
+            .with_module_name("types", "model")
+            .with_module_visibility("types", "pub(crate)")
+            .with_module_name("ports", "client")
+            .with_module_name("bindings", "client")
+            .with_module_visibility("bindings", "pub(super)");
+        let result = fw
+            .generate_to_string(RESOURCES, "faults.wsdl")
+            .expect("can not generate");
+
+        assert!(result.contains("pub(crate) mod model {"));
+        assert!(result.contains("pub mod messages {"));
+        assert_eq!(result.matches("pub(super) mod client {").count(), 1);
+        assert!(!result.contains("mod ports"));
+        assert!(!result.contains("mod bindings"));
+        assert!(result.contains("\tpub withdraw: model::Withdraw, \n"));
+        assert!(result.contains("impl client::Account for AccountSoap {"));
         assert!(result.contains(
-            r#"#[yaserde(
-	rename = "xmlAgentProfile",
-	namespace = "tns: http://xml.avaya.com/schema/import_csm_agent",
-	prefix = "tns",
-)]"#
+            "pub fn new_client(credentials: Option<(String, String)>) -> client::AccountSoap {"
         ));
+
+        let files = fw
+            .generate_to_files(RESOURCES, "faults.wsdl")
+            .expect("can not generate");
+        let names: Vec<&str> = files.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "mod.rs",
+                "model.rs",
+                "messages.rs",
+                "client.rs",
+                "services.rs",
+                "transport.rs"
+            ]
+        );
+        assert!(files[0]
+            .1
+            .contains("pub(crate) mod model;\npub mod messages;\npub(super) mod client;\n"));
     }
 
     #[test]
-    fn test_ns_prefix() {
-        let result = prepare_output(Some("ns2".to_string()), None);
-
-        assert!(result.contains(
-            r#"#[yaserde(
-	rename = "xmlAgentProfile",
-	namespace = "ns2: http://xml.avaya.com/schema/import_csm_agent",
-	prefix = "ns2",
-)]"#
-        ));
+    fn test_unresolved_type_stubs() {
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default());
+        let result = fw
+            .generate_to_string(RESOURCES, "unresolved_type.xsd")
+            .expect("can not generate");
+        assert!(!result.contains("pub type Custmer"));
+
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default())
+            .with_unresolved_type_stubs(true);
+        let result = fw
+            .generate_to_string(RESOURCES, "unresolved_type.xsd")
+            .expect("can not generate");
+        assert!(result.contains("pub type Custmer = String;"));
+        assert!(!result.contains("pub type Item = String;"));
     }
 
     #[test]
-    fn test_import() {
-        let result = prepare_output(None, None);
-        assert!(result.contains(r#"xmlContact"#));
+    fn test_check() {
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default()).with_strict(true);
+        let report = fw
+            .check(RESOURCES, "namespace_problems.xsd")
+            .expect("can not check");
+        assert!(report.has_problems());
+        assert_eq!(
+            report.unresolved,
+            vec![Problem::new(
+                "namespace_problems.xsd:13",
+                "unresolved type Name referenced by Shipment.carrier"
+            )]
+        );
+        assert_eq!(
+            report.namespaces,
+            vec![
+                Problem::new(
+                    "namespace_problems.xsd:13",
+                    "undeclared prefix carrier in type=\"carrier:name\""
+                ),
+                Problem::new(
+                    "namespaces_other.xsd:2",
+                    "imported as namespace http://example.com/fixtures/others, but the target namespace is http://example.com/fixtures/other"
+                ),
+            ]
+        );
+        assert!(fw.report().to_json().contains("\"namespaces\": ["));
+
+        // approximations are no problem
+        let report = fw.check(RESOURCES, "pattern.xsd").expect("can not check");
+        assert!(!report.has_problems());
+        assert_eq!(report.approximated().count(), 1);
     }
 }
 
 #[cfg(test)]
-mod test_wsdl {
+mod test_builtin_types {
     use super::*;
-    use std::io::Read;
 
-    fn prepare_output(ns_prefix: Option<String>, default_ns: Option<String>) -> String {
-        let mut buffer = DebugBuffer::default();
-        let mut fw = FileWriter::new_buffer(ns_prefix, default_ns, buffer.clone());
-        fw.process_file("resources/temp_converter/", "tempconverter.wsdl")
-            .expect("can not open wsdl");
+    #[test]
+    fn test_to_regex() {
+        assert_eq!(to_regex(&["[A-Z]{2}[0-9]{4}"]), "^(?:[A-Z]{2}[0-9]{4})$");
+        assert_eq!(to_regex(&["\\d+", "n/a"]), "^(?:\\d+|n/a)$");
+        assert_eq!(to_regex(&["\\i\\c*"]), "^(?:[_:A-Za-z][-._:A-Za-z0-9]*)$");
+        assert_eq!(to_regex(&["[a-z-[aeiou]]"]), "^(?:[a-z--[aeiou]])$");
+        assert_eq!(to_regex(&["[^$]^$"]), "^(?:[^\\$]\\^\\$)$");
+    }
 
-        let mut result = String::new();
-        buffer
-            .read_to_string(&mut result)
-            .expect("failed to get content");
-        result
+    #[test]
+    fn test_numeric_types() {
+        let fw = FileWriter::default();
+        let expected = [
+            ("xs:decimal", "f64"),
+            ("xs:double", "f64"),
+            ("xs:float", "f32"),
+            ("xs:integer", "i64"),
+            ("xs:negativeInteger", "i64"),
+            ("xs:nonPositiveInteger", "i64"),
+            ("xs:nonNegativeInteger", "u64"),
+            ("xs:positiveInteger", "u64"),
+            ("xs:long", "i64"),
+            ("xs:int", "i32"),
+            ("xs:short", "i16"),
+            ("xs:byte", "i8"),
+            ("xs:unsignedLong", "u64"),
+            ("xs:unsignedInt", "u32"),
+            ("xs:unsignedShort", "u16"),
+            ("xs:unsignedByte", "u8"),
+        ];
+
+        for (xsd_type, rust_type) in expected.iter() {
+            assert_eq!(fw.builtin_type(xsd_type), Some(*rust_type), "{}", xsd_type);
+        }
     }
+}
+
+#[cfg(test)]
+mod test_operation_filter {
+    use super::*;
+
+    const RESOURCES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../resources/temp_converter");
 
     #[test]
-    fn test_service() {
-        let result = prepare_output(None, None);
-        assert!(result.contains(r#"bindings::TempConverterEndpointServiceSoapBinding::new("http://www.learnwebservices.com/services/tempconverter", credentials)"#));
+    fn test_operation_filter() {
+        let mut fw = FileWriter::new_buffer(None, None, DebugBuffer::default())
+            .with_operation_filter(&["CelsiusToFahrenheit"]);
+        let result = fw
+            .generate_to_string(RESOURCES, "tempconverter.wsdl")
+            .expect("can not generate");
+
+        assert!(result.contains("async fn celsius_to_fahrenheit"));
+        assert!(result.contains("pub struct CelsiusToFahrenheitRequest"));
+        assert!(!result.contains("async fn fahrenheit_to_celsius"));
+        assert!(!result.contains("pub struct FahrenheitToCelsius"));
     }
 }
@@ -0,0 +1,325 @@
+//! Runs the generated round trip tests of scaffolds with cargo. This builds the dependencies of
+//! the generated crates from the registry cache, so it only runs when asked for:
+//! `cargo test -p zeep-lib --test round_trip_tests -- --ignored`
+use std::path::{Path, PathBuf};
+use zeep_lib::writer::FileWriter;
+
+const RESOURCES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../resources/fixtures");
+
+/// The directory of the crate of a test, in a directory of this run, so runs at the same time do
+/// not write over each other's crates. What is left of an earlier run is removed.
+fn crate_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir()
+        .join(format!("zeep_round_trip_tests_{}", std::process::id()))
+        .join(name);
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+/// Removes the crate of a test, and the directory of the run once it is empty.
+fn clean_up(dir: &Path) {
+    std::fs::remove_dir_all(dir).expect("can not clean up");
+    if let Some(parent) = dir.parent() {
+        let _ = std::fs::remove_dir(parent);
+    }
+}
+
+/// Runs the tests of a scaffold, with the versions of the dependencies of the workspace, and
+/// returns what they print. Panics when they fail. `target` picks the tests, as `--lib` or
+/// `--test=<name>`.
+fn cargo_test(dir: &Path, target: &str) -> String {
+    let workspace = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+    let _ = std::fs::copy(workspace.join("Cargo.lock"), dir.join("Cargo.lock"));
+    let output = std::process::Command::new(env!("CARGO"))
+        .args(["test", "--offline", "--quiet", target])
+        .current_dir(dir)
+        .env(
+            "CARGO_TARGET_DIR",
+            workspace.join("target").join("scaffold_tests"),
+        )
+        .output()
+        .expect("can not run cargo");
+    let printed = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(output.status.success(), "the tests fail:\n{}", printed);
+    printed
+}
+
+#[test]
+#[ignore = "runs cargo on the generated crates, which needs their dependencies in the registry cache"]
+fn test_round_trip_tests_pass() {
+    let dir = crate_dir("round_trip");
+    for input in &["binary.xsd", "choice.xsd", "qname.xsd", "range_facets.xsd"] {
+        let mut fw = FileWriter::new_scaffold(&dir, None, None).with_round_trip_tests(true);
+        fw.process_file(RESOURCES, input).expect("can not generate");
+        let output = cargo_test(&dir, "--lib");
+        assert!(output.contains(" 0 failed;"), "{}: {}", input, output);
+    }
+    clean_up(&dir);
+}
+
+#[test]
+#[ignore = "runs cargo on the generated crates, which needs their dependencies in the registry cache"]
+fn test_serde_round_trip_tests_pass() {
+    let dir = crate_dir("serde_round_trip");
+    for input in &[
+        "binary.xsd",
+        "choice.xsd",
+        "diamond.xsd",
+        "group.xsd",
+        "mixed.xsd",
+        "qname.xsd",
+        "substitution_group.xsd",
+        "xsi_type.xsd",
+    ] {
+        let mut fw = FileWriter::new_scaffold(&dir, None, None)
+            .with_serde_backend(true)
+            .with_round_trip_tests(true);
+        fw.process_file(RESOURCES, input).expect("can not generate");
+        let output = cargo_test(&dir, "--lib");
+        assert!(output.contains(" 0 failed;"), "{}: {}", input, output);
+    }
+    clean_up(&dir);
+}
+
+#[test]
+#[ignore = "runs cargo on the generated crates, which needs their dependencies in the registry cache"]
+fn test_soap12_fault_parses() {
+    let dir = crate_dir("soap12_fault");
+    let mut fw = FileWriter::new_scaffold(&dir, None, None).with_server_dispatchers(true);
+    fw.process_file(RESOURCES, "soap12.wsdl")
+        .expect("can not generate");
+    std::fs::create_dir_all(dir.join("tests")).expect("can not create directory");
+    std::fs::write(
+        dir.join("tests").join("fault.rs"),
+        r##"use yaserde::de::from_str;
+use yaserde::ser::to_string;
+use soap12_fault::bindings::{PingResponseSoap12Envelope, Soap12PingResponse};
+use soap12_fault::{Soap12Fault, SoapFault};
+
+const ENVELOPE: &str = r#"<env:Envelope xmlns:env="http://www.w3.org/2003/05/soap-envelope">
+    <env:Body><env:Fault>
+        <env:Code><env:Value>env:Sender</env:Value><env:Subcode><env:Value>m:Late</env:Value></env:Subcode></env:Code>
+        <env:Reason><env:Text xml:lang="en">the ping came too late</env:Text></env:Reason>
+        <env:Role>http://example.com/fixtures/echo</env:Role>
+        <env:Detail><m:delay xmlns:m="http://example.com/fixtures/delays">5</m:delay></env:Detail>
+    </env:Fault></env:Body>
+</env:Envelope>"#;
+
+#[test]
+fn test_fault() {
+    let response: PingResponseSoap12Envelope = from_str(ENVELOPE).unwrap();
+    let fault = SoapFault::from(response.body.fault.unwrap());
+    assert_eq!(fault.fault_code.as_deref(), Some("env:Sender"));
+    assert_eq!(fault.fault_string.as_deref(), Some("the ping came too late"));
+    assert_eq!(fault.fault_actor.as_deref(), Some("http://example.com/fixtures/echo"));
+    let detail = fault.detail.expect("no detail");
+    assert!(detail.content.contains(">5</m:delay>"), "{:?}", detail);
+
+    // a service answers with the codes of SOAP 1.2
+    let fault = SoapFault {
+        fault_code: Some("soapenv:Server".to_string()),
+        ..Default::default()
+    };
+    let body = Soap12PingResponse {
+        fault: Some(Soap12Fault::from(fault)),
+        ..Default::default()
+    };
+    let envelope = to_string(&PingResponseSoap12Envelope::new(body)).unwrap();
+    assert!(envelope.contains("<soapenv:Value>soapenv:Receiver</soapenv:Value>"), "{}", envelope);
+}
+"##,
+    )
+    .expect("can not write test");
+    let output = cargo_test(&dir, "--test=fault");
+    assert!(output.contains("1 passed; 0 failed;"), "{}", output);
+    clean_up(&dir);
+}
+
+#[test]
+#[ignore = "runs cargo on the generated crates, which needs their dependencies in the registry cache"]
+fn test_addressing_headers() {
+    let dir = crate_dir("addressing_headers");
+    let mut fw = FileWriter::new_scaffold(&dir, None, None).with_ws_addressing(true);
+    fw.process_file(RESOURCES, "soap_header.wsdl")
+        .expect("can not generate");
+    std::fs::create_dir_all(dir.join("tests")).expect("can not create directory");
+    std::fs::write(
+        dir.join("tests").join("headers.rs"),
+        r##"use yaserde::de::from_str;
+use yaserde::ser::to_string;
+use addressing_headers::addressing::{Addressing, AttributedUri};
+use addressing_headers::bindings::{PingRequestSoapEnvelope, PingResponseSoapEnvelope};
+
+#[test]
+fn test_headers() {
+    let addressing = Addressing::new()
+        .with_message_id("urn:uuid:1")
+        .with_reply_to("http://example.com/reply?a&b");
+    let mut request = PingRequestSoapEnvelope::new(Default::default());
+    request.header.get_or_insert_with(Default::default).addressing =
+        Some(addressing.headers("http://example.com/echo", "ping"));
+    let envelope = to_string(&request).unwrap();
+    assert!(envelope.contains(r#"<soapenv:Header><wsa:To xmlns:wsa="http://www.w3.org/2005/08/addressing">http://example.com/echo</wsa:To>"#), "{}", envelope);
+    assert!(envelope.contains("<wsa:Address>http://example.com/reply?a&amp;b</wsa:Address>"), "{}", envelope);
+
+    // the headers read back, and the SOAP headers of the operation are left out
+    let request: PingRequestSoapEnvelope = from_str(&envelope).unwrap();
+    let header = request.header.unwrap();
+    assert!(header.session.is_none());
+    assert_eq!(header.addressing, Some(addressing.headers("http://example.com/echo", "ping")));
+
+    let response: PingResponseSoapEnvelope = from_str(
+        r#"<soapenv:Envelope xmlns:soapenv="http://schemas.xmlsoap.org/soap/envelope/" xmlns:wsa="http://www.w3.org/2005/08/addressing">
+    <soapenv:Header><wsa:Action>pong</wsa:Action><wsa:RelatesTo>urn:uuid:1</wsa:RelatesTo></soapenv:Header>
+    <soapenv:Body/>
+</soapenv:Envelope>"#,
+    )
+    .unwrap();
+    let headers = response.header.unwrap().addressing.unwrap();
+    assert_eq!(headers.relates_to, Some(AttributedUri::new("urn:uuid:1")));
+    assert_eq!(headers.action, Some(AttributedUri::new("pong")));
+}
+"##,
+    )
+    .expect("can not write test");
+    let output = cargo_test(&dir, "--test=headers");
+    assert!(output.contains("1 passed; 0 failed;"), "{}", output);
+    clean_up(&dir);
+}
+
+#[test]
+#[ignore = "runs cargo on the generated crates, which needs their dependencies in the registry cache"]
+fn test_header_timestamp() {
+    let dir = crate_dir("header_timestamp");
+    let mut fw = FileWriter::new_scaffold(&dir, None, None).with_ws_security(true);
+    fw.process_file(RESOURCES, "soap_header.wsdl")
+        .expect("can not generate");
+    std::fs::create_dir_all(dir.join("tests")).expect("can not create directory");
+    std::fs::write(
+        dir.join("tests").join("timestamp.rs"),
+        r##"use std::time::{Duration, UNIX_EPOCH};
+use header_timestamp::security::Timestamp;
+
+const ENVELOPE: &str = r#"<soapenv:Envelope xmlns:soapenv="http://schemas.xmlsoap.org/soap/envelope/"
+    xmlns:wsse="http://docs.oasis-open.org/wss/2004/01/oasis-200401-wss-wssecurity-secext-1.0.xsd"
+    xmlns:wsu="http://docs.oasis-open.org/wss/2004/01/oasis-200401-wss-wssecurity-utility-1.0.xsd">
+    <soapenv:Header><wsse:Security>{}</wsse:Security></soapenv:Header>
+    <soapenv:Body><wsu:Timestamp><wsu:Created>now</wsu:Created><wsu:Expires>1970-01-01T00:00:00Z</wsu:Expires></wsu:Timestamp></soapenv:Body>
+</soapenv:Envelope>"#;
+
+#[test]
+fn test_check() {
+    let now = UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+    let timestamp = Timestamp::new(Duration::from_secs(60));
+
+    // the elements of the body are not the timestamp of the message
+    assert_eq!(timestamp.check(&ENVELOPE.replace("{}", ""), now), Ok(()));
+
+    assert!(timestamp.to_xml(now).contains("<wsu:Created>2020-09-13T12:26:40.000Z</wsu:Created>"));
+    assert!(timestamp.to_xml(now).contains("<wsu:Expires>2020-09-13T12:27:40.000Z</wsu:Expires>"));
+    let envelope = ENVELOPE.replace("{}", &timestamp.to_xml(now));
+    assert_eq!(timestamp.check(&envelope, now), Ok(()));
+    let later = now + Duration::from_secs(3600);
+    assert!(timestamp.check(&envelope, later).unwrap_err().starts_with("the message expired"));
+    let offset = ENVELOPE.replace("{}", "<wsu:Timestamp><wsu:Created>2020-09-13T14:26:40+02:00</wsu:Created></wsu:Timestamp>");
+    assert_eq!(timestamp.check(&offset, now), Ok(()));
+    assert!(timestamp.check(&offset, now - Duration::from_secs(3600)).unwrap_err().contains("in the future"));
+}
+"##,
+    )
+    .expect("can not write test");
+    let output = cargo_test(&dir, "--test=timestamp");
+    assert!(output.contains("1 passed; 0 failed;"), "{}", output);
+    clean_up(&dir);
+}
+
+#[test]
+#[ignore = "runs cargo on the generated crates, which needs their dependencies in the registry cache"]
+fn test_attachments_stream() {
+    let dir = crate_dir("attachments_stream");
+    let mut fw = FileWriter::new_scaffold(&dir, None, None).with_blocking_clients(true);
+    fw.process_file(RESOURCES, "mime.wsdl")
+        .expect("can not generate");
+    std::fs::create_dir_all(dir.join("tests")).expect("can not create directory");
+    std::fs::write(
+        dir.join("tests").join("stream.rs"),
+        r##"use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc;
+use std::time::Duration;
+use yaserde::ser::to_string;
+use attachments_stream::bindings::{DownloadResponseSoapEnvelope, FilesSoap, SoapDownloadResponse};
+use attachments_stream::ports::{DownloadResponse, Files};
+
+/// Answers a request with the envelope and the first half of an attachment, and sends the rest
+/// once the client has read that half. Returns whether it had to wait for the client.
+fn serve(listener: TcpListener, envelope: String, read: mpsc::Receiver<()>) -> bool {
+    let (stream, _) = listener.accept().unwrap();
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut length = 0;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+            length = value.trim().parse().unwrap();
+        }
+        if line == "\r\n" {
+            break;
+        }
+    }
+    reader.read_exact(&mut vec![0; length]).unwrap();
+
+    let mut stream = stream;
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: multipart/related; type=\"text/xml\"; start=\"<root>\"; boundary=\"part\"\r\nConnection: close\r\n\r\n"
+    )
+    .unwrap();
+    write!(stream, "preamble\r\n--part\r\nContent-ID: <root>\r\n\r\n{}\r\n--part\r\nContent-Type: text/plain\r\nContent-ID: <content=1>\r\n\r\nfirst half,", envelope).unwrap();
+    // what starts like a delimiter is held back until the rest of it is read
+    write!(stream, "\r\n--pa").unwrap();
+    stream.flush().unwrap();
+    let waited = read.recv_timeout(Duration::from_secs(10)).is_ok();
+    write!(stream, "ss it on\r\n--part--\r\nepilogue").unwrap();
+    waited
+}
+
+#[test]
+fn test_stream() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/files", listener.local_addr().unwrap());
+    let mut response = SoapDownloadResponse::default();
+    response.body.parameters.file_name = "notes.txt".to_string();
+    let envelope = to_string(&DownloadResponseSoapEnvelope::new(response)).unwrap();
+    let (half_read, read) = mpsc::channel();
+    let server = std::thread::spawn(move || serve(listener, envelope, read));
+
+    let client = FilesSoap::new(&url, None);
+    let (output, mut parts): (DownloadResponse, _) = client.download(Default::default()).unwrap();
+    assert_eq!(output.parameters.file_name, "notes.txt");
+
+    let mut part = parts.next_part().unwrap().expect("no attachment");
+    assert_eq!(part.content_id, "content=1");
+    assert_eq!(part.content_type, "text/plain");
+    assert!(part.is_referenced_by("cid:content%3D1"));
+    let mut data = vec![0; "first half,".len()];
+    part.read_exact(&mut data).unwrap();
+    assert_eq!(data, b"first half,");
+    half_read.send(()).unwrap();
+    let mut rest = String::new();
+    part.read_to_string(&mut rest).unwrap();
+    assert_eq!(rest, "\r\n--pass it on");
+    assert!(parts.next_part().unwrap().is_none());
+    assert!(server.join().unwrap(), "the first half is read before the rest is sent");
+}
+"##,
+    )
+    .expect("can not write test");
+    let output = cargo_test(&dir, "--test=stream");
+    assert!(output.contains("1 passed; 0 failed;"), "{}", output);
+    clean_up(&dir);
+}
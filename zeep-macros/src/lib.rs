@@ -0,0 +1,119 @@
+//! # Zeep macros
+//! Generates the code of a WSDL document, or an XML schema, at compile time into the current
+//! module:
+//!
+//! ```ignore
+//! mod hello {
+//!     zeep_macros::include_wsdl!("resources/hello.wsdl");
+//! }
+//! ```
+//!
+//! The path is relative to the root of the crate, and the documents it imports are read from the
+//! same directory. The crate is compiled again when one of the local documents changes. A
+//! document can be given by its URL as well, which is fetched on every build of the crate.
+//!
+use proc_macro::{Literal, TokenStream, TokenTree};
+use std::path::{Path, PathBuf};
+use zeep_lib::writer::FileWriter;
+
+/// Generates the types, traits and clients of a WSDL document into the current module
+#[proc_macro]
+pub fn include_wsdl(input: TokenStream) -> TokenStream {
+    match path_of(input).and_then(|path| generate(&path)) {
+        Ok(code) => code,
+        Err(message) => compile_error(&message),
+    }
+}
+
+/// Generates the types of an XML schema into the current module, like `include_wsdl!`
+#[proc_macro]
+pub fn include_xsd(input: TokenStream) -> TokenStream {
+    include_wsdl(input)
+}
+
+/// The location of the document: its path in the crate, or its URL
+fn path_of(input: TokenStream) -> Result<PathBuf, String> {
+    let tokens: Vec<TokenTree> = input.into_iter().collect();
+    let literal = match tokens.as_slice() {
+        [TokenTree::Literal(literal)] => literal.to_string(),
+        _ => return Err("expected the path of a document, like \"service.wsdl\"".to_string()),
+    };
+    let path = literal
+        .strip_prefix('"')
+        .and_then(|literal| literal.strip_suffix('"'))
+        .filter(|path| !path.contains('\\'))
+        .ok_or_else(|| format!("expected a plain string literal, got {}", literal))?;
+
+    if is_url(path) {
+        return Ok(PathBuf::from(path));
+    }
+
+    let root = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".to_string());
+    Ok(Path::new(&root).join(path))
+}
+
+fn generate(input: &Path) -> Result<TokenStream, String> {
+    let (base_path, file_name) = match input.to_str() {
+        Some(url) if is_url(url) => (Path::new("."), url.to_string()),
+        _ => (
+            input.parent().unwrap_or_else(|| Path::new(".")),
+            input
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+        ),
+    };
+
+    let mut writer = FileWriter::new(Option::None, Option::None).with_includable_output(true);
+    let code = writer
+        .generate_to_string(&base_path.to_string_lossy(), &file_name)
+        .map_err(|e| e.to_string())?;
+
+    // including the documents makes the compiler track them, so changing one rebuilds the crate
+    let mut tracked = String::new();
+    for source in tracked_files(writer.sources()) {
+        tracked.push_str(&format!(
+            "const _: &[u8] = include_bytes!({});\n",
+            Literal::string(source)
+        ));
+    }
+
+    format!("{}\n{}", tracked, code)
+        .parse()
+        .map_err(|e| format!("can not parse the code of {}: {:?}", input.display(), e))
+}
+
+/// The sources that are files, which `include_bytes!` can track, leaving out the documents
+/// fetched from URLs
+fn tracked_files(sources: &[String]) -> Vec<&String> {
+    sources
+        .iter()
+        .filter(|source| !is_url(source) && Path::new(source).is_file())
+        .collect()
+}
+
+fn is_url(location: &str) -> bool {
+    location.starts_with("http://") || location.starts_with("https://")
+}
+
+fn compile_error(message: &str) -> TokenStream {
+    format!("compile_error!({});", Literal::string(message))
+        .parse()
+        .expect("compile_error! is valid code")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracked_files() {
+        let wsdl = concat!(env!("CARGO_MANIFEST_DIR"), "/../resources/hello/hello.wsdl");
+        let sources = vec![
+            wsdl.to_string(),
+            "https://example.com/hello.xsd".to_string(),
+            "missing.xsd".to_string(),
+        ];
+        assert_eq!(tracked_files(&sources), vec![wsdl]);
+    }
+}
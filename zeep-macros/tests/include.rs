@@ -0,0 +1,28 @@
+//! Includes the code of documents, and checks that it compiles and (de)serializes
+// the derives of yaserde put their impls in a const
+#![allow(non_local_definitions)]
+
+// the generated code is not held to the lints of clippy
+#[allow(clippy::all)]
+mod hello {
+    zeep_macros::include_wsdl!("../resources/hello/hello.wsdl");
+}
+
+#[allow(clippy::all)]
+mod attributes {
+    zeep_macros::include_xsd!("../resources/fixtures/attributes.xsd");
+}
+
+#[test]
+fn test_include_wsdl() {
+    let client = hello::services::HelloEndpointService::new_client(None);
+    let _: &dyn hello::ports::HelloEndpoint = &client;
+}
+
+#[test]
+fn test_include_xsd() {
+    let xml =
+        yaserde::ser::to_string(&attributes::types::Book::default()).expect("can not serialize");
+    let book: attributes::types::Book = yaserde::de::from_str(&xml).expect("can not deserialize");
+    assert_eq!(yaserde::ser::to_string(&book), Ok(xml));
+}
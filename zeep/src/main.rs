@@ -1,23 +1,63 @@
-use clap::{App, Arg};
-use log::warn;
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use log::{info, warn, LevelFilter};
+use log4rs::append::console::{ConsoleAppender, Target};
+use log4rs::config::{Appender, Config, Root};
+use log4rs::encode::pattern::PatternEncoder;
 use std::fs::File;
+use std::path::{Path, PathBuf};
+use zeep_lib::config::{Config as ZeepConfig, CONFIG_FILE};
+use zeep_lib::coverage::GenerationReport;
+use zeep_lib::error::{ZeepError, ZeepResult};
 use zeep_lib::writer::FileWriter;
 
-fn main() {
-    if let Err(err) = log4rs::init_file("log4rs.yml", Default::default()) {
-        warn!("Unable to find log4rs.yml logging config. {}", err);
-    }
+/// The input could not be turned into code
+const EXIT_FAILURE: i32 = 1;
+/// The output, or one of the reports, could not be written
+const EXIT_IO_ERROR: i32 = 2;
 
+fn main() {
     let matches = App::new("Zeep - XSD/WSDL client generator for Rust")
-        .version("0.1.0")
+        .version(env!("CARGO_PKG_VERSION"))
         .author("Marcel Ibes <mibes@avaya.com>")
         .about("Generate Yaserde annotated Rust structs from XSD or WSDL")
+        .setting(AppSettings::SubcommandsNegateReqs)
+        .subcommand(
+            SubCommand::with_name("check")
+                .about("Check the documents for what no code is generated for, unresolved references and namespace problems, without generating code")
+                .arg(
+                    Arg::with_name("from_file")
+                        .value_name("INPUT")
+                        .required(true)
+                        .multiple(true)
+                        .help("Input from XSD/WSDL file, and more to check them in one run"),
+                )
+                .arg(
+                    Arg::with_name("strict")
+                        .long("strict")
+                        .help("Fail on approximated constructs too"),
+                )
+                .args(&analysis_args()),
+        )
         .arg(
             Arg::with_name("to_file")
                 .short("o")
                 .long("output")
                 .takes_value(true)
-                .help("Output to file"),
+                .help("Output to file, or to a directory with a file per module when it is one or ends with /"),
+        )
+        .arg(
+            Arg::with_name("to_dir")
+                .long("output-dir")
+                .takes_value(true)
+                .conflicts_with("to_file")
+                .help("Output to a directory, with a file per module"),
+        )
+        .arg(
+            Arg::with_name("scaffold")
+                .long("scaffold")
+                .takes_value(true)
+                .conflicts_with_all(&["to_file", "to_dir"])
+                .help("Output a crate to a directory, with a Cargo.toml and an example that calls the service"),
         )
         .arg(
             Arg::with_name("from_file")
@@ -25,15 +65,25 @@ fn main() {
                 .long("input")
                 .takes_value(true)
                 .required(true)
-                .help("Input from XSD/WSDL file"),
+                .multiple(true)
+                .number_of_values(1)
+                .help("Input from XSD/WSDL file, repeated to generate several in one run"),
         )
         .arg(
-            Arg::with_name("path")
-                .short("p")
-                .long("path")
-                .takes_value(true)
-                .required(true)
-                .help("Base path for the XSD file(s)"),
+            Arg::with_name("verbose")
+                .short("v")
+                .long("verbose")
+                .multiple(true)
+                .global(true)
+                .help("Log more: -v for info and -vv for debug messages, on stderr"),
+        )
+        .arg(
+            Arg::with_name("quiet")
+                .short("q")
+                .long("quiet")
+                .conflicts_with("verbose")
+                .global(true)
+                .help("Only log errors"),
         )
         .arg(
             Arg::with_name("ns")
@@ -49,28 +99,591 @@ fn main() {
                 .takes_value(true)
                 .help("Default namespace (URL)"),
         )
+        .arg(
+            Arg::with_name("namespace_modules")
+                .long("namespace-modules")
+                .help("Generate the types of every namespace in a module of their own"),
+        )
+        .arg(
+            Arg::with_name("namespace_module")
+                .long("namespace-module")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .help("Module name for the types of a namespace, as <namespace>=<module>"),
+        )
+        .arg(
+            Arg::with_name("derive")
+                .long("derive")
+                .takes_value(true)
+                .use_delimiter(true)
+                .help("Comma separated list of derives to add to every generated type"),
+        )
+        .arg(
+            Arg::with_name("type_derive")
+                .long("type-derive")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .help("Derives of one type instead, as <type>=<derive>,<derive>"),
+        )
+        .arg(
+            Arg::with_name("keyword_suffix")
+                .long("keyword-suffix")
+                .takes_value(true)
+                .help("Suffix for names that are Rust keywords, instead of raw identifiers"),
+        )
+        .arg(
+            Arg::with_name("typed_durations")
+                .long("typed-durations")
+                .help("Generate a Duration type with ISO 8601 (de)serialization for xs:duration"),
+        )
+        .arg(
+            Arg::with_name("builders")
+                .long("builders")
+                .help("Generate a builder for every struct"),
+        )
+        .arg(
+            Arg::with_name("extension_files")
+                .long("extension-files")
+                .help("Include a hand-written <module>_ext.rs file in every module file, created once"),
+        )
+        .arg(
+            Arg::with_name("round_trip_tests")
+                .long("round-trip-tests")
+                .help("Generate a test per struct that (de)serializes its default value"),
+        )
+        .arg(
+            Arg::with_name("chrono")
+                .long("chrono")
+                .help("Generate chrono based types for xs:dateTime, xs:date and xs:time"),
+        )
+        .arg(
+            Arg::with_name("blocking").long("blocking").help(
+                "Generate synchronous port traits and bindings on the blocking reqwest client",
+            ),
+        )
+        .arg(
+            Arg::with_name("blocking_feature")
+                .long("blocking-feature")
+                .conflicts_with("blocking")
+                .help("Generate blocking clients as well, in a blocking module behind the blocking feature"),
+        )
+        .arg(
+            Arg::with_name("format")
+                .long("format")
+                .help("Format the generated code with prettyplease"),
+        )
+        .arg(
+            Arg::with_name("mocks")
+                .long("mocks")
+                .help("Generate a mock of every port type, behind the mocks feature"),
+        )
+        .arg(
+            Arg::with_name("ws_security")
+                .long("ws-security")
+                .help("Generate WS-Security headers, like a UsernameToken, for the SOAP bindings"),
+        )
+        .arg(
+            Arg::with_name("ws_signature")
+                .long("ws-signature")
+                .help("Generate X.509 signatures of the requests as well, in the WS-Security header"),
+        )
+        .arg(
+            Arg::with_name("mtom")
+                .long("mtom")
+                .help("Send the binary values with an xmime:expectedContentTypes as MTOM attachments"),
+        )
+        .arg(
+            Arg::with_name("ws_addressing")
+                .long("ws-addressing")
+                .help("Generate WS-Addressing headers, like wsa:Action and wsa:MessageID, for the SOAP bindings"),
+        )
+        .arg(
+            Arg::with_name("serde").long("serde").help(
+                "Generate serde types, (de)serialized with quick-xml, instead of yaserde types, for XML schemas only, without clients",
+            ),
+        )
+        .arg(
+            Arg::with_name("server")
+                .long("server")
+                .help("Generate a dispatcher per SOAP binding, to serve the port types"),
+        )
+        .arg(
+            Arg::with_name("emit_ir")
+                .long("emit-ir")
+                .takes_value(true)
+                .help("Write the services, operations, messages and types as JSON to a file"),
+        )
+        .arg(
+            Arg::with_name("strict")
+                .long("strict")
+                .help("Fail on constructs of the documents that no code is generated for"),
+        )
+        .arg(
+            Arg::with_name("stub_unresolved")
+                .long("stub-unresolved")
+                .help("Generate a String alias for referenced types that are not defined"),
+        )
+        .args(&analysis_args())
         .get_matches();
 
-    let to_file_name = matches.value_of("to_file");
-    let from_file_name = matches.value_of("from_file").unwrap_or_default();
-    let base_path = matches.value_of("path").unwrap_or_default();
-    let ns_prefix = matches.value_of("ns").map(|ns| ns.to_string());
-    let default_namespace = matches.value_of("dns").map(|dns| dns.to_string());
+    // checking takes the options that change the analysis, by the same names
+    let (matches, check) = match matches.subcommand_matches("check") {
+        Some(check_matches) => (check_matches, true),
+        None => (&matches, false),
+    };
+    // the findings of a check are printed, so they are not logged as well
+    init_logging(
+        matches.occurrences_of("verbose"),
+        matches.is_present("quiet") || (check && matches.occurrences_of("verbose") == 0),
+    );
 
-    if let Some(output_file) = to_file_name {
-        let file = File::create(output_file).expect("can not create file");
-        let mut writer = FileWriter::new_file(file, ns_prefix, default_namespace);
+    // an output that is a directory takes a file per module
+    let (to_file_name, to_dir_name) = match matches.value_of("to_file") {
+        Some(output) if output.ends_with('/') || Path::new(output).is_dir() => (None, Some(output)),
+        to_file_name => (to_file_name, matches.value_of("to_dir")),
+    };
+    let inputs: Vec<&str> = matches
+        .values_of("from_file")
+        .map(|inputs| inputs.collect())
+        .unwrap_or_default();
+    let (base_path, from_file_name) = match matches.value_of("path") {
+        Some(path) => (path, inputs[0]),
+        None => split_input(inputs[0]),
+    };
+    let mut additional_inputs = vec![];
+    for input in &inputs[1..] {
+        match matches.value_of("path") {
+            Some(_) => additional_inputs.push(*input),
+            None => match split_input(input) {
+                (dir, file_name) if dir == base_path || is_url(input) => {
+                    additional_inputs.push(file_name)
+                }
+                _ => {
+                    eprintln!(
+                        "{} is in another directory than {}, use --path for a directory they share",
+                        input, inputs[0]
+                    );
+                    std::process::exit(EXIT_FAILURE);
+                }
+            },
+        }
+    }
+    // the options on the command line add to those of the configuration file
+    let mut config = match load_config(matches.value_of("config"), base_path) {
+        Ok(config) => config,
+        Err(err @ ZeepError::Io { .. }) => {
+            eprintln!("Failed to read the configuration: {}", err);
+            std::process::exit(EXIT_IO_ERROR);
+        }
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(EXIT_FAILURE);
+        }
+    };
+    let report_file_name = matches.value_of("report");
+    let scaffold_dir_name = matches.value_of("scaffold");
+    let cache_dir = matches
+        .value_of("cache_dir")
+        .map(PathBuf::from)
+        .or_else(default_cache_dir);
+    let offline = matches.is_present("offline");
+    let refresh = matches.is_present("refresh");
+    config.merge(cli_config(matches, check));
+    if config.extension_files && to_dir_name.is_none() && scaffold_dir_name.is_none() {
+        warn!("ignoring --extension-files, which takes an output directory");
+    }
+    if let Some(limit) = serde_limit(&config).filter(|_| !check) {
+        eprintln!("{}", limit);
+        std::process::exit(EXIT_FAILURE);
+    }
+
+    let ns_prefix = config.ns_prefix.clone();
+    let default_namespace = config.default_namespace.clone();
+    let (writer, output) = if let Some(scaffold_dir) = scaffold_dir_name {
+        (
+            FileWriter::new_scaffold(scaffold_dir, ns_prefix, default_namespace),
+            Some(scaffold_dir),
+        )
+    } else if let Some(output_dir) = to_dir_name {
+        (
+            FileWriter::new_dir(output_dir, ns_prefix, default_namespace),
+            Some(output_dir),
+        )
+    } else if let Some(output_file) = to_file_name {
+        let file = match File::create(output_file) {
+            Ok(file) => file,
+            Err(err) => {
+                eprintln!("Failed to create {}: {}", output_file, err);
+                std::process::exit(EXIT_IO_ERROR);
+            }
+        };
+        (
+            FileWriter::new_file(file, ns_prefix, default_namespace),
+            Some(output_file),
+        )
+    } else {
+        (FileWriter::new(ns_prefix, default_namespace), None)
+    };
+
+    let mut writer = config
+        .apply(writer)
+        .with_additional_inputs(&additional_inputs)
+        .with_offline(offline)
+        .with_refresh(refresh);
+    if let Some(cache_dir) = &cache_dir {
+        writer = writer.with_cache_dir(cache_dir);
+    }
+    if let Some(output) = output {
+        println!("parsing {}/{} --> {}", base_path, from_file_name, output);
+    }
+    let result = if check {
+        writer.check(base_path, from_file_name).map(|_| ())
+    } else {
+        writer.process_file(base_path, from_file_name)
+    };
+    if let Some(report_file_name) = report_file_name {
+        if let Err(err) = std::fs::write(report_file_name, writer.report().to_json()) {
+            eprintln!("Failed to write {}: {}", report_file_name, err);
+            if result.is_ok() {
+                std::process::exit(EXIT_IO_ERROR);
+            }
+        }
+    }
+    if let Err(err) = result {
+        eprintln!("Failed to process {}: {}", from_file_name, err);
+        std::process::exit(exit_code(&err));
+    }
+    if check {
+        let strict = matches.is_present("strict");
+        if !print_findings(writer.report(), strict) {
+            std::process::exit(EXIT_FAILURE);
+        }
+        return;
+    }
+    if let Some(ir_file_name) = matches.value_of("emit_ir") {
+        if let Err(err) = std::fs::write(ir_file_name, writer.model_json()) {
+            eprintln!("Failed to write {}: {}", ir_file_name, err);
+            std::process::exit(EXIT_IO_ERROR);
+        }
+    }
+}
+
+/// The exit code of an error processing the input: failing to write the output is an I/O error,
+/// failing to read a document is a failure to turn the input into code
+fn exit_code(err: &ZeepError) -> i32 {
+    match err {
+        ZeepError::Output { .. } => EXIT_IO_ERROR,
+        _ => EXIT_FAILURE,
+    }
+}
+
+/// The options of the command line that the configuration file has a key for
+fn cli_config(matches: &ArgMatches, check: bool) -> ZeepConfig {
+    let mut config = ZeepConfig {
+        ns_prefix: matches.value_of("ns").map(str::to_string),
+        default_namespace: matches.value_of("dns").map(str::to_string),
+        stub_unresolved: matches.is_present("stub_unresolved"),
+        strict: matches.is_present("strict") && !check,
+        typed_durations: matches.is_present("typed_durations"),
+        chrono: matches.is_present("chrono"),
+        regex: matches.is_present("regex"),
+        blocking: matches.is_present("blocking"),
+        blocking_feature: matches.is_present("blocking_feature"),
+        server: matches.is_present("server"),
+        mocks: matches.is_present("mocks"),
+        ws_security: matches.is_present("ws_security"),
+        ws_signature: matches.is_present("ws_signature"),
+        ws_addressing: matches.is_present("ws_addressing"),
+        mtom: matches.is_present("mtom"),
+        format: matches.is_present("format"),
+        serde: matches.is_present("serde"),
+        builders: matches.is_present("builders"),
+        round_trip_tests: matches.is_present("round_trip_tests"),
+        extension_files: matches.is_present("extension_files"),
+        namespace_modules: matches.is_present("namespace_modules"),
+        keyword_suffix: matches.value_of("keyword_suffix").map(str::to_string),
+        ..Default::default()
+    };
+    if let Some(operations) = matches.values_of("operations") {
+        config.operations = operations.map(|op| op.to_string()).collect();
+    }
+    if let Some(derives) = matches.values_of("derive") {
+        config.derives = derives.map(|d| d.to_string()).collect();
+    }
+    config.catalog = key_values(matches, "catalog", "<namespace>=<location>")
+        .into_iter()
+        .collect();
+    config.modules = key_values(matches, "namespace_module", "<namespace>=<module>")
+        .into_iter()
+        .collect();
+    for (type_name, derives) in key_values(matches, "type_derive", "<type>=<derive>,<derive>") {
+        let derives = derives
+            .split(',')
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty())
+            .collect();
+        config.type_derives.insert(type_name, derives);
+    }
+    config
+}
+
+/// Prints what checking the documents found, one finding a line, and whether they pass the
+/// check. Approximated constructs only fail it in strict mode.
+fn print_findings(report: &GenerationReport, strict: bool) -> bool {
+    for construct in report.skipped() {
         println!(
-            "parsing {}/{} --> {}",
-            base_path, from_file_name, output_file
+            "{}:{}: unsupported {}, {}",
+            construct.file, construct.line, construct.tag, construct.reason
         );
-        if let Err(err) = writer.process_file(base_path, from_file_name) {
-            println!("Failed to process {}: {}", from_file_name, err.to_string())
+    }
+    for construct in report.approximated() {
+        println!(
+            "{}:{}: approximated {}, {}",
+            construct.file, construct.line, construct.tag, construct.reason
+        );
+    }
+    for problem in report.unresolved.iter().chain(&report.namespaces) {
+        println!("{}", problem);
+    }
+
+    let approximated = report.approximated().count();
+    println!(
+        "{} unsupported, {} approximated, {} unresolved, {} namespace problems",
+        report.skipped().count(),
+        approximated,
+        report.unresolved.len(),
+        report.namespaces.len()
+    );
+    !report.has_problems() && (!strict || approximated == 0)
+}
+
+/// The options that change what the analysis of the documents finds, which checking them takes
+/// as well as generating
+fn analysis_args() -> Vec<Arg<'static, 'static>> {
+    vec![
+        Arg::with_name("path")
+            .short("p")
+            .long("path")
+            .visible_alias("base-path")
+            .takes_value(true)
+            .help("Base path for the XSD file(s), the directory of the input by default"),
+        Arg::with_name("operations")
+            .long("operations")
+            .takes_value(true)
+            .use_delimiter(true)
+            .help("Comma separated list of the operations to generate"),
+        Arg::with_name("catalog")
+            .long("catalog")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .help("Schema location for imports of a namespace, as <namespace>=<file or URL>"),
+        Arg::with_name("regex")
+            .long("regex")
+            .help("Validate the pattern facets of string restrictions with the regex crate"),
+        Arg::with_name("report")
+            .long("report")
+            .takes_value(true)
+            .help("Write a JSON report of the uncovered constructs and the problems to a file"),
+        Arg::with_name("config")
+            .long("config")
+            .takes_value(true)
+            .help("Configuration file [default: zeep.toml in the base path, when it exists]"),
+        Arg::with_name("cache_dir")
+            .long("cache-dir")
+            .takes_value(true)
+            .help("Directory of the documents fetched from URLs [default: ~/.cache/zeep]"),
+        Arg::with_name("offline")
+            .long("offline")
+            .conflicts_with("refresh")
+            .help("Read documents with a URL from the cache only"),
+        Arg::with_name("refresh")
+            .long("refresh")
+            .help("Fetch documents with a URL again, and update the cache"),
+    ]
+}
+
+/// Logs to stderr at the level of the verbosity flags. Without them, the log4rs.yml in the
+/// working directory configures the logging, when there is one.
+fn init_logging(verbosity: u64, quiet: bool) {
+    let level = match (quiet, verbosity) {
+        (true, _) => LevelFilter::Error,
+        (false, 0) => LevelFilter::Warn,
+        (false, 1) => LevelFilter::Info,
+        _ => LevelFilter::Debug,
+    };
+    if !quiet && verbosity == 0 && log4rs::init_file("log4rs.yml", Default::default()).is_ok() {
+        return;
+    }
+
+    let stderr = ConsoleAppender::builder()
+        .target(Target::Stderr)
+        .encoder(Box::new(PatternEncoder::new("{l}: {m}{n}")))
+        .build();
+    let config = Config::builder()
+        .appender(Appender::builder().build("stderr", Box::new(stderr)))
+        .build(Root::builder().appender("stderr").build(level));
+    if let Ok(config) = config {
+        let _ = log4rs::init_config(config);
+    }
+}
+
+/// Reads the configuration file given on the command line, or else the one in the base path.
+/// Without either, the options are those of the command line only.
+fn load_config(config_file: Option<&str>, base_path: &str) -> ZeepResult<ZeepConfig> {
+    match config_file {
+        Some(config_file) => ZeepConfig::from_file(config_file),
+        None => {
+            let config_file = Path::new(base_path).join(CONFIG_FILE);
+            if config_file.is_file() {
+                info!("reading the configuration of {}", config_file.display());
+                ZeepConfig::from_file(config_file)
+            } else {
+                Ok(ZeepConfig::default())
+            }
         }
+    }
+}
+
+/// The values of an option that are of the form <key>=<value>, warning about the others
+fn key_values(matches: &ArgMatches, name: &str, form: &str) -> Vec<(String, String)> {
+    matches
+        .values_of(name)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| match entry.split_once('=') {
+                    None => {
+                        let option = name.replace('_', "-");
+                        warn!("ignoring --{} {}, expected {}", option, entry, form);
+                        None
+                    }
+                    Some((key, value)) => Some((key.to_string(), value.to_string())),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Why the serde backend can not generate the code the options ask for: it generates the types of
+/// XML schemas, so there are no clients to take the options of clients. The library turns down
+/// WSDL documents themselves, with their position.
+fn serde_limit(config: &ZeepConfig) -> Option<String> {
+    if !config.serde {
+        return None;
+    }
+    let client_options: Vec<&str> = [
+        ("--blocking", config.blocking),
+        ("--blocking-feature", config.blocking_feature),
+        ("--server", config.server),
+        ("--mocks", config.mocks),
+        ("--ws-security", config.ws_security),
+        ("--ws-signature", config.ws_signature),
+        ("--ws-addressing", config.ws_addressing),
+        ("--mtom", config.mtom),
+        ("--operations", !config.operations.is_empty()),
+    ]
+    .iter()
+    .filter(|(_, enabled)| *enabled)
+    .map(|(option, _)| *option)
+    .collect();
+    if client_options.is_empty() {
+        None
     } else {
-        let mut writer = FileWriter::new(ns_prefix, default_namespace);
-        if let Err(err) = writer.process_file(base_path, from_file_name) {
-            println!("Failed to process {}: {}", from_file_name, err.to_string())
-        }
+        Some(format!(
+            "--serde generates the types of XML schemas only, without the clients that {} are for",
+            client_options.join(", ")
+        ))
+    }
+}
+
+/// The cache of the user, as the XDG base directories define it
+fn default_cache_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .map(|dir| dir.join("zeep"))
+}
+
+fn is_url(input: &str) -> bool {
+    input.starts_with("http://") || input.starts_with("https://")
+}
+
+/// Splits the input into the directory it is in, which is the base path, and its file name.
+/// URLs are taken as they are.
+fn split_input(input: &str) -> (&str, &str) {
+    if is_url(input) {
+        return (".", input);
+    }
+    match input.rsplit_once('/') {
+        Some(("", file_name)) => ("/", file_name),
+        Some((dir, file_name)) => (dir, file_name),
+        None => (".", input),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESOURCES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../resources/fixtures");
+
+    #[test]
+    fn test_exit_code() {
+        // the output directory is a file, so the modules can not be written in it
+        let output = std::env::temp_dir().join("zeep_test_exit_code");
+        std::fs::write(&output, "").expect("can not create file");
+        let err = FileWriter::new_dir(&output, None, None)
+            .process_file(RESOURCES, "attributes.xsd")
+            .expect_err("writes in a file");
+        assert_eq!(exit_code(&err), EXIT_IO_ERROR);
+        std::fs::remove_file(&output).expect("can not clean up");
+
+        let err = FileWriter::new(None, None)
+            .process_file(RESOURCES, "missing.xsd")
+            .expect_err("reads a missing input");
+        assert_eq!(exit_code(&err), EXIT_FAILURE);
+
+        // a document missing from the output directory is still an input that can not be read
+        let dir = std::env::temp_dir().join(format!("zeep_test_exit_code_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("can not create directory");
+        std::fs::write(
+            dir.join("a.xsd"),
+            r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"><xs:import namespace="urn:missing" schemaLocation="missing.xsd"/></xs:schema>"#,
+        )
+        .expect("can not write input");
+        let err = FileWriter::new_dir(&dir, None, None)
+            .process_file(dir.to_str().expect("no UTF-8 path"), "a.xsd")
+            .expect_err("reads a missing import");
+        assert!(matches!(err, ZeepError::Io { .. }), "{}", err);
+        assert_eq!(exit_code(&err), EXIT_FAILURE);
+        std::fs::remove_dir_all(&dir).expect("can not clean up");
+
+        let err = ZeepError::Output {
+            path: "output".to_string(),
+            source: std::io::Error::from(std::io::ErrorKind::BrokenPipe),
+        };
+        assert_eq!(exit_code(&err), EXIT_IO_ERROR);
+    }
+
+    #[test]
+    fn test_serde_limit() {
+        let mut config = ZeepConfig {
+            serde: true,
+            ..Default::default()
+        };
+        assert_eq!(serde_limit(&config), None);
+
+        config.server = true;
+        config.mtom = true;
+        assert_eq!(
+            serde_limit(&config).as_deref(),
+            Some("--serde generates the types of XML schemas only, without the clients that --server, --mtom are for")
+        );
+
+        config.serde = false;
+        assert_eq!(serde_limit(&config), None);
     }
 }